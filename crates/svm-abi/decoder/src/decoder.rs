@@ -37,6 +37,9 @@ enum TypeKind {
 #[derive(Debug)]
 pub enum ValueError {
     NotEnoughBytes,
+
+    /// The value isn't encoded using the minimal number of bytes
+    NonMinimalLength,
 }
 
 /// Denotes a decode error
@@ -250,7 +253,16 @@ impl Decoder {
             layout::ARR_4 => 4,
             layout::ARR_5 => 5,
             layout::ARR_6 => 6,
-            layout::ARR_0_255 => self.read_byte(cursor)?,
+            layout::ARR_0_255 => {
+                let nitems = self.read_byte(cursor)?;
+
+                // arrays of up to 6 items have their own dedicated markers
+                if nitems <= 6 {
+                    return Err(DecodeError::Value(ValueError::NonMinimalLength));
+                }
+
+                nitems
+            }
             _ => unreachable!(),
         };
 
@@ -292,6 +304,12 @@ impl Decoder {
         }
 
         let ptr = self.read_bytes(cursor, nbytes)?;
+
+        // a number encoded using `nbytes` must not fit into fewer bytes
+        if nbytes > 1 && unsafe { *ptr } == 0 {
+            return Err(DecodeError::Value(ValueError::NonMinimalLength));
+        }
+
         let num = from_be_bytes!(ptr, nbytes);
 
         Ok(num)
@@ -370,13 +388,7 @@ impl Decoder {
             | layout::ARR_6
             | layout::ARR_0_255 => TypeKind::Array,
 
-            _ => {
-                extern crate std;
-
-                let err = std::format!("svm-abi-decoder: Unsupported type-kind {}", byte);
-
-                unreachable!(err)
-            }
+            _ => return Err(DecodeError::Type(TypeError::InvalidTypeKind(byte))),
         };
 
         Ok(kind)
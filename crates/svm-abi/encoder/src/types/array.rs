@@ -27,6 +27,10 @@ macro_rules! impl_encode {
 
             w.push(marker);
 
+            if marker == layout::ARR_0_255 {
+                w.push(self.len() as u8);
+            }
+
             for elem in self.iter() {
                 elem.encode(w);
             }
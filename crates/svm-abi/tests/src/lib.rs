@@ -421,6 +421,42 @@ mod tests {
         assert_eq!(f, f_);
     }
 
+    #[test]
+    fn encode_decode_array_of_more_than_6_items() {
+        test_array!([u32; 7], [1u32, 2, 3, 4, 5, 6, 7]);
+        test_array!([u32; 10], [1u32, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn decode_rejects_non_minimal_lengths() {
+        use svm_abi_decoder::{Cursor, DecodeError, Decoder, ValueError};
+        use svm_abi_layout::layout;
+
+        macro_rules! assert_non_minimal {
+            ($bytes:expr) => {{
+                let bytes: &[u8] = &$bytes;
+                let mut cursor = Cursor::new(bytes);
+
+                let res = Decoder::new().decode_value(&mut cursor);
+
+                assert!(matches!(
+                    res,
+                    Err(DecodeError::Value(ValueError::NonMinimalLength))
+                ));
+            }};
+        }
+
+        // `10u32` fits into a single byte
+        assert_non_minimal!([layout::U32_2B, 0x00, 0x0A]);
+        assert_non_minimal!([layout::U32_4B, 0x00, 0x00, 0x00, 0x0A]);
+
+        // `Amount(1)` fits into a single byte
+        assert_non_minimal!([layout::AMOUNT_8B, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+        // an array of 2 items has its own marker
+        assert_non_minimal!([layout::ARR_0_255, 2, layout::BOOL_TRUE, layout::BOOL_FALSE]);
+    }
+
     #[test]
     fn display_addr() {
         let bytes = [
@@ -486,3 +486,87 @@ describe("Execute App (a.k.a `Call Method`)", function () {
     });
   });
 });
+
+describe("Signed Envelope", function () {
+  it("Signs & Decodes a valid envelope", function () {
+    return compileWasmCodec().then((instance) => {
+      const sender = generateAddress("1020304050");
+
+      const envelope = {
+        tx_type: "exec-app",
+        sender: sender,
+        value: 10,
+        gas_limit: 1000,
+        gas_price: 1,
+        nonce: 3,
+        payload: "C0DE",
+        scheme: "ed25519",
+        secret_key: "1F".repeat(32),
+      };
+
+      let buf = wasmNewBuffer(instance, envelope);
+      let result = instanceCall(instance, "wasm_sign_envelope", buf);
+
+      let len = wasmBufferLength(instance, result);
+      const slice = wasmBufferDataSlice(instance, result, 0, len);
+      assert.strictEqual(slice[0], OK_MARKER);
+
+      const data = binToString(slice.slice(1));
+
+      wasmBufferFree(instance, buf);
+      wasmBufferFree(instance, result);
+
+      buf = wasmNewBuffer(instance, { data: data });
+      result = instanceCall(instance, "wasm_decode_signed_envelope", buf);
+      const json = loadWasmBufferDataAsJson(instance, result);
+
+      assert.strictEqual(json.tx_type, "exec-app");
+      assert.strictEqual(json.sender, sender);
+      assert.strictEqual(json.nonce, 3);
+      assert.strictEqual(json.payload, "C0DE");
+      assert.strictEqual(json.verified, true);
+
+      wasmBufferFree(instance, buf);
+      wasmBufferFree(instance, result);
+    });
+  });
+});
+
+describe("Decode Receipt", function () {
+  it("Decodes a failed receipt", function () {
+    return compileWasmCodec().then((instance) => {
+      // a failed `exec-app` receipt (out-of-gas) without logs
+      const buf = wasmNewBuffer(instance, { data: "020000000000" });
+      const result = instanceCall(instance, "wasm_decode_receipt", buf);
+      const json = loadWasmBufferDataAsJson(instance, result);
+
+      assert.deepStrictEqual(json, {
+        type: "exec-app",
+        version: 0,
+        success: false,
+        err_type: "oog",
+        gas_used: -1,
+        logs: [],
+      });
+
+      wasmBufferFree(instance, buf);
+      wasmBufferFree(instance, result);
+    });
+  });
+
+  it("Handles errors for invalid receipts", function () {
+    return compileWasmCodec().then((instance) => {
+      const buf = wasmNewBuffer(instance, { data: "" });
+      const result = instanceCall(instance, "wasm_decode_receipt", buf);
+
+      const error = loadWasmBufferError(instance, result);
+      assert.strictEqual(
+        error,
+        'InvalidField { field: "data", reason: "receipt is empty" }'
+      );
+
+      wasmBufferFree(instance, buf);
+      wasmBufferFree(instance, result);
+    });
+  });
+});
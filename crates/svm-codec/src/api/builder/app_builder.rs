@@ -9,6 +9,7 @@ pub struct SpawnAppBuilder {
     template: Option<TemplateAddr>,
     name: Option<String>,
     ctor_name: Option<String>,
+    value: Option<u64>,
     calldata: Option<Vec<u8>>,
 }
 
@@ -28,9 +29,10 @@ pub struct SpawnAppBuilder {
 /// let calldata = vec![0x10, 0x20, 0x30];
 ///
 /// let bytes = SpawnAppBuilder::new()
-///             .with_version(0)
+///             .with_version(1)
 ///             .with_template(&template)
 ///             .with_name(&name)
+///             .with_value(100)
 ///             .with_ctor(ctor_name)
 ///             .with_calldata(&calldata)
 ///             .build();
@@ -38,8 +40,9 @@ pub struct SpawnAppBuilder {
 /// let mut cursor = Cursor::new(&bytes[..]);
 /// let actual = app::decode_spawn_app(&mut cursor).unwrap();
 /// let expected = SpawnApp {
-///                  version: 0,
+///                  version: 1,
 ///                  app: App { name, template },
+///                  value: 100,
 ///                  ctor_name: ctor_name.to_string(),
 ///                  calldata,
 ///                };
@@ -56,6 +59,7 @@ impl SpawnAppBuilder {
             template: None,
             name: None,
             ctor_name: None,
+            value: None,
             calldata: None,
         }
     }
@@ -80,6 +84,11 @@ impl SpawnAppBuilder {
         self
     }
 
+    pub fn with_value(mut self, value: u64) -> Self {
+        self.value = Some(value);
+        self
+    }
+
     pub fn with_calldata(mut self, calldata: &Vec<u8>) -> Self {
         self.calldata = Some(calldata.clone());
         self
@@ -91,6 +100,8 @@ impl SpawnAppBuilder {
         let name = self.name.unwrap();
         let ctor_name = self.ctor_name.unwrap();
 
+        let value = self.value.unwrap_or(0);
+
         let calldata = match self.calldata {
             None => vec![],
             Some(calldata) => calldata.to_vec(),
@@ -99,6 +110,7 @@ impl SpawnAppBuilder {
         let spawn = SpawnApp {
             version,
             app: App { name, template },
+            value,
             ctor_name,
             calldata,
         };
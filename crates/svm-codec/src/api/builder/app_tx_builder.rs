@@ -8,6 +8,7 @@ pub struct AppTxBuilder {
     version: Option<u16>,
     app: Option<AppAddr>,
     func_name: Option<String>,
+    value: Option<u64>,
     calldata: Option<Vec<u8>>,
 }
 
@@ -27,8 +28,9 @@ pub struct AppTxBuilder {
 /// let calldata = vec![0x10, 0x20, 0x30];
 ///
 /// let bytes = AppTxBuilder::new()
-///            .with_version(0)
+///            .with_version(1)
 ///            .with_app(&app)
+///            .with_value(100)
 ///            .with_func(func_name)
 ///            .with_calldata(&calldata)
 ///            .build();
@@ -36,8 +38,9 @@ pub struct AppTxBuilder {
 /// let mut cursor = Cursor::new(&bytes[..]);
 /// let actual = transaction::decode_exec_app(&mut cursor).unwrap();
 /// let expected = AppTransaction {
-///                  version: 0,
+///                  version: 1,
 ///                  app,
+///                  value: 100,
 ///                  func_name: func_name.to_string(),
 ///                  calldata,
 ///                };
@@ -53,6 +56,7 @@ impl AppTxBuilder {
             version: None,
             app: None,
             func_name: None,
+            value: None,
             calldata: None,
         }
     }
@@ -72,6 +76,11 @@ impl AppTxBuilder {
         self
     }
 
+    pub fn with_value(mut self, value: u64) -> Self {
+        self.value = Some(value);
+        self
+    }
+
     pub fn with_calldata(mut self, calldata: &Vec<u8>) -> Self {
         self.calldata = Some(calldata.to_vec());
         self
@@ -82,6 +91,8 @@ impl AppTxBuilder {
         let app = self.app.unwrap();
         let func_name = self.func_name.unwrap();
 
+        let value = self.value.unwrap_or(0);
+
         let calldata = match self.calldata {
             None => vec![],
             Some(calldata) => calldata.to_vec(),
@@ -90,6 +101,7 @@ impl AppTxBuilder {
         let tx = AppTransaction {
             version,
             app,
+            value,
             func_name,
             calldata,
         };
@@ -1,7 +1,7 @@
 use svm_layout::DataLayout;
 use svm_types::AppTemplate;
 
-use crate::template;
+use crate::template::{self, CodeHash};
 
 /// Builds a raw representation for `deploy-template`
 /// Should be used for testing only.
@@ -9,6 +9,7 @@ pub struct DeployAppTemplateBuilder {
     version: Option<u16>,
     name: Option<String>,
     code: Option<Vec<u8>>,
+    code_hash: Option<CodeHash>,
     data: Option<DataLayout>,
     ctors: Option<Vec<String>>,
 }
@@ -56,6 +57,7 @@ impl DeployAppTemplateBuilder {
             version: None,
             name: None,
             code: None,
+            code_hash: None,
             data: None,
             ctors: None,
         }
@@ -76,6 +78,12 @@ impl DeployAppTemplateBuilder {
         self
     }
 
+    /// References the code by its hash (building a compact `deploy-template`)
+    pub fn with_code_hash(mut self, code_hash: &CodeHash) -> Self {
+        self.code_hash = Some(*code_hash);
+        self
+    }
+
     pub fn with_data(mut self, data: &DataLayout) -> Self {
         self.data = Some(data.clone());
         self
@@ -89,7 +97,10 @@ impl DeployAppTemplateBuilder {
     pub fn build(self) -> Vec<u8> {
         let version = self.version.unwrap();
         let name = self.name.unwrap();
-        let code = self.code.unwrap();
+        let code = match self.code_hash {
+            Some(..) => Vec::new(),
+            None => self.code.unwrap(),
+        };
         let data = self.data.unwrap();
         let ctors = self.ctors.unwrap();
 
@@ -103,7 +114,10 @@ impl DeployAppTemplateBuilder {
 
         let mut w = Vec::new();
 
-        template::encode_deploy_template(&app, &mut w);
+        match self.code_hash {
+            Some(code_hash) => template::encode_compact_deploy_template(&app, &code_hash, &mut w),
+            None => template::encode_deploy_template(&app, &mut w),
+        }
 
         w
     }
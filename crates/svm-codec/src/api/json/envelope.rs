@@ -0,0 +1,241 @@
+use std::io::Cursor;
+
+use serde_json::{json, Value};
+
+use crate::api::json::{self, JsonError};
+use crate::envelope::{self, Envelope, SignatureScheme, SignedEnvelope, TxType};
+
+///
+/// Encodes an (unsigned) `Envelope`. The output is the message to be signed.
+///
+/// ```json
+/// {
+///   tx_type: 'exec-app',  // string (`deploy-template`, `spawn-app` or `exec-app`)
+///   sender: 'A2FB...',    // string
+///   value: 10,            // number (optional, defaults to `0`)
+///   gas_limit: 1000,      // number
+///   gas_price: 1,         // number
+///   nonce: 3,             // number
+///   payload: '0C00...',   // string (the encoded SVM transaction)
+/// }
+/// ```
+pub fn encode_envelope(json: &Value) -> Result<Vec<u8>, JsonError> {
+    let envelope = as_envelope(json)?;
+
+    let mut buf = Vec::new();
+    envelope::encode_envelope(&envelope, &mut buf);
+
+    Ok(buf)
+}
+
+///
+/// Encodes a `SignedEnvelope`, signing the `Envelope` given by the same JSON fields as `encode_envelope`.
+///
+/// ```json
+/// {
+///   ...                   // the `encode_envelope` fields
+///   scheme: 'ed25519',    // string
+///   secret_key: '1F1F...' // string
+/// }
+/// ```
+pub fn sign_envelope(json: &Value) -> Result<Vec<u8>, JsonError> {
+    let envelope = as_envelope(json)?;
+    let scheme = json::as_string(json, "scheme")?;
+    let secret_key = json::as_blob(json, "secret_key")?;
+
+    let signed = match scheme.as_str() {
+        #[cfg(feature = "ed25519")]
+        envelope::Ed25519::NAME => sign::<envelope::Ed25519>(envelope, &secret_key)?,
+        _ => {
+            return Err(JsonError::InvalidField {
+                field: "scheme".to_string(),
+                reason: format!("unsupported signature scheme `{}`", scheme),
+            })
+        }
+    };
+
+    let mut buf = Vec::new();
+    envelope::encode_signed_envelope(&signed, &mut buf);
+
+    Ok(buf)
+}
+
+///
+/// Decodes a `SignedEnvelope` and verifies its signature.
+///
+/// ```json
+/// {
+///   data: '0400...',      // string (the encoded signed envelope)
+/// }
+/// ```
+pub fn decode_signed_envelope(json: &Value) -> Result<Value, JsonError> {
+    let data = json::as_string(json, "data")?;
+    let bytes = json::str_to_bytes(&data, "data")?;
+
+    let mut cursor = Cursor::new(&bytes[..]);
+    let signed =
+        envelope::decode_signed_envelope(&mut cursor).map_err(|e| JsonError::InvalidField {
+            field: "data".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    let verified = envelope::verify(&signed);
+    let envelope = &signed.envelope;
+
+    let json = json!({
+        "tx_type": tx_type_to_str(envelope.tx_type),
+        "sender": json::addr_to_str(&envelope.sender),
+        "value": envelope.value,
+        "gas_limit": envelope.gas_limit,
+        "gas_price": envelope.gas_price,
+        "nonce": envelope.nonce,
+        "payload": json::bytes_to_str(&envelope.payload),
+        "scheme": signed.scheme,
+        "public_key": json::bytes_to_str(&signed.public_key),
+        "signature": json::bytes_to_str(&signed.signature),
+        "verified": verified.is_ok(),
+    });
+
+    Ok(json)
+}
+
+fn sign<S: SignatureScheme>(
+    envelope: Envelope,
+    secret_key: &[u8],
+) -> Result<SignedEnvelope, JsonError> {
+    let secret_key = S::secret_key(secret_key).ok_or(JsonError::InvalidField {
+        field: "secret_key".to_string(),
+        reason: format!("value isn't a valid `{}` secret key", S::NAME),
+    })?;
+
+    Ok(SignedEnvelope::sign::<S>(envelope, &secret_key))
+}
+
+fn as_envelope(json: &Value) -> Result<Envelope, JsonError> {
+    let tx_type = as_tx_type(json, "tx_type")?;
+    let sender = json::as_addr(json, "sender")?;
+    let value = json::as_optional_u64(json, "value")?.unwrap_or(0);
+    let gas_limit = json::as_u64(json, "gas_limit")?;
+    let gas_price = json::as_u64(json, "gas_price")?;
+    let nonce = json::as_u64(json, "nonce")?;
+    let payload = json::as_blob(json, "payload")?;
+
+    let envelope = Envelope {
+        tx_type,
+        sender,
+        value,
+        gas_limit,
+        gas_price,
+        nonce,
+        payload,
+    };
+
+    Ok(envelope)
+}
+
+fn as_tx_type(json: &Value, field: &str) -> Result<TxType, JsonError> {
+    let ty = json::as_string(json, field)?;
+
+    match ty.as_str() {
+        "deploy-template" => Ok(TxType::DeployTemplate),
+        "spawn-app" => Ok(TxType::SpawnApp),
+        "exec-app" => Ok(TxType::ExecApp),
+        _ => Err(JsonError::InvalidField {
+            field: field.to_string(),
+            reason: format!("unknown transaction type `{}`", ty),
+        }),
+    }
+}
+
+fn tx_type_to_str(ty: TxType) -> &'static str {
+    match ty {
+        TxType::DeployTemplate => "deploy-template",
+        TxType::SpawnApp => "spawn-app",
+        TxType::ExecApp => "exec-app",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope_json() -> Value {
+        json!({
+            "tx_type": "exec-app",
+            "sender": "10203040506070809000A0B0C0D0E0F0ABCDEFFF",
+            "value": 10,
+            "gas_limit": 1000,
+            "gas_price": 2,
+            "nonce": 3,
+            "payload": "C0DE",
+        })
+    }
+
+    #[test]
+    fn json_envelope_invalid_tx_type() {
+        let mut json = envelope_json();
+        json["tx_type"] = json!("transfer");
+
+        let err = encode_envelope(&json).unwrap_err();
+        assert_eq!(
+            err,
+            JsonError::InvalidField {
+                field: "tx_type".to_string(),
+                reason: "unknown transaction type `transfer`".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn json_sign_envelope_unsupported_scheme() {
+        let mut json = envelope_json();
+        json["scheme"] = json!("rsa");
+        json["secret_key"] = json!("1F1F");
+
+        let err = sign_envelope(&json).unwrap_err();
+        assert_eq!(
+            err,
+            JsonError::InvalidField {
+                field: "scheme".to_string(),
+                reason: "unsupported signature scheme `rsa`".to_string(),
+            }
+        );
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn json_sign_and_decode_envelope() {
+        let mut json = envelope_json();
+        json["scheme"] = json!("ed25519");
+        json["secret_key"] = json!(json::bytes_to_str(&[0x1F; 32]));
+
+        let bytes = sign_envelope(&json).unwrap();
+
+        let data = json::bytes_to_str(&bytes);
+        let decoded = decode_signed_envelope(&json!({ "data": data })).unwrap();
+
+        assert_eq!(decoded["tx_type"], json!("exec-app"));
+        assert_eq!(decoded["sender"], json["sender"]);
+        assert_eq!(decoded["value"], json!(10));
+        assert_eq!(decoded["gas_limit"], json!(1000));
+        assert_eq!(decoded["gas_price"], json!(2));
+        assert_eq!(decoded["nonce"], json!(3));
+        assert_eq!(decoded["payload"], json!("C0DE"));
+        assert_eq!(decoded["scheme"], json!(0));
+        assert_eq!(decoded["verified"], json!(true));
+
+        // the signed message is the encoded (unsigned) envelope
+        let msg = encode_envelope(&envelope_json()).unwrap();
+        assert_eq!(&bytes[..msg.len()], &msg[..]);
+
+        // tampering the nonce
+        let mut tampered = bytes.clone();
+        tampered[1 + 20 + 8 + 8 + 8 + 7] ^= 0xFF;
+
+        let data = json::bytes_to_str(&tampered);
+        let decoded = decode_signed_envelope(&json!({ "data": data })).unwrap();
+
+        assert_eq!(decoded["nonce"], json!(3 ^ 0xFF));
+        assert_eq!(decoded["verified"], json!(false));
+    }
+}
@@ -12,6 +12,7 @@ use svm_types::{AddressOf, App, AppTransaction};
 /// {
 ///   version: 0,           // number
 ///   app: 'A2FB...',       // string
+///   value: 10,            // number (optional, defaults to `0`, requires version `1`)
 ///   func_name: 'do_work', // string
 ///   calldata: '',         // string
 /// }
@@ -19,6 +20,14 @@ use svm_types::{AddressOf, App, AppTransaction};
 pub fn encode_exec_app(json: &Value) -> Result<Vec<u8>, JsonError> {
     let version = json::as_u32(json, "version")? as u16;
     let app = json::as_addr(json, "app")?.into();
+    let value = json::as_optional_u64(json, "value")?.unwrap_or(0);
+
+    if version == 0 && value != 0 {
+        return Err(JsonError::InvalidField {
+            field: "value".to_string(),
+            reason: "version `0` transactions have no `value`".to_string(),
+        });
+    }
     let func_name = json::as_string(json, "func_name")?;
 
     let calldata = json::as_string(json, "calldata")?;
@@ -27,6 +36,7 @@ pub fn encode_exec_app(json: &Value) -> Result<Vec<u8>, JsonError> {
     let tx = AppTransaction {
         version,
         app,
+        value,
         func_name,
         calldata,
     };
@@ -42,7 +52,10 @@ pub fn decode_exec_app(json: &Value) -> Result<Value, JsonError> {
     let bytes = json::str_to_bytes(&data, "data")?;
 
     let mut cursor = Cursor::new(&bytes[..]);
-    let tx = transaction::decode_exec_app(&mut cursor).unwrap();
+    let tx = transaction::decode_exec_app(&mut cursor).map_err(|e| JsonError::InvalidField {
+        field: "data".to_string(),
+        reason: e.to_string(),
+    })?;
 
     let version = tx.version;
     let func_name = tx.func_name.clone();
@@ -54,6 +67,7 @@ pub fn decode_exec_app(json: &Value) -> Result<Value, JsonError> {
     let json = json!({
         "version": version,
         "app": app,
+        "value": tx.value,
         "func_name": func_name,
         "calldata": calldata,
     });
@@ -133,6 +147,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn json_exec_app_value_requires_version_1() {
+        let json = json!({
+            "version": 0,
+            "app": "10203040506070809000A0B0C0D0E0F0ABCDEFFF",
+            "value": 10,
+            "func_name": "do_something",
+            "calldata": "",
+        });
+
+        let err = encode_exec_app(&json).unwrap_err();
+        assert_eq!(
+            err,
+            JsonError::InvalidField {
+                field: "value".to_string(),
+                reason: "version `0` transactions have no `value`".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn json_exec_app_valid() {
         let calldata = json::encode_calldata(&json!({
@@ -157,6 +191,7 @@ mod tests {
             json!({
                 "version": 0,
                 "app": "10203040506070809000A0B0C0D0E0F0ABCDEFFF",
+                "value": 0,
                 "func_name": "do_something",
                 "calldata": {
                     "abi": ["i32", "i64"],
@@ -165,4 +200,16 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn json_exec_app_decode_truncated() {
+        let err = decode_exec_app(&json!({ "data": "0001" })).unwrap_err();
+        assert_eq!(
+            err,
+            JsonError::InvalidField {
+                field: "data".to_string(),
+                reason: "Not enough bytes for field `AppAddr`".to_string(),
+            }
+        );
+    }
 }
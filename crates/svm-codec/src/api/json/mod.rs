@@ -1,5 +1,6 @@
 mod calldata;
 mod deploy_template;
+mod envelope;
 mod error;
 mod exec_app;
 mod receipt;
@@ -7,15 +8,17 @@ mod spawn_app;
 
 pub use calldata::{decode_calldata, encode_calldata};
 pub use deploy_template::deploy_template;
+pub use envelope::{decode_signed_envelope, encode_envelope, sign_envelope};
 pub use error::JsonError;
 pub use exec_app::{decode_exec_app, encode_exec_app};
-pub use receipt::decode_receipt;
+pub use receipt::{decode_receipt, receipt_to_json};
 pub use spawn_app::{decode_spawn_app, encode_spawn_app};
 
 use serde_json::{json, Value};
 
 use svm_sdk_types::Amount;
-use svm_types::{gas::MaybeGas, receipt::Log, Address, State};
+use svm_types::receipt::{BalanceChange, Log, StorageChange};
+use svm_types::{gas::MaybeGas, Address, State};
 
 pub(crate) fn to_bytes(json: &Value) -> Result<Vec<u8>, JsonError> {
     match serde_json::to_string(&json) {
@@ -163,6 +166,14 @@ pub(crate) fn as_u64(json: &Value, field: &str) -> Result<u64, JsonError> {
     })
 }
 
+pub(crate) fn as_optional_u64(json: &Value, field: &str) -> Result<Option<u64>, JsonError> {
+    if json[field].is_null() {
+        Ok(None)
+    } else {
+        as_u64(json, field).map(Some)
+    }
+}
+
 pub(crate) fn as_i64(json: &Value, field: &str) -> Result<i64, JsonError> {
     let v: &Value = &json[field];
 
@@ -295,6 +306,39 @@ pub(crate) fn logs_to_json(logs: &[Log]) -> Vec<Value> {
         .collect()
 }
 
+pub(crate) fn balance_changes_to_json(changes: &[BalanceChange]) -> Vec<Value> {
+    changes
+        .iter()
+        .map(|change| {
+            json!({
+                "addr": addr_to_str(&change.addr),
+                "old_balance": change.old_balance,
+                "new_balance": change.new_balance,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn storage_changes_to_json(changes: Option<&[StorageChange]>) -> Value {
+    match changes {
+        None => Value::Null,
+        Some(changes) => {
+            let changes = changes
+                .iter()
+                .map(|change| {
+                    json!({
+                        "var_id": change.var_id,
+                        "old_value": bytes_to_str(&change.old_value),
+                        "new_value": bytes_to_str(&change.new_value),
+                    })
+                })
+                .collect();
+
+            Value::Array(changes)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
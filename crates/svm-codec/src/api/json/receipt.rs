@@ -1,19 +1,48 @@
 use serde_json::{json, Value};
 
 use crate::api::json::{self, JsonError};
-use crate::receipt;
+use crate::receipt::{self, types};
 
 use svm_types::receipt::{
-    ExecReceipt, Log, ReceiptError, ReceiptOwned, SpawnAppReceipt, TemplateReceipt,
+    ExecReceipt, ReceiptError, ReceiptOwned, SpawnAppReceipt, TemplateReceipt,
 };
 
+/// Given a `JSON` of the form `{ "data": "<hex-encoded receipt>" }`,
+/// returns the receipt rendered as `JSON` (see `receipt_to_json`).
 pub fn decode_receipt(json: &Value) -> Result<Value, JsonError> {
     let data = json::as_string(json, "data")?;
     let bytes = json::str_to_bytes(&data, "data")?;
 
-    assert!(bytes.len() > 0);
+    receipt_to_json(&bytes)
+}
+
+/// Renders a binary encoded receipt (of any type) as `JSON`.
+///
+/// Each rendered receipt has the `type`, `version`, `success`, `gas_used` and `logs` fields.
+/// (`gas_used` is `-1` when the gas isn't known, as for failed receipts).
+///
+/// A successful receipt has also the fields specific to its type,
+/// while a failed receipt has an `err_type` field alongside the fields of its `ReceiptError`.
+pub fn receipt_to_json(bytes: &[u8]) -> Result<Value, JsonError> {
+    let ty = bytes.first().ok_or_else(|| JsonError::InvalidField {
+        field: "data".to_string(),
+        reason: "receipt is empty".to_string(),
+    })?;
+
+    match *ty {
+        types::DEPLOY_TEMPLATE | types::SPAWN_APP | types::EXEC_APP => (),
+        _ => {
+            return Err(JsonError::InvalidField {
+                field: "data".to_string(),
+                reason: format!("unknown receipt type `{}`", ty),
+            })
+        }
+    }
 
-    let receipt = receipt::decode_receipt(&bytes);
+    let receipt = receipt::decode_receipt(bytes).map_err(|e| JsonError::InvalidField {
+        field: "data".to_string(),
+        reason: e.to_string(),
+    })?;
     let ty = receipt_type(&receipt);
 
     let json = if receipt.success() {
@@ -23,11 +52,7 @@ pub fn decode_receipt(json: &Value) -> Result<Value, JsonError> {
             ReceiptOwned::ExecApp(receipt) => decode_exe_app(&receipt, ty),
         }
     } else {
-        let ty = receipt_type(&receipt);
-        let logs = receipt.get_logs();
-        let err = receipt.get_error();
-
-        decode_error(ty, err, logs)
+        decode_error(ty, &receipt)
     };
 
     Ok(json)
@@ -41,7 +66,9 @@ fn receipt_type(receipt: &ReceiptOwned) -> &'static str {
     }
 }
 
-fn decode_error(ty: &'static str, err: &ReceiptError, logs: &[Log]) -> Value {
+fn decode_error(ty: &'static str, receipt: &ReceiptOwned) -> Value {
+    let err = receipt.get_error();
+
     let mut json = {
         match err {
             ReceiptError::OOG => json!({
@@ -109,16 +136,80 @@ fn decode_error(ty: &'static str, err: &ReceiptError, logs: &[Log]) -> Value {
                 "func": func,
                 "message": msg,
             }),
+            ReceiptError::InsufficientFunds {
+                addr,
+                balance,
+                amount,
+            } => json!({
+                "err_type": "insufficient-funds",
+                "addr": json::addr_to_str(addr),
+                "balance": balance,
+                "amount": amount,
+            }),
+            ReceiptError::ParseFailed(msg) => json!({
+                "err_type": "parse-failed",
+                "message": msg,
+            }),
+            ReceiptError::MissingMemoryExport {
+                app_addr,
+                template_addr,
+            } => json!({
+                "err_type": "missing-memory-export",
+                "template_addr": json::addr_to_str(template_addr.inner()),
+                "app_addr": json::addr_to_str(app_addr.inner()),
+            }),
+            ReceiptError::MemoryOutOfBounds {
+                app_addr,
+                template_addr,
+                offset,
+                len,
+            } => json!({
+                "err_type": "memory-out-of-bounds",
+                "template_addr": json::addr_to_str(template_addr.inner()),
+                "app_addr": json::addr_to_str(app_addr.inner()),
+                "offset": offset,
+                "length": len,
+            }),
+            ReceiptError::InvalidVarId {
+                app_addr,
+                template_addr,
+                var_id,
+            } => json!({
+                "err_type": "invalid-var-id",
+                "template_addr": json::addr_to_str(template_addr.inner()),
+                "app_addr": json::addr_to_str(app_addr.inner()),
+                "var_id": var_id,
+            }),
+            ReceiptError::StorageLimitExceeded {
+                app_addr,
+                template_addr,
+                size,
+                limit,
+            } => json!({
+                "err_type": "storage-limit-exceeded",
+                "template_addr": json::addr_to_str(template_addr.inner()),
+                "app_addr": json::addr_to_str(app_addr.inner()),
+                "size": size,
+                "limit": limit,
+            }),
+            ReceiptError::UnknownState { app_addr, state } => json!({
+                "err_type": "unknown-state",
+                "app_addr": json::addr_to_str(app_addr.inner()),
+                "state": json::state_to_str(state),
+            }),
         }
     };
 
-    let logs = json::logs_to_json(logs);
+    let logs = json::logs_to_json(receipt.get_logs());
+    let gas_used = json::gas_to_json(&receipt.get_gas_used());
 
     let map: &mut serde_json::Map<String, Value> = json.as_object_mut().unwrap();
     let mut map: serde_json::Map<String, Value> = std::mem::take(map);
 
     map.insert("type".into(), Value::String(ty.into()));
+    map.insert("version".into(), receipt.version().into());
     map.insert("success".into(), Value::Bool(false));
+    map.insert("gas_used".into(), gas_used.into());
     map.insert("logs".into(), Value::Array(logs));
 
     map.into()
@@ -137,6 +228,7 @@ fn decode_deploy_template(receipt: &TemplateReceipt, ty: &'static str) -> Value
 
     json!({
         "type": ty,
+        "version": receipt.version,
         "success": true,
         "addr": json::addr_to_str(addr.as_ref().unwrap().inner()),
         "gas_used": json::gas_to_json(&gas_used),
@@ -159,12 +251,14 @@ fn decode_spawn_app(receipt: &SpawnAppReceipt, ty: &'static str) -> Value {
 
     json!({
         "type": ty,
+        "version": receipt.version,
         "success": true,
         "app": json::addr_to_str(app_addr.as_ref().unwrap().inner()),
         "state": json::state_to_str(init_state.as_ref().unwrap()),
         "returndata": json::bytes_to_str(returndata.as_ref().unwrap()),
         "gas_used": json::gas_to_json(&gas_used),
         "logs": json::logs_to_json(&receipt.logs),
+        "balance_changes": json::balance_changes_to_json(&receipt.balance_changes),
     })
 }
 
@@ -182,11 +276,15 @@ fn decode_exe_app(receipt: &ExecReceipt, ty: &'static str) -> Value {
 
     json!({
         "type": ty,
+        "version": receipt.version,
         "success": true,
         "new_state": json::state_to_str(new_state.as_ref().unwrap()),
         "returndata": json::bytes_to_str(returndata.as_ref().unwrap()),
         "gas_used": json::gas_to_json(&gas_used),
         "logs": json::logs_to_json(&receipt.logs),
+        "balance_changes": json::balance_changes_to_json(&receipt.balance_changes),
+        "storage_changes": json::storage_changes_to_json(receipt.get_storage_changes()),
+        "storage_changes_truncated": receipt.storage_changes_truncated,
     })
 }
 
@@ -196,13 +294,42 @@ mod tests {
 
     use super::json;
 
-    use svm_types::{gas::MaybeGas, receipt::Log, Address, AppAddr, State, WasmValue};
+    use std::path::PathBuf;
 
-    #[test]
-    fn decode_receipt_deploy_template_receipt_success() {
-        let template: Address = [0x10; 20].into();
+    use svm_types::receipt::{BalanceChange, Log, StorageChange};
+    use svm_types::{gas::MaybeGas, Address, AppAddr, State, TemplateAddr};
+
+    /// Asserts that `json` equals the contents of the golden file `golden/receipts/{name}.json`.
+    ///
+    /// Running the tests with the `SVM_UPDATE_GOLDEN` environment variable set
+    /// (re)writes the golden files instead.
+    fn assert_golden(name: &str, json: &Value) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("golden")
+            .join("receipts")
+            .join(format!("{}.json", name));
 
-        let logs = vec![
+        if std::env::var_os("SVM_UPDATE_GOLDEN").is_some() {
+            let pretty = serde_json::to_string_pretty(json).unwrap();
+
+            std::fs::write(&path, format!("{}\n", pretty)).unwrap();
+        }
+
+        let golden = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("Missing golden file `{}`", path.display()));
+        let expected: Value = serde_json::from_str(&golden).unwrap();
+
+        assert_eq!(json, &expected, "Golden file `{}` mismatch", path.display());
+    }
+
+    fn to_json(bytes: &[u8]) -> Value {
+        let data = json::bytes_to_str(bytes);
+
+        decode_receipt(&json!({ "data": data })).unwrap()
+    }
+
+    fn logs() -> Vec<Log> {
+        vec![
             Log {
                 msg: b"Log entry #1".to_vec(),
                 code: 100,
@@ -211,7 +338,12 @@ mod tests {
                 msg: b"Log entry #2".to_vec(),
                 code: 200,
             },
-        ];
+        ]
+    }
+
+    #[test]
+    fn decode_receipt_deploy_template_receipt_success() {
+        let template: Address = [0x10; 20].into();
 
         let receipt = TemplateReceipt {
             version: 0,
@@ -219,26 +351,24 @@ mod tests {
             error: None,
             addr: Some(template.into()),
             gas_used: MaybeGas::with(10),
-            logs,
+            logs: logs(),
         };
 
         let bytes = crate::receipt::encode_template_receipt(&receipt);
-        let data = json::bytes_to_str(&bytes);
-        let json = decode_receipt(&json!({ "data": data })).unwrap();
-
-        assert_eq!(
-            json,
-            json!({
-                "success": true,
-                "type": "deploy-template",
-                "addr": "1010101010101010101010101010101010101010",
-                "gas_used": 10,
-                "logs": [
-                    {"msg": "Log entry #1", "code": 100},
-                    {"msg": "Log entry #2", "code": 200}
-                ]
-            })
+
+        assert_golden("deploy-template-success", &to_json(&bytes));
+    }
+
+    #[test]
+    fn decode_receipt_deploy_template_receipt_error() {
+        let receipt = TemplateReceipt::from_err(
+            ReceiptError::ParseFailed("Not enough bytes".to_string()),
+            logs(),
         );
+
+        let bytes = crate::receipt::encode_template_receipt(&receipt);
+
+        assert_golden("deploy-template-error", &to_json(&bytes));
     }
 
     #[test]
@@ -246,47 +376,25 @@ mod tests {
         let app: Address = [0x10; 20].into();
         let state: State = [0xA0; 32].into();
 
-        let logs = vec![
-            Log {
-                msg: b"Log entry #1".to_vec(),
-                code: 100,
-            },
-            Log {
-                msg: b"Log entry #2".to_vec(),
-                code: 200,
-            },
-        ];
-
         let receipt = SpawnAppReceipt {
-            version: 0,
+            version: 1,
             success: true,
             error: None,
             app_addr: Some(app.into()),
             init_state: Some(state),
             returndata: Some(vec![0x10, 0x20, 0x30]),
             gas_used: MaybeGas::with(10),
-            logs,
+            logs: logs(),
+            balance_changes: vec![BalanceChange {
+                addr: [0x10; 20].into(),
+                old_balance: 0,
+                new_balance: 10,
+            }],
         };
 
         let bytes = crate::receipt::encode_app_receipt(&receipt);
-        let data = json::bytes_to_str(&bytes);
-        let json = decode_receipt(&json!({ "data": data })).unwrap();
-
-        assert_eq!(
-            json,
-            json!({
-                "success": true,
-                "type": "spawn-app",
-                "app": "1010101010101010101010101010101010101010",
-                "gas_used": 10,
-                "returndata": "102030",
-                "state": "A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0",
-                "logs": [
-                    {"msg": "Log entry #1", "code": 100},
-                    {"msg": "Log entry #2", "code": 200}
-                ]
-            })
-        );
+
+        assert_golden("spawn-app-success", &to_json(&bytes));
     }
 
     #[test]
@@ -296,74 +404,222 @@ mod tests {
             code: 0,
         }];
 
-        let receipt = SpawnAppReceipt {
-            version: 0,
-            success: false,
-            error: Some(ReceiptError::OOG),
-            app_addr: None,
-            init_state: None,
-            returndata: None,
-            gas_used: MaybeGas::with(1000),
-            logs,
-        };
-
+        let receipt = SpawnAppReceipt::from_err(ReceiptError::OOG, logs);
         let bytes = crate::receipt::encode_app_receipt(&receipt);
-        let data = json::bytes_to_str(&bytes);
-        let json = decode_receipt(&json!({ "data": data })).unwrap();
-
-        assert_eq!(
-            json,
-            json!({
-               "type": "spawn-app",
-               "success": false,
-               "err_type": "oog",
-               "logs": [{"code": 0, "msg": "Reached OOG"}],
-            })
-        );
+
+        assert_golden("spawn-app-error", &to_json(&bytes));
     }
 
     #[test]
     fn decode_receipt_exec_app_receipt_success() {
         let state: State = [0xA0; 32].into();
 
-        let logs = vec![
-            Log {
-                msg: b"Log entry #1".to_vec(),
-                code: 100,
-            },
-            Log {
-                msg: b"Log entry #2".to_vec(),
-                code: 200,
-            },
-        ];
-
         let receipt = ExecReceipt {
-            version: 0,
+            version: 2,
             success: true,
             error: None,
             new_state: Some(state),
             returndata: Some(vec![0x10, 0x20]),
             gas_used: MaybeGas::with(10),
-            logs,
+            logs: logs(),
+            balance_changes: Vec::new(),
+            storage_changes: Some(vec![StorageChange {
+                var_id: 2,
+                old_value: vec![0x00, 0x00],
+                new_value: vec![0x30, 0x40],
+            }]),
+            storage_changes_truncated: false,
         };
 
         let bytes = crate::receipt::encode_exec_receipt(&receipt);
-        let data = json::bytes_to_str(&bytes);
-        let json = decode_receipt(&json!({ "data": data })).unwrap();
-
-        assert_eq!(
-            json,
-            json!({
-                "success": true,
-                "type": "exec-app",
-                "gas_used": 10,
-                "returndata": "1020",
-                "new_state": "A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0",
-                "logs": [
-                    {"msg": "Log entry #1", "code": 100},
-                    {"msg": "Log entry #2", "code": 200}
-                ]
-            })
-        );
+
+        assert_golden("exec-app-success", &to_json(&bytes));
+    }
+
+    #[test]
+    fn decode_receipt_exec_app_receipt_errors() {
+        let template_addr: TemplateAddr = Address::of("my-template").into();
+        let app_addr: AppAddr = Address::of("my-app").into();
+        let func = "do_something".to_string();
+        let msg = "Something went wrong".to_string();
+
+        let errors = vec![
+            ("oog", ReceiptError::OOG),
+            (
+                "template-not-found",
+                ReceiptError::TemplateNotFound(template_addr.clone()),
+            ),
+            ("app-not-found", ReceiptError::AppNotFound(app_addr.clone())),
+            (
+                "compilation-failed",
+                ReceiptError::CompilationFailed {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    msg: msg.clone(),
+                },
+            ),
+            (
+                "instantiation-failed",
+                ReceiptError::InstantiationFailed {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    msg: msg.clone(),
+                },
+            ),
+            (
+                "function-not-found",
+                ReceiptError::FuncNotFound {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    func: func.clone(),
+                },
+            ),
+            (
+                "function-failed",
+                ReceiptError::FuncFailed {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    func: func.clone(),
+                    msg: msg.clone(),
+                },
+            ),
+            (
+                "function-not-allowed",
+                ReceiptError::FuncNotAllowed {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    func: func.clone(),
+                    msg: msg.clone(),
+                },
+            ),
+            (
+                "insufficient-funds",
+                ReceiptError::InsufficientFunds {
+                    addr: Address::of("sender"),
+                    balance: 10,
+                    amount: 20,
+                },
+            ),
+            ("parse-failed", ReceiptError::ParseFailed(msg.clone())),
+            (
+                "missing-memory-export",
+                ReceiptError::MissingMemoryExport {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                },
+            ),
+            (
+                "memory-out-of-bounds",
+                ReceiptError::MemoryOutOfBounds {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    offset: 100,
+                    len: 20,
+                },
+            ),
+            (
+                "invalid-var-id",
+                ReceiptError::InvalidVarId {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    var_id: 3,
+                },
+            ),
+            (
+                "storage-limit-exceeded",
+                ReceiptError::StorageLimitExceeded {
+                    template_addr: template_addr.clone(),
+                    app_addr: app_addr.clone(),
+                    size: 2048,
+                    limit: 1024,
+                },
+            ),
+            (
+                "unknown-state",
+                ReceiptError::UnknownState {
+                    app_addr: app_addr.clone(),
+                    state: State::of("some-state"),
+                },
+            ),
+        ];
+
+        for (name, err) in errors {
+            let receipt = ExecReceipt::from_err(err, logs());
+            let bytes = crate::receipt::encode_exec_receipt(&receipt);
+
+            assert_golden(&format!("exec-app-error-{}", name), &to_json(&bytes));
+        }
+    }
+
+    #[test]
+    fn decode_receipt_empty() {
+        let actual = decode_receipt(&json!({ "data": "" }));
+
+        let expected = Err(JsonError::InvalidField {
+            field: "data".to_string(),
+            reason: "receipt is empty".to_string(),
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn decode_receipt_unknown_type() {
+        let actual = decode_receipt(&json!({ "data": "0A0000" }));
+
+        let expected = Err(JsonError::InvalidField {
+            field: "data".to_string(),
+            reason: "unknown receipt type `10`".to_string(),
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn decode_receipt_truncated() {
+        let receipt = ExecReceipt::from_err(ReceiptError::OOG, logs());
+        let mut bytes = crate::receipt::encode_exec_receipt(&receipt);
+        bytes.truncate(bytes.len() - 1);
+
+        let actual = decode_receipt(&json!({ "data": json::bytes_to_str(&bytes) }));
+
+        let expected = Err(JsonError::InvalidField {
+            field: "data".to_string(),
+            reason: "Not enough bytes for field `LogCode`".to_string(),
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn decode_receipt_trailing_bytes() {
+        let receipt = ExecReceipt::from_err(ReceiptError::OOG, logs());
+        let mut bytes = crate::receipt::encode_exec_receipt(&receipt);
+        bytes.push(0);
+
+        let actual = decode_receipt(&json!({ "data": json::bytes_to_str(&bytes) }));
+
+        let expected = Err(JsonError::InvalidField {
+            field: "data".to_string(),
+            reason: "Expected EOF but there are more left bytes".to_string(),
+        });
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn decode_receipt_invalid_status() {
+        let receipt = ExecReceipt::from_err(ReceiptError::OOG, logs());
+        let mut bytes = crate::receipt::encode_exec_receipt(&receipt);
+        bytes[3] = 2;
+
+        let actual = decode_receipt(&json!({ "data": json::bytes_to_str(&bytes) }));
+
+        let expected = Err(JsonError::InvalidField {
+            field: "data".to_string(),
+            reason: "Unexpected Wasm value layout for field `ReceiptStatus`".to_string(),
+        });
+
+        assert_eq!(actual, expected);
     }
 }
@@ -14,6 +14,7 @@ use crate::app;
 ///   version: 0,              // number
 ///   template: 'A2FB...',     // string
 ///   name: 'My App',          // string
+///   value: 10,               // number (optional, defaults to `0`, requires version `1`)
 ///   ctor_name: 'initialize', // number
 ///   calldata: '',            // string
 /// }
@@ -22,6 +23,14 @@ pub fn encode_spawn_app(json: &Value) -> Result<Vec<u8>, JsonError> {
     let version = json::as_u32(json, "version")? as u16;
     let template = json::as_addr(json, "template")?.into();
     let name = json::as_string(json, "name")?;
+    let value = json::as_optional_u64(json, "value")?.unwrap_or(0);
+
+    if version == 0 && value != 0 {
+        return Err(JsonError::InvalidField {
+            field: "value".to_string(),
+            reason: "version `0` transactions have no `value`".to_string(),
+        });
+    }
     let ctor_name = json::as_string(json, "ctor_name")?;
 
     let calldata = json::as_string(json, "calldata")?;
@@ -30,6 +39,7 @@ pub fn encode_spawn_app(json: &Value) -> Result<Vec<u8>, JsonError> {
     let spawn = SpawnApp {
         version,
         app: App { name, template },
+        value,
         ctor_name,
         calldata,
     };
@@ -45,9 +55,13 @@ pub fn decode_spawn_app(json: &Value) -> Result<Value, JsonError> {
     let bytes = json::str_to_bytes(&data, "data")?;
 
     let mut cursor = Cursor::new(&bytes[..]);
-    let spawn = app::decode_spawn_app(&mut cursor).unwrap();
+    let spawn = app::decode_spawn_app(&mut cursor).map_err(|e| JsonError::InvalidField {
+        field: "data".to_string(),
+        reason: e.to_string(),
+    })?;
 
     let version = spawn.version;
+    let value = spawn.value;
     let ctor_name = spawn.ctor_name;
     let template = json::addr_to_str(&spawn.app.template.inner());
 
@@ -60,6 +74,7 @@ pub fn decode_spawn_app(json: &Value) -> Result<Value, JsonError> {
         "version": version,
         "template": template,
         "name": name,
+        "value": value,
         "ctor_name": ctor_name,
         "calldata": calldata,
     });
@@ -172,6 +187,7 @@ mod tests {
             "version": 1,
             "template": "10203040506070809000A0B0C0D0E0F0ABCDEFFF",
             "name": "My App",
+            "value": 10,
             "ctor_name": "initialize",
             "calldata": calldata["calldata"],
         });
@@ -186,6 +202,7 @@ mod tests {
                 "version": 1,
                 "template": "10203040506070809000A0B0C0D0E0F0ABCDEFFF",
                 "name": "My App",
+                "value": 10,
                 "ctor_name": "initialize",
                 "calldata": {
                     "abi": ["i32", "i64"],
@@ -194,4 +211,16 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn json_spawn_app_decode_truncated() {
+        let err = decode_spawn_app(&json!({ "data": "0001" })).unwrap_err();
+        assert_eq!(
+            err,
+            JsonError::InvalidField {
+                field: "data".to_string(),
+                reason: "Not enough bytes for field `Address`".to_string(),
+            }
+        );
+    }
 }
@@ -0,0 +1,90 @@
+use serde_json::Value;
+
+use super::wasm_buf_apply;
+use crate::{api, api::json::JsonError};
+
+///
+/// Encodes an `Envelope` json input into a binary (unsigned) `Envelope`.
+/// The json input is passed by giving WASM memory start address (`ptr` parameter).
+///
+/// Returns a pointer to a `transaction buffer`.
+///
+/// See also: `alloc` and `free`
+///
+pub fn encode_envelope(ptr: usize) -> Result<usize, JsonError> {
+    wasm_buf_apply(ptr, api::json::encode_envelope)
+}
+
+///
+/// Encodes an `Envelope` json input (along with a signature scheme and secret key)
+/// into a binary `SignedEnvelope`.
+/// The json input is passed by giving WASM memory start address (`ptr` parameter).
+///
+/// Returns a pointer to a `transaction buffer`.
+///
+/// See also: `alloc` and `free`
+///
+pub fn sign_envelope(ptr: usize) -> Result<usize, JsonError> {
+    wasm_buf_apply(ptr, api::json::sign_envelope)
+}
+
+pub fn decode_signed_envelope(ptr: usize) -> Result<usize, JsonError> {
+    wasm_buf_apply(ptr, |json: &Value| {
+        let json = api::json::decode_signed_envelope(json)?;
+
+        api::json::to_bytes(&json)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::api::json;
+    use crate::api::wasm::{free, to_wasm_buffer, wasm_buffer_data, BUF_OK_MARKER};
+
+    use serde_json::json;
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn wasm_sign_envelope_valid() {
+        let json = json!({
+            "tx_type": "spawn-app",
+            "sender": "1122334455667788990011223344556677889900",
+            "gas_limit": 1000,
+            "gas_price": 1,
+            "nonce": 0,
+            "payload": "C0DE",
+            "scheme": "ed25519",
+            "secret_key": json::bytes_to_str(&[0x1F; 32]),
+        });
+
+        let json = serde_json::to_string(&json).unwrap();
+        let json_buf = to_wasm_buffer(json.as_bytes());
+        let tx_buf = sign_envelope(json_buf).unwrap();
+
+        let data = wasm_buffer_data(tx_buf);
+        assert_eq!(data[0], BUF_OK_MARKER);
+
+        let data = json::bytes_to_str(&data[1..]);
+        let json = json!({ "data": data });
+        let json = serde_json::to_string(&json).unwrap();
+
+        free(json_buf);
+        let json_buf = to_wasm_buffer(json.as_bytes());
+
+        free(tx_buf);
+        let tx_buf = decode_signed_envelope(json_buf).unwrap();
+        let data = wasm_buffer_data(tx_buf);
+        assert_eq!(data[0], BUF_OK_MARKER);
+
+        let json: Value = serde_json::from_slice(&data[1..]).unwrap();
+
+        assert_eq!(json["tx_type"], "spawn-app");
+        assert_eq!(json["value"], 0);
+        assert_eq!(json["verified"], true);
+
+        free(json_buf);
+        free(tx_buf);
+    }
+}
@@ -49,7 +49,7 @@ mod test {
         .unwrap();
 
         let json = json!({
-          "version": 1,
+          "version": 0,
           "app": app_addr,
           "func_name": "do_something",
           "calldata": calldata["calldata"],
@@ -79,8 +79,9 @@ mod test {
         assert_eq!(
             json,
             json!({
-                "version": 1,
+                "version": 0,
                 "app": app_addr,
+                "value": 0,
                 "func_name": "do_something",
                 "calldata": {
                     "abi": ["i32", "i64"],
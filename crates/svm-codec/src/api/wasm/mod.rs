@@ -1,5 +1,6 @@
 mod calldata;
 mod deploy_template;
+mod envelope;
 mod error;
 mod exec_app;
 mod receipt;
@@ -7,6 +8,7 @@ mod spawn_app;
 
 pub use calldata::{decode_calldata, encode_calldata};
 pub use deploy_template::encode_deploy_template;
+pub use envelope::{decode_signed_envelope, encode_envelope, sign_envelope};
 pub use error::{error_as_string, into_error_buffer};
 pub use exec_app::{decode_exec_app, encode_exec_app};
 pub use receipt::decode_receipt;
@@ -43,6 +43,7 @@ mod test {
             returndata: Some(vec![0x10, 0x20]),
             gas_used: MaybeGas::with(10),
             logs,
+            balance_changes: Vec::new(),
         };
 
         let bytes = crate::receipt::encode_app_receipt(&receipt);
@@ -63,11 +64,13 @@ mod test {
             json!({
                 "success": true,
                 "type": "spawn-app",
+                "version": 0,
                 "app": "1010101010101010101010101010101010101010",
                 "gas_used": 10,
                 "returndata": "1020",
                 "state": "A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0A0",
-                "logs": []
+                "logs": [],
+                "balance_changes": []
             })
         );
 
@@ -45,7 +45,7 @@ mod test {
         .unwrap();
 
         let json = json!({
-          "version": 1,
+          "version": 0,
           "template": template_addr,
           "name": "My App",
           "ctor_name": "initialize",
@@ -76,9 +76,10 @@ mod test {
         assert_eq!(
             json,
             json!({
-                "version": 1,
+                "version": 0,
                 "template": template_addr,
                 "name": "My App",
+                "value": 0,
                 "ctor_name": "initialize",
                 "calldata": {
                     "abi": ["i32", "i64"],
@@ -4,6 +4,12 @@
 //  |             |                                          |
 //  |  `version`  |        `AppTemplate` (`Address`)         |
 //  |_____________|__________________________________________|
+//  |                                                        |
+//  |        value (8 bytes, starting version `1`)           |
+//  |________________________________________________________|
+//  |                                                        |
+//  |                   App Name (String)                    |
+//  |________________________________________________________|
 //  |               |                                        |
 //  | ctor (String) |           ctor `CallData`              |
 //  |_______________|________________________________________|
@@ -2,13 +2,17 @@ use std::io::Cursor;
 
 use svm_types::{App, SpawnApp, TemplateAddr, WasmValue};
 
+use crate::version::Format;
 use crate::{calldata, common};
 use crate::{Field, ParseError, ReadExt, WriteExt};
 
 /// Encodes a raw Spawn-App transaction.
+///
+/// Transactions of version `0` can't carry a `value`.
 pub fn encode_spawn_app(spawn: &SpawnApp, w: &mut Vec<u8>) {
     encode_version(spawn, w);
     encode_template(spawn, w);
+    encode_value(spawn, w);
     encode_name(spawn, w);
     encode_ctor(spawn, w);
     encode_ctor_calldata(spawn, w);
@@ -16,19 +20,23 @@ pub fn encode_spawn_app(spawn: &SpawnApp, w: &mut Vec<u8>) {
 
 /// Parsing a raw `spawn-app` transaction given as raw bytes.
 /// Returns the parsed transaction as a tuple consisting of an `App` struct and `ctor_name` buffer args.
-/// On failure, returns `ParseError`.
+/// On failure (including when there are trailing bytes), returns `ParseError`.
 pub fn decode_spawn_app(cursor: &mut Cursor<&[u8]>) -> Result<SpawnApp, ParseError> {
     let version = decode_version(cursor)?;
     let template = decode_template(cursor)?;
+    let value = decode_value(cursor, version)?;
     let name = decode_name(cursor)?;
     let ctor_name = decode_ctor(cursor)?;
     let calldata = decode_ctor_calldata(cursor)?;
 
+    common::decode_eof(cursor)?;
+
     let app = App { name, template };
 
     let spawn = SpawnApp {
         version,
         app,
+        value,
         ctor_name,
         calldata,
     };
@@ -56,6 +64,14 @@ fn encode_template(spawn: &SpawnApp, w: &mut Vec<u8>) {
     w.write_address(template.inner());
 }
 
+fn encode_value(spawn: &SpawnApp, w: &mut Vec<u8>) {
+    if spawn.version >= 1 {
+        w.write_u64_be(spawn.value);
+    } else {
+        assert_eq!(spawn.value, 0);
+    }
+}
+
 fn encode_ctor(spawn: &SpawnApp, w: &mut Vec<u8>) {
     let ctor = &spawn.ctor_name;
 
@@ -72,7 +88,7 @@ fn encode_ctor_calldata(spawn: &SpawnApp, w: &mut Vec<u8>) {
 
 #[inline]
 fn decode_version(cursor: &mut Cursor<&[u8]>) -> Result<u16, ParseError> {
-    common::decode_version(cursor)
+    common::decode_version(cursor, Format::SpawnApp)
 }
 
 fn decode_template(cursor: &mut Cursor<&[u8]>) -> Result<TemplateAddr, ParseError> {
@@ -82,6 +98,16 @@ fn decode_template(cursor: &mut Cursor<&[u8]>) -> Result<TemplateAddr, ParseErro
     }
 }
 
+fn decode_value(cursor: &mut Cursor<&[u8]>, version: u16) -> Result<u64, ParseError> {
+    if version == 0 {
+        return Ok(0);
+    }
+
+    cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Value))
+}
+
 fn decode_name(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
     match cursor.read_string() {
         Ok(Ok(name)) => Ok(name),
@@ -111,11 +137,12 @@ mod tests {
     #[test]
     fn encode_decode_spawn_app() {
         let spawn = SpawnApp {
-            version: 0,
+            version: 1,
             app: App {
                 name: "my-app".to_string(),
                 template: Address::of("my-template").into(),
             },
+            value: 100,
             ctor_name: "initialize".to_string(),
             calldata: vec![0x10, 0x20, 0x30],
         };
@@ -129,4 +156,38 @@ mod tests {
 
         assert_eq!(spawn, decoded);
     }
+
+    #[test]
+    fn decode_spawn_app_version_0() {
+        let template = Address::of("my-template");
+
+        // a version `0` transaction has no `value` field
+        let mut bytes = Vec::new();
+        common::encode_version(0, &mut bytes);
+        bytes.write_address(&template);
+        bytes.write_string("my-app");
+        bytes.write_string("initialize");
+        calldata::encode_calldata(&[0x10, 0x20, 0x30], &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_spawn_app(&mut cursor).unwrap();
+
+        let spawn = SpawnApp {
+            version: 0,
+            app: App {
+                name: "my-app".to_string(),
+                template: template.into(),
+            },
+            value: 0,
+            ctor_name: "initialize".to_string(),
+            calldata: vec![0x10, 0x20, 0x30],
+        };
+
+        assert_eq!(decoded, spawn);
+
+        let mut encoded = Vec::new();
+        encode_spawn_app(&spawn, &mut encoded);
+
+        assert_eq!(encoded, bytes);
+    }
 }
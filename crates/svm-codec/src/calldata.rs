@@ -1,5 +1,7 @@
 use std::io::Cursor;
 
+use svm_abi_decoder::{DecodeError, Decoder, ValueError};
+
 use crate::{Field, ParseError, ReadExt, WriteExt};
 
 pub fn encode_calldata(calldata: &[u8], w: &mut Vec<u8>) {
@@ -11,7 +13,26 @@ pub fn encode_calldata(calldata: &[u8], w: &mut Vec<u8>) {
     w.write_bytes(calldata);
 }
 
-pub fn decode_calldata<'a>(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseError> {
+/// Decodes a transaction `Calldata`.
+///
+/// The `Calldata` is expected to be a sequence of canonically ABI encoded values
+/// (see the `svm-abi-encoder` crate).
+pub fn decode_calldata(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseError> {
+    let calldata = decode_blob(cursor)?;
+
+    validate_calldata(&calldata)?;
+
+    Ok(calldata)
+}
+
+/// Decodes a `Calldata`-like blob without interpreting its contents.
+///
+/// Used for the `returndata` of receipts.
+pub fn decode_returndata(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseError> {
+    decode_blob(cursor)
+}
+
+fn decode_blob(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseError> {
     match cursor.read_byte() {
         Err(..) => Err(ParseError::NotEnoughBytes(Field::CallDataLength)),
         Ok(byte) => {
@@ -23,3 +44,82 @@ pub fn decode_calldata<'a>(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseE
         }
     }
 }
+
+fn validate_calldata(calldata: &[u8]) -> Result<(), ParseError> {
+    let mut cursor = svm_abi_decoder::Cursor::new(calldata);
+    let decoder = Decoder::new();
+
+    while !cursor.is_eof() {
+        decoder.decode_value(&mut cursor).map_err(|e| match e {
+            DecodeError::Type(..) => ParseError::UnexpectedLayout(Field::CallData),
+            DecodeError::Value(ValueError::NotEnoughBytes) => {
+                ParseError::NotEnoughBytes(Field::CallData)
+            }
+            DecodeError::Value(ValueError::NonMinimalLength) => {
+                ParseError::NonCanonical(Field::CallData)
+            }
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use svm_abi_encoder::Encoder;
+
+    fn decode(calldata: &[u8]) -> Result<Vec<u8>, ParseError> {
+        let mut bytes = Vec::new();
+        encode_calldata(calldata, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        decode_calldata(&mut cursor)
+    }
+
+    #[test]
+    fn decode_calldata_valid() {
+        let mut calldata = Vec::new();
+        10u32.encode(&mut calldata);
+        vec![true, false].encode(&mut calldata);
+
+        assert_eq!(decode(&calldata), Ok(calldata));
+    }
+
+    #[test]
+    fn decode_calldata_unknown_type() {
+        assert_eq!(
+            decode(&[0xFF]),
+            Err(ParseError::UnexpectedLayout(Field::CallData))
+        );
+    }
+
+    #[test]
+    fn decode_calldata_truncated_value() {
+        let mut calldata = Vec::new();
+        1000u32.encode(&mut calldata);
+        calldata.pop();
+
+        assert_eq!(
+            decode(&calldata),
+            Err(ParseError::NotEnoughBytes(Field::CallData))
+        );
+    }
+
+    #[test]
+    fn decode_calldata_non_minimal_number() {
+        // `0x01_00u32` is encoded using 2 bytes
+        let mut calldata = Vec::new();
+        0x01_00u32.encode(&mut calldata);
+
+        // `0x00_0A` fits into a single byte
+        calldata[1] = 0x00;
+        calldata[2] = 0x0A;
+
+        assert_eq!(
+            decode(&calldata),
+            Err(ParseError::NonCanonical(Field::CallData))
+        );
+    }
+}
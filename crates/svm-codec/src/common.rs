@@ -1,13 +1,34 @@
 use std::io::Cursor;
 
+use crate::version::Format;
 use crate::{Field, ParseError, ReadExt, WriteExt};
 
+/// Asserts that the `cursor` has no bytes left.
+///
+/// Returns `ParseError::ExpectedEOF` otherwise (trailing bytes aren't part of a canonical encoding).
+pub fn decode_eof(cursor: &Cursor<&[u8]>) -> Result<(), ParseError> {
+    if cursor.position() == cursor.get_ref().len() as u64 {
+        Ok(())
+    } else {
+        Err(ParseError::ExpectedEOF)
+    }
+}
+
 pub fn encode_version(version: u16, w: &mut Vec<u8>) {
     w.write_u16_be(version);
 }
 
-pub fn decode_version(cursor: &mut Cursor<&[u8]>) -> Result<u16, ParseError> {
-    cursor
+/// Decodes the version of `format`.
+///
+/// Returns `ParseError::UnsupportedVersion` when `format` doesn't support the decoded version.
+pub fn decode_version(cursor: &mut Cursor<&[u8]>, format: Format) -> Result<u16, ParseError> {
+    let version = cursor
         .read_u16_be()
-        .map_err(|_| ParseError::NotEnoughBytes(Field::Version))
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Version))?;
+
+    if format.is_supported(version) {
+        Ok(version)
+    } else {
+        Err(ParseError::UnsupportedVersion(version))
+    }
 }
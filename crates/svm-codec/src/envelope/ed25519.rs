@@ -0,0 +1,108 @@
+use std::convert::TryFrom;
+
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey, Signature};
+
+use super::{SignatureError, SignatureScheme};
+
+/// The `ed25519` signature scheme.
+///
+/// Secret keys are 32 bytes long (the `ed25519` seed), public keys are 32 bytes long
+/// and signatures are 64 bytes long.
+pub struct Ed25519;
+
+impl SignatureScheme for Ed25519 {
+    const ID: u8 = 0;
+
+    const NAME: &'static str = "ed25519";
+
+    type SecretKey = SecretKey;
+
+    fn secret_key(bytes: &[u8]) -> Option<SecretKey> {
+        SecretKey::from_bytes(bytes).ok()
+    }
+
+    fn public_key(secret_key: &SecretKey) -> Vec<u8> {
+        let public_key: PublicKey = secret_key.into();
+
+        public_key.to_bytes().to_vec()
+    }
+
+    fn sign(secret_key: &SecretKey, msg: &[u8]) -> Vec<u8> {
+        let public_key: PublicKey = secret_key.into();
+        let expanded: ExpandedSecretKey = secret_key.into();
+
+        let signature = expanded.sign(msg, &public_key);
+
+        signature.to_bytes().to_vec()
+    }
+
+    fn verify(public_key: &[u8], msg: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+        let public_key =
+            PublicKey::from_bytes(public_key).map_err(|_| SignatureError::InvalidPublicKey)?;
+
+        let signature =
+            Signature::try_from(signature).map_err(|_| SignatureError::InvalidSignature)?;
+
+        public_key
+            .verify_strict(msg, &signature)
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::envelope::{verify, verify_with, Envelope, SignedEnvelope, TxType};
+
+    use svm_types::Address;
+
+    fn signed() -> SignedEnvelope {
+        let envelope = Envelope {
+            tx_type: TxType::SpawnApp,
+            sender: Address::of("sender"),
+            value: 10,
+            gas_limit: 1000,
+            gas_price: 2,
+            nonce: 1,
+            payload: vec![0x10, 0x20],
+        };
+
+        let secret_key = Ed25519::secret_key(&[0x1F; 32]).unwrap();
+
+        SignedEnvelope::sign::<Ed25519>(envelope, &secret_key)
+    }
+
+    #[test]
+    fn ed25519_sign_and_verify() {
+        let signed = signed();
+
+        assert_eq!(signed.scheme, Ed25519::ID);
+        assert_eq!(signed.public_key.len(), 32);
+        assert_eq!(signed.signature.len(), 64);
+
+        assert_eq!(verify_with::<Ed25519>(&signed), Ok(()));
+        assert_eq!(verify(&signed), Ok(()));
+    }
+
+    #[test]
+    fn ed25519_verify_tampered_envelope() {
+        let mut signed = signed();
+        signed.envelope.nonce += 1;
+
+        assert_eq!(verify(&signed), Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn ed25519_verify_invalid_signature() {
+        let mut signed = signed();
+        signed.signature.truncate(10);
+
+        assert_eq!(verify(&signed), Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn ed25519_invalid_secret_key() {
+        assert!(Ed25519::secret_key(&[0x1F; 31]).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+//! Signed Transaction Envelope Raw Format Version 0.0
+//!
+//! The `Transaction Envelope` wraps an SVM transaction (`deploy-template`, `spawn-app` or `exec-app`)
+//! with the transaction data living outside of SVM (see `docs/go-spacemesh-integration.md`).
+//!
+//! A Signed Envelope is the encoded `Envelope` followed by a signature over it.
+//!
+//!  +-----------------------------------------------------------+
+//!  |  tx type  |         sender           |       value        |
+//!  |  (1 byte) |       (20 bytes)         |     (8 bytes)      |
+//!  +___________|__________________________|____________________+
+//!  |     gas_limit      |     gas_price     |      nonce       |
+//!  |     (8 bytes)      |     (8 bytes)     |    (8 bytes)     |
+//!  +____________________|___________________|__________________+
+//!  |  payload length  |             payload (blob)             |
+//!  |    (4 bytes)     | (the raw SVM transaction of `tx type`) |
+//!  +__________________|________________________________________+
+//!  |  scheme   |  public key  |  public key  |    signature    |
+//!  |  (1 byte) |    length    |    (blob)    |     length      |
+//!  |           |  (2 bytes)   |              |    (2 bytes)    |
+//!  +___________|______________|______________|_________________+
+//!  |                    signature (blob)                       |
+//!  +___________________________________________________________+
+//!
+//! The signature signs the encoded `Envelope` bytes (i.e everything preceding the `scheme` byte).
+//!
+//! The signature scheme is pluggable (see `SignatureScheme`). `ed25519` is the first backend (see `Ed25519`).
+//!
+
+mod signature;
+
+#[cfg(feature = "ed25519")]
+mod ed25519;
+
+pub use signature::{verify, verify_with, SignatureError, SignatureScheme};
+
+#[cfg(feature = "ed25519")]
+pub use ed25519::Ed25519;
+
+use std::io::Cursor;
+
+use svm_types::Address;
+
+use crate::common;
+use crate::{Field, ParseError, ReadExt, WriteExt};
+
+/// The type of the SVM transaction wrapped by an `Envelope`
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TxType {
+    DeployTemplate = 2,
+    SpawnApp = 3,
+    ExecApp = 4,
+}
+
+impl TxType {
+    /// Returns the `TxType` encoded as `byte` (`None` when there is no such type).
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            2 => Some(TxType::DeployTemplate),
+            3 => Some(TxType::SpawnApp),
+            4 => Some(TxType::ExecApp),
+            _ => None,
+        }
+    }
+}
+
+/// The `Transaction Envelope`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope {
+    /// The type of the wrapped transaction
+    pub tx_type: TxType,
+
+    /// The account sending (and paying for) the transaction
+    pub sender: Address,
+
+    /// The amount of coins transferred by the transaction
+    pub value: u64,
+
+    /// The maximum amount of gas the transaction may consume
+    pub gas_limit: u64,
+
+    /// The price the `sender` pays for each unit of gas
+    pub gas_price: u64,
+
+    /// The `sender`'s transaction counter
+    pub nonce: u64,
+
+    /// The raw SVM transaction (encoded according to `tx_type`)
+    pub payload: Vec<u8>,
+}
+
+/// An `Envelope` signed by its `sender`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedEnvelope {
+    /// The signed `Envelope`
+    pub envelope: Envelope,
+
+    /// The signature scheme identifier (see `SignatureScheme::ID`)
+    pub scheme: u8,
+
+    /// The public key of the signer
+    pub public_key: Vec<u8>,
+
+    /// The signature over the encoded `envelope`
+    pub signature: Vec<u8>,
+}
+
+impl SignedEnvelope {
+    /// Signs `envelope` with `secret_key` using signature scheme `S`.
+    pub fn sign<S: SignatureScheme>(envelope: Envelope, secret_key: &S::SecretKey) -> Self {
+        let mut msg = Vec::new();
+        encode_envelope(&envelope, &mut msg);
+
+        Self {
+            envelope,
+            scheme: S::ID,
+            public_key: S::public_key(secret_key),
+            signature: S::sign(secret_key, &msg),
+        }
+    }
+}
+
+/// Encodes an (unsigned) `Envelope`.
+///
+/// The output is the message signed by a `SignedEnvelope`.
+pub fn encode_envelope(envelope: &Envelope, w: &mut Vec<u8>) {
+    w.write_byte(envelope.tx_type as u8);
+    w.write_address(&envelope.sender);
+    w.write_u64_be(envelope.value);
+    w.write_u64_be(envelope.gas_limit);
+    w.write_u64_be(envelope.gas_price);
+    w.write_u64_be(envelope.nonce);
+
+    w.write_u32_be(envelope.payload.len() as u32);
+    w.write_bytes(&envelope.payload);
+}
+
+/// Encodes a `SignedEnvelope`.
+pub fn encode_signed_envelope(signed: &SignedEnvelope, w: &mut Vec<u8>) {
+    encode_envelope(&signed.envelope, w);
+
+    w.write_byte(signed.scheme);
+
+    w.write_u16_be(signed.public_key.len() as u16);
+    w.write_bytes(&signed.public_key);
+
+    w.write_u16_be(signed.signature.len() as u16);
+    w.write_bytes(&signed.signature);
+}
+
+/// Decodes an (unsigned) `Envelope`.
+pub fn decode_envelope(cursor: &mut Cursor<&[u8]>) -> Result<Envelope, ParseError> {
+    let tx_type = decode_tx_type(cursor)?;
+
+    let sender = cursor
+        .read_address()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Sender))?;
+
+    let value = decode_u64(cursor, Field::Value)?;
+    let gas_limit = decode_u64(cursor, Field::GasLimit)?;
+    let gas_price = decode_u64(cursor, Field::GasPrice)?;
+    let nonce = decode_u64(cursor, Field::Nonce)?;
+
+    let payload_len = cursor
+        .read_u32_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::PayloadLength))?;
+
+    let payload = cursor
+        .read_bytes(payload_len as usize)
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Payload))?;
+
+    let envelope = Envelope {
+        tx_type,
+        sender,
+        value,
+        gas_limit,
+        gas_price,
+        nonce,
+        payload,
+    };
+
+    Ok(envelope)
+}
+
+/// Decodes a `SignedEnvelope`.
+///
+/// Decoding doesn't verify the signature (see `verify`).
+pub fn decode_signed_envelope(cursor: &mut Cursor<&[u8]>) -> Result<SignedEnvelope, ParseError> {
+    let envelope = decode_envelope(cursor)?;
+
+    let scheme = cursor
+        .read_byte()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::SignatureScheme))?;
+
+    let public_key = decode_blob(cursor, Field::PublicKey)?;
+    let signature = decode_blob(cursor, Field::Signature)?;
+
+    common::decode_eof(cursor)?;
+
+    let signed = SignedEnvelope {
+        envelope,
+        scheme,
+        public_key,
+        signature,
+    };
+
+    Ok(signed)
+}
+
+fn decode_tx_type(cursor: &mut Cursor<&[u8]>) -> Result<TxType, ParseError> {
+    let byte = cursor
+        .read_byte()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::TxType))?;
+
+    TxType::from_byte(byte).ok_or(ParseError::NotSupported(Field::TxType))
+}
+
+fn decode_u64(cursor: &mut Cursor<&[u8]>, field: Field) -> Result<u64, ParseError> {
+    cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(field))
+}
+
+fn decode_blob(cursor: &mut Cursor<&[u8]>, field: Field) -> Result<Vec<u8>, ParseError> {
+    let len = cursor
+        .read_u16_be()
+        .map_err(|_| ParseError::NotEnoughBytes(field))?;
+
+    cursor
+        .read_bytes(len as usize)
+        .map_err(|_| ParseError::NotEnoughBytes(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> Envelope {
+        Envelope {
+            tx_type: TxType::ExecApp,
+            sender: Address::of("sender"),
+            value: 10,
+            gas_limit: 1000,
+            gas_price: 2,
+            nonce: 7,
+            payload: vec![0x10, 0x20, 0x30],
+        }
+    }
+
+    #[test]
+    fn encode_decode_envelope() {
+        let envelope = envelope();
+
+        let mut bytes = Vec::new();
+        encode_envelope(&envelope, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_envelope(&mut cursor).unwrap();
+
+        assert_eq!(decoded, envelope);
+    }
+
+    #[test]
+    fn encode_decode_signed_envelope() {
+        let signed = SignedEnvelope {
+            envelope: envelope(),
+            scheme: 0xFF,
+            public_key: vec![0xAA; 4],
+            signature: vec![0xBB; 8],
+        };
+
+        let mut bytes = Vec::new();
+        encode_signed_envelope(&signed, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_signed_envelope(&mut cursor).unwrap();
+
+        assert_eq!(decoded, signed);
+    }
+
+    #[test]
+    fn decode_envelope_unsupported_tx_type() {
+        let mut bytes = Vec::new();
+        encode_envelope(&envelope(), &mut bytes);
+
+        bytes[0] = 0;
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_envelope(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::NotSupported(Field::TxType));
+    }
+
+    #[test]
+    fn decode_signed_envelope_missing_signature() {
+        let mut bytes = Vec::new();
+        encode_envelope(&envelope(), &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_signed_envelope(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::NotEnoughBytes(Field::SignatureScheme));
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+use super::{encode_envelope, SignedEnvelope};
+
+/// A signature scheme used for signing `Envelope`s.
+///
+/// Each scheme has its own `ID`, which is encoded alongside the signature (see `SignedEnvelope`).
+pub trait SignatureScheme {
+    /// The scheme identifier
+    const ID: u8;
+
+    /// The scheme name (used by the JSON API)
+    const NAME: &'static str;
+
+    /// The secret key type
+    type SecretKey;
+
+    /// Parses a secret key out of raw `bytes` (`None` when `bytes` isn't a valid secret key).
+    fn secret_key(bytes: &[u8]) -> Option<Self::SecretKey>;
+
+    /// Derives the raw public key of `secret_key`.
+    fn public_key(secret_key: &Self::SecretKey) -> Vec<u8>;
+
+    /// Signs `msg` and returns the raw signature.
+    fn sign(secret_key: &Self::SecretKey, msg: &[u8]) -> Vec<u8>;
+
+    /// Verifies the raw `signature` of `msg` against the raw `public_key`.
+    fn verify(public_key: &[u8], msg: &[u8], signature: &[u8]) -> Result<(), SignatureError>;
+}
+
+/// The reasons a `SignedEnvelope` fails verification.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignatureError {
+    UnsupportedScheme(u8),
+    InvalidPublicKey,
+    InvalidSignature,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignatureError::UnsupportedScheme(id) => {
+                write!(f, "Unsupported signature scheme `{}`", id)
+            }
+            SignatureError::InvalidPublicKey => write!(f, "Invalid public key"),
+            SignatureError::InvalidSignature => write!(f, "Invalid signature"),
+        }
+    }
+}
+
+/// Verifies `signed` using signature scheme `S`.
+///
+/// Fails with `SignatureError::UnsupportedScheme` when `signed` isn't signed using `S`.
+pub fn verify_with<S: SignatureScheme>(signed: &SignedEnvelope) -> Result<(), SignatureError> {
+    if signed.scheme != S::ID {
+        return Err(SignatureError::UnsupportedScheme(signed.scheme));
+    }
+
+    let mut msg = Vec::new();
+    encode_envelope(&signed.envelope, &mut msg);
+
+    S::verify(&signed.public_key, &msg, &signed.signature)
+}
+
+/// Verifies `signed` using the signature scheme it's signed with (out of the built-in schemes).
+pub fn verify(signed: &SignedEnvelope) -> Result<(), SignatureError> {
+    match signed.scheme {
+        #[cfg(feature = "ed25519")]
+        super::Ed25519::ID => verify_with::<super::Ed25519>(signed),
+        scheme => Err(SignatureError::UnsupportedScheme(scheme)),
+    }
+}
@@ -12,6 +12,9 @@ pub enum ParseError {
     NotSupported(Field),
     InvalidUTF8String(Field),
     UnexpectedLayout(Field),
+    UnsupportedVersion(u16),
+    NonCanonical(Field),
+    CodeNotFound,
 }
 
 impl fmt::Display for ParseError {
@@ -28,6 +31,9 @@ impl fmt::Display for ParseError {
             ParseError::UnexpectedLayout(f) => {
                 write!(fmt, "Unexpected Wasm value layout for field `{}`", f)
             }
+            ParseError::UnsupportedVersion(v) => write!(fmt, "Unsupported version `{}`", v),
+            ParseError::NonCanonical(f) => write!(fmt, "Field `{}` isn't canonically encoded", f),
+            ParseError::CodeNotFound => write!(fmt, "The code referenced by hash was not found"),
         }
     }
 }
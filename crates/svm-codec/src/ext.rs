@@ -54,6 +54,13 @@ impl ReadExt for Cursor<&[u8]> {
     }
 
     fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>> {
+        // avoid allocating a buffer for a length exceeding the bytes left
+        let left = (self.get_ref().len() as u64).saturating_sub(self.position());
+
+        if length as u64 > left {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+
         let mut buf = vec![0; length];
 
         let _ = self.read_exact(&mut buf)?;
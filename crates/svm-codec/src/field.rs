@@ -5,6 +5,9 @@ use std::fmt;
 pub enum Field {
     String,
     GasUsed,
+    Value,
+    BalanceChangesCount,
+    Balance,
     Author,
     Creator,
     Version,
@@ -12,6 +15,8 @@ pub enum Field {
     CodeSize,
     State,
     Code,
+    CodeKind,
+    CodeHash,
     Address,
     TemplateAddr,
     AppAddr,
@@ -25,10 +30,31 @@ pub enum Field {
     CtorsCount,
     ReceiptType,
     ReceiptStatus,
+    ReceiptErrorType,
+    ReceiptErrorData,
     LogsCount,
     LogMessage,
     LogMessageLength,
     LogCode,
+    HostCtxFieldsCount,
+    HostCtxField,
+    HostCtxFieldLength,
+    HostCtxFieldValue,
+    StorageChangesStatus,
+    StorageChangesCount,
+    VarId,
+    StorageChangeValueLength,
+    StorageChangeValue,
+    TxType,
+    Sender,
+    GasLimit,
+    GasPrice,
+    Nonce,
+    PayloadLength,
+    Payload,
+    SignatureScheme,
+    PublicKey,
+    Signature,
 }
 
 impl fmt::Display for Field {
@@ -0,0 +1,159 @@
+//! `HostCtx` Raw Format Version 0
+//!
+//!  +-------------+--------------+
+//!  |  version    |   #fields    |
+//!  |  (2 bytes)  |  (2 bytes)   |
+//!  +_____________|______________+___________________________+
+//!  |                 |                 |                    |
+//!  |  field #1 index |  field #1 length |  field #1 value   |
+//!  |    (4 bytes)    |    (2 bytes)     |      (blob)       |
+//!  +_________________|__________________|___________________+
+//!  |                            .                           |
+//!  |                            .                           |
+//!  +_________________|__________________|___________________+
+//!  |                 |                  |                   |
+//!  |  field #N index |  field #N length |  field #N value   |
+//!  |    (4 bytes)    |    (2 bytes)     |      (blob)       |
+//!  +_________________|__________________|___________________+
+//!
+//!  The fields are encoded ordered by their index.
+//!
+
+use std::io::Cursor;
+
+use svm_types::HostCtx;
+
+use crate::common;
+use crate::version::Format;
+use crate::{Field, ParseError, ReadExt, WriteExt};
+
+/// Encodes a `HostCtx`.
+pub fn encode_host_ctx(host_ctx: &HostCtx, w: &mut Vec<u8>) {
+    common::encode_version(0, w);
+
+    assert!(host_ctx.len() <= u16::MAX as usize);
+    w.write_u16_be(host_ctx.len() as u16);
+
+    for (field, value) in host_ctx.iter() {
+        assert!(value.len() <= u16::MAX as usize);
+
+        w.write_u32_be(field);
+        w.write_u16_be(value.len() as u16);
+        w.write_bytes(value);
+    }
+}
+
+/// Parsing a raw `HostCtx` given as raw bytes.
+/// Returns the parsed `HostCtx` struct.
+/// On failure (including when there are trailing bytes), returns `ParseError`.
+pub fn decode_host_ctx(cursor: &mut Cursor<&[u8]>) -> Result<HostCtx, ParseError> {
+    let version = common::decode_version(cursor, Format::HostCtx)?;
+
+    if version != 0 {
+        return Err(ParseError::NotSupported(Field::Version));
+    }
+
+    let nfields = cursor
+        .read_u16_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::HostCtxFieldsCount))?;
+
+    let mut host_ctx = HostCtx::new();
+    let mut prev_field = None;
+
+    for _ in 0..nfields {
+        let field = cursor
+            .read_u32_be()
+            .map_err(|_| ParseError::NotEnoughBytes(Field::HostCtxField))?;
+
+        // fields must be strictly ordered by their index (without duplicates)
+        if prev_field.map_or(false, |prev| field <= prev) {
+            return Err(ParseError::NonCanonical(Field::HostCtxField));
+        }
+
+        prev_field = Some(field);
+
+        let len = cursor
+            .read_u16_be()
+            .map_err(|_| ParseError::NotEnoughBytes(Field::HostCtxFieldLength))?;
+
+        let value = cursor
+            .read_bytes(len as usize)
+            .map_err(|_| ParseError::NotEnoughBytes(Field::HostCtxFieldValue))?;
+
+        host_ctx.set(field, value);
+    }
+
+    common::decode_eof(cursor)?;
+
+    Ok(host_ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use svm_types::Address;
+
+    #[test]
+    fn encode_decode_host_ctx_empty() {
+        let host_ctx = HostCtx::new();
+
+        let mut bytes = Vec::new();
+        encode_host_ctx(&host_ctx, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_host_ctx(&mut cursor).unwrap();
+
+        assert_eq!(host_ctx, decoded);
+    }
+
+    #[test]
+    fn encode_decode_host_ctx() {
+        let mut host_ctx = HostCtx::new();
+
+        host_ctx.set(HostCtx::SENDER, Address::of("sender").as_slice().to_vec());
+        host_ctx.set(HostCtx::LAYER_ID, 10u64.to_be_bytes().to_vec());
+        host_ctx.set(100, Vec::new());
+
+        let mut bytes = Vec::new();
+        encode_host_ctx(&host_ctx, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_host_ctx(&mut cursor).unwrap();
+
+        assert_eq!(host_ctx, decoded);
+    }
+
+    #[test]
+    fn decode_host_ctx_not_enough_bytes() {
+        let mut host_ctx = HostCtx::new();
+        host_ctx.set(HostCtx::NONCE, vec![0x10, 0x20]);
+
+        let mut bytes = Vec::new();
+        encode_host_ctx(&host_ctx, &mut bytes);
+        bytes.pop();
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_host_ctx(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::NotEnoughBytes(Field::HostCtxFieldValue));
+    }
+
+    #[test]
+    fn decode_host_ctx_unordered_fields() {
+        let mut bytes = Vec::new();
+
+        common::encode_version(0, &mut bytes);
+        bytes.write_u16_be(2);
+
+        for field in &[HostCtx::NONCE, HostCtx::SENDER] {
+            bytes.write_u32_be(*field);
+            bytes.write_u16_be(0);
+        }
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_host_ctx(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::NonCanonical(Field::HostCtxField));
+    }
+}
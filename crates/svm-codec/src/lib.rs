@@ -19,8 +19,11 @@ mod field;
 mod serialize;
 
 pub mod app;
+pub mod envelope;
+pub mod host_ctx;
 pub mod template;
 pub mod transaction;
+pub mod version;
 
 pub use ext::{ReadExt, WriteExt};
 pub use field::Field;
@@ -150,6 +153,38 @@ pub extern "C" fn wasm_decode_exec_app(ptr: i32) -> i32 {
     wasm_func_call!(decode_exec_app, ptr)
 }
 
+/// ## WASM Envelope
+///
+/// Reads the WASM buffer given at parameter `ptr` containing a JSON value.
+/// Encodes an (unsigned) `Envelope` using that JSON value (the output is the message to be signed).
+///
+/// Returns a pointer to a new WASM buffer holding the encoded envelope.
+/// If the encoding failed, the returned WASM buffer will contain a String containing the error message.
+#[no_mangle]
+#[cfg(target_arch = "wasm32")]
+pub extern "C" fn wasm_encode_envelope(ptr: i32) -> i32 {
+    wasm_func_call!(encode_envelope, ptr)
+}
+
+/// ## WASM Signed Envelope
+///
+/// Reads the WASM buffer given at parameter `ptr` containing a JSON value.
+/// Encodes a `SignedEnvelope` using that JSON value (holding the envelope fields and the signing secret key).
+///
+/// Returns a pointer to a new WASM buffer holding the encoded signed envelope.
+/// If the encoding failed, the returned WASM buffer will contain a String containing the error message.
+#[no_mangle]
+#[cfg(target_arch = "wasm32")]
+pub extern "C" fn wasm_sign_envelope(ptr: i32) -> i32 {
+    wasm_func_call!(sign_envelope, ptr)
+}
+
+#[no_mangle]
+#[cfg(target_arch = "wasm32")]
+pub extern "C" fn wasm_decode_signed_envelope(ptr: i32) -> i32 {
+    wasm_func_call!(decode_signed_envelope, ptr)
+}
+
 /// ## WASM Buffer Allocation
 ///
 /// Allocates a new WASM Buffer holding data of `length` bytes.
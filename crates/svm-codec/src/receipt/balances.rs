@@ -0,0 +1,115 @@
+use std::io::Cursor;
+
+use svm_types::receipt::BalanceChange;
+
+use crate::{Field, ParseError, ReadExt, WriteExt};
+
+///
+/// +----------------------------+
+/// | #balance changes (2 bytes) |
+/// +----------------------------+-----------------------------------------+
+/// |  Address (20 bytes) | old balance (8 bytes) | new balance (8 bytes)  |  ---> change #0
+/// +----------------------------------------------------------------------+
+///                                  .
+///                                  .
+///                                  .
+/// +----------------------------------------------------------------------+
+/// |  Address (20 bytes) | old balance (8 bytes) | new balance (8 bytes)  |  ---> change #N
+/// +----------------------------------------------------------------------+
+///
+pub fn encode_balance_changes(changes: &[BalanceChange], w: &mut Vec<u8>) {
+    let nchanges = changes.len();
+    assert!(nchanges <= u16::MAX as usize);
+
+    w.write_u16_be(nchanges as u16);
+
+    for change in changes.iter() {
+        w.write_address(&change.addr);
+        w.write_u64_be(change.old_balance);
+        w.write_u64_be(change.new_balance);
+    }
+}
+
+pub fn decode_balance_changes(
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<Vec<BalanceChange>, ParseError> {
+    match cursor.read_u16_be() {
+        Ok(nchanges) => {
+            let mut changes = Vec::with_capacity(nchanges as usize);
+
+            for _ in 0..nchanges {
+                let change = decode_balance_change(cursor)?;
+
+                changes.push(change);
+            }
+
+            Ok(changes)
+        }
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::BalanceChangesCount)),
+    }
+}
+
+fn decode_balance_change(cursor: &mut Cursor<&[u8]>) -> Result<BalanceChange, ParseError> {
+    let addr = cursor
+        .read_address()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Address))?;
+
+    let old_balance = cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Balance))?;
+
+    let new_balance = cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Balance))?;
+
+    let change = BalanceChange {
+        addr,
+        old_balance,
+        new_balance,
+    };
+
+    Ok(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use svm_types::Address;
+
+    #[test]
+    fn encode_balance_changes_empty() {
+        let mut buf = Vec::new();
+
+        encode_balance_changes(&[], &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let changes = decode_balance_changes(&mut cursor).unwrap();
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn encode_balance_changes_multiple_entries() {
+        let mut buf = Vec::new();
+
+        let change1 = BalanceChange {
+            addr: Address::of("sender"),
+            old_balance: 100,
+            new_balance: 90,
+        };
+
+        let change2 = BalanceChange {
+            addr: Address::of("my-app"),
+            old_balance: 0,
+            new_balance: 10,
+        };
+
+        encode_balance_changes(&[change1.clone(), change2.clone()], &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let changes = decode_balance_changes(&mut cursor).unwrap();
+
+        assert_eq!(changes, vec![change1, change2]);
+    }
+}
@@ -19,7 +19,8 @@ use svm_types::receipt::{Receipt, TemplateReceipt};
 use super::{decode_error, decode_receipt, encode_error, gas, logs, types};
 
 use crate::common;
-use crate::{ReadExt, WriteExt};
+use crate::version::Format;
+use crate::{Field, ParseError, ReadExt, WriteExt};
 
 pub fn encode_template_receipt(receipt: &TemplateReceipt) -> Vec<u8> {
     let mut w = Vec::new();
@@ -33,47 +34,46 @@ pub fn encode_template_receipt(receipt: &TemplateReceipt) -> Vec<u8> {
         gas::encode_gas_used(&receipt.gas_used, &mut w);
         logs::encode_logs(&receipt.logs, &mut w);
     } else {
-        let logs = Vec::new();
+        let logs = receipt.get_logs();
 
-        encode_error(receipt.get_error(), &logs, &mut w);
+        encode_error(receipt.get_error(), logs, &mut w);
     };
 
     w
 }
 
-pub fn decode_template_receipt(bytes: &[u8]) -> TemplateReceipt {
+pub fn decode_template_receipt(bytes: &[u8]) -> Result<TemplateReceipt, ParseError> {
     let mut cursor = Cursor::new(bytes);
 
-    let ty = cursor.read_byte().unwrap();
-    debug_assert_eq!(ty, types::DEPLOY_TEMPLATE);
+    super::decode_type(&mut cursor, types::DEPLOY_TEMPLATE)?;
 
-    let version = common::decode_version(&mut cursor).unwrap();
-    debug_assert_eq!(version, 0);
+    let version = common::decode_version(&mut cursor, Format::Receipt)?;
+    let is_success = super::decode_is_success(&mut cursor)?;
 
-    let is_success = cursor.read_bool().unwrap();
+    let receipt = if is_success {
+        let addr = cursor
+            .read_address()
+            .map_err(|_| ParseError::NotEnoughBytes(Field::TemplateAddr))?;
+        let gas_used = gas::decode_gas_used(&mut cursor)?;
+        let logs = logs::decode_logs(&mut cursor)?;
 
-    match is_success {
-        false => {
-            let (err, logs) = decode_error(&mut cursor);
-
-            TemplateReceipt::from_err(err, logs)
-        }
-        true => {
-            let addr = cursor.read_address().expect("expected an address");
-            let gas_used = gas::decode_gas_used(&mut cursor).unwrap();
-            let logs = logs::decode_logs(&mut cursor).unwrap();
-
-            TemplateReceipt {
-                version,
-                success: true,
-                error: None,
-                addr: Some(addr.into()),
-                gas_used,
-                logs,
-            }
+        TemplateReceipt {
+            version,
+            success: true,
+            error: None,
+            addr: Some(addr.into()),
+            gas_used,
+            logs,
         }
-        _ => unreachable!(),
-    }
+    } else {
+        let (err, logs) = decode_error(&mut cursor)?;
+
+        TemplateReceipt::from_err(err, logs)
+    };
+
+    common::decode_eof(&cursor)?;
+
+    Ok(receipt)
 }
 
 fn encode_version(receipt: &TemplateReceipt, w: &mut Vec<u8>) {
@@ -112,7 +112,7 @@ mod tests {
         };
 
         let bytes = encode_template_receipt(&receipt);
-        let decoded = decode_receipt(&bytes);
+        let decoded = decode_receipt(&bytes).unwrap();
 
         assert_eq!(decoded.into_deploy_template(), receipt);
     }
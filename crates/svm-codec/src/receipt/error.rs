@@ -60,13 +60,55 @@
 //!   |   (20 bytes)      |   (20 bytes)  |   (String) | (UTF-8 String) |
 //!   +-------------------+---------------------------------------------+
 //!
+//!  * Insufficient Funds
+//!   +-------------------+---------------+---------------+
+//!   |  Account Address  |   Balance     |    Amount     |
+//!   |   (20 bytes)      |  (8 bytes)    |  (8 bytes)    |
+//!   +-------------------+---------------+---------------+
+//!
+//!  * Parse Failed
+//!   +-----------------+
+//!   |     Error       |
+//!   |  (UTF-8 String) |
+//!   +-----------------+
+//!
+//!  * Missing Memory Export
+//!   +-------------------+---------------+
+//!   |  Template Address | App Address   |
+//!   |   (20 bytes)      |  (20 bytes)   |
+//!   +-------------------+---------------+
+//!
+//!  * Memory Out-of-Bounds
+//!   +-------------------+---------------+-------------+-------------+
+//!   |  Template Address | App Address   |   Offset    |   Length    |
+//!   |   (20 bytes)      |  (20 bytes)   |  (4 bytes)  |  (4 bytes)  |
+//!   +-------------------+---------------+-------------+-------------+
+//!
+//!  * Invalid Var Id
+//!   +-------------------+---------------+-------------+
+//!   |  Template Address | App Address   |   Var Id    |
+//!   |   (20 bytes)      |  (20 bytes)   |  (4 bytes)  |
+//!   +-------------------+---------------+-------------+
+//!
+//!  * Storage Limit Exceeded
+//!   +-------------------+---------------+-------------+-------------+
+//!   |  Template Address | App Address   |    Size     |    Limit    |
+//!   |   (20 bytes)      |  (20 bytes)   |  (8 bytes)  |  (8 bytes)  |
+//!   +-------------------+---------------+-------------+-------------+
+//!
+//!  * Unknown State
+//!   +-------------------+---------------+
+//!   |    App Address    |     State     |
+//!   |    (20 bytes)     |  (32 bytes)   |
+//!   +-------------------+---------------+
+//!
 
 use std::io::{Cursor, Read};
 
-use crate::{Field, ReadExt, WriteExt};
+use crate::{Field, ParseError, ReadExt, WriteExt};
 
 use svm_types::receipt::{Log, ReceiptError, ReceiptError as Err};
-use svm_types::{Address, AppAddr, TemplateAddr};
+use svm_types::{Address, AppAddr, State, TemplateAddr};
 
 use super::logs;
 
@@ -124,6 +166,58 @@ pub(crate) fn encode_error(err: &ReceiptError, logs: &[Log], w: &mut Vec<u8>) {
             w.write_string(func);
             w.write_string(msg);
         }
+        Err::InsufficientFunds {
+            addr,
+            balance,
+            amount,
+        } => {
+            w.write_address(addr);
+            w.write_u64_be(*balance);
+            w.write_u64_be(*amount);
+        }
+        Err::ParseFailed(msg) => w.write_string(msg),
+        Err::MissingMemoryExport {
+            app_addr,
+            template_addr,
+        } => {
+            w.write_address(template_addr.inner());
+            w.write_address(app_addr.inner());
+        }
+        Err::MemoryOutOfBounds {
+            app_addr,
+            template_addr,
+            offset,
+            len,
+        } => {
+            w.write_address(template_addr.inner());
+            w.write_address(app_addr.inner());
+            w.write_u32_be(*offset);
+            w.write_u32_be(*len);
+        }
+        Err::InvalidVarId {
+            app_addr,
+            template_addr,
+            var_id,
+        } => {
+            w.write_address(template_addr.inner());
+            w.write_address(app_addr.inner());
+            w.write_u32_be(*var_id);
+        }
+        Err::StorageLimitExceeded {
+            app_addr,
+            template_addr,
+            size,
+            limit,
+        } => {
+            w.write_address(template_addr.inner());
+            w.write_address(app_addr.inner());
+            w.write_u64_be(*size);
+            w.write_u64_be(*limit);
+        }
+        Err::UnknownState { app_addr, state } => {
+            w.write_address(app_addr.inner());
+            w.write_state(state);
+        }
     };
 }
 
@@ -137,14 +231,26 @@ fn encode_err_type(err: &ReceiptError, w: &mut Vec<u8>) {
         Err::FuncNotFound { .. } => 5,
         Err::FuncFailed { .. } => 6,
         Err::FuncNotAllowed { .. } => 7,
+        Err::InsufficientFunds { .. } => 8,
+        Err::ParseFailed(..) => 9,
+        Err::MissingMemoryExport { .. } => 10,
+        Err::MemoryOutOfBounds { .. } => 11,
+        Err::InvalidVarId { .. } => 12,
+        Err::StorageLimitExceeded { .. } => 13,
+        Err::UnknownState { .. } => 14,
     };
 
     w.push(ty);
 }
 
-pub(crate) fn decode_error(cursor: &mut Cursor<&[u8]>) -> (ReceiptError, Vec<Log>) {
-    let ty = cursor.read_byte().unwrap();
-    let logs = logs::decode_logs(cursor).unwrap();
+pub(crate) fn decode_error(
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<(ReceiptError, Vec<Log>), ParseError> {
+    let ty = cursor
+        .read_byte()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::ReceiptErrorType))?;
+
+    let logs = logs::decode_logs(cursor)?;
 
     let err = {
         match ty {
@@ -156,109 +262,227 @@ pub(crate) fn decode_error(cursor: &mut Cursor<&[u8]>) -> (ReceiptError, Vec<Log
             5 => decode_func_not_found(cursor),
             6 => decode_func_failed(cursor),
             7 => decode_func_not_allowed(cursor),
-            _ => unreachable!(),
+            8 => decode_insufficient_funds(cursor),
+            9 => decode_parse_failed(cursor),
+            10 => decode_missing_memory_export(cursor),
+            11 => decode_memory_out_of_bounds(cursor),
+            12 => decode_invalid_var_id(cursor),
+            13 => decode_storage_limit_exceeded(cursor),
+            14 => decode_unknown_state(cursor),
+            _ => Err(ParseError::UnexpectedLayout(Field::ReceiptErrorType)),
         }
-    };
+    }?;
 
-    (err, logs)
+    Ok((err, logs))
 }
 
-fn decode_oog(_cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    ReceiptError::OOG
+fn decode_oog(_cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    Ok(ReceiptError::OOG)
 }
 
-fn decode_template_not_found(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let template_addr = decode_template_addr(cursor);
+fn decode_template_not_found(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let template_addr = decode_template_addr(cursor)?;
 
-    ReceiptError::TemplateNotFound(template_addr.into())
+    Ok(ReceiptError::TemplateNotFound(template_addr.into()))
 }
 
-fn decode_app_not_found(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let app_addr = decode_app_addr(cursor);
+fn decode_app_not_found(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let app_addr = decode_app_addr(cursor)?;
 
-    ReceiptError::AppNotFound(app_addr.into())
+    Ok(ReceiptError::AppNotFound(app_addr.into()))
 }
 
-fn decode_compilation_err(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let (template_addr, app_addr) = decode_addrs(cursor);
-    let msg = decode_msg(cursor);
+fn decode_compilation_err(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let msg = decode_msg(cursor)?;
 
-    ReceiptError::CompilationFailed {
+    Ok(ReceiptError::CompilationFailed {
         template_addr,
         app_addr,
         msg,
-    }
+    })
 }
 
-fn decode_instantiation_err(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let (template_addr, app_addr) = decode_addrs(cursor);
-    let msg = decode_msg(cursor);
+fn decode_instantiation_err(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let msg = decode_msg(cursor)?;
 
-    ReceiptError::InstantiationFailed {
+    Ok(ReceiptError::InstantiationFailed {
         template_addr,
         app_addr,
         msg,
-    }
+    })
 }
 
-fn decode_func_not_found(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let (template_addr, app_addr) = decode_addrs(cursor);
-    let func = decode_func(cursor);
+fn decode_func_not_found(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let func = decode_func(cursor)?;
 
-    ReceiptError::FuncNotFound {
+    Ok(ReceiptError::FuncNotFound {
         template_addr,
         app_addr,
         func,
-    }
+    })
 }
 
-fn decode_func_failed(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let (template_addr, app_addr) = decode_addrs(cursor);
-    let func = decode_func(cursor);
-    let msg = decode_msg(cursor);
+fn decode_func_failed(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let func = decode_func(cursor)?;
+    let msg = decode_msg(cursor)?;
 
-    ReceiptError::FuncFailed {
+    Ok(ReceiptError::FuncFailed {
         template_addr,
         app_addr,
         func,
         msg,
-    }
+    })
 }
 
-fn decode_func_not_allowed(cursor: &mut Cursor<&[u8]>) -> ReceiptError {
-    let (template_addr, app_addr) = decode_addrs(cursor);
-    let func = decode_func(cursor);
-    let msg = decode_msg(cursor);
+fn decode_func_not_allowed(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let func = decode_func(cursor)?;
+    let msg = decode_msg(cursor)?;
 
-    ReceiptError::FuncNotAllowed {
+    Ok(ReceiptError::FuncNotAllowed {
         template_addr,
         app_addr,
         func,
         msg,
+    })
+}
+
+fn decode_insufficient_funds(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let addr = cursor
+        .read_address()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Address))?;
+
+    let balance = cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Balance))?;
+
+    let amount = cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Value))?;
+
+    Ok(ReceiptError::InsufficientFunds {
+        addr,
+        balance,
+        amount,
+    })
+}
+
+fn decode_parse_failed(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let msg = decode_msg(cursor)?;
+
+    Ok(ReceiptError::ParseFailed(msg))
+}
+
+fn decode_missing_memory_export(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+
+    Ok(ReceiptError::MissingMemoryExport {
+        template_addr,
+        app_addr,
+    })
+}
+
+fn decode_memory_out_of_bounds(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let offset = decode_u32(cursor)?;
+    let len = decode_u32(cursor)?;
+
+    Ok(ReceiptError::MemoryOutOfBounds {
+        template_addr,
+        app_addr,
+        offset,
+        len,
+    })
+}
+
+fn decode_invalid_var_id(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+
+    let var_id = cursor
+        .read_u32_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::VarId))?;
+
+    Ok(ReceiptError::InvalidVarId {
+        template_addr,
+        app_addr,
+        var_id,
+    })
+}
+
+fn decode_storage_limit_exceeded(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let (template_addr, app_addr) = decode_addrs(cursor)?;
+    let size = decode_u64(cursor)?;
+    let limit = decode_u64(cursor)?;
+
+    Ok(ReceiptError::StorageLimitExceeded {
+        template_addr,
+        app_addr,
+        size,
+        limit,
+    })
+}
+
+fn decode_unknown_state(cursor: &mut Cursor<&[u8]>) -> Result<ReceiptError, ParseError> {
+    let app_addr = decode_app_addr(cursor)?;
+    let state = cursor
+        .read_state()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::State))?;
+
+    Ok(ReceiptError::UnknownState {
+        app_addr: app_addr.into(),
+        state,
+    })
+}
+
+fn decode_func(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
+    match cursor.read_string() {
+        Ok(Ok(func)) => Ok(func),
+        Ok(Err(..)) => Err(ParseError::InvalidUTF8String(Field::Function)),
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::Function)),
     }
 }
 
-fn decode_func(cursor: &mut Cursor<&[u8]>) -> String {
-    cursor.read_string().unwrap().unwrap()
+fn decode_addrs(cursor: &mut Cursor<&[u8]>) -> Result<(TemplateAddr, AppAddr), ParseError> {
+    let template_addr = decode_template_addr(cursor)?;
+    let app_addr = decode_app_addr(cursor)?;
+
+    Ok((template_addr.into(), app_addr.into()))
 }
 
-fn decode_addrs(cursor: &mut Cursor<&[u8]>) -> (TemplateAddr, AppAddr) {
-    let template_addr = decode_template_addr(cursor);
-    let app_addr = decode_app_addr(cursor);
+fn decode_template_addr(cursor: &mut Cursor<&[u8]>) -> Result<Address, ParseError> {
+    cursor
+        .read_address()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::TemplateAddr))
+}
 
-    (template_addr.into(), app_addr.into())
+fn decode_app_addr(cursor: &mut Cursor<&[u8]>) -> Result<Address, ParseError> {
+    cursor
+        .read_address()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::AppAddr))
 }
 
-fn decode_template_addr(cursor: &mut Cursor<&[u8]>) -> Address {
-    cursor.read_address().unwrap()
+fn decode_msg(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
+    match cursor.read_string() {
+        Ok(Ok(msg)) => Ok(msg),
+        Ok(Err(..)) => Err(ParseError::InvalidUTF8String(Field::String)),
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::String)),
+    }
 }
 
-fn decode_app_addr(cursor: &mut Cursor<&[u8]>) -> Address {
-    cursor.read_address().unwrap()
+fn decode_u32(cursor: &mut Cursor<&[u8]>) -> Result<u32, ParseError> {
+    cursor
+        .read_u32_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::ReceiptErrorData))
 }
 
-fn decode_msg(cursor: &mut Cursor<&[u8]>) -> String {
-    cursor.read_string().unwrap().unwrap()
+fn decode_u64(cursor: &mut Cursor<&[u8]>) -> Result<u64, ParseError> {
+    cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::ReceiptErrorData))
 }
 
 #[cfg(test)]
@@ -288,7 +512,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -301,7 +525,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -314,7 +538,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut bytes);
 
         let mut cursor = Cursor::new(&bytes[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -332,7 +556,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -350,7 +574,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -369,7 +593,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -390,7 +614,7 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
     }
 
     #[test]
@@ -411,6 +635,158 @@ mod tests {
         encode_error(&err, &test_logs(), &mut buf);
 
         let mut cursor = Cursor::new(&buf[..]);
-        let decoded = decode_error(&mut cursor);
+        let decoded = decode_error(&mut cursor).unwrap();
+    }
+
+    #[test]
+    fn decode_receipt_insufficient_funds() {
+        let err = ReceiptError::InsufficientFunds {
+            addr: Address::of("sender"),
+            balance: 10,
+            amount: 20,
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_parse_failed() {
+        let err = ReceiptError::ParseFailed("Not enough bytes (field: `Name`)".to_string());
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_missing_memory_export() {
+        let err = ReceiptError::MissingMemoryExport {
+            app_addr: Address::of("some-app").into(),
+            template_addr: Address::of("some-template").into(),
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_memory_out_of_bounds() {
+        let err = ReceiptError::MemoryOutOfBounds {
+            app_addr: Address::of("some-app").into(),
+            template_addr: Address::of("some-template").into(),
+            offset: 65530,
+            len: 10,
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_invalid_var_id() {
+        let err = ReceiptError::InvalidVarId {
+            app_addr: Address::of("some-app").into(),
+            template_addr: Address::of("some-template").into(),
+            var_id: 3,
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_storage_limit_exceeded() {
+        let err = ReceiptError::StorageLimitExceeded {
+            app_addr: Address::of("some-app").into(),
+            template_addr: Address::of("some-template").into(),
+            size: 1100,
+            limit: 1024,
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_unknown_state() {
+        let err = ReceiptError::UnknownState {
+            app_addr: Address::of("some-app").into(),
+            state: State::of("some-state"),
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, logs) = decode_error(&mut cursor).unwrap();
+
+        assert_eq!(decoded, err);
+        assert_eq!(logs, test_logs());
+    }
+
+    #[test]
+    fn decode_receipt_error_unknown_type() {
+        let mut buf = vec![0xFF];
+        logs::encode_logs(&test_logs(), &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let err = decode_error(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::UnexpectedLayout(Field::ReceiptErrorType));
+    }
+
+    #[test]
+    fn decode_receipt_error_truncated() {
+        let err = ReceiptError::FuncFailed {
+            app_addr: Address::of("some-app").into(),
+            template_addr: Address::of("some-template").into(),
+            func: "run".to_string(),
+            msg: "Invalid input".to_string(),
+        };
+
+        let mut buf = Vec::new();
+        encode_error(&err, &test_logs(), &mut buf);
+
+        for len in 0..buf.len() {
+            let mut cursor = Cursor::new(&buf[..len]);
+
+            assert!(decode_error(&mut cursor).is_err());
+        }
     }
 }
@@ -1,4 +1,4 @@
-//! `Exec App` Receipt Raw Format Version 0
+//! `Exec App` Receipt Raw Format
 //!
 //!  On success (`is_success = 1`)
 //!  +---------------------------------------------------+
@@ -11,6 +11,18 @@
 //!  |          |            |         |                 |
 //!  |  #logs   | log 1 blob |  . . .  |     log #N      |
 //!  +__________|____________|_________|_________________+
+//!  |            |            |         |               |
+//!  |  #changes  |  change 1  |  . . .  |   change #N   |
+//!  +____________|____________|_________|_______________+
+//!  |                                                   |
+//!  |       storage changes (optional, see below)       |
+//!  +___________________________________________________+
+//!
+//!  (see [balances.rs][./balances.rs] for the balance changes encoding)
+//!  (see [storage.rs][./storage.rs] for the storage changes encoding)
+//!
+//!  The balance changes are part of the receipt starting from version `1`,
+//!  and the storage changes starting from version `2`.
 //!
 //!
 //!  On success (`is_success = 0`)
@@ -21,10 +33,11 @@ use std::io::Cursor;
 use svm_types::gas::MaybeGas;
 use svm_types::receipt::{ExecReceipt, Log, Receipt};
 
-use super::{decode_error, encode_error, gas, logs};
+use super::{balances, decode_error, encode_error, gas, logs, storage};
 
+use crate::version::Format;
 use crate::{calldata, common};
-use crate::{ReadExt, WriteExt};
+use crate::{Field, ParseError, ReadExt, WriteExt};
 
 pub fn encode_exec_receipt(receipt: &ExecReceipt) -> Vec<u8> {
     let mut w = Vec::new();
@@ -38,6 +51,16 @@ pub fn encode_exec_receipt(receipt: &ExecReceipt) -> Vec<u8> {
         encode_returndata(receipt, &mut w);
         gas::encode_gas_used(&receipt.gas_used, &mut w);
         logs::encode_logs(&receipt.logs, &mut w);
+
+        if receipt.version >= 1 {
+            balances::encode_balance_changes(&receipt.balance_changes, &mut w);
+        }
+
+        if receipt.version >= 2 {
+            let changes = receipt.get_storage_changes();
+
+            storage::encode_storage_changes(changes, receipt.storage_changes_truncated, &mut w);
+        }
     } else {
         let logs = receipt.get_logs();
 
@@ -47,40 +70,53 @@ pub fn encode_exec_receipt(receipt: &ExecReceipt) -> Vec<u8> {
     w
 }
 
-pub fn decode_exec_receipt(bytes: &[u8]) -> ExecReceipt {
+pub fn decode_exec_receipt(bytes: &[u8]) -> Result<ExecReceipt, ParseError> {
     let mut cursor = Cursor::new(bytes);
 
-    let ty = cursor.read_byte().unwrap();
-    debug_assert_eq!(ty, crate::receipt::types::EXEC_APP);
+    super::decode_type(&mut cursor, super::types::EXEC_APP)?;
+
+    let version = common::decode_version(&mut cursor, Format::Receipt)?;
+    let is_success = super::decode_is_success(&mut cursor)?;
+
+    let receipt = if is_success {
+        let new_state = cursor
+            .read_state()
+            .map_err(|_| ParseError::NotEnoughBytes(Field::State))?;
+        let returndata = calldata::decode_returndata(&mut cursor)?;
+        let gas_used = gas::decode_gas_used(&mut cursor)?;
+        let logs = logs::decode_logs(&mut cursor)?;
+        let balance_changes = if version >= 1 {
+            balances::decode_balance_changes(&mut cursor)?
+        } else {
+            Vec::new()
+        };
+        let (storage_changes, storage_changes_truncated) = if version >= 2 {
+            storage::decode_storage_changes(&mut cursor)?
+        } else {
+            (None, false)
+        };
+
+        ExecReceipt {
+            version,
+            success: true,
+            error: None,
+            new_state: Some(new_state),
+            returndata: Some(returndata),
+            gas_used,
+            logs,
+            balance_changes,
+            storage_changes,
+            storage_changes_truncated,
+        }
+    } else {
+        let (err, logs) = decode_error(&mut cursor)?;
 
-    let version = common::decode_version(&mut cursor).unwrap();
-    debug_assert_eq!(0, version);
+        ExecReceipt::from_err(err, logs)
+    };
 
-    let is_success = cursor.read_bool().unwrap();
+    common::decode_eof(&cursor)?;
 
-    match is_success {
-        false => {
-            let (err, logs) = decode_error(&mut cursor);
-            ExecReceipt::from_err(err, logs)
-        }
-        true => {
-            let new_state = cursor.read_state().unwrap();
-            let returndata = calldata::decode_calldata(&mut cursor).unwrap();
-            let gas_used = gas::decode_gas_used(&mut cursor).unwrap();
-            let logs = logs::decode_logs(&mut cursor).unwrap();
-
-            ExecReceipt {
-                version,
-                success: true,
-                error: None,
-                new_state: Some(new_state),
-                returndata: Some(returndata),
-                gas_used,
-                logs,
-            }
-        }
-        _ => unreachable!(),
-    }
+    Ok(receipt)
 }
 
 fn encode_new_state(receipt: &ExecReceipt, w: &mut Vec<u8>) {
@@ -102,7 +138,7 @@ mod tests {
     use super::*;
 
     use svm_types::gas::MaybeGas;
-    use svm_types::receipt::ReceiptError;
+    use svm_types::receipt::{BalanceChange, ReceiptError, StorageChange};
     use svm_types::{Address, State};
 
     #[test]
@@ -123,10 +159,13 @@ mod tests {
             returndata: None,
             gas_used: MaybeGas::new(),
             logs,
+            balance_changes: Vec::new(),
+            storage_changes: None,
+            storage_changes_truncated: false,
         };
 
         let bytes = encode_exec_receipt(&receipt);
-        let decoded = crate::receipt::decode_receipt(&bytes[..]);
+        let decoded = crate::receipt::decode_receipt(&bytes[..]).unwrap();
 
         assert_eq!(decoded.into_exec_app(), receipt);
     }
@@ -148,10 +187,13 @@ mod tests {
             returndata: Some(Vec::new()),
             gas_used: MaybeGas::with(100),
             logs: logs.clone(),
+            balance_changes: Vec::new(),
+            storage_changes: None,
+            storage_changes_truncated: false,
         };
 
         let bytes = encode_exec_receipt(&receipt);
-        let decoded = crate::receipt::decode_receipt(&bytes[..]);
+        let decoded = crate::receipt::decode_receipt(&bytes[..]).unwrap();
 
         assert_eq!(decoded.into_exec_app(), receipt);
     }
@@ -167,18 +209,123 @@ mod tests {
         }];
 
         let receipt = ExecReceipt {
-            version: 0,
+            version: 2,
             success: true,
             error: None,
             new_state: Some(new_state),
             returndata: Some(returndata),
             gas_used: MaybeGas::with(100),
             logs: logs.clone(),
+            balance_changes: vec![BalanceChange {
+                addr: Address::of("sender"),
+                old_balance: 100,
+                new_balance: 90,
+            }],
+            storage_changes: Some(vec![StorageChange {
+                var_id: 1,
+                old_value: vec![0, 0],
+                new_value: vec![0x10, 0x20],
+            }]),
+            storage_changes_truncated: false,
         };
 
         let bytes = encode_exec_receipt(&receipt);
-        let decoded = crate::receipt::decode_receipt(&bytes[..]);
+        let decoded = crate::receipt::decode_receipt(&bytes[..]).unwrap();
 
         assert_eq!(decoded.into_exec_app(), receipt);
     }
+
+    #[test]
+    fn encode_decode_exec_receipt_version_0_has_no_balance_changes() {
+        let receipt = ExecReceipt {
+            version: 0,
+            success: true,
+            error: None,
+            new_state: Some(State::of("some-state")),
+            returndata: Some(vec![0x10, 0x20]),
+            gas_used: MaybeGas::with(100),
+            logs: Vec::new(),
+            balance_changes: vec![BalanceChange {
+                addr: Address::of("sender"),
+                old_balance: 100,
+                new_balance: 90,
+            }],
+            storage_changes: None,
+            storage_changes_truncated: false,
+        };
+
+        let bytes = encode_exec_receipt(&receipt);
+        let decoded = crate::receipt::decode_receipt(&bytes[..])
+            .unwrap()
+            .into_exec_app();
+
+        assert_eq!(decoded.version, 0);
+        assert!(decoded.balance_changes.is_empty());
+        assert_eq!(decoded.new_state, receipt.new_state);
+        assert_eq!(decoded.returndata, receipt.returndata);
+    }
+
+    #[test]
+    fn encode_decode_exec_receipt_version_1_has_no_storage_changes() {
+        let receipt = ExecReceipt {
+            version: 1,
+            success: true,
+            error: None,
+            new_state: Some(State::of("some-state")),
+            returndata: Some(vec![0x10, 0x20]),
+            gas_used: MaybeGas::with(100),
+            logs: Vec::new(),
+            balance_changes: Vec::new(),
+            storage_changes: Some(vec![StorageChange {
+                var_id: 1,
+                old_value: vec![0, 0],
+                new_value: vec![0x10, 0x20],
+            }]),
+            storage_changes_truncated: false,
+        };
+
+        let bytes = encode_exec_receipt(&receipt);
+        let decoded = crate::receipt::decode_receipt(&bytes[..])
+            .unwrap()
+            .into_exec_app();
+
+        assert_eq!(decoded.version, 1);
+        assert!(decoded.storage_changes.is_none());
+        assert_eq!(decoded.returndata, receipt.returndata);
+    }
+
+    #[test]
+    fn encode_decode_exec_receipt_truncated_storage_changes() {
+        let change = StorageChange {
+            var_id: 1,
+            old_value: vec![0, 0],
+            new_value: vec![0x10, 0x20],
+        };
+        let too_long = StorageChange {
+            var_id: 2,
+            old_value: vec![0; u16::MAX as usize + 1],
+            new_value: vec![1; u16::MAX as usize + 1],
+        };
+
+        let receipt = ExecReceipt {
+            version: 2,
+            success: true,
+            error: None,
+            new_state: Some(State::of("some-state")),
+            returndata: Some(Vec::new()),
+            gas_used: MaybeGas::with(100),
+            logs: Vec::new(),
+            balance_changes: Vec::new(),
+            storage_changes: Some(vec![too_long, change.clone()]),
+            storage_changes_truncated: false,
+        };
+
+        let bytes = encode_exec_receipt(&receipt);
+        let decoded = crate::receipt::decode_receipt(&bytes[..])
+            .unwrap()
+            .into_exec_app();
+
+        assert_eq!(decoded.storage_changes, Some(vec![change]));
+        assert!(decoded.storage_changes_truncated);
+    }
 }
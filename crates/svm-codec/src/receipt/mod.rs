@@ -4,11 +4,13 @@ mod exec_app;
 mod gas;
 mod spawn_app;
 
+pub(crate) mod balances;
 pub(crate) mod logs;
+pub(crate) mod storage;
 
 pub(crate) use error::{decode_error, encode_error};
 
-mod types {
+pub(crate) mod types {
     pub const DEPLOY_TEMPLATE: u8 = 0;
 
     pub const SPAWN_APP: u8 = 1;
@@ -20,26 +22,53 @@ pub use deploy_template::{decode_template_receipt, encode_template_receipt};
 pub use exec_app::{decode_exec_receipt, encode_exec_receipt};
 pub use spawn_app::{decode_app_receipt, encode_app_receipt};
 
+use std::io::Cursor;
+
 use svm_types::receipt::{ExecReceipt, ReceiptOwned, SpawnAppReceipt, TemplateReceipt};
 
-pub fn decode_receipt(bytes: &[u8]) -> ReceiptOwned {
-    assert!(bytes.len() > 0);
+use crate::{Field, ParseError, ReadExt};
 
-    let ty = bytes[0];
+/// Decodes a binary receipt (of any type).
+///
+/// Returns a `ParseError` when the receipt is malformed (truncated, of an unknown type
+/// or of an unsupported version).
+pub fn decode_receipt(bytes: &[u8]) -> Result<ReceiptOwned, ParseError> {
+    let ty = bytes
+        .first()
+        .ok_or(ParseError::NotEnoughBytes(Field::ReceiptType))?;
 
-    match ty {
+    match *ty {
         types::DEPLOY_TEMPLATE => {
-            let receipt = decode_template_receipt(bytes);
-            ReceiptOwned::DeployTemplate(receipt)
+            let receipt = decode_template_receipt(bytes)?;
+            Ok(ReceiptOwned::DeployTemplate(receipt))
         }
         types::SPAWN_APP => {
-            let receipt = decode_app_receipt(bytes);
-            ReceiptOwned::SpawnApp(receipt)
+            let receipt = decode_app_receipt(bytes)?;
+            Ok(ReceiptOwned::SpawnApp(receipt))
         }
         types::EXEC_APP => {
-            let receipt = decode_exec_receipt(bytes);
-            ReceiptOwned::ExecApp(receipt)
+            let receipt = decode_exec_receipt(bytes)?;
+            Ok(ReceiptOwned::ExecApp(receipt))
         }
-        _ => unreachable!(),
+        _ => Err(ParseError::UnexpectedLayout(Field::ReceiptType)),
+    }
+}
+
+/// Decodes the receipt type, which is expected to be `expected`.
+pub(crate) fn decode_type(cursor: &mut Cursor<&[u8]>, expected: u8) -> Result<(), ParseError> {
+    match cursor.read_byte() {
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::ReceiptType)),
+        Ok(ty) if ty == expected => Ok(()),
+        Ok(..) => Err(ParseError::UnexpectedLayout(Field::ReceiptType)),
+    }
+}
+
+/// Decodes the `is_success` flag of a receipt.
+pub(crate) fn decode_is_success(cursor: &mut Cursor<&[u8]>) -> Result<bool, ParseError> {
+    match cursor.read_byte() {
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::ReceiptStatus)),
+        Ok(0) => Ok(false),
+        Ok(1) => Ok(true),
+        Ok(..) => Err(ParseError::UnexpectedLayout(Field::ReceiptStatus)),
     }
 }
@@ -1,4 +1,4 @@
-//! `Spawn App` Receipt Raw Format Version 0
+//! `Spawn App` Receipt Raw Format
 //!
 //!  On success (`is_success = 1`)
 //!  +-----------------------------------------------------+
@@ -12,6 +12,13 @@
 //!  |          |            |         |                   |
 //!  |  #logs   | log 1 blob |  . . .  |     log #N        |
 //!  +__________|____________|_________|___________________+
+//!  |            |            |         |                 |
+//!  |  #changes  |  change 1  |  . . .  |   change #N     |
+//!  +____________|____________|_________|_________________+
+//!
+//!  (see [balances.rs][./balances.rs] for the balance changes encoding)
+//!
+//!  The balance changes are part of the receipt starting from version `1`.
 //!
 //!
 //!  On success (`is_success = 0`)
@@ -22,10 +29,11 @@ use std::io::Cursor;
 use svm_types::gas::MaybeGas;
 use svm_types::receipt::{Receipt, SpawnAppReceipt};
 
-use super::{decode_error, decode_receipt, encode_error, gas, logs, types};
+use super::{balances, decode_error, decode_receipt, encode_error, gas, logs, types};
 
+use crate::version::Format;
 use crate::{calldata, common};
-use crate::{ReadExt, WriteExt};
+use crate::{Field, ParseError, ReadExt, WriteExt};
 
 pub fn encode_app_receipt(receipt: &SpawnAppReceipt) -> Vec<u8> {
     let mut w = Vec::new();
@@ -40,6 +48,10 @@ pub fn encode_app_receipt(receipt: &SpawnAppReceipt) -> Vec<u8> {
         encode_returndata(&receipt, &mut w);
         gas::encode_gas_used(&receipt.gas_used, &mut w);
         logs::encode_logs(&receipt.logs, &mut w);
+
+        if receipt.version >= 1 {
+            balances::encode_balance_changes(&receipt.balance_changes, &mut w);
+        }
     } else {
         let logs = receipt.get_logs();
 
@@ -49,42 +61,50 @@ pub fn encode_app_receipt(receipt: &SpawnAppReceipt) -> Vec<u8> {
     w
 }
 
-pub fn decode_app_receipt(bytes: &[u8]) -> SpawnAppReceipt {
+pub fn decode_app_receipt(bytes: &[u8]) -> Result<SpawnAppReceipt, ParseError> {
     let mut cursor = Cursor::new(bytes);
 
-    let ty = cursor.read_byte().unwrap();
-    debug_assert_eq!(ty, types::SPAWN_APP);
+    super::decode_type(&mut cursor, types::SPAWN_APP)?;
+
+    let version = common::decode_version(&mut cursor, Format::Receipt)?;
+    let is_success = super::decode_is_success(&mut cursor)?;
+
+    let receipt = if is_success {
+        let addr = cursor
+            .read_address()
+            .map_err(|_| ParseError::NotEnoughBytes(Field::AppAddr))?;
+        let init_state = cursor
+            .read_state()
+            .map_err(|_| ParseError::NotEnoughBytes(Field::State))?;
+        let returndata = calldata::decode_returndata(&mut cursor)?;
+        let gas_used = gas::decode_gas_used(&mut cursor)?;
+        let logs = logs::decode_logs(&mut cursor)?;
+        let balance_changes = if version >= 1 {
+            balances::decode_balance_changes(&mut cursor)?
+        } else {
+            Vec::new()
+        };
 
-    let version = common::decode_version(&mut cursor).unwrap();
-    debug_assert_eq!(0, version);
+        SpawnAppReceipt {
+            version,
+            success: true,
+            error: None,
+            app_addr: Some(addr.into()),
+            init_state: Some(init_state),
+            returndata: Some(returndata),
+            gas_used,
+            logs,
+            balance_changes,
+        }
+    } else {
+        let (err, logs) = decode_error(&mut cursor)?;
 
-    let is_success = cursor.read_bool().unwrap();
+        SpawnAppReceipt::from_err(err, logs)
+    };
 
-    match is_success {
-        false => {
-            let (err, logs) = decode_error(&mut cursor);
-            SpawnAppReceipt::from_err(err, logs)
-        }
-        true => {
-            let addr = cursor.read_address().unwrap();
-            let init_state = cursor.read_state().unwrap();
-            let returndata = calldata::decode_calldata(&mut cursor).unwrap();
-            let gas_used = gas::decode_gas_used(&mut cursor).unwrap();
-            let logs = logs::decode_logs(&mut cursor).unwrap();
-
-            SpawnAppReceipt {
-                version,
-                success: true,
-                error: None,
-                app_addr: Some(addr.into()),
-                init_state: Some(init_state),
-                returndata: Some(returndata),
-                gas_used,
-                logs,
-            }
-        }
-        _ => unreachable!(),
-    }
+    common::decode_eof(&cursor)?;
+
+    Ok(receipt)
 }
 
 fn encode_version(receipt: &SpawnAppReceipt, w: &mut Vec<u8>) {
@@ -121,7 +141,7 @@ fn encode_returndata(receipt: &SpawnAppReceipt, w: &mut Vec<u8>) {
 mod tests {
     use super::*;
 
-    use svm_types::receipt::{Log, ReceiptError};
+    use svm_types::receipt::{BalanceChange, Log, ReceiptError};
     use svm_types::{gas::MaybeGas, Address, AppAddr, State};
 
     #[test]
@@ -139,10 +159,11 @@ mod tests {
             returndata: None,
             gas_used: MaybeGas::new(),
             logs: Vec::new(),
+            balance_changes: Vec::new(),
         };
 
         let bytes = encode_app_receipt(&receipt);
-        let decoded = decode_receipt(&bytes);
+        let decoded = decode_receipt(&bytes).unwrap();
 
         assert_eq!(decoded.into_spawn_app(), receipt);
     }
@@ -166,10 +187,11 @@ mod tests {
             returndata: Some(Vec::new()),
             gas_used: MaybeGas::with(100),
             logs: logs.clone(),
+            balance_changes: Vec::new(),
         };
 
         let bytes = encode_app_receipt(&receipt);
-        let decoded = decode_receipt(&bytes);
+        let decoded = decode_receipt(&bytes).unwrap();
 
         assert_eq!(decoded.into_spawn_app(), receipt);
     }
@@ -185,7 +207,7 @@ mod tests {
         }];
 
         let receipt = SpawnAppReceipt {
-            version: 0,
+            version: 1,
             success: true,
             error: None,
             app_addr: Some(addr),
@@ -193,11 +215,43 @@ mod tests {
             returndata: Some(returndata),
             gas_used: MaybeGas::with(100),
             logs: logs.clone(),
+            balance_changes: vec![BalanceChange {
+                addr: Address::of("my-app"),
+                old_balance: 0,
+                new_balance: 10,
+            }],
         };
 
         let bytes = encode_app_receipt(&receipt);
-        let decoded = decode_receipt(&bytes);
+        let decoded = decode_receipt(&bytes).unwrap();
 
         assert_eq!(decoded.into_spawn_app(), receipt);
     }
+
+    #[test]
+    fn encode_decode_spawn_app_receipt_version_0_has_no_balance_changes() {
+        let receipt = SpawnAppReceipt {
+            version: 0,
+            success: true,
+            error: None,
+            app_addr: Some(Address::of("my-app").into()),
+            init_state: Some(State::of("some-state")),
+            returndata: Some(vec![0x10, 0x20]),
+            gas_used: MaybeGas::with(100),
+            logs: Vec::new(),
+            balance_changes: vec![BalanceChange {
+                addr: Address::of("my-app"),
+                old_balance: 0,
+                new_balance: 10,
+            }],
+        };
+
+        let bytes = encode_app_receipt(&receipt);
+        let decoded = decode_receipt(&bytes).unwrap().into_spawn_app();
+
+        assert_eq!(decoded.version, 0);
+        assert!(decoded.balance_changes.is_empty());
+        assert_eq!(decoded.init_state, receipt.init_state);
+        assert_eq!(decoded.returndata, receipt.returndata);
+    }
 }
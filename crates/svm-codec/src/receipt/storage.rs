@@ -0,0 +1,231 @@
+use std::io::Cursor;
+
+use svm_types::receipt::StorageChange;
+
+use crate::{Field, ParseError, ReadExt, WriteExt};
+
+/// The storage changes weren't collected
+const NOT_COLLECTED: u8 = 0;
+
+/// All the storage changes follow
+const COLLECTED: u8 = 1;
+
+/// Only part of the storage changes follow (the rest didn't fit the encoding)
+const TRUNCATED: u8 = 2;
+
+///
+/// +-----------------------------+
+/// |    status (1 byte)          |  ---> `0` (not collected) - nothing follows
+/// +-----------------------------+       `1` (collected) or `2` (truncated) - the changes follow
+/// | #storage changes (2 bytes)  |
+/// +-----------------------------+-----------------------------------------------------------+
+/// |  var id (4 bytes) | value length (2 bytes) | old value (blob) | new value (blob)  |  ---> change #0
+/// +-----------------------------------------------------------------------------------------+
+///                                           .
+///                                           .
+///                                           .
+/// +-----------------------------------------------------------------------------------------+
+/// |  var id (4 bytes) | value length (2 bytes) | old value (blob) | new value (blob)  |  ---> change #N
+/// +-----------------------------------------------------------------------------------------+
+///
+/// (The old and new values of a variable are of the same length)
+///
+/// A change whose values are longer than `u16::MAX` bytes (or exceeding the first `u16::MAX` changes)
+/// can't be encoded. Such changes are left out, and the status is set to `truncated`.
+///
+pub fn encode_storage_changes(changes: Option<&[StorageChange]>, truncated: bool, w: &mut Vec<u8>) {
+    match changes {
+        None => w.write_byte(NOT_COLLECTED),
+        Some(changes) => {
+            let encodable = changes
+                .iter()
+                .filter(|change| is_encodable(change))
+                .take(u16::MAX as usize)
+                .collect::<Vec<_>>();
+
+            if truncated || encodable.len() < changes.len() {
+                w.write_byte(TRUNCATED);
+            } else {
+                w.write_byte(COLLECTED);
+            }
+
+            w.write_u16_be(encodable.len() as u16);
+
+            for change in encodable {
+                encode_storage_change(change, w);
+            }
+        }
+    }
+}
+
+/// Decodes the storage changes, along with whether they've been truncated.
+pub fn decode_storage_changes(
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<(Option<Vec<StorageChange>>, bool), ParseError> {
+    let truncated = match cursor.read_byte() {
+        Err(..) => return Err(ParseError::NotEnoughBytes(Field::StorageChangesStatus)),
+        Ok(NOT_COLLECTED) => return Ok((None, false)),
+        Ok(COLLECTED) => false,
+        Ok(TRUNCATED) => true,
+        Ok(..) => return Err(ParseError::UnexpectedLayout(Field::StorageChangesStatus)),
+    };
+
+    match cursor.read_u16_be() {
+        Ok(nchanges) => {
+            let mut changes = Vec::with_capacity(nchanges as usize);
+
+            for _ in 0..nchanges {
+                let change = decode_storage_change(cursor)?;
+
+                changes.push(change);
+            }
+
+            Ok((Some(changes), truncated))
+        }
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::StorageChangesCount)),
+    }
+}
+
+fn is_encodable(change: &StorageChange) -> bool {
+    let len = change.new_value.len();
+
+    change.old_value.len() == len && len <= u16::MAX as usize
+}
+
+fn encode_storage_change(change: &StorageChange, w: &mut Vec<u8>) {
+    debug_assert!(is_encodable(change));
+
+    let len = change.new_value.len();
+
+    w.write_u32_be(change.var_id);
+    w.write_u16_be(len as u16);
+    w.write_bytes(&change.old_value);
+    w.write_bytes(&change.new_value);
+}
+
+fn decode_storage_change(cursor: &mut Cursor<&[u8]>) -> Result<StorageChange, ParseError> {
+    let var_id = cursor
+        .read_u32_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::VarId))?;
+
+    let len = cursor
+        .read_u16_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::StorageChangeValueLength))?;
+
+    let old_value = cursor
+        .read_bytes(len as usize)
+        .map_err(|_| ParseError::NotEnoughBytes(Field::StorageChangeValue))?;
+
+    let new_value = cursor
+        .read_bytes(len as usize)
+        .map_err(|_| ParseError::NotEnoughBytes(Field::StorageChangeValue))?;
+
+    let change = StorageChange {
+        var_id,
+        old_value,
+        new_value,
+    };
+
+    Ok(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_storage_changes_not_collected() {
+        let mut buf = Vec::new();
+
+        encode_storage_changes(None, false, &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (changes, truncated) = decode_storage_changes(&mut cursor).unwrap();
+
+        assert!(changes.is_none());
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn encode_storage_changes_multiple_entries() {
+        let mut buf = Vec::new();
+
+        let change1 = StorageChange {
+            var_id: 0,
+            old_value: vec![0, 0, 0, 0],
+            new_value: vec![10, 20, 30, 40],
+        };
+
+        let change2 = StorageChange {
+            var_id: 3,
+            old_value: vec![0xAA; 20],
+            new_value: vec![0xBB; 20],
+        };
+
+        encode_storage_changes(Some(&[change1.clone(), change2.clone()]), false, &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (changes, truncated) = decode_storage_changes(&mut cursor).unwrap();
+
+        assert_eq!(changes, Some(vec![change1, change2]));
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn encode_storage_changes_truncates_too_long_values() {
+        let mut buf = Vec::new();
+
+        let change1 = StorageChange {
+            var_id: 0,
+            old_value: vec![0; u16::MAX as usize + 1],
+            new_value: vec![1; u16::MAX as usize + 1],
+        };
+
+        let change2 = StorageChange {
+            var_id: 1,
+            old_value: vec![0, 0],
+            new_value: vec![10, 20],
+        };
+
+        encode_storage_changes(Some(&[change1, change2.clone()]), false, &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (changes, truncated) = decode_storage_changes(&mut cursor).unwrap();
+
+        assert_eq!(changes, Some(vec![change2]));
+        assert!(truncated);
+    }
+
+    #[test]
+    fn encode_storage_changes_truncates_too_many_changes() {
+        let mut buf = Vec::new();
+
+        let change = StorageChange {
+            var_id: 0,
+            old_value: vec![0],
+            new_value: vec![1],
+        };
+        let changes = vec![change; u16::MAX as usize + 1];
+
+        encode_storage_changes(Some(&changes), false, &mut buf);
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let (decoded, truncated) = decode_storage_changes(&mut cursor).unwrap();
+
+        assert_eq!(decoded.unwrap().len(), u16::MAX as usize);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn decode_storage_changes_unknown_status() {
+        let buf = [3, 0, 0];
+
+        let mut cursor = Cursor::new(&buf[..]);
+        let err = decode_storage_changes(&mut cursor).unwrap_err();
+
+        assert_eq!(
+            err,
+            ParseError::UnexpectedLayout(Field::StorageChangesStatus)
+        );
+    }
+}
@@ -14,12 +14,26 @@
 //!  |  #variables   |  length     |  . . .  |   length    |
 //!  +_______________|_____________|_________|_____________+
 //!
+//! Starting from version `1`, the code is prefixed with its kind (1 byte):
+//!
+//! * `0` - The code is included in full (as above).
+//! * `1` - The code is referenced by its hash (32 bytes), a.k.a a compact Deploy-Template.
+//!
+//!  +_______________________________________________+
+//!  |            |                                  |
+//!  |  kind = 1  |          Code Hash               |
+//!  |  (1 byte)  |          (32 bytes)              |
+//!  +____________|__________________________________+
+//!
 //!
 
 mod raw;
 mod serialize;
 mod validate;
 
-pub use raw::{decode_deploy_template, encode_deploy_template};
+pub use raw::{
+    decode_compact_deploy_template, decode_deploy_template, encode_compact_deploy_template,
+    encode_deploy_template, CodeHash,
+};
 pub use serialize::{DefaultAppTemplateDeserializer, DefaultAppTemplateSerializer};
 pub use validate::validate_template;
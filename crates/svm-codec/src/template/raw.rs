@@ -5,8 +5,18 @@ use svm_layout::{DataLayout, DataLayoutBuilder};
 use svm_types::AppTemplate;
 
 use crate::common;
+use crate::version::Format;
 use crate::{Field, ParseError, ReadExt, WriteExt};
 
+/// The hash referencing the code of a compact Deploy-Template.
+pub type CodeHash = [u8; 32];
+
+/// The code is included in full
+const CODE_INLINE: u8 = 0;
+
+/// The code is referenced by its hash
+const CODE_HASH: u8 = 1;
+
 /// Encodes a raw Deploy-Template.
 pub fn encode_deploy_template(template: &AppTemplate, w: &mut Vec<u8>) {
     encode_version(template, w);
@@ -16,11 +26,61 @@ pub fn encode_deploy_template(template: &AppTemplate, w: &mut Vec<u8>) {
     encode_ctors(template, w);
 }
 
+/// Encodes a raw compact Deploy-Template.
+/// The template's `code` is omitted and referenced by `code_hash` instead.
+///
+/// Compact Deploy-Templates are supported starting from version `1`.
+pub fn encode_compact_deploy_template(
+    template: &AppTemplate,
+    code_hash: &CodeHash,
+    w: &mut Vec<u8>,
+) {
+    assert!(template.version >= 1);
+
+    encode_version(template, w);
+    encode_name(template, w);
+    encode_code_hash(code_hash, w);
+    encode_data(template, w);
+    encode_ctors(template, w);
+}
+
 /// Decodes a raw Deploy-Template.
+///
+/// Trailing bytes (following the Deploy-Template) are rejected.
+/// A compact Deploy-Template is rejected with `ParseError::CodeNotFound`.
 pub fn decode_deploy_template(cursor: &mut Cursor<&[u8]>) -> Result<AppTemplate, ParseError> {
+    match decode_compact_deploy_template(cursor)? {
+        (template, None) => Ok(template),
+        (_, Some(..)) => Err(ParseError::CodeNotFound),
+    }
+}
+
+/// Decodes a raw Deploy-Template which may be compact.
+///
+/// Returns the `AppTemplate` along with its code hash when compact (in which case the template's `code` is empty).
+/// Trailing bytes (following the Deploy-Template) are rejected.
+pub fn decode_compact_deploy_template(
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<(AppTemplate, Option<CodeHash>), ParseError> {
+    let decoded = decode_template(cursor)?;
+
+    common::decode_eof(cursor)?;
+
+    Ok(decoded)
+}
+
+/// Decodes the fields of a raw Deploy-Template, leaving the `cursor` right after them.
+pub(crate) fn decode_template(
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<(AppTemplate, Option<CodeHash>), ParseError> {
     let version = decode_version(cursor)?;
     let name = decode_name(cursor)?;
-    let code = decode_code(cursor)?;
+
+    let (code, code_hash) = match decode_code_kind(cursor, version)? {
+        CODE_INLINE => (decode_code(cursor)?, None),
+        _ => (Vec::new(), Some(decode_code_hash(cursor)?)),
+    };
+
     let data = decode_data(cursor)?;
     let ctors = decode_ctors(cursor)?;
 
@@ -32,7 +92,7 @@ pub fn decode_deploy_template(cursor: &mut Cursor<&[u8]>) -> Result<AppTemplate,
         data,
     };
 
-    Ok(template)
+    Ok((template, code_hash))
 }
 
 /// Encoders
@@ -50,11 +110,13 @@ fn encode_name(template: &AppTemplate, w: &mut Vec<u8>) {
 fn encode_data(template: &AppTemplate, w: &mut Vec<u8>) {
     let nvars = template.data.len();
 
-    assert!(nvars < std::u16::MAX as usize);
+    assert!(nvars <= std::u16::MAX as usize);
 
     w.write_u16_be(nvars as u16);
 
     for (_varid, _off, len) in template.data.iter() {
+        assert!(len <= std::u16::MAX as u32);
+
         w.write_u16_be(len as u16);
     }
 }
@@ -62,9 +124,13 @@ fn encode_data(template: &AppTemplate, w: &mut Vec<u8>) {
 fn encode_code(template: &AppTemplate, w: &mut Vec<u8>) {
     let code = &template.code;
 
+    if template.version >= 1 {
+        w.write_byte(CODE_INLINE);
+    }
+
     // code length
     let length = code.len();
-    assert!(length < std::u32::MAX as usize);
+    assert!(length <= std::u32::MAX as usize);
 
     w.write_u32_be(length as u32);
 
@@ -72,10 +138,15 @@ fn encode_code(template: &AppTemplate, w: &mut Vec<u8>) {
     w.write_bytes(code);
 }
 
+fn encode_code_hash(code_hash: &CodeHash, w: &mut Vec<u8>) {
+    w.write_byte(CODE_HASH);
+    w.write_bytes(code_hash);
+}
+
 fn encode_ctors(template: &AppTemplate, w: &mut Vec<u8>) {
     let count = template.ctors.len();
 
-    assert!(count < std::u8::MAX as usize);
+    assert!(count <= std::u8::MAX as usize);
 
     w.write_byte(count as u8);
 
@@ -88,7 +159,7 @@ fn encode_ctors(template: &AppTemplate, w: &mut Vec<u8>) {
 
 #[inline]
 fn decode_version(cursor: &mut Cursor<&[u8]>) -> Result<u16, ParseError> {
-    common::decode_version(cursor)
+    common::decode_version(cursor, Format::DeployTemplate)
 }
 
 fn decode_name(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
@@ -118,6 +189,28 @@ fn decode_data(cursor: &mut Cursor<&[u8]>) -> Result<DataLayout, ParseError> {
     }
 }
 
+fn decode_code_kind(cursor: &mut Cursor<&[u8]>, version: u16) -> Result<u8, ParseError> {
+    if version == 0 {
+        return Ok(CODE_INLINE);
+    }
+
+    match cursor.read_byte() {
+        Err(..) => Err(ParseError::NotEnoughBytes(Field::CodeKind)),
+        Ok(kind @ CODE_INLINE) | Ok(kind @ CODE_HASH) => Ok(kind),
+        Ok(..) => Err(ParseError::UnexpectedLayout(Field::CodeKind)),
+    }
+}
+
+fn decode_code_hash(cursor: &mut Cursor<&[u8]>) -> Result<CodeHash, ParseError> {
+    let mut code_hash = [0; 32];
+
+    cursor
+        .read_exact(&mut code_hash)
+        .map_err(|_| ParseError::NotEnoughBytes(Field::CodeHash))?;
+
+    Ok(code_hash)
+}
+
 fn decode_code(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, ParseError> {
     match cursor.read_u32_be() {
         Err(..) => Err(ParseError::NotEnoughBytes(Field::Code)),
@@ -169,4 +262,70 @@ mod tests {
 
         assert_eq!(template, decoded);
     }
+
+    #[test]
+    fn encode_decode_deploy_template_v1() {
+        let template = AppTemplate {
+            version: 1,
+            name: "My Template".to_string(),
+            code: vec![0x0C, 0x00, 0x0D, 0x0E],
+            data: vec![5, 10].into(),
+            ctors: vec!["init".into()],
+        };
+
+        let mut bytes = Vec::new();
+        encode_deploy_template(&template, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_deploy_template(&mut cursor).unwrap();
+
+        assert_eq!(template, decoded);
+    }
+
+    #[test]
+    fn encode_decode_compact_deploy_template() {
+        let template = AppTemplate {
+            version: 1,
+            name: "My Template".to_string(),
+            code: Vec::new(),
+            data: vec![5, 10].into(),
+            ctors: vec!["init".into()],
+        };
+        let code_hash = [0xAB; 32];
+
+        let mut bytes = Vec::new();
+        encode_compact_deploy_template(&template, &code_hash, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_compact_deploy_template(&mut cursor).unwrap();
+
+        assert_eq!((template, Some(code_hash)), decoded);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_deploy_template(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::CodeNotFound);
+    }
+
+    #[test]
+    fn decode_deploy_template_invalid_code_kind() {
+        let template = AppTemplate {
+            version: 1,
+            name: "My Template".to_string(),
+            code: vec![0x0C, 0x00, 0x0D, 0x0E],
+            data: vec![5].into(),
+            ctors: vec!["init".into()],
+        };
+
+        let mut bytes = Vec::new();
+        encode_deploy_template(&template, &mut bytes);
+
+        // the code kind follows the version (2 bytes) and name (1 + 11 bytes)
+        bytes[14] = 2;
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_deploy_template(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::UnexpectedLayout(Field::CodeKind));
+    }
 }
@@ -28,8 +28,8 @@ impl AppTemplateDeserializer for DefaultAppTemplateDeserializer {
     fn deserialize(bytes: &[u8]) -> Option<(AppTemplate, AuthorAddr)> {
         let mut cursor = Cursor::new(bytes);
 
-        let template = match template::decode_deploy_template(&mut cursor) {
-            Ok(template) => template,
+        let template = match template::raw::decode_template(&mut cursor) {
+            Ok((template, None)) => template,
             _ => return None,
         };
 
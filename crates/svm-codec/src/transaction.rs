@@ -1,4 +1,4 @@
-//! Execute `AppTransaction` Raw Format Version 0.0
+//! Execute `AppTransaction` Raw Format
 //!
 //!  +--------------------------------------------+
 //!  |             |                              |
@@ -6,6 +6,9 @@
 //!  |  (2 bytes)  |         (20 bytes)           |
 //!  |_____________|______________________________|
 //!  |                                            |
+//!  |    value (8 bytes, starting version `1`)   |
+//!  |____________________________________________|
+//!  |                                            |
 //!  |            Function (String)               |
 //!  |____________________________________________|
 //!  |              |                             |
@@ -13,35 +16,44 @@
 //!  |   #length    |         (blob)              |
 //!  +____________  |_____________________________+
 //!
+//! Version `0` transactions have no `value` field (their `value` is `0`).
 //!
 
 use std::io::{Cursor, Read};
 
 use svm_types::{AppAddr, AppTransaction};
 
+use crate::version::Format;
 use crate::{calldata, common};
 use crate::{Field, ParseError, ReadExt, WriteExt};
 
 /// Encodes a raw App transaction.
+///
+/// Transactions of version `0` can't carry a `value`.
 pub fn encode_exec_app(tx: &AppTransaction, w: &mut Vec<u8>) {
     encode_version(tx, w);
     encode_app(tx, w);
+    encode_value(tx, w);
     encode_func(tx, w);
     encode_calldata(tx, w);
 }
 
 /// Parsing a raw `AppTransaction` transaction given as raw bytes.
 /// Returns the parsed transaction as a `AppTransaction` struct.
-/// On failure, returns `ParseError`.
+/// On failure (including when there are trailing bytes), returns `ParseError`.
 pub fn decode_exec_app(cursor: &mut Cursor<&[u8]>) -> Result<AppTransaction, ParseError> {
     let version = decode_version(cursor)?;
     let app = decode_app(cursor)?;
+    let value = decode_value(cursor, version)?;
     let func_name = decode_func(cursor)?;
     let calldata = calldata::decode_calldata(cursor)?;
 
+    common::decode_eof(cursor)?;
+
     let tx = AppTransaction {
         version,
         app,
+        value,
         func_name,
         calldata,
     };
@@ -63,6 +75,14 @@ fn encode_app(tx: &AppTransaction, w: &mut Vec<u8>) {
     w.write_address(addr);
 }
 
+fn encode_value(tx: &AppTransaction, w: &mut Vec<u8>) {
+    if tx.version >= 1 {
+        w.write_u64_be(tx.value);
+    } else {
+        assert_eq!(tx.value, 0);
+    }
+}
+
 fn encode_func(tx: &AppTransaction, w: &mut Vec<u8>) {
     let func = &tx.func_name;
 
@@ -79,7 +99,7 @@ fn encode_calldata(tx: &AppTransaction, w: &mut Vec<u8>) {
 
 #[inline]
 fn decode_version(cursor: &mut Cursor<&[u8]>) -> Result<u16, ParseError> {
-    common::decode_version(cursor)
+    common::decode_version(cursor, Format::ExecApp)
 }
 
 fn decode_app(cursor: &mut Cursor<&[u8]>) -> Result<AppAddr, ParseError> {
@@ -89,6 +109,16 @@ fn decode_app(cursor: &mut Cursor<&[u8]>) -> Result<AppAddr, ParseError> {
     }
 }
 
+fn decode_value(cursor: &mut Cursor<&[u8]>, version: u16) -> Result<u64, ParseError> {
+    if version == 0 {
+        return Ok(0);
+    }
+
+    cursor
+        .read_u64_be()
+        .map_err(|_| ParseError::NotEnoughBytes(Field::Value))
+}
+
 fn decode_func(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseError> {
     match cursor.read_string() {
         Ok(Ok(func)) => Ok(func),
@@ -106,8 +136,9 @@ mod tests {
     #[test]
     fn encode_decode_exec_app() {
         let tx = AppTransaction {
-            version: 0,
+            version: 1,
             app: Address::of("my-app").into(),
+            value: 100,
             func_name: "do_work".to_string(),
             calldata: vec![0x10, 0x0, 0x30],
         };
@@ -120,4 +151,74 @@ mod tests {
 
         assert_eq!(tx, decoded);
     }
+
+    #[test]
+    fn decode_exec_app_unsupported_version() {
+        let tx = AppTransaction {
+            version: 0xFF,
+            app: Address::of("my-app").into(),
+            value: 100,
+            func_name: "do_work".to_string(),
+            calldata: vec![0x10, 0x0, 0x30],
+        };
+
+        let mut bytes = Vec::new();
+        encode_exec_app(&tx, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_exec_app(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::UnsupportedVersion(0xFF));
+    }
+
+    #[test]
+    fn decode_exec_app_trailing_bytes() {
+        let tx = AppTransaction {
+            version: 1,
+            app: Address::of("my-app").into(),
+            value: 100,
+            func_name: "do_work".to_string(),
+            calldata: vec![0x10, 0x0, 0x30],
+        };
+
+        let mut bytes = Vec::new();
+        encode_exec_app(&tx, &mut bytes);
+        bytes.push(0xFF);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let err = decode_exec_app(&mut cursor).unwrap_err();
+
+        assert_eq!(err, ParseError::ExpectedEOF);
+    }
+
+    #[test]
+    fn decode_exec_app_version_0() {
+        let app = Address::of("my-app");
+
+        // a version `0` transaction has no `value` field
+        let mut bytes = Vec::new();
+        common::encode_version(0, &mut bytes);
+        bytes.write_address(&app);
+        bytes.write_string("do_work");
+        calldata::encode_calldata(&[0x10, 0x0, 0x30], &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = decode_exec_app(&mut cursor).unwrap();
+
+        let tx = AppTransaction {
+            version: 0,
+            app: app.into(),
+            value: 0,
+            func_name: "do_work".to_string(),
+            calldata: vec![0x10, 0x0, 0x30],
+        };
+
+        assert_eq!(decoded, tx);
+
+        // and it's encoded back the same
+        let mut encoded = Vec::new();
+        encode_exec_app(&tx, &mut encoded);
+
+        assert_eq!(encoded, bytes);
+    }
 }
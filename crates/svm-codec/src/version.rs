@@ -0,0 +1,59 @@
+//! Raw formats versioning.
+//!
+//! Every raw format starts with a version (see `common::encode_version`).
+//! Each format lists the versions it supports, and decoders reject any other version
+//! with `ParseError::UnsupportedVersion`.
+//!
+//! Introducing a new version of a format means appending it to the format's versions
+//! (and having its decoder dispatch on the decoded version), so data encoded using
+//! older versions (transactions and receipts alike) remains decodable.
+
+/// The versioned raw formats
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    DeployTemplate,
+    SpawnApp,
+    ExecApp,
+    HostCtx,
+    Receipt,
+}
+
+/// Version `1` prefixes the template code with its kind (see the `template` module),
+/// allowing the code to be referenced by its hash instead of being included in full.
+const DEPLOY_TEMPLATE_VERSIONS: &[u16] = &[0, 1];
+
+/// Version `1` adds the `value` field (see the `app` module).
+const SPAWN_APP_VERSIONS: &[u16] = &[0, 1];
+
+/// Version `1` adds the `value` field (see the `transaction` module).
+const EXEC_APP_VERSIONS: &[u16] = &[0, 1];
+
+const HOST_CTX_VERSIONS: &[u16] = &[0];
+
+/// Version `1` appends the balance changes to the `Spawn App` and `Exec App` receipts,
+/// and version `2` appends the storage changes to the `Exec App` receipts (see the `receipt` module).
+const RECEIPT_VERSIONS: &[u16] = &[0, 1, 2];
+
+impl Format {
+    /// The versions supported by the format (in ascending order)
+    pub fn versions(&self) -> &'static [u16] {
+        match self {
+            Format::DeployTemplate => DEPLOY_TEMPLATE_VERSIONS,
+            Format::SpawnApp => SPAWN_APP_VERSIONS,
+            Format::ExecApp => EXEC_APP_VERSIONS,
+            Format::HostCtx => HOST_CTX_VERSIONS,
+            Format::Receipt => RECEIPT_VERSIONS,
+        }
+    }
+
+    /// The most recent version of the format
+    pub fn latest(&self) -> u16 {
+        *self.versions().last().unwrap()
+    }
+
+    /// Returns whether the format supports `version`
+    pub fn is_supported(&self, version: u16) -> bool {
+        self.versions().contains(&version)
+    }
+}
@@ -0,0 +1,560 @@
+use std::io::Cursor;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use svm_abi_encoder::Encoder;
+use svm_codec::envelope::{self, Envelope, SignedEnvelope, TxType};
+use svm_codec::template::CodeHash;
+use svm_codec::version::Format;
+use svm_codec::{app, host_ctx, receipt, template, transaction, ParseError};
+use svm_sdk_types::Amount;
+use svm_types::gas::MaybeGas;
+use svm_types::receipt::{
+    BalanceChange, ExecReceipt, Log, ReceiptError, SpawnAppReceipt, StorageChange, TemplateReceipt,
+};
+use svm_types::{
+    Address, App, AppAddr, AppTemplate, AppTransaction, HostCtx, SpawnApp, State, TemplateAddr,
+};
+
+// Each transaction type is checked for:
+//
+// * round-tripping: `decode(encode(x)) == x`
+// * canonical decoding: `encode(decode(bytes)) == bytes` for every `bytes` that decodes successfully.
+//   (the `bytes` are obtained by mutating a valid encoding)
+
+fn abi_encode<T: Encoder>(value: T) -> Vec<u8> {
+    let mut w = Vec::new();
+    value.encode(&mut w);
+
+    w
+}
+
+fn abi_value() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        any::<bool>().prop_map(abi_encode),
+        any::<i8>().prop_map(abi_encode),
+        any::<u8>().prop_map(abi_encode),
+        any::<i16>().prop_map(abi_encode),
+        any::<u16>().prop_map(abi_encode),
+        any::<i32>().prop_map(abi_encode),
+        any::<u32>().prop_map(abi_encode),
+        any::<i64>().prop_map(abi_encode),
+        any::<u64>().prop_map(abi_encode),
+        any::<u64>().prop_map(|v| abi_encode(Amount(v))),
+        any::<[u8; 20]>().prop_map(|v| abi_encode(svm_sdk_types::Address::from(v))),
+        vec(any::<u32>(), 0..12).prop_map(abi_encode),
+    ]
+}
+
+fn calldata() -> impl Strategy<Value = Vec<u8>> {
+    vec(abi_value(), 0..4)
+        .prop_map(|values| values.concat())
+        .prop_filter("`Calldata` is limited to 255 bytes", |calldata| {
+            calldata.len() <= 255
+        })
+}
+
+fn address() -> impl Strategy<Value = Address> {
+    any::<[u8; 20]>().prop_map(Address::from)
+}
+
+fn addrs() -> impl Strategy<Value = (Address, Address)> {
+    (address(), address())
+}
+
+fn state() -> impl Strategy<Value = State> {
+    any::<[u8; 32]>().prop_map(State::from)
+}
+
+fn string() -> impl Strategy<Value = String> {
+    "\\PC{0,32}"
+}
+
+fn template() -> impl Strategy<Value = AppTemplate> {
+    (
+        string(),
+        vec(any::<u8>(), 0..128),
+        vec(1..=std::u16::MAX as u32, 0..8),
+        vec(string(), 0..4),
+        0..=1u16,
+    )
+        .prop_map(|(name, code, vars, ctors, version)| AppTemplate {
+            version,
+            name,
+            code,
+            data: vars.into(),
+            ctors,
+        })
+}
+
+fn compact_template() -> impl Strategy<Value = (AppTemplate, Option<CodeHash>)> {
+    (template(), any::<[u8; 32]>()).prop_map(|(mut template, code_hash)| {
+        template.version = 1;
+        template.code = Vec::new();
+
+        (template, Some(code_hash))
+    })
+}
+
+fn encode_compact_template(compact: &(AppTemplate, Option<CodeHash>), w: &mut Vec<u8>) {
+    match compact {
+        (template, Some(code_hash)) => {
+            template::encode_compact_deploy_template(template, code_hash, w)
+        }
+        (template, None) => template::encode_deploy_template(template, w),
+    }
+}
+
+fn spawn_app() -> impl Strategy<Value = SpawnApp> {
+    (
+        address(),
+        any::<u64>(),
+        string(),
+        string(),
+        calldata(),
+        0..=1u16,
+    )
+        .prop_map(
+            |(template, value, name, ctor_name, calldata, version)| SpawnApp {
+                version,
+                app: App {
+                    name,
+                    template: TemplateAddr::new(template),
+                },
+                // version `0` has no `value` field
+                value: if version == 0 { 0 } else { value },
+                ctor_name,
+                calldata,
+            },
+        )
+}
+
+fn exec_app() -> impl Strategy<Value = AppTransaction> {
+    (address(), any::<u64>(), string(), calldata(), 0..=1u16).prop_map(
+        |(app, value, func_name, calldata, version)| AppTransaction {
+            version,
+            app: AppAddr::new(app),
+            // version `0` has no `value` field
+            value: if version == 0 { 0 } else { value },
+            func_name,
+            calldata,
+        },
+    )
+}
+
+fn host_ctx() -> impl Strategy<Value = HostCtx> {
+    vec((any::<u32>(), vec(any::<u8>(), 0..32)), 0..8).prop_map(|fields| {
+        let mut host_ctx = HostCtx::new();
+
+        for (field, value) in fields {
+            host_ctx.set(field, value);
+        }
+
+        host_ctx
+    })
+}
+
+fn signed_envelope() -> impl Strategy<Value = SignedEnvelope> {
+    let tx_type = prop_oneof![
+        Just(TxType::DeployTemplate),
+        Just(TxType::SpawnApp),
+        Just(TxType::ExecApp),
+    ];
+
+    (
+        (
+            tx_type,
+            address(),
+            any::<[u64; 4]>(),
+            vec(any::<u8>(), 0..64),
+        ),
+        any::<u8>(),
+        vec(any::<u8>(), 0..64),
+        vec(any::<u8>(), 0..64),
+    )
+        .prop_map(
+            |(
+                (tx_type, sender, [value, gas_limit, gas_price, nonce], payload),
+                scheme,
+                public_key,
+                signature,
+            )| {
+                SignedEnvelope {
+                    envelope: Envelope {
+                        tx_type,
+                        sender,
+                        value,
+                        gas_limit,
+                        gas_price,
+                        nonce,
+                        payload,
+                    },
+                    scheme,
+                    public_key,
+                    signature,
+                }
+            },
+        )
+}
+
+fn logs() -> impl Strategy<Value = Vec<Log>> {
+    vec(
+        (vec(any::<u8>(), 0..32), any::<u8>()).prop_map(|(msg, code)| Log { msg, code }),
+        0..4,
+    )
+}
+
+fn balance_changes() -> impl Strategy<Value = Vec<BalanceChange>> {
+    vec(
+        (address(), any::<u64>(), any::<u64>()).prop_map(|(addr, old_balance, new_balance)| {
+            BalanceChange {
+                addr,
+                old_balance,
+                new_balance,
+            }
+        }),
+        0..4,
+    )
+}
+
+fn storage_changes() -> impl Strategy<Value = Option<Vec<StorageChange>>> {
+    let change = (any::<u32>(), vec(any::<u8>(), 0..16)).prop_flat_map(|(var_id, old_value)| {
+        let len = old_value.len();
+
+        vec(any::<u8>(), len).prop_map(move |new_value| StorageChange {
+            var_id,
+            old_value: old_value.clone(),
+            new_value,
+        })
+    });
+
+    proptest::option::of(vec(change, 0..4))
+}
+
+fn receipt_error() -> impl Strategy<Value = ReceiptError> {
+    prop_oneof![
+        Just(ReceiptError::OOG),
+        address().prop_map(|addr| ReceiptError::TemplateNotFound(addr.into())),
+        address().prop_map(|addr| ReceiptError::AppNotFound(addr.into())),
+        (addrs(), string()).prop_map(|((t, a), msg)| ReceiptError::CompilationFailed {
+            template_addr: t.into(),
+            app_addr: a.into(),
+            msg,
+        }),
+        (addrs(), string()).prop_map(|((t, a), msg)| ReceiptError::InstantiationFailed {
+            template_addr: t.into(),
+            app_addr: a.into(),
+            msg,
+        }),
+        (addrs(), string()).prop_map(|((t, a), func)| ReceiptError::FuncNotFound {
+            template_addr: t.into(),
+            app_addr: a.into(),
+            func,
+        }),
+        (addrs(), string(), string()).prop_map(|((t, a), func, msg)| {
+            ReceiptError::FuncFailed {
+                template_addr: t.into(),
+                app_addr: a.into(),
+                func,
+                msg,
+            }
+        }),
+        (addrs(), string(), string()).prop_map(|((t, a), func, msg)| {
+            ReceiptError::FuncNotAllowed {
+                template_addr: t.into(),
+                app_addr: a.into(),
+                func,
+                msg,
+            }
+        }),
+        (address(), any::<u64>(), any::<u64>()).prop_map(|(addr, balance, amount)| {
+            ReceiptError::InsufficientFunds {
+                addr,
+                balance,
+                amount,
+            }
+        }),
+        string().prop_map(ReceiptError::ParseFailed),
+        addrs().prop_map(|(t, a)| ReceiptError::MissingMemoryExport {
+            template_addr: t.into(),
+            app_addr: a.into(),
+        }),
+        (addrs(), any::<u32>(), any::<u32>()).prop_map(|((t, a), offset, len)| {
+            ReceiptError::MemoryOutOfBounds {
+                template_addr: t.into(),
+                app_addr: a.into(),
+                offset,
+                len,
+            }
+        }),
+        (addrs(), any::<u32>()).prop_map(|((t, a), var_id)| ReceiptError::InvalidVarId {
+            template_addr: t.into(),
+            app_addr: a.into(),
+            var_id,
+        }),
+        (addrs(), any::<u64>(), any::<u64>()).prop_map(|((t, a), size, limit)| {
+            ReceiptError::StorageLimitExceeded {
+                template_addr: t.into(),
+                app_addr: a.into(),
+                size,
+                limit,
+            }
+        }),
+        (addrs(), state()).prop_map(|((_t, a), state)| ReceiptError::UnknownState {
+            app_addr: a.into(),
+            state,
+        }),
+    ]
+}
+
+fn template_receipt() -> impl Strategy<Value = TemplateReceipt> {
+    let success = (address(), any::<u64>(), logs()).prop_map(|(addr, gas, logs)| TemplateReceipt {
+        version: 0,
+        success: true,
+        error: None,
+        addr: Some(addr.into()),
+        gas_used: MaybeGas::with(gas),
+        logs,
+    });
+
+    let failure =
+        (receipt_error(), logs()).prop_map(|(err, logs)| TemplateReceipt::from_err(err, logs));
+
+    prop_oneof![success, failure]
+}
+
+fn spawn_app_receipt() -> impl Strategy<Value = SpawnAppReceipt> {
+    let success = (
+        address(),
+        state(),
+        calldata(),
+        any::<u64>(),
+        logs(),
+        balance_changes(),
+    )
+        .prop_map(
+            |(addr, state, returndata, gas, logs, balance_changes)| SpawnAppReceipt {
+                version: Format::Receipt.latest(),
+                success: true,
+                error: None,
+                app_addr: Some(addr.into()),
+                init_state: Some(state),
+                returndata: Some(returndata),
+                gas_used: MaybeGas::with(gas),
+                logs,
+                balance_changes,
+            },
+        );
+
+    let failure =
+        (receipt_error(), logs()).prop_map(|(err, logs)| SpawnAppReceipt::from_err(err, logs));
+
+    prop_oneof![success, failure]
+}
+
+fn exec_app_receipt() -> impl Strategy<Value = ExecReceipt> {
+    let success = (
+        state(),
+        calldata(),
+        any::<u64>(),
+        logs(),
+        balance_changes(),
+        storage_changes(),
+    )
+        .prop_map(
+            |(state, returndata, gas, logs, balance_changes, storage_changes)| ExecReceipt {
+                version: Format::Receipt.latest(),
+                success: true,
+                error: None,
+                new_state: Some(state),
+                returndata: Some(returndata),
+                gas_used: MaybeGas::with(gas),
+                logs,
+                balance_changes,
+                storage_changes,
+                storage_changes_truncated: false,
+            },
+        );
+
+    let failure =
+        (receipt_error(), logs()).prop_map(|(err, logs)| ExecReceipt::from_err(err, logs));
+
+    prop_oneof![success, failure]
+}
+
+#[derive(Debug, Clone)]
+enum Mutation {
+    Replace(Index, u8),
+    Insert(Index, u8),
+    Remove(Index),
+    Truncate(Index),
+    Append(u8),
+}
+
+fn mutation() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        (any::<Index>(), any::<u8>()).prop_map(|(i, b)| Mutation::Replace(i, b)),
+        (any::<Index>(), any::<u8>()).prop_map(|(i, b)| Mutation::Insert(i, b)),
+        any::<Index>().prop_map(Mutation::Remove),
+        any::<Index>().prop_map(Mutation::Truncate),
+        any::<u8>().prop_map(Mutation::Append),
+    ]
+}
+
+fn mutate(mut bytes: Vec<u8>, mutation: &Mutation) -> Vec<u8> {
+    match *mutation {
+        Mutation::Replace(ref i, b) => {
+            let i = i.index(bytes.len());
+            bytes[i] = b;
+        }
+        Mutation::Insert(ref i, b) => {
+            let i = i.index(bytes.len() + 1);
+            bytes.insert(i, b);
+        }
+        Mutation::Remove(ref i) => {
+            let i = i.index(bytes.len());
+            bytes.remove(i);
+        }
+        Mutation::Truncate(ref i) => {
+            let i = i.index(bytes.len());
+            bytes.truncate(i);
+        }
+        Mutation::Append(b) => bytes.push(b),
+    }
+
+    bytes
+}
+
+/// Asserts that `bytes` are either rejected or are the canonical encoding of what they decode to.
+fn assert_canonical<T, D, E>(bytes: &[u8], decode: D, encode: E) -> Result<(), TestCaseError>
+where
+    D: Fn(&mut Cursor<&[u8]>) -> Result<T, ParseError>,
+    E: Fn(&T, &mut Vec<u8>),
+{
+    let mut cursor = Cursor::new(bytes);
+
+    if let Ok(decoded) = decode(&mut cursor) {
+        let mut encoded = Vec::new();
+        encode(&decoded, &mut encoded);
+
+        prop_assert_eq!(encoded, bytes.to_vec());
+    }
+
+    Ok(())
+}
+
+macro_rules! round_trip {
+    ($value:expr, $encode:path, $decode:path) => {{
+        let mut bytes = Vec::new();
+        $encode(&$value, &mut bytes);
+
+        let mut cursor = Cursor::new(&bytes[..]);
+        let decoded = $decode(&mut cursor).unwrap();
+
+        prop_assert_eq!(decoded, $value);
+
+        bytes
+    }};
+}
+
+proptest! {
+    #[test]
+    fn deploy_template_round_trip(template in template(), mutation in mutation()) {
+        let bytes = round_trip!(template, template::encode_deploy_template, template::decode_deploy_template);
+
+        let bytes = mutate(bytes, &mutation);
+        assert_canonical(&bytes, template::decode_deploy_template, template::encode_deploy_template)?;
+    }
+
+    #[test]
+    fn compact_deploy_template_round_trip(compact in compact_template(), mutation in mutation()) {
+        let bytes = round_trip!(compact, encode_compact_template, template::decode_compact_deploy_template);
+
+        let bytes = mutate(bytes, &mutation);
+        assert_canonical(&bytes, template::decode_compact_deploy_template, encode_compact_template)?;
+    }
+
+    #[test]
+    fn spawn_app_round_trip(spawn in spawn_app(), mutation in mutation()) {
+        let bytes = round_trip!(spawn, app::encode_spawn_app, app::decode_spawn_app);
+
+        let bytes = mutate(bytes, &mutation);
+        assert_canonical(&bytes, app::decode_spawn_app, app::encode_spawn_app)?;
+    }
+
+    #[test]
+    fn exec_app_round_trip(tx in exec_app(), mutation in mutation()) {
+        let bytes = round_trip!(tx, transaction::encode_exec_app, transaction::decode_exec_app);
+
+        let bytes = mutate(bytes, &mutation);
+        assert_canonical(&bytes, transaction::decode_exec_app, transaction::encode_exec_app)?;
+    }
+
+    #[test]
+    fn host_ctx_round_trip(ctx in host_ctx(), mutation in mutation()) {
+        let bytes = round_trip!(ctx, host_ctx::encode_host_ctx, host_ctx::decode_host_ctx);
+
+        let bytes = mutate(bytes, &mutation);
+        assert_canonical(&bytes, host_ctx::decode_host_ctx, host_ctx::encode_host_ctx)?;
+    }
+
+    #[test]
+    fn signed_envelope_round_trip(signed in signed_envelope(), mutation in mutation()) {
+        let bytes = round_trip!(signed, envelope::encode_signed_envelope, envelope::decode_signed_envelope);
+
+        let bytes = mutate(bytes, &mutation);
+        assert_canonical(&bytes, envelope::decode_signed_envelope, envelope::encode_signed_envelope)?;
+    }
+
+    #[test]
+    fn calldata_round_trip(calldata in calldata()) {
+        use svm_abi_decoder::{Cursor, Decoder};
+
+        let mut cursor = Cursor::new(&calldata);
+        let decoder = Decoder::new();
+        let mut encoded = Vec::new();
+
+        while !cursor.is_eof() {
+            let value = decoder.decode_value(&mut cursor).unwrap();
+
+            value.encode(&mut encoded);
+        }
+
+        prop_assert_eq!(encoded, calldata);
+    }
+
+    #[test]
+    fn template_receipt_round_trip(receipt in template_receipt(), mutation in mutation()) {
+        let bytes = receipt::encode_template_receipt(&receipt);
+
+        prop_assert_eq!(receipt::decode_template_receipt(&bytes), Ok(receipt));
+
+        // malformed receipts are rejected (rather than panicking)
+        let bytes = mutate(bytes, &mutation);
+        let _ = receipt::decode_receipt(&bytes);
+    }
+
+    #[test]
+    fn spawn_app_receipt_round_trip(receipt in spawn_app_receipt(), mutation in mutation()) {
+        let bytes = receipt::encode_app_receipt(&receipt);
+
+        prop_assert_eq!(receipt::decode_app_receipt(&bytes), Ok(receipt));
+
+        // malformed receipts are rejected (rather than panicking)
+        let bytes = mutate(bytes, &mutation);
+        let _ = receipt::decode_receipt(&bytes);
+    }
+
+    #[test]
+    fn exec_app_receipt_round_trip(receipt in exec_app_receipt(), mutation in mutation()) {
+        let bytes = receipt::encode_exec_receipt(&receipt);
+
+        prop_assert_eq!(receipt::decode_exec_receipt(&bytes), Ok(receipt));
+
+        // malformed receipts are rejected (rather than panicking)
+        let bytes = mutate(bytes, &mutation);
+        let _ = receipt::decode_receipt(&bytes);
+    }
+}
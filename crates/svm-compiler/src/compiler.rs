@@ -1,26 +1,71 @@
-use wasmer::{Module, Store, JIT};
+use std::fmt;
+use std::sync::Arc;
+
+use wasmer::{CompilerConfig, Cranelift, Module, Store, JIT};
 use wasmer_compiler::CompileError;
 
+use crate::middleware::{self, GasMeteringMiddleware, ParseError};
+
+/// Errors raised by `compile`
+#[derive(Debug)]
+pub enum CompilationError {
+    /// The wasm contains an opcode which isn't supported by `SVM`
+    Validation(ParseError),
+
+    /// `wasmer` failed compiling the wasm
+    Compile(CompileError),
+}
+
+impl fmt::Display for CompilationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompilationError::Validation(err) => err.fmt(f),
+            CompilationError::Compile(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<ParseError> for CompilationError {
+    fn from(err: ParseError) -> Self {
+        CompilationError::Validation(err)
+    }
+}
+
+impl From<CompileError> for CompilationError {
+    fn from(err: CompileError) -> Self {
+        CompilationError::Compile(err)
+    }
+}
+
 /// Compiles the SVM app
 ///
-// TODO:
-// ====
-// * inject `gas-metering` middleware.
-// * inject the validation middleware.
-#[must_use]
-pub fn compile(
-    store: &Store,
-    wasm: &[u8],
-    _gas_limit: u64,
-    _gas_metering: bool,
-) -> Result<Module, CompileError> {
-    Module::from_binary(&store, wasm)
+/// The wasm is validated to contain only opcodes supported by `SVM` (see `middleware::validate`),
+/// and the compiled `Module` is gas-metered (see `GasMeteringMiddleware`).
+/// The gas limit is set per instance, using `svm_compiler::set_gas_left`.
+///
+/// The compilation state (of the `GasMeteringMiddleware`) is owned by `store`, so modules of different
+/// `Store`(s) are compiled concurrently. The compilations using the same `Store` (or its clones) are serialized
+/// by its `wasmer` JIT engine (which owns the middlewares).
+#[must_use = "compiling a module without using it is a waste"]
+pub fn compile(store: &Store, wasm: &[u8]) -> Result<Module, CompilationError> {
+    middleware::validate(wasm)?;
+
+    let module = Module::from_binary(store, wasm)?;
+
+    Ok(module)
 }
 
 /// New fresh `Store`
+///
+/// Each `Store` has its own (fresh) middlewares, thus its own compilation state.
+/// The `Store` should be used for compiling modules only through `compile`.
 #[must_use]
 pub fn new_store() -> Store {
-    let engine = JIT::new(wasmer::Cranelift::default()).engine();
+    let mut compiler = Cranelift::default();
+
+    compiler.push_middleware(Arc::new(GasMeteringMiddleware::new()));
+
+    let engine = JIT::new(compiler).engine();
 
     Store::new(&engine)
 }
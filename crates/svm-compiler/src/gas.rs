@@ -0,0 +1,49 @@
+use wasmer::{Global, Instance, Value};
+
+use crate::middleware::{GAS_EXHAUSTED_GLOBAL, GAS_LEFT_GLOBAL};
+
+/// Sets the gas left for running `instance`.
+///
+/// # Panics
+///
+/// Panics if `instance` hasn't been compiled with the `GasMeteringMiddleware`.
+pub fn set_gas_left(instance: &Instance, gas_left: u64) {
+    let global = instance.exports.get_global(GAS_LEFT_GLOBAL).unwrap();
+
+    global.set(Value::I64(gas_left as i64)).unwrap();
+}
+
+/// Returns the gas left of `instance`.
+/// In case the instance has reached `Out-of-Gas` returns `None`.
+///
+/// # Panics
+///
+/// Panics if `instance` hasn't been compiled with the `GasMeteringMiddleware`.
+pub fn gas_left(instance: &Instance) -> Option<u64> {
+    let exhausted = instance.exports.get_global(GAS_EXHAUSTED_GLOBAL).unwrap();
+
+    if exhausted.get().unwrap_i32() != 0 {
+        return None;
+    }
+
+    let global = instance.exports.get_global(GAS_LEFT_GLOBAL).unwrap();
+    let gas_left = global.get().unwrap_i64() as u64;
+
+    Some(gas_left)
+}
+
+/// Returns the `Global` holding the gas left of `instance`.
+///
+/// Host functions charging gas should decrement it, so that the Wasm instructions
+/// and the host functions share the same budget.
+///
+/// # Panics
+///
+/// Panics if `instance` hasn't been compiled with the `GasMeteringMiddleware`.
+pub fn gas_left_global(instance: &Instance) -> Global {
+    instance
+        .exports
+        .get_global(GAS_LEFT_GLOBAL)
+        .unwrap()
+        .clone()
+}
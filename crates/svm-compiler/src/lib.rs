@@ -7,5 +7,16 @@
 //! Additionally, it implements required `wasmer` compiler milddlewares for `SVM` usage.
 
 mod compiler;
+mod gas;
 
-pub use compiler::{compile, new_store};
+/// Implements `wasmer` compiler middlewares.
+pub mod middleware;
+
+pub use compiler::{compile, new_store, CompilationError};
+
+/// The version of the native code emitted by `compile`.
+///
+/// It must be bumped on any change affecting the emitted code (e.g the middlewares or the gas metering),
+/// so that artifacts persisted by previous versions won't be loaded.
+pub const ARTIFACT_VERSION: u32 = 1;
+pub use gas::{gas_left, gas_left_global, set_gas_left};
@@ -1,4 +1,26 @@
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+use std::fmt;
+
+/// Errors raised by `validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
-    UnsupportedOpcode,
+    /// Function `func_index` contains `opcode` which isn't supported by `SVM`.
+    UnsupportedOpcode {
+        /// The function index (imported functions included)
+        func_index: u32,
+
+        /// The unsupported opcode name
+        opcode: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnsupportedOpcode { func_index, opcode } => write!(
+                f,
+                "function #{} contains an unsupported opcode `{}`",
+                func_index, opcode
+            ),
+        }
+    }
 }
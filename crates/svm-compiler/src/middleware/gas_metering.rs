@@ -0,0 +1,203 @@
+use std::sync::Mutex;
+
+use wasmer::wasmparser::{Operator, Type as WpType, TypeOrFuncType};
+use wasmer::{
+    ExportIndex, FunctionMiddleware, GlobalInit, GlobalType, LocalFunctionIndex, MiddlewareError,
+    MiddlewareReaderState, ModuleMiddleware, Mutability, Type,
+};
+use wasmer_types::GlobalIndex;
+use wasmer_vm::ModuleInfo;
+
+/// The name of the exported global holding the instance's gas left.
+pub const GAS_LEFT_GLOBAL: &str = "svm_gas_left";
+
+/// The name of the exported global signaling whether the instance has reached `Out-of-Gas`.
+pub const GAS_EXHAUSTED_GLOBAL: &str = "svm_gas_exhausted";
+
+/// The `GasMeteringMiddleware` injects gas-metering instructions into each function of the compiled module.
+///
+/// Two new globals are appended to the module (and exported):
+/// * `svm_gas_left` (`i64`)      - the gas left for running the instance.
+/// * `svm_gas_exhausted` (`i32`) - set to `1` right before trapping on `Out-of-Gas`.
+///
+/// The charging is done per basic-block. The accumulated price of the block's instructions
+/// is deducted right before the block terminates (i.e before any branching instruction).
+/// In case there is not enough gas left, the instance traps (by executing `unreachable`).
+///
+/// Both globals are initialized with zero, so it's the responsibility of the host to set
+/// the gas left (see `svm_compiler::set_gas_left`) prior to calling any of the instance functions.
+#[derive(Debug)]
+pub struct GasMeteringMiddleware {
+    /// The indexes of the injected globals (of the module being currently compiled).
+    globals: Mutex<Option<MeteringGlobals>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct MeteringGlobals {
+    gas_left: GlobalIndex,
+
+    exhausted: GlobalIndex,
+}
+
+impl GasMeteringMiddleware {
+    /// Creates a new `GasMeteringMiddleware`
+    pub fn new() -> Self {
+        Self {
+            globals: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for GasMeteringMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModuleMiddleware for GasMeteringMiddleware {
+    fn generate_function_middleware(&self, _: LocalFunctionIndex) -> Box<dyn FunctionMiddleware> {
+        let globals = self.globals.lock().unwrap();
+        let globals = globals.expect("`transform_module_info` should have been called first");
+
+        Box::new(FunctionGasMetering {
+            globals,
+            block_price: 0,
+        })
+    }
+
+    /// The same middleware (owned by the `Store`) is being used for compiling many modules.
+    /// That's why we're overriding the globals indexes each time a new module is about to be compiled.
+    ///
+    /// The `Store` JIT engine compiles its modules one at a time (see `svm_compiler::compile`),
+    /// so the indexes are never overridden while a module is being compiled.
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) {
+        let gas_left = module_info
+            .globals
+            .push(GlobalType::new(Type::I64, Mutability::Var));
+
+        module_info
+            .global_initializers
+            .push(GlobalInit::I64Const(0));
+
+        module_info
+            .exports
+            .insert(GAS_LEFT_GLOBAL.to_string(), ExportIndex::Global(gas_left));
+
+        let exhausted = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+
+        module_info.exports.insert(
+            GAS_EXHAUSTED_GLOBAL.to_string(),
+            ExportIndex::Global(exhausted),
+        );
+
+        let mut globals = self.globals.lock().unwrap();
+
+        *globals = Some(MeteringGlobals {
+            gas_left,
+            exhausted,
+        });
+    }
+}
+
+#[derive(Debug)]
+struct FunctionGasMetering {
+    globals: MeteringGlobals,
+
+    /// The accumulated price of the current basic-block.
+    block_price: u64,
+}
+
+impl FunctionMiddleware for FunctionGasMetering {
+    fn feed<'a>(
+        &mut self,
+        op: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        self.block_price += op_price(&op);
+
+        if is_block_end(&op) && self.block_price > 0 {
+            let gas_left = self.globals.gas_left.as_u32();
+            let exhausted = self.globals.exhausted.as_u32();
+            let price = self.block_price as i64;
+
+            state.extend(&[
+                // if gas_left < price {
+                //   exhausted = 1;
+                //   unreachable;
+                // }
+                Operator::GlobalGet {
+                    global_index: gas_left,
+                },
+                Operator::I64Const { value: price },
+                Operator::I64LtU,
+                Operator::If {
+                    ty: TypeOrFuncType::Type(WpType::EmptyBlockType),
+                },
+                Operator::I32Const { value: 1 },
+                Operator::GlobalSet {
+                    global_index: exhausted,
+                },
+                Operator::Unreachable,
+                Operator::End,
+                // gas_left = gas_left - price
+                Operator::GlobalGet {
+                    global_index: gas_left,
+                },
+                Operator::I64Const { value: price },
+                Operator::I64Sub,
+                Operator::GlobalSet {
+                    global_index: gas_left,
+                },
+            ]);
+
+            self.block_price = 0;
+        }
+
+        state.push_operator(op);
+
+        Ok(())
+    }
+}
+
+/// Returns whether `op` terminates the current basic-block.
+fn is_block_end(op: &Operator) -> bool {
+    matches!(
+        op,
+        Operator::Loop { .. }
+            | Operator::If { .. }
+            | Operator::End
+            | Operator::Else
+            | Operator::Br { .. }
+            | Operator::BrIf { .. }
+            | Operator::BrTable { .. }
+            | Operator::Call { .. }
+            | Operator::CallIndirect { .. }
+            | Operator::Return
+    )
+}
+
+/// The price of a single wasm instruction.
+///
+/// The pricing is aligned with the one used by `svm-gas` for gas estimation:
+/// * Structural instructions are free.
+/// * Calls are free (the callee function or vmcall is being charged on its own).
+/// * Any other instruction costs a single gas unit.
+fn op_price(op: &Operator) -> u64 {
+    match op {
+        Operator::Nop
+        | Operator::Block { .. }
+        | Operator::Loop { .. }
+        | Operator::If { .. }
+        | Operator::Else
+        | Operator::End
+        | Operator::Call { .. }
+        | Operator::CallIndirect { .. } => 0,
+        _ => 1,
+    }
+}
@@ -1,4 +1,7 @@
 mod error;
+mod gas_metering;
 mod validation;
 
-pub use validation::ValidationMiddleware;
+pub use error::ParseError;
+pub use gas_metering::{GasMeteringMiddleware, GAS_EXHAUSTED_GLOBAL, GAS_LEFT_GLOBAL};
+pub use validation::validate;
@@ -1,47 +1,49 @@
-use wasmer_runtime_core::{
-    codegen::{Event, EventSink, FunctionMiddleware},
-    module::ModuleInfo,
-    wasmparser::Operator,
-};
+use wasmer::wasmparser::{Import, ImportSectionEntryType, Operator, Parser, Payload};
 
 use super::error::ParseError;
 
-/// The `ValidationMiddleware` has two main objectives:
-/// * validation - make sure the wasm is valid and doesn't contain and opcodes not supported by `svm` (for example: floats)
-/// * preprocessing - we want to know whether the input contains loops or not.
-///   In case there no loop we can later compute ahead-of-time the gas for each function,
-///   otherwise we'll have a dynamic gas-metering used.
-pub struct ValidationMiddleware;
+/// Validates that `wasm` contains only opcodes supported by `SVM` (for example: no floats).
+///
+/// On failure, returns the first unsupported opcode found (along with its function index).
+///
+/// The functions are scanned sequentially, so the returned error is deterministic.
+/// (A `ModuleMiddleware` can't provide that since `wasmer` compiles the functions in parallel).
+/// A malformed `wasm` isn't rejected here (it's left for the compilation to reject).
+pub fn validate(wasm: &[u8]) -> Result<(), ParseError> {
+    let mut func_index = 0;
 
-impl ValidationMiddleware {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
-impl FunctionMiddleware for ValidationMiddleware {
-    type Error = ParseError;
-
-    fn feed_event<'a, 'b: 'a>(
-        &mut self,
-        event: Event<'a, 'b>,
-        _module_info: &ModuleInfo,
-        sink: &mut EventSink<'a, 'b>,
-        _source_loc: u32,
-    ) -> Result<(), Self::Error> {
-        match event {
-            Event::Wasm(op) => parse_wasm_opcode(op)?,
-            Event::WasmOwned(ref op) => parse_wasm_opcode(op)?,
-            _ => (),
-        };
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload {
+            Ok(Payload::ImportSection(reader)) => {
+                for import in reader {
+                    if let Ok(Import {
+                        ty: ImportSectionEntryType::Function(..),
+                        ..
+                    }) = import
+                    {
+                        func_index += 1;
+                    }
+                }
+            }
+            Ok(Payload::CodeSectionEntry(body)) => {
+                if let Ok(mut reader) = body.get_operators_reader() {
+                    while let Ok(op) = reader.read() {
+                        parse_wasm_opcode(&op, func_index)?;
+                    }
+                }
 
-        sink.push(event);
-        Ok(())
+                func_index += 1;
+            }
+            Ok(..) => (),
+            Err(..) => break,
+        }
     }
+
+    Ok(())
 }
 
 /// we explicitly whitelist the supported opcodes
-fn parse_wasm_opcode(opcode: &Operator) -> Result<(), ParseError> {
+fn parse_wasm_opcode(opcode: &Operator, func_index: u32) -> Result<(), ParseError> {
     match opcode {
         Operator::Unreachable
         | Operator::Nop
@@ -146,65 +148,22 @@ fn parse_wasm_opcode(opcode: &Operator) -> Result<(), ParseError> {
         | Operator::I64Rotr
         | Operator::I32WrapI64
         | Operator::I64ExtendI32S
+        | Operator::I64ExtendI32U
         | Operator::I32Extend8S
         | Operator::I32Extend16S
         | Operator::I64Extend8S
         | Operator::I64Extend16S
         | Operator::I64Extend32S => Ok(()),
-        _ => Err(ParseError::UnsupportedOpcode),
+        _ => Err(ParseError::UnsupportedOpcode {
+            func_index,
+            opcode: opcode_name(opcode),
+        }),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::compile_program;
-    use wasmer_runtime::error::CompileError;
-    use wasmer_runtime::{imports, Func};
-
-    #[test]
-    fn valid_wasm_instance_sanity() {
-        let input = r#"
-            (module
-                (func (export "sum") (param i32 i32) (result i32)
-                    get_local 0
-                    get_local 1
-                    i32.add
-                ))
-            "#;
-
-        let gas_metering = false;
-        let gas_limit = 0;
-        let wasm = wabt::wat2wasm(input).unwrap();
-        let module = compile_program(&wasm, gas_limit, gas_metering).unwrap();
-        let instance = module.instantiate(&imports! {}).unwrap();
+/// Returns the opcode name (without its immediates)
+fn opcode_name(opcode: &Operator) -> String {
+    let name = format!("{:?}", opcode);
 
-        let func: Func<(i32, i32), i32> = instance.exports.get("sum").unwrap();
-        let res = func.call(10, 20);
-        assert!(res.is_ok());
-        assert_eq!(30, res.unwrap());
-    }
-
-    #[test]
-    fn floats_are_not_supported() {
-        let input = r#"
-            (module
-                (func $to_float (param i32) (result f32)
-                    get_local 0
-                    f32.convert_u/i32
-                ))
-            "#;
-
-        let gas_metering = false;
-        let gas_limit = 0;
-        let wasm = wabt::wat2wasm(input).unwrap();
-        let res = compile_program(&wasm, gas_limit, gas_metering);
-
-        assert!(res.is_err());
-
-        if let Err(CompileError::InternalError { msg }) = res {
-            assert_eq!("Codegen(\"UnsupportedOpcode\")", msg.as_str());
-        } else {
-            unreachable!()
-        }
-    }
+    name.split(&[' ', '{', '('][..]).next().unwrap().to_string()
 }
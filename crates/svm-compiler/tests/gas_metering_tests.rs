@@ -0,0 +1,145 @@
+use std::sync::{Arc, Barrier};
+
+use wasmer::{imports, Instance, NativeFunc};
+
+fn instantiate(wat: &str, gas_left: u64) -> Instance {
+    let store = svm_compiler::new_store();
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = svm_compiler::compile(&store, &wasm).unwrap();
+
+    let instance = Instance::new(&module, &imports! {}).unwrap();
+    svm_compiler::set_gas_left(&instance, gas_left);
+
+    instance
+}
+
+#[test]
+fn gas_metering_straight_code() {
+    let wat = r#"
+        (module
+          (func (export "sum") (param i32 i32) (result i32)
+            local.get 0
+            local.get 1
+            i32.add))"#;
+
+    let instance = instantiate(wat, 10);
+
+    let func: NativeFunc<(i32, i32), i32> = instance.exports.get_native_function("sum").unwrap();
+    assert_eq!(func.call(10, 20).unwrap(), 30);
+
+    assert_eq!(svm_compiler::gas_left(&instance), Some(7));
+}
+
+#[test]
+fn gas_metering_if_else() {
+    let wat = r#"
+        (module
+          (func (export "run") (param i32) (result i32)
+            local.get 0
+            if (result i32)
+              i32.const 10
+              i32.const 20
+              i32.add
+            else
+              i32.const 30
+            end))"#;
+
+    let instance = instantiate(wat, 100);
+    let func: NativeFunc<i32, i32> = instance.exports.get_native_function("run").unwrap();
+
+    assert_eq!(func.call(1).unwrap(), 30);
+    assert_eq!(svm_compiler::gas_left(&instance), Some(100 - 1 - 3));
+
+    assert_eq!(func.call(0).unwrap(), 30);
+    assert_eq!(svm_compiler::gas_left(&instance), Some(100 - 1 - 3 - 1 - 1));
+}
+
+#[test]
+fn gas_metering_reaches_oog() {
+    let wat = r#"
+        (module
+          (func (export "run") (param i32)
+            (loop $loop
+              local.get 0
+              i32.const 1
+              i32.sub
+              local.tee 0
+              br_if $loop)))"#;
+
+    let instance = instantiate(wat, 100);
+    let func: NativeFunc<i32, ()> = instance.exports.get_native_function("run").unwrap();
+
+    // each iteration costs 5 gas units
+    assert!(func.call(10).is_ok());
+    assert_eq!(svm_compiler::gas_left(&instance), Some(50));
+
+    assert!(func.call(11).is_err());
+    assert_eq!(svm_compiler::gas_left(&instance), None);
+}
+
+#[test]
+fn gas_metering_concurrent_compilation_same_store() {
+    compile_concurrently(true);
+}
+
+#[test]
+fn gas_metering_concurrent_compilation_distinct_stores() {
+    compile_concurrently(false);
+}
+
+fn compile_concurrently(same_store: bool) {
+    // modules having a different number of globals (so their metering globals indexes differ)
+    // and many functions (so their compilations overlap)
+    let wat = |nglobals: usize| {
+        let globals = "(global i32 (i32.const 0))".repeat(nglobals);
+        let funcs =
+            "(func (param i32 i32) (result i32) local.get 0 local.get 1 i32.add)".repeat(200);
+
+        format!(
+            r#"
+            (module
+              {}
+              {}
+              (func (export "sum") (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add))"#,
+            globals, funcs
+        )
+    };
+
+    let store = svm_compiler::new_store();
+    let nthreads = 16;
+    let barrier = Arc::new(Barrier::new(nthreads));
+
+    let handles: Vec<_> = (0..nthreads)
+        .map(|nglobals| {
+            let store = if same_store {
+                store.clone()
+            } else {
+                svm_compiler::new_store()
+            };
+            let barrier = Arc::clone(&barrier);
+            let wasm = wat::parse_str(wat(nglobals)).unwrap();
+
+            std::thread::spawn(move || {
+                barrier.wait();
+
+                let module = svm_compiler::compile(&store, &wasm).unwrap();
+
+                let instance = Instance::new(&module, &imports! {}).unwrap();
+                svm_compiler::set_gas_left(&instance, 10);
+
+                let func: NativeFunc<(i32, i32), i32> =
+                    instance.exports.get_native_function("sum").unwrap();
+                assert_eq!(func.call(10, 20).unwrap(), 30);
+
+                assert_eq!(svm_compiler::gas_left(&instance), Some(7));
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+use svm_compiler::middleware::ParseError;
+use svm_compiler::CompilationError;
+
+fn compile(wat: &str) -> Result<(), CompilationError> {
+    let store = svm_compiler::new_store();
+    let wasm = wat::parse_str(wat).unwrap();
+
+    svm_compiler::compile(&store, &wasm).map(|_| ())
+}
+
+#[test]
+fn validation_integers_are_supported() {
+    let wat = r#"
+        (module
+          (func $sum (param i32 i32) (result i32)
+            local.get 0
+            local.get 1
+            i32.add)
+
+          (func (export "run") (param i32) (result i64)
+            local.get 0
+            local.get 0
+            call $sum
+            i64.extend_i32_u))"#;
+
+    assert!(compile(wat).is_ok());
+}
+
+#[test]
+fn validation_floats_are_not_supported() {
+    let wat = r#"
+        (module
+          (func $vmcall (import "svm" "vmcall"))
+
+          (func $nop
+            nop)
+
+          (func $to_float (param i32) (result f32)
+            local.get 0
+            f32.convert_i32_u))"#;
+
+    let err = compile(wat).unwrap_err();
+
+    match err {
+        CompilationError::Validation(ParseError::UnsupportedOpcode { func_index, opcode }) => {
+            assert_eq!(func_index, 2);
+            assert_eq!(opcode, "F32ConvertI32U");
+        }
+        _ => unreachable!(),
+    }
+
+    let expected = "function #2 contains an unsupported opcode `F32ConvertI32U`";
+    assert_eq!(err_to_string(wat), expected);
+}
+
+#[test]
+fn validation_unsupported_opcode_in_nested_block() {
+    let wat = r#"
+        (module
+          (func (export "run") (param i32)
+            local.get 0
+            if
+              f64.const 1.5
+              drop
+            end))"#;
+
+    let err = compile(wat).unwrap_err();
+
+    match err {
+        CompilationError::Validation(ParseError::UnsupportedOpcode { func_index, opcode }) => {
+            assert_eq!(func_index, 0);
+            assert_eq!(opcode, "F64Const");
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn validation_reports_the_first_unsupported_opcode() {
+    let wat = r#"
+        (module
+          (func $first (result f32)
+            f32.const 1.5)
+
+          (func $second (result f64)
+            f64.const 2.5))"#;
+
+    let expected = "function #0 contains an unsupported opcode `F32Const`";
+
+    for _ in 0..10 {
+        assert_eq!(err_to_string(wat), expected);
+    }
+}
+
+fn err_to_string(wat: &str) -> String {
+    compile(wat).unwrap_err().to_string()
+}
@@ -6,7 +6,7 @@ use crate::{
 
 use std::collections::HashMap;
 
-use parity_wasm::elements::{ImportCountType, Module};
+use parity_wasm::elements::{External, ImportCountType, Internal, Module};
 
 /// Reads wasm input and contruct a `Program` struct
 pub(crate) fn read_program(wasm: &[u8]) -> Result<Program, ProgramError> {
@@ -26,6 +26,8 @@ pub(crate) fn read_program(wasm: &[u8]) -> Result<Program, ProgramError> {
     let program = Program {
         functions,
         import_count,
+        imports: module_imports(&module),
+        exports: module_exports(&module),
     };
 
     Ok(program)
@@ -41,6 +43,34 @@ fn module_import_count(module: &Module) -> Result<u16, ProgramError> {
     }
 }
 
+fn module_imports(module: &Module) -> Vec<(String, String)> {
+    module.import_section().map_or_else(Vec::new, |section| {
+        section
+            .entries()
+            .iter()
+            .filter(|entry| matches!(entry.external(), External::Function(..)))
+            .map(|entry| (entry.module().to_string(), entry.field().to_string()))
+            .collect()
+    })
+}
+
+fn module_exports(module: &Module) -> HashMap<String, FuncIndex> {
+    module
+        .export_section()
+        .map_or_else(HashMap::new, |section| {
+            section
+                .entries()
+                .iter()
+                .filter_map(|entry| match *entry.internal() {
+                    Internal::Function(idx) if idx <= u32::from(u16::MAX) => {
+                        Some((entry.field().to_string(), FuncIndex(idx as u16)))
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+}
+
 #[inline]
 fn read_wasm(wasm: &[u8]) -> Result<Module, ProgramError> {
     parity_wasm::deserialize_buffer(wasm).map_err(|_| ProgramError::InvalidWasm)
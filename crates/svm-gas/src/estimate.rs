@@ -4,11 +4,13 @@ use crate::{
     error::ProgramError,
     function::{FuncIndex, FuncsGas},
     gas::Gas,
+    mode::EstimateMode,
     op::Op,
     program::Program,
     traits::VMCallsGasEstimator,
 };
 
+use std::cmp;
 use std::collections::HashMap;
 
 use parity_wasm::elements::Instruction;
@@ -18,11 +20,68 @@ static FUNC_BLOCK_MAX_DEPTH: usize = 256;
 /// Recursives a parsed program as `Program`.
 /// On success, returns for each function-index its estimated gas.
 /// On failure, returns an error.
-pub fn estimate_code<VME>(wasm: &[u8]) -> Result<HashMap<FuncIndex, Gas>, ProgramError>
+///
+/// The estimation is done under `EstimateMode::Static`.
+pub fn estimate_code<VME>(wasm: &[u8], vme: &VME) -> Result<HashMap<FuncIndex, Gas>, ProgramError>
+where
+    VME: VMCallsGasEstimator,
+{
+    estimate_code_with_mode(wasm, vme, EstimateMode::Static)
+}
+
+/// Same as `estimate_code`, but under the given `EstimateMode`.
+pub fn estimate_code_with_mode<VME>(
+    wasm: &[u8],
+    vme: &VME,
+    mode: EstimateMode,
+) -> Result<HashMap<FuncIndex, Gas>, ProgramError>
+where
+    VME: VMCallsGasEstimator,
+{
+    let program = crate::code_reader::read_program(wasm)?;
+
+    estimate_program(&program, vme, mode)
+}
+
+/// Same as `estimate_code_with_mode`, but returns the estimated gas of each exported function (by its export name).
+pub fn estimate_exports<VME>(
+    wasm: &[u8],
+    vme: &VME,
+    mode: EstimateMode,
+) -> Result<HashMap<String, Gas>, ProgramError>
 where
     VME: VMCallsGasEstimator,
 {
     let program = crate::code_reader::read_program(wasm)?;
+    let funcs_gas = estimate_program(&program, vme, mode)?;
+
+    let exports = program
+        .exports
+        .iter()
+        .map(|(name, &func_idx)| {
+            let gas = if program.is_imported(func_idx) {
+                let (module, import_name) = program.get_import(func_idx);
+
+                vme.estimate_code(func_idx, module, import_name)
+            } else {
+                funcs_gas[&func_idx]
+            };
+
+            (name.clone(), gas)
+        })
+        .collect();
+
+    Ok(exports)
+}
+
+fn estimate_program<VME>(
+    program: &Program,
+    vme: &VME,
+    mode: EstimateMode,
+) -> Result<HashMap<FuncIndex, Gas>, ProgramError>
+where
+    VME: VMCallsGasEstimator,
+{
     let funcs_ids = program.functions_ids();
 
     let mut funcs_blocks = FuncsBlocks::new();
@@ -30,13 +89,13 @@ where
     let mut call_graph = CallGraph::new(funcs_ids.clone());
 
     for &func_idx in funcs_ids.iter() {
-        estimate_func(func_idx, &program, &mut funcs_blocks, &mut call_graph)?;
+        estimate_func(func_idx, program, &mut funcs_blocks, &mut call_graph, mode)?;
     }
 
     call_graph.ensure_no_recursive_calls()?;
 
     for func_idx in call_graph.topological_sort().iter() {
-        let gas = estimate_func_gas::<VME>(*func_idx, &funcs_blocks, &funcs_gas);
+        let gas = estimate_func_gas(*func_idx, program, &funcs_blocks, &funcs_gas, vme);
         funcs_gas.set_func_gas(*func_idx, gas);
     }
 
@@ -48,10 +107,11 @@ fn estimate_func(
     program: &Program,
     funcs_blocks: &mut FuncsBlocks,
     call_graph: &mut CallGraph,
+    mode: EstimateMode,
 ) -> Result<(), ProgramError> {
     let func_body = program.get_function_body(func_idx).to_vec();
 
-    let (_, block) = estimate_func_block(func_idx, program, &func_body, 0, call_graph)?;
+    let (_, block) = estimate_func_block(func_idx, program, &func_body, 0, call_graph, mode)?;
     funcs_blocks.add_func_block(func_idx, block);
 
     Ok(())
@@ -63,17 +123,43 @@ fn estimate_func_block(
     block_ops: &[Instruction],
     block_offset: usize,
     call_graph: &mut CallGraph,
+    mode: EstimateMode,
 ) -> Result<(usize, OpsBlock), ProgramError> {
     let mut block = OpsBlock::new();
     let mut cursor = block_offset;
 
     while let Some(op) = block_ops.get(cursor) {
         match *op {
-            Instruction::Loop(..) => return Err(ProgramError::LoopNotAllowed),
-            Instruction::Br(..) => return Err(ProgramError::BrNotAllowed),
-            Instruction::BrIf(..) => return Err(ProgramError::BrIfNotAllowed),
-            Instruction::BrTable(..) => return Err(ProgramError::BrTableNotAllowed),
-            Instruction::CallIndirect(..) => return Err(ProgramError::CallIndirectNotAllowed),
+            Instruction::Loop(..)
+            | Instruction::Br(..)
+            | Instruction::BrIf(..)
+            | Instruction::BrTable(..)
+            | Instruction::CallIndirect(..)
+                if mode == EstimateMode::Static =>
+            {
+                return Err(crate::validation::static_mode_error(op))
+            }
+            Instruction::Loop(..) => {
+                let (cont_cursor, inner) = estimate_func_block(
+                    func_idx,
+                    program,
+                    block_ops,
+                    cursor + 1,
+                    call_graph,
+                    mode,
+                )?;
+
+                block.append(Op::Loop(inner));
+                cursor = cont_cursor;
+            }
+            Instruction::Br(..) | Instruction::BrIf(..) | Instruction::BrTable(..) => {
+                block.append(Op::Branch(op.clone()));
+                cursor += 1;
+            }
+            Instruction::CallIndirect(..) => {
+                block.append(Op::CallIndirect);
+                cursor += 1;
+            }
             Instruction::Call(to) => {
                 let to = FuncIndex(to as u16);
 
@@ -90,15 +176,27 @@ fn estimate_func_block(
                 cursor += 1;
             }
             Instruction::Block(..) => {
-                let (cont_cursor, inner) =
-                    estimate_func_block(func_idx, program, block_ops, cursor + 1, call_graph)?;
+                let (cont_cursor, inner) = estimate_func_block(
+                    func_idx,
+                    program,
+                    block_ops,
+                    cursor + 1,
+                    call_graph,
+                    mode,
+                )?;
 
                 block.append(Op::Block(inner));
                 cursor = cont_cursor;
             }
             Instruction::If(..) => {
-                let (if_cont_cursor, if_block) =
-                    estimate_func_block(func_idx, program, block_ops, cursor + 1, call_graph)?;
+                let (if_cont_cursor, if_block) = estimate_func_block(
+                    func_idx,
+                    program,
+                    block_ops,
+                    cursor + 1,
+                    call_graph,
+                    mode,
+                )?;
 
                 if let Some(Instruction::Else) = block_ops.get(if_cont_cursor) {
                     let (else_cont_cursor, else_block) = estimate_func_block(
@@ -107,6 +205,7 @@ fn estimate_func_block(
                         block_ops,
                         if_cont_cursor + 1,
                         call_graph,
+                        mode,
                     )?;
 
                     block.append(Op::IfElseBlock(if_block, else_block));
@@ -133,8 +232,10 @@ fn estimate_func_block(
 
 fn estimate_func_gas<VME>(
     func_idx: FuncIndex,
+    program: &Program,
     funcs_blocks: &FuncsBlocks,
     funcs_gas: &FuncsGas,
+    vme: &VME,
 ) -> Gas
 where
     VME: VMCallsGasEstimator,
@@ -145,11 +246,26 @@ where
 
     let func_block = funcs_blocks.get_func_block(func_idx);
     let block_ctx = BlockCtx::new(func_idx, func_block);
+    let (gas, _escapes) = estimate_block_gas(&block_ctx, program, funcs_gas, vme);
 
-    estimate_block_gas::<VME>(&block_ctx, funcs_gas)
+    gas
 }
 
-fn estimate_block_gas<VME>(ctx: &BlockCtx, funcs_gas: &FuncsGas) -> Gas
+/// Estimates the gas of a block.
+///
+/// Once a branching instruction (`br / br_if / br_table`) has been seen, any following instruction
+/// of the block might be skipped. So from that point on, the lower-gas boundary of each instruction is zero.
+/// A branch targeting an outer block (`br N` where `N > 0`) might skip the rest of the enclosing blocks too,
+/// up to the targeted one. Instructions following the targeted block aren't affected.
+///
+/// Returns the gas of the block, along with the number of enclosing blocks whose rest might be skipped
+/// by a branch within it.
+fn estimate_block_gas<VME>(
+    ctx: &BlockCtx,
+    program: &Program,
+    funcs_gas: &FuncsGas,
+    vme: &VME,
+) -> (Gas, u32)
 where
     VME: VMCallsGasEstimator,
 {
@@ -158,29 +274,74 @@ where
     }
 
     let mut gas = Gas::Fixed(0);
+    let mut branched = false;
+    let mut escapes = 0;
 
     for op in ctx.ops.0.iter() {
-        let op_gas = match *op {
-            Op::Plain(Instruction::Nop) => Gas::Fixed(0),
-            Op::Plain(..) => Gas::Fixed(1),
-            Op::Block(ref inner) => estimate_block_gas::<VME>(&ctx.child_block(inner), funcs_gas),
-            Op::VMCall(fid) => VME::estimate_code(fid),
-            Op::FuncCall(fid) => funcs_gas.get_func_gas(fid).unwrap(),
+        let skippable = branched;
+
+        // `skips` is the number of blocks (starting from the current one) whose rest might be skipped by `op`
+        let (op_gas, skips) = match *op {
+            Op::Plain(Instruction::Nop) => (Gas::Fixed(0), 0),
+            Op::Plain(..) => (Gas::Fixed(1), 0),
+            Op::Block(ref inner) => {
+                estimate_block_gas(&ctx.child_block(inner), program, funcs_gas, vme)
+            }
+            Op::Loop(ref inner) => {
+                let (body_gas, skips) =
+                    estimate_block_gas(&ctx.child_block(inner), program, funcs_gas, vme);
+
+                (Gas::unbounded(body_gas.min()), skips)
+            }
+            Op::Branch(ref op) => (Gas::Fixed(1), branch_depth(op) + 1),
+            Op::CallIndirect => (Gas::unbounded(0), 0),
+            Op::VMCall(fid) => {
+                let (module, name) = program.get_import(fid);
+
+                (vme.estimate_code(fid, module, name), 0)
+            }
+            Op::FuncCall(fid) => (funcs_gas.get_func_gas(fid).unwrap(), 0),
             Op::IfBlock(ref true_block) => {
-                let true_gas = estimate_block_gas::<VME>(&ctx.child_block(true_block), funcs_gas);
+                let (true_gas, skips) =
+                    estimate_block_gas(&ctx.child_block(true_block), program, funcs_gas, vme);
                 let else_gas = Gas::Fixed(0);
-                true_gas + else_gas
+
+                (true_gas + else_gas, skips)
             }
             Op::IfElseBlock(ref true_block, ref else_block) => {
-                let true_gas = estimate_block_gas::<VME>(&ctx.child_block(true_block), funcs_gas);
-                let else_gas = estimate_block_gas::<VME>(&ctx.child_block(else_block), funcs_gas);
+                let (true_gas, true_skips) =
+                    estimate_block_gas(&ctx.child_block(true_block), program, funcs_gas, vme);
+                let (else_gas, else_skips) =
+                    estimate_block_gas(&ctx.child_block(else_block), program, funcs_gas, vme);
 
-                true_gas + else_gas
+                (true_gas + else_gas, cmp::max(true_skips, else_skips))
             }
         };
 
-        gas *= op_gas;
+        if skippable {
+            // the instruction is either executed or skipped
+            gas *= op_gas + Gas::Fixed(0);
+        } else {
+            gas *= op_gas;
+        }
+
+        if skips > 0 {
+            branched = true;
+            escapes = cmp::max(escapes, skips - 1);
+        }
     }
 
-    gas
+    (gas, escapes)
+}
+
+/// Returns the (relative) depth of the outermost block targeted by a branching instruction.
+fn branch_depth(op: &Instruction) -> u32 {
+    match *op {
+        Instruction::Br(depth) | Instruction::BrIf(depth) => depth,
+        Instruction::BrTable(ref data) => data
+            .table
+            .iter()
+            .fold(data.default, |max, &depth| cmp::max(max, depth)),
+        _ => unreachable!(),
+    }
 }
@@ -8,7 +8,9 @@ pub enum Gas {
     /// Gas is known-ahead precisely
     Fixed(u64),
 
-    /// Gas is within a range
+    /// Gas is within a range.
+    ///
+    /// A `max` of `u64::MAX` stands for an unbounded range (see `Gas::unbounded`).
     Range {
         /// Lower-gas boundary. Below that the executed program will run out-of-gas
         min: u64,
@@ -19,10 +21,44 @@ pub enum Gas {
     },
 }
 
+impl Gas {
+    /// Returns a `Gas::Range` having no upper-boundary.
+    ///
+    /// It's used for code whose cost can't be determined statically (for example, code having loops).
+    /// Running such code relies on dynamic gas metering.
+    pub fn unbounded(min: u64) -> Self {
+        Gas::Range { min, max: u64::MAX }
+    }
+
+    /// Returns whether the `Gas` has no upper-boundary.
+    pub fn is_unbounded(&self) -> bool {
+        self.max() == u64::MAX
+    }
+
+    /// The lower-gas boundary.
+    pub fn min(&self) -> u64 {
+        match *self {
+            Gas::Fixed(n) => n,
+            Gas::Range { min, .. } => min,
+        }
+    }
+
+    /// The upper-gas boundary.
+    pub fn max(&self) -> u64 {
+        match *self {
+            Gas::Fixed(n) => n,
+            Gas::Range { max, .. } => max,
+        }
+    }
+}
+
 impl fmt::Debug for Gas {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Gas::Fixed(n) => write!(f, "Gas({})", n),
+            Gas::Range { min, .. } if self.is_unbounded() => {
+                write!(f, "Gas(min: {}, max: unbounded)", min)
+            }
             Gas::Range { min, max } => write!(f, "Gas(min: {}, max: {})", min, max),
         }
     }
@@ -58,24 +94,25 @@ impl Add for Gas {
 }
 
 /// `Mul` serves here as a logical `AND`
+///
+/// The addition saturates, so an unbounded `Gas` remains unbounded.
 impl Mul for Gas {
     type Output = Gas;
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
     fn mul(self, rhs: Gas) -> Self::Output {
         match (self, rhs) {
-            (Gas::Fixed(x), Gas::Fixed(y)) => Gas::Fixed(x + y),
+            (Gas::Fixed(x), Gas::Fixed(y)) => Gas::Fixed(x.saturating_add(y)),
             (Gas::Fixed(x), Gas::Range { min: a, max: b }) => Gas::Range {
-                min: a + x,
-                max: b + x,
+                min: a.saturating_add(x),
+                max: b.saturating_add(x),
             },
             (Gas::Range { min: a, max: b }, Gas::Fixed(x)) => Gas::Range {
-                min: a + x,
-                max: b + x,
+                min: a.saturating_add(x),
+                max: b.saturating_add(x),
             },
             (Gas::Range { min: a, max: b }, Gas::Range { min: c, max: d }) => Gas::Range {
-                min: a + c,
-                max: b + d,
+                min: a.saturating_add(c),
+                max: b.saturating_add(d),
             },
         }
     }
@@ -287,6 +324,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gas_mul_unbounded() {
+        assert_eq!(Gas::unbounded(1 + 3), Gas::unbounded(1) * Gas::Fixed(3));
+        assert_eq!(Gas::unbounded(1 + 3), Gas::Fixed(3) * Gas::unbounded(1));
+
+        assert_eq!(
+            Gas::unbounded(1 + 3),
+            Gas::Range { min: 1, max: 2 } * Gas::unbounded(3)
+        );
+
+        assert!((Gas::unbounded(0) * Gas::unbounded(0)).is_unbounded());
+    }
+
+    #[test]
+    fn gas_add_unbounded() {
+        assert_eq!(Gas::unbounded(1), Gas::unbounded(3) + Gas::Fixed(1));
+        assert_eq!(
+            Gas::unbounded(2),
+            Gas::Range { min: 2, max: 5 } + Gas::unbounded(3)
+        );
+    }
+
     #[test]
     fn gas_mul_range_range() {
         assert_eq!(
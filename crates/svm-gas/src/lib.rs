@@ -12,6 +12,7 @@ mod code_reader;
 mod estimate;
 mod function;
 mod gas;
+mod mode;
 mod op;
 mod program;
 mod validation;
@@ -22,7 +23,8 @@ pub mod error;
 /// This is the place for the crate traits
 pub mod traits;
 
-pub use estimate::estimate_code;
+pub use estimate::{estimate_code, estimate_code_with_mode, estimate_exports};
 pub use function::FuncIndex;
 pub use gas::Gas;
-pub use validation::validate_code;
+pub use mode::EstimateMode;
+pub use validation::{validate_code, validate_code_with_mode};
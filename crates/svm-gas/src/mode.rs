@@ -0,0 +1,15 @@
+/// The mode under which a program is validated and estimated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EstimateMode {
+    /// Only programs whose gas can be estimated statically are valid.
+    ///
+    /// Programs containing one of `loop / br / br_if / br_table / call_indirect` are rejected.
+    Static,
+
+    /// Programs containing one of `loop / br / br_if / br_table / call_indirect` are valid too.
+    ///
+    /// Functions using these constructs are estimated with an unbounded `Gas::Range`
+    /// and their execution relies on dynamic gas metering.
+    /// Functions without them are still estimated precisely.
+    Dynamic,
+}
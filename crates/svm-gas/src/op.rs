@@ -9,6 +9,9 @@ pub(crate) enum Op {
     Block(OpsBlock),
     IfBlock(OpsBlock),
     IfElseBlock(OpsBlock, OpsBlock),
+    Loop(OpsBlock),
+    Branch(Instruction),
+    CallIndirect,
     VMCall(FuncIndex),
     FuncCall(FuncIndex),
 }
@@ -5,6 +5,8 @@ use crate::function::{FuncBody, FuncIndex};
 #[derive(Debug)]
 pub(crate) struct Program {
     pub import_count: u16,
+    pub imports: Vec<(String, String)>,
+    pub exports: HashMap<String, FuncIndex>,
     pub functions: HashMap<FuncIndex, FuncBody>,
 }
 
@@ -13,6 +15,15 @@ impl Program {
         func_idx.0 < self.import_count
     }
 
+    /// Returns the `(module, name)` of an imported function
+    pub fn get_import(&self, func_idx: FuncIndex) -> (&str, &str) {
+        debug_assert!(self.is_imported(func_idx));
+
+        let (module, name) = &self.imports[func_idx.0 as usize];
+
+        (module, name)
+    }
+
     pub fn get_function_body(&self, func_idx: FuncIndex) -> &FuncBody {
         self.functions.get(&func_idx).as_ref().unwrap()
     }
@@ -2,6 +2,7 @@ use crate::{function::FuncIndex, Gas};
 
 /// Represents logicc that will give gas estimation for SVM vmcalls
 pub trait VMCallsGasEstimator {
-    /// Receives vmcall function index and returns its gas estimation
-    fn estimate_code(func_idx: FuncIndex) -> Gas;
+    /// Receives vmcall function index (along with its import `module` and `name`)
+    /// and returns its gas estimation
+    fn estimate_code(&self, func_idx: FuncIndex, module: &str, name: &str) -> Gas;
 }
@@ -1,4 +1,7 @@
-use crate::{call_graph::CallGraph, error::ProgramError, function::FuncIndex, program::Program};
+use crate::{
+    call_graph::CallGraph, error::ProgramError, function::FuncIndex, mode::EstimateMode,
+    program::Program,
+};
 
 use parity_wasm::elements::Instruction;
 
@@ -7,20 +10,28 @@ use parity_wasm::elements::Instruction;
 /// The wasm program is NOT valid when:
 /// * It has more than `std::u16::MAX` imported functions.
 /// * The sum of imported functions and program functions exceeds `std::u16::MAX`.
-/// * It contains one of: `loop / br / br_if / br_table / call_indirect`.
+/// * It contains one of: `loop / br / br_if / br_table / call_indirect`
+///   (see `validate_code_with_mode` for relaxing this rule).
 /// * It contains chain of recursive calls.
 ///   For example: function `F` calls function `G` which calls function `H` which calls again function `F`.
 ///   The recursive chain call is: `F -> G -> H -> F`.
 /// * It contains instructions using floats.
 ///
 pub fn validate_code(wasm: &[u8]) -> Result<(), ProgramError> {
+    validate_code_with_mode(wasm, EstimateMode::Static)
+}
+
+/// Same as `validate_code`, but under the given `EstimateMode`.
+///
+/// Under `EstimateMode::Dynamic` the wasm program may contain `loop / br / br_if / br_table / call_indirect`.
+pub fn validate_code_with_mode(wasm: &[u8], mode: EstimateMode) -> Result<(), ProgramError> {
     let program = crate::code_reader::read_program(wasm)?;
 
     let funcs_ids = program.functions_ids();
     let mut call_graph = CallGraph::new(funcs_ids.clone());
 
     for &func_idx in funcs_ids.iter() {
-        validate_func(func_idx, &program, &mut call_graph)?;
+        validate_func(func_idx, &program, &mut call_graph, mode)?;
     }
 
     call_graph.ensure_no_recursive_calls()?;
@@ -32,10 +43,11 @@ fn validate_func(
     func_idx: FuncIndex,
     program: &Program,
     call_graph: &mut CallGraph,
+    mode: EstimateMode,
 ) -> Result<(), ProgramError> {
     let func_body = program.get_function_body(func_idx).to_vec();
 
-    let _ = validate_func_block(func_idx, program, &func_body, 0, call_graph)?;
+    let _ = validate_func_block(func_idx, program, &func_body, 0, call_graph, mode)?;
 
     Ok(())
 }
@@ -46,16 +58,37 @@ fn validate_func_block(
     block_ops: &[Instruction],
     block_offset: usize,
     call_graph: &mut CallGraph,
+    mode: EstimateMode,
 ) -> Result<usize, ProgramError> {
     let mut cursor = block_offset;
 
     while let Some(op) = block_ops.get(cursor) {
         match *op {
-            Instruction::Loop(..) => return Err(ProgramError::LoopNotAllowed),
-            Instruction::Br(..) => return Err(ProgramError::BrNotAllowed),
-            Instruction::BrIf(..) => return Err(ProgramError::BrIfNotAllowed),
-            Instruction::BrTable(..) => return Err(ProgramError::BrTableNotAllowed),
-            Instruction::CallIndirect(..) => return Err(ProgramError::CallIndirectNotAllowed),
+            Instruction::Loop(..)
+            | Instruction::Br(..)
+            | Instruction::BrIf(..)
+            | Instruction::BrTable(..)
+            | Instruction::CallIndirect(..)
+                if mode == EstimateMode::Static =>
+            {
+                return Err(static_mode_error(op))
+            }
+            Instruction::Loop(..) => {
+                cursor = validate_func_block(
+                    func_idx,
+                    program,
+                    block_ops,
+                    cursor + 1,
+                    call_graph,
+                    mode,
+                )?;
+            }
+            Instruction::Br(..)
+            | Instruction::BrIf(..)
+            | Instruction::BrTable(..)
+            | Instruction::CallIndirect(..) => {
+                cursor += 1;
+            }
             Instruction::Call(to) => {
                 validate_func_index(to)?;
 
@@ -71,11 +104,24 @@ fn validate_func_block(
                 cursor += 1;
             }
             Instruction::Block(..) => {
-                cursor = validate_func_block(func_idx, program, block_ops, cursor + 1, call_graph)?;
+                cursor = validate_func_block(
+                    func_idx,
+                    program,
+                    block_ops,
+                    cursor + 1,
+                    call_graph,
+                    mode,
+                )?;
             }
             Instruction::If(..) => {
-                let if_cont_cursor =
-                    validate_func_block(func_idx, program, block_ops, cursor + 1, call_graph)?;
+                let if_cont_cursor = validate_func_block(
+                    func_idx,
+                    program,
+                    block_ops,
+                    cursor + 1,
+                    call_graph,
+                    mode,
+                )?;
 
                 if let Some(Instruction::Else) = block_ops.get(if_cont_cursor) {
                     let else_cont_cursor = validate_func_block(
@@ -84,6 +130,7 @@ fn validate_func_block(
                         block_ops,
                         if_cont_cursor + 1,
                         call_graph,
+                        mode,
                     )?;
                     cursor = else_cont_cursor;
                 } else {
@@ -106,6 +153,18 @@ fn validate_func_block(
     Ok(cursor)
 }
 
+/// Returns the error for an instruction which isn't allowed under `EstimateMode::Static`.
+pub(crate) fn static_mode_error(op: &Instruction) -> ProgramError {
+    match *op {
+        Instruction::Loop(..) => ProgramError::LoopNotAllowed,
+        Instruction::Br(..) => ProgramError::BrNotAllowed,
+        Instruction::BrIf(..) => ProgramError::BrIfNotAllowed,
+        Instruction::BrTable(..) => ProgramError::BrTableNotAllowed,
+        Instruction::CallIndirect(..) => ProgramError::CallIndirectNotAllowed,
+        _ => unreachable!(),
+    }
+}
+
 fn validate_func_index(func_idx: u32) -> Result<(), ProgramError> {
     if func_idx <= std::u16::MAX as u32 {
         Ok(())
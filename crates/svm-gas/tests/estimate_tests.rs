@@ -1,11 +1,13 @@
 use maplit::hashmap;
 
-use svm_gas::{estimate_code, traits::VMCallsGasEstimator, FuncIndex, Gas};
+use svm_gas::{
+    error::ProgramError, estimate_code, traits::VMCallsGasEstimator, EstimateMode, FuncIndex, Gas,
+};
 
 struct PanicVMMCallstimator;
 
 impl VMCallsGasEstimator for PanicVMMCallstimator {
-    fn estimate_code(_func_idx: FuncIndex) -> Gas {
+    fn estimate_code(&self, _func_idx: FuncIndex, _module: &str, _name: &str) -> Gas {
         panic!()
     }
 }
@@ -14,7 +16,15 @@ macro_rules! estimate_code {
     ($code:expr) => {{
         let wasm = wabt::wat2wasm($code).unwrap();
 
-        estimate_code::<PanicVMMCallstimator>(&wasm[..])
+        estimate_code(&wasm[..], &PanicVMMCallstimator)
+    }};
+}
+
+macro_rules! estimate_code_dynamic {
+    ($code:expr) => {{
+        let wasm = wabt::wat2wasm($code).unwrap();
+
+        svm_gas::estimate_code_with_mode(&wasm[..], &PanicVMMCallstimator, EstimateMode::Dynamic)
     }};
 }
 
@@ -306,3 +316,149 @@ fn estimate_if_stmt_with_else_nested() {
         res.unwrap()
     );
 }
+
+#[test]
+fn estimate_loop_in_static_mode_fails() {
+    let code = r#"
+          (module
+            (func $func0
+                (loop (nop))))
+        "#;
+
+    let res = estimate_code!(code);
+    assert_eq!(Err(ProgramError::LoopNotAllowed), res);
+}
+
+#[test]
+fn estimate_loop_in_dynamic_mode() {
+    let code = r#"
+          (module
+            (func $func0 (param i32)
+                (loop $loop
+                    (local.get 0)       ;; 1
+                    (i32.const 1)       ;; 2
+                    (i32.sub)           ;; 3
+                    (local.tee 0)       ;; 4
+                    (br_if $loop)))     ;; 5
+
+            (func $func1 (result i32)
+                (i32.const 1)
+                (i32.const 2)
+                (i32.add)))
+        "#;
+
+    let res = estimate_code_dynamic!(code);
+    assert_eq!(
+        hashmap! {
+            FuncIndex(0) => Gas::unbounded(5),
+            FuncIndex(1) => Gas::Fixed(3),
+        },
+        res.unwrap()
+    );
+}
+
+#[test]
+fn estimate_br_if_in_dynamic_mode() {
+    let code = r#"
+          (module
+            (func $func0 (param i32)
+                (block $out
+                    (local.get 0)       ;; fixed(1)
+                    (br_if $out)        ;; fixed(1)
+
+                    ;; from here on, the block instructions might be skipped
+                    (i32.const 1)       ;; range(0, 1)
+                    (drop))             ;; range(0, 1)
+
+                ;; the instructions following the targeted block are always executed
+                (i32.const 2)           ;; fixed(1)
+                (drop)))                ;; fixed(1)
+        "#;
+
+    let res = estimate_code_dynamic!(code);
+    assert_eq!(
+        hashmap! {
+            FuncIndex(0) => Gas::Range { min: 4, max: 6 },
+        },
+        res.unwrap()
+    );
+}
+
+#[test]
+fn estimate_code_after_if_with_br_if_in_dynamic_mode() {
+    let code = r#"
+          (module
+            (func $func0 (param i32)
+                (local.get 0)           ;; fixed(1)
+                (if
+                    (then
+                        (local.get 0)   ;; range(0, 1)
+                        (br_if 0)       ;; range(0, 1)
+                        (i32.const 1)   ;; range(0, 1)
+                        (drop)))        ;; range(0, 1)
+
+                ;; the `br_if` exits only the `if` block
+                (i32.const 2)           ;; fixed(1)
+                (drop)))                ;; fixed(1)
+        "#;
+
+    let res = estimate_code_dynamic!(code);
+    assert_eq!(
+        hashmap! {
+            FuncIndex(0) => Gas::Range { min: 3, max: 7 },
+        },
+        res.unwrap()
+    );
+}
+
+#[test]
+fn estimate_br_if_to_outer_block_in_dynamic_mode() {
+    let code = r#"
+          (module
+            (func $func0 (param i32)
+                (block $out
+                    (block
+                        (local.get 0)   ;; fixed(1)
+                        (br_if $out)    ;; fixed(1)
+                        (i32.const 1)   ;; range(0, 1)
+                        (drop))         ;; range(0, 1)
+
+                    ;; skipped when branching to `$out`
+                    (i32.const 2)       ;; range(0, 1)
+                    (drop))             ;; range(0, 1)
+
+                (i32.const 3)           ;; fixed(1)
+                (drop)))                ;; fixed(1)
+        "#;
+
+    let res = estimate_code_dynamic!(code);
+    assert_eq!(
+        hashmap! {
+            FuncIndex(0) => Gas::Range { min: 4, max: 8 },
+        },
+        res.unwrap()
+    );
+}
+
+#[test]
+fn estimate_call_func_with_loop_in_dynamic_mode() {
+    let code = r#"
+          (module
+            (func $func0
+                (loop (nop)))
+
+            (func $func1
+                (i32.const 0)
+                (drop)
+                (call $func0)))
+        "#;
+
+    let res = estimate_code_dynamic!(code);
+    assert_eq!(
+        hashmap! {
+            FuncIndex(0) => Gas::unbounded(0),
+            FuncIndex(1) => Gas::unbounded(2),
+        },
+        res.unwrap()
+    );
+}
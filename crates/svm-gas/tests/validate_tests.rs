@@ -1,4 +1,4 @@
-use svm_gas::{error::ProgramError, FuncIndex};
+use svm_gas::{error::ProgramError, EstimateMode, FuncIndex};
 
 macro_rules! validate_code {
     ($code:expr) => {{
@@ -123,3 +123,29 @@ fn validate_floats_not_allowed() {
     let res = validate_code!(code);
     assert_eq!(Err(ProgramError::FloatsNotAllowed), res);
 }
+
+#[test]
+fn validate_loops_and_branches_allowed_in_dynamic_mode() {
+    let code = r#"
+          (module
+            (table 1 funcref)
+            (type $t (func))
+
+            (func $func0 (param i32)
+                (block $out
+                    (loop $loop
+                        (local.get 0)
+                        (br_if $out)
+                        (i32.const 0)
+                        (call_indirect (type $t))
+                        (br $loop)))))
+        "#;
+
+    let wasm = wabt::wat2wasm(code).unwrap();
+
+    let res = svm_gas::validate_code_with_mode(&wasm[..], EstimateMode::Dynamic);
+    assert!(res.is_ok());
+
+    let res = svm_gas::validate_code(&wasm[..]);
+    assert_eq!(Err(ProgramError::LoopNotAllowed), res);
+}
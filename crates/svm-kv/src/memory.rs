@@ -55,6 +55,14 @@ impl RawKV for MemRawKV {
             self.map.insert(k, v);
         }
     }
+
+    fn delete(&mut self, keys: &[&[u8]]) {
+        info!("Deletes in-memory raw-kv keys");
+
+        for k in keys {
+            self.map.remove(*k);
+        }
+    }
 }
 
 impl Drop for MemRawKV {
@@ -1,3 +1,4 @@
+use std::io;
 use std::path::Path;
 
 use crate::traits::RawKV;
@@ -11,12 +12,15 @@ pub struct Rocksdb {
 
 impl Rocksdb {
     /// New `Rocksdb` under the given `path`
-    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+    ///
+    /// Returns an error when the database can't be opened (or created).
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
         info!("Opening rocksdb. (path = \"{}\")", path.as_ref().display());
 
-        Self {
-            db: rocksdb::DB::open_default(path).unwrap(),
-        }
+        let db =
+            rocksdb::DB::open_default(path).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+        Ok(Self { db })
     }
 }
 
@@ -49,6 +53,24 @@ impl RawKV for Rocksdb {
             panic!("failed storing changes.");
         }
     }
+
+    fn delete(&mut self, keys: &[&[u8]]) {
+        let mut batch = rocksdb::WriteBatch::default();
+
+        for k in keys {
+            let res = batch.delete(k);
+
+            if res.is_err() {
+                panic!("failed `delete`-ing bach data");
+            }
+        }
+
+        let res = self.db.write(batch);
+
+        if res.is_err() {
+            panic!("failed deleting keys.");
+        }
+    }
 }
 
 impl Drop for Rocksdb {
@@ -63,7 +85,7 @@ mod tests {
 
     #[test]
     fn rocksdb_sanity() {
-        let mut db = Rocksdb::new("rocksdb-tests");
+        let mut db = Rocksdb::new("rocksdb-tests").unwrap();
 
         let key = vec![10, 20, 30];
         let val = vec![40, 50, 60];
@@ -76,8 +98,11 @@ mod tests {
 
         drop(db);
 
-        let db = Rocksdb::new("rocksdb-tests");
+        let mut db = Rocksdb::new("rocksdb-tests").unwrap();
         let v = db.get(&key).unwrap();
         assert_eq!(val, v);
+
+        db.delete(&[&key[..]]);
+        assert!(db.get(&key).is_none());
     }
 }
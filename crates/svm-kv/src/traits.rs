@@ -13,4 +13,9 @@ pub trait RawKV {
     ///
     /// Each change is tuple denoting `(key, value)`
     fn set(&mut self, changes: &[(&[u8], &[u8])]);
+
+    /// Deletes a batch of keys.
+    ///
+    /// Deleting a missing key does nothing.
+    fn delete(&mut self, keys: &[&[u8]]);
 }
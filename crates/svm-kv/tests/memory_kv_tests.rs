@@ -81,3 +81,28 @@ fn clear() {
     assert_no_key!(kv, key1);
     assert_no_key!(kv, key2);
 }
+
+#[test]
+fn raw_kv_delete_keys() {
+    init();
+
+    let mut kv = MemRawKV::new();
+    let addr1 = Address::of("Alice");
+    let addr2 = Address::of("Bob");
+
+    let key1 = addr1.as_slice();
+    let key2 = addr2.as_slice();
+
+    let val1 = vec![10, 20, 30];
+    let val2 = vec![40, 50, 60];
+
+    let changes = [(&key1[..], &val1[..]), (&key2[..], &val2[..])];
+
+    kv.set(&changes);
+
+    let missing = Address::of("Carol");
+    kv.delete(&[&key1[..], missing.as_slice()]);
+
+    assert_no_key!(kv, key1);
+    assert_key_value!(kv, key2, val2);
+}
@@ -28,6 +28,13 @@ impl DataLayout {
         self.vars[vid]
     }
 
+    /// Returns varialbe's layout. i.e: `(offset, length)`
+    ///
+    /// Returns `None` when there is no layout to variable `var_id`
+    pub fn try_get_var(&self, var_id: VarId) -> Option<(u32, u32)> {
+        self.vars.get(var_id.0 as usize).copied()
+    }
+
     /// Returns a iterator over the data-layout variables.
     /// The iterators will return each time an entry of `(var_id, var_offset, var_length)`.
     pub fn iter(&self) -> DataLayoutIter {
@@ -141,6 +148,14 @@ mod tests {
         assert_eq!(layout.get_var(VarId(3)), (60, 40));
     }
 
+    #[test]
+    fn data_layout_try_get_var() {
+        let layout: DataLayout = vec![10, 20].into();
+
+        assert_eq!(layout.try_get_var(VarId(1)), Some((10, 20)));
+        assert_eq!(layout.try_get_var(VarId(2)), None);
+    }
+
     #[test]
     fn data_layout_iter() {
         let mut builder = DataLayoutBuilder::with_capacity(2);
@@ -1,7 +1,7 @@
 use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::ffi::c_void;
-use std::io;
+use std::io::{self, Cursor};
 use std::path::Path;
 use std::ptr::NonNull;
 use std::rc::Rc;
@@ -13,7 +13,7 @@ use svm_codec::receipt;
 
 use svm_layout::DataLayout;
 use svm_storage::kv::{ExternKV, StatefulKV};
-use svm_types::{Address, State, Type, WasmType};
+use svm_types::{Address, HostCtx, State, Type, WasmType};
 
 use svm_runtime::env::default::DefaultSerializerTypes;
 use svm_runtime::{gas::DefaultGasEstimator, Context, ExternImport, Runtime, RuntimePtr};
@@ -22,7 +22,9 @@ use svm_ffi::{
     svm_byte_array, svm_env_t, svm_func_callback_t, svm_resource_iter_t, svm_resource_t, tracking,
 };
 
-use crate::{raw_error, raw_io_error, raw_utf8_error, raw_validate_error, svm_result_t};
+use crate::{
+    raw_error, raw_io_error, raw_panic_error, raw_utf8_error, raw_validate_error, svm_result_t,
+};
 
 macro_rules! max_gas {
     ($estimation:expr) => {{
@@ -40,6 +42,7 @@ static VALIDATE_TX_APP_ADDR_TYPE: Type = Type::Str("svm_validate_tx app_addr");
 static DEPLOY_TEMPLATE_RECEIPT_TYPE: Type = Type::Str("deploy-template receipt");
 static SPAWN_APP_RECEIPT_TYPE: Type = Type::Str("spawn-app receipt");
 static EXEC_APP_RECEIPT_TYPE: Type = Type::Str("exec-app receipt");
+static RECEIPT_JSON_TYPE: Type = Type::Str("receipt json");
 static ENCODE_DEPLOY_TEMPLATE_TYPE: Type = Type::Str("svm_encode_app_template");
 static ENCODE_SPAWN_APP_TYPE: Type = Type::Str("svm_encode_spawn_app");
 static ENCODE_EXEC_APP_TYPE: Type = Type::Str("svm_encode_app_tx");
@@ -93,6 +96,34 @@ macro_rules! to_svm_byte_array {
     }};
 }
 
+/// Decodes the `HostCtx` given as raw bytes (see `svm_codec::host_ctx`).
+unsafe fn decode_host_ctx(host_ctx: svm_byte_array) -> Result<HostCtx, String> {
+    let bytes: &[u8] = host_ctx.into();
+    let mut cursor = Cursor::new(bytes);
+
+    svm_codec::host_ctx::decode_host_ctx(&mut cursor).map_err(|e| e.to_string())
+}
+
+/// Runs `$body` while catching any `panic` raised by it.
+///
+/// Unwinding across the FFI boundary is Undefined Behavior, so a caught `panic` is turned into
+/// `SVM_FAILURE` (its message is returned via the `$error` parameter).
+macro_rules! catch_unwind {
+    ($error:expr, $body:block) => {{
+        use std::panic::{self, AssertUnwindSafe};
+
+        match panic::catch_unwind(AssertUnwindSafe(|| $body)) {
+            Ok(res) => res,
+            Err(panic) => {
+                error!("caught a `panic` at the FFI boundary");
+
+                raw_panic_error(panic, $error);
+                svm_result_t::SVM_FAILURE
+            }
+        }
+    }};
+}
+
 /// Validates syntactically a raw `deploy template` transaction.
 ///
 /// Should be called while the transaction is in the `mempool` of the Host.
@@ -133,16 +164,18 @@ pub unsafe extern "C" fn svm_validate_template(
     bytes: svm_byte_array,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-
-    match runtime.validate_template(bytes.into()) {
-        Ok(()) => svm_result_t::SVM_SUCCESS,
-        Err(e) => {
-            error!("`svm_validate_template` returns `SVM_FAILURE`");
-            raw_validate_error(&e, error);
-            svm_result_t::SVM_FAILURE
+    catch_unwind!(error, {
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        match runtime.validate_template(bytes.into()) {
+            Ok(()) => svm_result_t::SVM_SUCCESS,
+            Err(e) => {
+                error!("`svm_validate_template` returns `SVM_FAILURE`");
+                raw_validate_error(&e, error);
+                svm_result_t::SVM_FAILURE
+            }
         }
-    }
+    })
 }
 
 /// Validates syntactically a raw `spawn app` transaction.
@@ -184,16 +217,18 @@ pub unsafe extern "C" fn svm_validate_app(
     bytes: svm_byte_array,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-
-    match runtime.validate_app(bytes.into()) {
-        Ok(()) => svm_result_t::SVM_SUCCESS,
-        Err(e) => {
-            error!("`svm_validate_app` returns `SVM_FAILURE`");
-            raw_validate_error(&e, error);
-            svm_result_t::SVM_FAILURE
+    catch_unwind!(error, {
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        match runtime.validate_app(bytes.into()) {
+            Ok(()) => svm_result_t::SVM_SUCCESS,
+            Err(e) => {
+                error!("`svm_validate_app` returns `SVM_FAILURE`");
+                raw_validate_error(&e, error);
+                svm_result_t::SVM_FAILURE
+            }
         }
-    }
+    })
 }
 
 /// Validates syntactically a raw `execute app` transaction.
@@ -235,25 +270,27 @@ pub unsafe extern "C" fn svm_validate_tx(
     bytes: svm_byte_array,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    debug!("`svm_validate_tx` start");
-
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-
-    match runtime.validate_tx(bytes.into()) {
-        Ok(addr) => {
-            // returning encoded `AppReceipt` as `svm_byte_array`.
-            // should call later `svm_receipt_destroy`
-            addr_to_svm_byte_array!(VALIDATE_TX_APP_ADDR_TYPE, app_addr, addr.unwrap());
-
-            debug!("`svm_validate_tx` returns `SVM_SUCCESS`");
-            svm_result_t::SVM_SUCCESS
-        }
-        Err(e) => {
-            error!("`svm_validate_tx` returns `SVM_FAILURE`");
-            raw_validate_error(&e, error);
-            svm_result_t::SVM_FAILURE
+    catch_unwind!(error, {
+        debug!("`svm_validate_tx` start");
+
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        match runtime.validate_tx(bytes.into()) {
+            Ok(addr) => {
+                // returning encoded `AppReceipt` as `svm_byte_array`.
+                // should call later `svm_receipt_destroy`
+                addr_to_svm_byte_array!(VALIDATE_TX_APP_ADDR_TYPE, app_addr, addr.unwrap());
+
+                debug!("`svm_validate_tx` returns `SVM_SUCCESS`");
+                svm_result_t::SVM_SUCCESS
+            }
+            Err(e) => {
+                error!("`svm_validate_tx` returns `SVM_FAILURE`");
+                raw_validate_error(&e, error);
+                svm_result_t::SVM_FAILURE
+            }
         }
-    }
+    })
 }
 
 /// Allocates space for the host imports.
@@ -588,40 +625,52 @@ pub unsafe extern "C" fn svm_runtime_create(
     imports: *mut c_void,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    debug!("`svm_runtime_create` start");
+    catch_unwind!(error, {
+        debug!("`svm_runtime_create` start");
 
-    let kv_path: Result<String, std::string::FromUtf8Error> = String::try_from(kv_path);
+        let kv_path: Result<String, std::string::FromUtf8Error> = String::try_from(kv_path);
 
-    if kv_path.is_err() {
-        raw_utf8_error(kv_path, error);
-        return svm_result_t::SVM_FAILURE;
-    }
+        if kv_path.is_err() {
+            raw_utf8_error(kv_path, error);
+            return svm_result_t::SVM_FAILURE;
+        }
 
-    let kv_path = kv_path.unwrap();
-    let imports = svm_ffi::as_mut::<Vec<ExternImport>>(imports);
+        let kv_path = kv_path.unwrap();
+        let imports = svm_ffi::as_mut::<Vec<ExternImport>>(imports);
 
-    let rocksdb_runtime = svm_runtime::create_rocksdb_runtime::<
-        &Path,
-        DefaultSerializerTypes,
-        DefaultGasEstimator,
-    >(Path::new(&kv_path), imports);
+        let rocksdb_runtime = svm_runtime::create_rocksdb_runtime::<
+            &Path,
+            DefaultSerializerTypes,
+            DefaultGasEstimator,
+        >(Path::new(&kv_path), imports);
 
-    let res = box_runtime!(runtime, rocksdb_runtime);
+        let rocksdb_runtime = match rocksdb_runtime {
+            Ok(rocksdb_runtime) => rocksdb_runtime,
+            Err(e) => {
+                raw_io_error(e, error);
+                return svm_result_t::SVM_FAILURE;
+            }
+        };
 
-    debug!("`svm_runtime_create` end");
+        let res = box_runtime!(runtime, rocksdb_runtime);
 
-    res
+        debug!("`svm_runtime_create` end");
+
+        res
+    })
 }
 
 /// Deploys a new app-template
 ///
+/// The `host_ctx` is given encoded (see `svm_codec::host_ctx`).
+///
 /// # Example
 ///
 /// ```rust, no_run
 /// use svm_runtime_c_api::*;
 ///
 /// use svm_ffi::svm_byte_array;
-/// use svm_types::{Address, Type};
+/// use svm_types::{Address, HostCtx, Type};
 ///
 /// // allocate imports
 /// let mut imports = testing::imports_alloc(0);
@@ -640,6 +689,9 @@ pub unsafe extern "C" fn svm_runtime_create(
 /// let mut receipt = svm_byte_array::default();
 /// let ty = Type::Str("author");
 /// let author: svm_byte_array = (ty, Address::of("@author")).into();
+/// let mut host_ctx = Vec::new();
+/// svm_codec::host_ctx::encode_host_ctx(&HostCtx::new(), &mut host_ctx);
+/// let host_ctx: svm_byte_array = (Type::Str("host ctx"), host_ctx).into();
 /// let template_bytes = svm_byte_array::default();
 /// let gas_metering = false;
 /// let gas_limit = 0;
@@ -650,6 +702,7 @@ pub unsafe extern "C" fn svm_runtime_create(
 ///     runtime,
 ///     template_bytes,
 ///     author,
+///     host_ctx,
 ///     gas_metering,
 ///     gas_limit,
 ///     &mut error)
@@ -665,45 +718,62 @@ pub unsafe extern "C" fn svm_deploy_template(
     runtime: *mut c_void,
     bytes: svm_byte_array,
     author: svm_byte_array,
+    host_ctx: svm_byte_array,
     gas_metering: bool,
     gas_limit: u64,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    debug!("`svm_deploy_template` start`");
+    catch_unwind!(error, {
+        debug!("`svm_deploy_template` start`");
 
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
 
-    let author: Result<Address, String> = Address::try_from(author);
+        let author: Result<Address, String> = Address::try_from(author);
 
-    if let Err(s) = author {
-        raw_error(s, error);
-        return svm_result_t::SVM_FAILURE;
-    }
+        if let Err(s) = author {
+            raw_error(s, error);
+            return svm_result_t::SVM_FAILURE;
+        }
+
+        let host_ctx = decode_host_ctx(host_ctx);
 
-    let gas_limit = maybe_gas!(gas_metering, gas_limit);
+        if let Err(s) = host_ctx {
+            raw_error(s, error);
+            return svm_result_t::SVM_FAILURE;
+        }
 
-    let rust_receipt = runtime.deploy_template(bytes.into(), &author.unwrap().into(), gas_limit);
+        let gas_limit = maybe_gas!(gas_metering, gas_limit);
 
-    let mut receipt_bytes = receipt::encode_template_receipt(&rust_receipt);
+        let rust_receipt = runtime.deploy_template(
+            bytes.into(),
+            &author.unwrap().into(),
+            &host_ctx.unwrap(),
+            gas_limit,
+        );
 
-    // returning encoded `TemplateReceipt` as `svm_byte_array`.
-    // should call later `svm_receipt_destroy`
-    vec_to_svm_byte_array!(DEPLOY_TEMPLATE_RECEIPT_TYPE, receipt, receipt_bytes);
+        let mut receipt_bytes = receipt::encode_template_receipt(&rust_receipt);
 
-    debug!("`svm_deploy_template` returns `SVM_SUCCESS`");
+        // returning encoded `TemplateReceipt` as `svm_byte_array`.
+        // should call later `svm_receipt_destroy`
+        vec_to_svm_byte_array!(DEPLOY_TEMPLATE_RECEIPT_TYPE, receipt, receipt_bytes);
 
-    svm_result_t::SVM_SUCCESS
+        debug!("`svm_deploy_template` returns `SVM_SUCCESS`");
+
+        svm_result_t::SVM_SUCCESS
+    })
 }
 
 /// Spawns a new App.
 ///
+/// The `host_ctx` is given encoded (see `svm_codec::host_ctx`).
+///
 /// # Example
 ///
 /// ```rust, no_run
 /// use svm_runtime_c_api::*;
 ///
 /// use svm_ffi::svm_byte_array;
-/// use svm_types::{Address, Type};
+/// use svm_types::{Address, HostCtx, Type};
 ///
 /// // allocate imports
 /// let mut imports = testing::imports_alloc(0);
@@ -725,6 +795,9 @@ pub unsafe extern "C" fn svm_deploy_template(
 ///
 /// let spawner_ty = Type::Str("spawner");
 /// let spawner: svm_byte_array = (spawner_ty, Address::of("@spawner")).into();
+/// let mut host_ctx = Vec::new();
+/// svm_codec::host_ctx::encode_host_ctx(&HostCtx::new(), &mut host_ctx);
+/// let host_ctx: svm_byte_array = (Type::Str("host ctx"), host_ctx).into();
 /// let app_bytes = svm_byte_array::default();
 /// let gas_metering = false;
 /// let gas_limit = 0;
@@ -735,6 +808,7 @@ pub unsafe extern "C" fn svm_deploy_template(
 ///     runtime,
 ///     app_bytes,
 ///     spawner,
+///     host_ctx,
 ///     gas_metering,
 ///     gas_limit,
 ///     &mut error)
@@ -748,38 +822,56 @@ pub unsafe extern "C" fn svm_spawn_app(
     runtime: *mut c_void,
     bytes: svm_byte_array,
     spawner: svm_byte_array,
+    host_ctx: svm_byte_array,
     gas_metering: bool,
     gas_limit: u64,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    debug!("`svm_spawn_app` start");
+    catch_unwind!(error, {
+        debug!("`svm_spawn_app` start");
 
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-    let spawner: Result<Address, String> = Address::try_from(spawner);
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+        let spawner: Result<Address, String> = Address::try_from(spawner);
 
-    if let Err(s) = spawner {
-        raw_error(s, error);
-        return svm_result_t::SVM_FAILURE;
-    }
+        if let Err(s) = spawner {
+            raw_error(s, error);
+            return svm_result_t::SVM_FAILURE;
+        }
+
+        let host_ctx = decode_host_ctx(host_ctx);
 
-    let gas_limit = maybe_gas!(gas_metering, gas_limit);
+        if let Err(s) = host_ctx {
+            raw_error(s, error);
+            return svm_result_t::SVM_FAILURE;
+        }
 
-    let rust_receipt = runtime.spawn_app(bytes.into(), &spawner.unwrap().into(), gas_limit);
+        let gas_limit = maybe_gas!(gas_metering, gas_limit);
 
-    let mut receipt_bytes = receipt::encode_app_receipt(&rust_receipt);
+        let rust_receipt = runtime.spawn_app(
+            bytes.into(),
+            &spawner.unwrap().into(),
+            &host_ctx.unwrap(),
+            gas_limit,
+        );
 
-    // returning encoded `AppReceipt` as `svm_byte_array`.
-    // should call later `svm_receipt_destroy`
-    vec_to_svm_byte_array!(SPAWN_APP_RECEIPT_TYPE, receipt, receipt_bytes);
+        let mut receipt_bytes = receipt::encode_app_receipt(&rust_receipt);
 
-    debug!("`svm_spawn_app` returns `SVM_SUCCESS`");
+        // returning encoded `AppReceipt` as `svm_byte_array`.
+        // should call later `svm_receipt_destroy`
+        vec_to_svm_byte_array!(SPAWN_APP_RECEIPT_TYPE, receipt, receipt_bytes);
 
-    svm_result_t::SVM_SUCCESS
+        debug!("`svm_spawn_app` returns `SVM_SUCCESS`");
+
+        svm_result_t::SVM_SUCCESS
+    })
 }
 
 /// Triggers an app-transaction execution of an already deployed app.
 /// Returns the receipt of the execution via the `receipt` parameter.
 ///
+/// The `host_ctx` is given encoded (see `svm_codec::host_ctx`).
+/// Its `sender` field is the account paying the transaction `value`.
+///
 /// # Example
 ///
 /// ```rust, no_run
@@ -787,7 +879,7 @@ pub unsafe extern "C" fn svm_spawn_app(
 ///
 /// use svm_runtime_c_api::*;
 ///
-/// use svm_types::{State, Address, Type};
+/// use svm_types::{State, HostCtx, Type};
 /// use svm_ffi::svm_byte_array;
 ///
 /// // allocate imports
@@ -807,6 +899,9 @@ pub unsafe extern "C" fn svm_spawn_app(
 ///
 /// let mut exec_receipt = svm_byte_array::default();
 /// let bytes = svm_byte_array::default();
+/// let mut host_ctx = Vec::new();
+/// svm_codec::host_ctx::encode_host_ctx(&HostCtx::new(), &mut host_ctx);
+/// let host_ctx: svm_byte_array = (Type::Str("host ctx"), host_ctx).into();
 /// let ty = Type::of::<State>();
 /// let state = (ty, State::zeros()).into();
 /// let gas_metering = false;
@@ -817,6 +912,7 @@ pub unsafe extern "C" fn svm_spawn_app(
 ///     &mut exec_receipt,
 ///     runtime,
 ///     bytes,
+///     host_ctx,
 ///     state,
 ///     gas_metering,
 ///     gas_limit,
@@ -830,33 +926,168 @@ pub unsafe extern "C" fn svm_exec_app(
     receipt: *mut svm_byte_array,
     runtime: *mut c_void,
     bytes: svm_byte_array,
+    host_ctx: svm_byte_array,
     state: svm_byte_array,
     gas_metering: bool,
     gas_limit: u64,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    debug!("`svm_exec_app` start");
+    catch_unwind!(error, {
+        debug!("`svm_exec_app` start");
 
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-    let state: Result<State, String> = State::try_from(state);
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+        let host_ctx = decode_host_ctx(host_ctx);
 
-    if let Err(msg) = state {
-        raw_error(msg, error);
-        return svm_result_t::SVM_FAILURE;
-    }
+        if let Err(msg) = host_ctx {
+            raw_error(msg, error);
+            return svm_result_t::SVM_FAILURE;
+        }
+
+        let state: Result<State, String> = State::try_from(state);
 
-    let gas_limit = maybe_gas!(gas_metering, gas_limit);
+        if let Err(msg) = state {
+            raw_error(msg, error);
+            return svm_result_t::SVM_FAILURE;
+        }
 
-    let rust_receipt = runtime.exec_app(bytes.into(), &state.unwrap(), gas_limit);
-    let mut receipt_bytes = receipt::encode_exec_receipt(&rust_receipt);
+        let gas_limit = maybe_gas!(gas_metering, gas_limit);
 
-    // returning encoded `ExecReceipt` as `svm_byte_array`.
-    // should call later `svm_receipt_destroy`
-    vec_to_svm_byte_array!(EXEC_APP_RECEIPT_TYPE, receipt, receipt_bytes);
+        let rust_receipt =
+            runtime.exec_app(bytes.into(), &host_ctx.unwrap(), &state.unwrap(), gas_limit);
+        let mut receipt_bytes = receipt::encode_exec_receipt(&rust_receipt);
 
-    debug!("`svm_exec_app` returns `SVM_SUCCESS`");
+        // returning encoded `ExecReceipt` as `svm_byte_array`.
+        // should call later `svm_receipt_destroy`
+        vec_to_svm_byte_array!(EXEC_APP_RECEIPT_TYPE, receipt, receipt_bytes);
 
-    svm_result_t::SVM_SUCCESS
+        debug!("`svm_exec_app` returns `SVM_SUCCESS`");
+
+        svm_result_t::SVM_SUCCESS
+    })
+}
+
+/// Returns the storage usage of an app (as of the given storage `state`).
+///
+/// On success, the usage is returned via the `bytes`, `pages` and `entries` parameters.
+///
+/// # Errors
+///
+/// Returns `SVM_FAILURE` (with the error via the `error` parameter) when `app_addr` or `state` are invalid,
+/// or when there is no app having address `app_addr` (or `state` isn't its current state).
+///
+#[must_use]
+#[no_mangle]
+pub unsafe extern "C" fn svm_storage_usage(
+    bytes: *mut u64,
+    pages: *mut u32,
+    entries: *mut u32,
+    runtime: *mut c_void,
+    app_addr: svm_byte_array,
+    state: svm_byte_array,
+    error: *mut svm_byte_array,
+) -> svm_result_t {
+    catch_unwind!(error, {
+        debug!("`svm_storage_usage` start");
+
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        let app_addr: Result<Address, String> = Address::try_from(app_addr);
+
+        if let Err(msg) = app_addr {
+            raw_error(msg, error);
+            return svm_result_t::SVM_FAILURE;
+        }
+
+        let state: Result<State, String> = State::try_from(state);
+
+        if let Err(msg) = state {
+            raw_error(msg, error);
+            return svm_result_t::SVM_FAILURE;
+        }
+
+        let app_addr = app_addr.unwrap().into();
+
+        let state = state.unwrap();
+
+        match runtime.storage_usage(&app_addr, &state) {
+            Some(usage) => {
+                *bytes = usage.bytes;
+                *pages = usage.pages;
+                *entries = usage.entries;
+
+                debug!("`svm_storage_usage` returns `SVM_SUCCESS`");
+
+                svm_result_t::SVM_SUCCESS
+            }
+            None => {
+                let msg = format!(
+                    "App `{:?}` not found (or `{:?}` isn't its current state)",
+                    app_addr, state
+                );
+                raw_error(msg, error);
+
+                svm_result_t::SVM_FAILURE
+            }
+        }
+    })
+}
+
+/// Renders an encoded receipt (as returned by `svm_deploy_template`, `svm_spawn_app` or `svm_exec_app`) as JSON.
+///
+/// On success, the UTF-8 JSON is returned via the `json` parameter
+/// (see `svm_codec::api::json::receipt_to_json` for the JSON schema).
+///
+/// # Example
+///
+/// ```rust
+/// use svm_runtime_c_api::*;
+///
+/// use svm_ffi::svm_byte_array;
+/// use svm_types::Type;
+///
+/// // a failed `exec-app` receipt (out-of-gas) without logs
+/// let receipt: svm_byte_array = (Type::Str("receipt"), vec![2, 0, 0, 0, 0, 0]).into();
+///
+/// let mut json = svm_byte_array::default();
+/// let mut error = svm_byte_array::default();
+///
+/// let res = unsafe { svm_receipt_to_json(&mut json, receipt, &mut error) };
+/// assert!(res.is_ok());
+/// ```
+///
+#[must_use]
+#[no_mangle]
+pub unsafe extern "C" fn svm_receipt_to_json(
+    json: *mut svm_byte_array,
+    receipt: svm_byte_array,
+    error: *mut svm_byte_array,
+) -> svm_result_t {
+    catch_unwind!(error, {
+        debug!("`svm_receipt_to_json` start");
+
+        let bytes: &[u8] = receipt.into();
+
+        match svm_codec::api::json::receipt_to_json(bytes) {
+            Ok(value) => {
+                let mut json_bytes = value.to_string().into_bytes();
+
+                // returning the receipt JSON as `svm_byte_array`.
+                // should call later `svm_byte_array_destroy`
+                vec_to_svm_byte_array!(RECEIPT_JSON_TYPE, json, json_bytes);
+
+                debug!("`svm_receipt_to_json` returns `SVM_SUCCESS`");
+
+                svm_result_t::SVM_SUCCESS
+            }
+            Err(e) => {
+                error!("`svm_receipt_to_json` returns `SVM_FAILURE`");
+
+                raw_error(format!("{:?}", e), error);
+
+                svm_result_t::SVM_FAILURE
+            }
+        }
+    })
 }
 
 #[must_use]
@@ -1021,10 +1252,10 @@ pub unsafe extern "C" fn svm_wasm_error_create(msg: svm_byte_array) -> *mut svm_
 /// Given a raw `deploy-template` transaction (the `bytes` parameter),
 /// if it's valid (i.e: passes the `svm_validate_template`), returns `SVM_SUCCESS` and the estimated gas that will be required
 /// in order to execute the transaction (via the `estimate` parameter).
-
-/// # Panics
 ///
-/// Panics when `bytes` input is not a valid `deploy-template` raw transaction.
+/// # Errors
+///
+/// Returns `SVM_FAILURE` (with the error via the `error` parameter) when `bytes` input is not a valid `deploy-template` raw transaction.
 /// Having `bytes` a valid raw input doesn't necessarily imply that `svm_validate_template` passes.
 ///
 #[no_mangle]
@@ -1034,27 +1265,29 @@ pub unsafe extern "C" fn svm_estimate_deploy_template(
     bytes: svm_byte_array,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-
-    match runtime.estimate_deploy_template(bytes.into()) {
-        Ok(est) => {
-            *estimation = max_gas!(est);
-            svm_result_t::SVM_SUCCESS
-        }
-        Err(e) => {
-            raw_validate_error(&e, error);
-            svm_result_t::SVM_FAILURE
+    catch_unwind!(error, {
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        match runtime.estimate_deploy_template(bytes.into()) {
+            Ok(est) => {
+                *estimation = max_gas!(est);
+                svm_result_t::SVM_SUCCESS
+            }
+            Err(e) => {
+                raw_validate_error(&e, error);
+                svm_result_t::SVM_FAILURE
+            }
         }
-    }
+    })
 }
 
 /// Given a raw `spawn-app` transaction (the `bytes` parameter),
 /// if it's valid (i.e: passes the `svm_validate_app`), returns `SVM_SUCCESS` and the estimated gas that will be required
 /// in order to execute the transaction (via the `estimate` parameter).
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics when `bytes` input is not a valid `spawn-app` raw transaction.
+/// Returns `SVM_FAILURE` (with the error via the `error` parameter) when `bytes` input is not a valid `spawn-app` raw transaction.
 /// Having `bytes` a valid raw input doesn't necessarily imply that `svm_validate_app` passes.
 ///
 #[no_mangle]
@@ -1064,27 +1297,29 @@ pub unsafe extern "C" fn svm_estimate_spawn_app(
     bytes: svm_byte_array,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-
-    match runtime.estimate_spawn_app(bytes.into()) {
-        Ok(est) => {
-            *estimation = max_gas!(est);
-            svm_result_t::SVM_SUCCESS
+    catch_unwind!(error, {
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        match runtime.estimate_spawn_app(bytes.into()) {
+            Ok(est) => {
+                *estimation = max_gas!(est);
+                svm_result_t::SVM_SUCCESS
+            }
+            Err(e) => {
+                raw_validate_error(&e, error);
+                svm_result_t::SVM_FAILURE
+            }
         }
-        Err(e) => {
-            raw_validate_error(&e, error);
-            svm_result_t::SVM_FAILURE
-        }
-    }
+    })
 }
 
 /// Given a raw `exec-app` transaction (the `bytes` parameter),
 /// if it's valid (i.e: passes the `svm_validate_tx`), returns `SVM_SUCCESS` and the estimated gas that will be required
 /// in order to execute the transaction (via the `estimate` parameter).
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics when `bytes` input is not a valid `exec-app` raw transaction.
+/// Returns `SVM_FAILURE` (with the error via the `error` parameter) when `bytes` input is not a valid `exec-app` raw transaction.
 /// Having `bytes` a valid raw input doesn't necessarily imply that `svm_validate_tx` passes.
 ///
 #[no_mangle]
@@ -1094,16 +1329,18 @@ pub unsafe extern "C" fn svm_estimate_exec_app(
     bytes: svm_byte_array,
     error: *mut svm_byte_array,
 ) -> svm_result_t {
-    let runtime: &mut Box<dyn Runtime> = runtime.into();
-
-    match runtime.estimate_exec_app(bytes.into()) {
-        Ok(est) => {
-            *estimation = max_gas!(est);
-            svm_result_t::SVM_SUCCESS
-        }
-        Err(e) => {
-            raw_validate_error(&e, error);
-            svm_result_t::SVM_FAILURE
+    catch_unwind!(error, {
+        let runtime: &mut Box<dyn Runtime> = runtime.into();
+
+        match runtime.estimate_exec_app(bytes.into()) {
+            Ok(est) => {
+                *estimation = max_gas!(est);
+                svm_result_t::SVM_SUCCESS
+            }
+            Err(e) => {
+                raw_validate_error(&e, error);
+                svm_result_t::SVM_FAILURE
+            }
         }
-    }
+    })
 }
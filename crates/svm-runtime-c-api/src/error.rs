@@ -1,3 +1,4 @@
+use std::any::Any;
 use std::io;
 use std::string::FromUtf8Error;
 
@@ -24,6 +25,18 @@ pub(crate) unsafe fn raw_utf8_error<T>(
     raw_error(utf8_err.to_string(), raw_err);
 }
 
+pub(crate) unsafe fn raw_panic_error(panic: Box<dyn Any + Send>, raw_err: *mut svm_byte_array) {
+    let msg = if let Some(msg) = panic.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = panic.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    };
+
+    raw_error(format!("SVM has panicked: {}", msg), raw_err);
+}
+
 pub(crate) unsafe fn raw_error(s: String, raw_err: *mut svm_byte_array) {
     let ty = Type::Str("runtime-c-api-error");
     let err: svm_byte_array = (ty, s).into();
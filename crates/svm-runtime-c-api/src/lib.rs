@@ -13,7 +13,9 @@ mod api;
 mod error;
 mod result;
 
-pub(crate) use error::{raw_error, raw_io_error, raw_utf8_error, raw_validate_error};
+pub(crate) use error::{
+    raw_error, raw_io_error, raw_panic_error, raw_utf8_error, raw_validate_error,
+};
 
 #[cfg(feature = "default-rocksdb")]
 pub use api::svm_runtime_create;
@@ -25,6 +27,12 @@ pub use api::{
     svm_exec_app,
     svm_deploy_template,
     svm_spawn_app,
+
+    // Storage
+    svm_storage_usage,
+
+    // Receipts
+    svm_receipt_to_json,
     
     // Gas Estimations
     svm_estimate_deploy_template,
@@ -10,7 +10,7 @@ use svm_codec::receipt;
 use svm_ffi::{svm_byte_array, svm_env_t, svm_resource_iter_t, svm_resource_t, tracking};
 use svm_layout::DataLayout;
 use svm_runtime::{testing::WasmFile, vmcalls, Context};
-use svm_types::{Address, State, Type, WasmType, WasmValue};
+use svm_types::{Address, HostCtx, State, Type, WasmType, WasmValue};
 
 use svm_sdk::traits::Encoder;
 use svm_sdk::ReturnData;
@@ -20,7 +20,7 @@ use maplit::hashmap;
 static TEST_STRING_TY: Type = Type::Str("test String");
 static AUTHOR: Type = Type::Str("author");
 static SPAWNER: Type = Type::Str("spawner");
-static SENDER: Type = Type::Str("sender");
+static HOST_CTX: Type = Type::Str("host ctx");
 static TEMPLATE_ADDR: Type = Type::Str("template address");
 static APP_ADDR: Type = Type::Str("app address");
 static INIT_STATE: Type = Type::Str("init state");
@@ -226,6 +226,16 @@ unsafe fn create_success_imports() -> *mut c_void {
     imports as _
 }
 
+fn host_ctx_bytes(sender: &Address) -> Vec<u8> {
+    let mut host_ctx = HostCtx::new();
+    host_ctx.set(HostCtx::SENDER, sender.as_slice().to_vec());
+
+    let mut bytes = Vec::new();
+    svm_codec::host_ctx::encode_host_ctx(&host_ctx, &mut bytes);
+
+    bytes
+}
+
 fn deploy_template_bytes(version: u16, name: &str, ctors: &[String], wasm: &[u8]) -> Vec<u8> {
     let data: DataLayout = vec![4].into();
 
@@ -340,6 +350,7 @@ fn svm_runtime_failure() {
 
         // 2) deploy app-template
         let author: svm_byte_array = (AUTHOR, Address::of("author")).into();
+        let host_ctx: svm_byte_array = (HOST_CTX, host_ctx_bytes(&Address::of("sender"))).into();
         let wasm = include_bytes!("wasm/failure.wasm");
 
         // raw template
@@ -353,6 +364,7 @@ fn svm_runtime_failure() {
             runtime,
             msg.clone(),
             author.clone(),
+            host_ctx.clone(),
             gas_metering,
             gas_limit,
             &mut error,
@@ -360,8 +372,9 @@ fn svm_runtime_failure() {
         assert!(res.is_ok());
 
         // extract the `template-address` out of theh receipt
-        let receipt =
-            receipt::decode_receipt(template_receipt.clone().into()).into_deploy_template();
+        let receipt = receipt::decode_receipt(template_receipt.clone().into())
+            .unwrap()
+            .into_deploy_template();
         let template_addr: &Address = receipt.get_template_addr().inner();
         let template_addr: svm_byte_array = (TEMPLATE_ADDR, template_addr).into();
 
@@ -382,6 +395,7 @@ fn svm_runtime_failure() {
             runtime,
             app_bytes.clone(),
             spawner.clone(),
+            host_ctx.clone(),
             gas_metering,
             gas_limit,
             &mut error,
@@ -389,7 +403,9 @@ fn svm_runtime_failure() {
         assert!(res.is_ok());
 
         // extracts the spawned-app `Address` and initial `State`.
-        let receipt = receipt::decode_receipt(spawn_receipt.clone().into()).into_spawn_app();
+        let receipt = receipt::decode_receipt(spawn_receipt.clone().into())
+            .unwrap()
+            .into_spawn_app();
         assert_eq!(receipt.success, true);
 
         let app_addr = receipt.get_app_addr().inner();
@@ -422,6 +438,7 @@ fn svm_runtime_failure() {
             &mut exec_receipt,
             runtime,
             exec_bytes.clone(),
+            host_ctx.clone(),
             init_state.clone(),
             gas_metering,
             gas_limit,
@@ -429,7 +446,9 @@ fn svm_runtime_failure() {
         );
         assert!(res.is_ok());
 
-        let receipt = receipt::decode_receipt(exec_receipt.clone().into()).into_exec_app();
+        let receipt = receipt::decode_receipt(exec_receipt.clone().into())
+            .unwrap()
+            .into_exec_app();
         assert_eq!(receipt.success, false);
 
         assert_ne!(tracking::total_live(), 0);
@@ -439,6 +458,7 @@ fn svm_runtime_failure() {
         let _ = api::svm_byte_array_destroy(exec_bytes);
         let _ = api::svm_byte_array_destroy(author);
         let _ = api::svm_byte_array_destroy(spawner);
+        let _ = api::svm_byte_array_destroy(host_ctx);
         let _ = api::svm_byte_array_destroy(template_addr);
         let _ = api::svm_byte_array_destroy(app_addr);
         let _ = api::svm_byte_array_destroy(derived_app_addr);
@@ -446,6 +466,7 @@ fn svm_runtime_failure() {
         let _ = api::svm_byte_array_destroy(template_receipt);
         let _ = api::svm_byte_array_destroy(spawn_receipt);
         let _ = api::svm_byte_array_destroy(exec_receipt);
+        let _ = api::svm_byte_array_destroy(receipt_json);
         let _ = api::svm_imports_destroy(imports);
         let _ = api::svm_runtime_destroy(runtime);
         let _ = api::svm_state_kv_destroy(state_kv);
@@ -481,6 +502,7 @@ fn svm_runtime_success() {
 
         // 2) deploy app-template
         let author: svm_byte_array = (AUTHOR, Address::of("author")).into();
+        let host_ctx: svm_byte_array = (HOST_CTX, host_ctx_bytes(&Address::of("sender"))).into();
         let ctors = vec!["initialize".to_string()];
         let wasm = include_bytes!("wasm/counter.wasm");
 
@@ -494,6 +516,7 @@ fn svm_runtime_success() {
             runtime,
             msg.clone(),
             author.clone(),
+            host_ctx.clone(),
             gas_metering,
             gas_limit,
             &mut error,
@@ -501,8 +524,9 @@ fn svm_runtime_success() {
         assert!(res.is_ok());
 
         // extract the `template-address` out of theh receipt
-        let receipt =
-            receipt::decode_receipt(template_receipt.clone().into()).into_deploy_template();
+        let receipt = receipt::decode_receipt(template_receipt.clone().into())
+            .unwrap()
+            .into_deploy_template();
         let template_addr: &Address = receipt.get_template_addr().inner();
         let template_addr: svm_byte_array = (TEMPLATE_ADDR, template_addr).into();
 
@@ -526,6 +550,7 @@ fn svm_runtime_success() {
             runtime,
             app_bytes.clone(),
             spawner.clone(),
+            host_ctx.clone(),
             gas_metering,
             gas_limit,
             &mut error,
@@ -533,7 +558,9 @@ fn svm_runtime_success() {
         assert!(res.is_ok());
 
         // extracts the spawned-app `Address` and initial `State`.
-        let receipt = receipt::decode_receipt(spawn_receipt.clone().into()).into_spawn_app();
+        let receipt = receipt::decode_receipt(spawn_receipt.clone().into())
+            .unwrap()
+            .into_spawn_app();
         assert_eq!(receipt.success, true);
         let app_addr = receipt.get_app_addr().inner();
         let app_addr: svm_byte_array = (APP_ADDR, app_addr).into();
@@ -571,6 +598,7 @@ fn svm_runtime_success() {
             &mut exec_receipt,
             runtime,
             exec_bytes.clone(),
+            host_ctx.clone(),
             init_state.clone(),
             gas_metering,
             gas_limit,
@@ -578,7 +606,9 @@ fn svm_runtime_success() {
         );
         assert!(res.is_ok());
 
-        let receipt = receipt::decode_receipt(exec_receipt.clone().into()).into_exec_app();
+        let receipt = receipt::decode_receipt(exec_receipt.clone().into())
+            .unwrap()
+            .into_exec_app();
         assert_eq!(receipt.success, true);
 
         let bytes = receipt.get_returndata();
@@ -591,6 +621,19 @@ fn svm_runtime_success() {
             (counter_init, counter_init + add, (counter_init + add) * mul)
         );
 
+        // 5) render the exec-app receipt as JSON
+        let mut receipt_json = svm_byte_array::default();
+
+        let res = api::svm_receipt_to_json(&mut receipt_json, exec_receipt.clone(), &mut error);
+        assert!(res.is_ok());
+
+        let json: &[u8] = receipt_json.clone().into();
+        let json: serde_json::Value = serde_json::from_slice(json).unwrap();
+
+        assert_eq!(json["type"], "exec-app");
+        assert_eq!(json["version"], 0);
+        assert_eq!(json["success"], true);
+
         assert_ne!(tracking::total_live(), 0);
 
         let _ = api::svm_byte_array_destroy(msg);
@@ -598,6 +641,7 @@ fn svm_runtime_success() {
         let _ = api::svm_byte_array_destroy(exec_bytes);
         let _ = api::svm_byte_array_destroy(author);
         let _ = api::svm_byte_array_destroy(spawner);
+        let _ = api::svm_byte_array_destroy(host_ctx);
         let _ = api::svm_byte_array_destroy(template_addr);
         let _ = api::svm_byte_array_destroy(app_addr);
         let _ = api::svm_byte_array_destroy(derived_app_addr);
@@ -605,6 +649,7 @@ fn svm_runtime_success() {
         let _ = api::svm_byte_array_destroy(template_receipt);
         let _ = api::svm_byte_array_destroy(spawn_receipt);
         let _ = api::svm_byte_array_destroy(exec_receipt);
+        let _ = api::svm_byte_array_destroy(receipt_json);
         let _ = api::svm_imports_destroy(imports);
         let _ = api::svm_runtime_destroy(runtime);
         let _ = api::svm_state_kv_destroy(state_kv);
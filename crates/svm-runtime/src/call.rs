@@ -0,0 +1,76 @@
+use svm_types::gas::MaybeGas;
+use svm_types::receipt::{BalanceChange, Log};
+use svm_types::{AppAddr, HostCtx};
+
+/// The maximum depth of nested app-to-app calls (the top-level running app counts as depth `1`).
+pub const MAX_CALL_DEPTH: usize = 16;
+
+/// A request (issued by the `svm_call` vmcall) for running a function of another `App`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppCall {
+    /// The called `App` address
+    pub app: AppAddr,
+
+    /// The called function name
+    pub func_name: String,
+
+    /// The calldata given to the called function
+    pub calldata: Vec<u8>,
+
+    /// The maximum amount of gas the called function may use
+    pub gas_limit: MaybeGas,
+
+    /// The `App`s being currently executed (ordered from the top-level running app to the called `app`)
+    pub call_stack: Vec<AppAddr>,
+
+    /// The pending balance changes made so far by the calling apps
+    pub balance_changes: Vec<BalanceChange>,
+
+    /// The `HostCtx` of the executed transaction
+    pub host_ctx: HostCtx,
+}
+
+/// The outcome of an `AppCall`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallOutcome {
+    /// Whether the called function has succeeded
+    pub success: bool,
+
+    /// The `returndata` of the called function (empty upon failure)
+    pub returndata: Vec<u8>,
+
+    /// The gas used by the called function
+    pub gas_used: u64,
+
+    /// The logs emitted by the called function
+    pub logs: Vec<Log>,
+
+    /// The pending balance changes (including the ones given by `AppCall`) upon success.
+    /// Upon failure, it's left empty.
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+impl CallOutcome {
+    /// A failed `AppCall` having used `gas_used` gas units.
+    pub fn failure(gas_used: u64, logs: Vec<Log>) -> Self {
+        Self {
+            success: false,
+            returndata: Vec::new(),
+            gas_used,
+            logs,
+            balance_changes: Vec::new(),
+        }
+    }
+}
+
+/// Runs `AppCall`s on behalf of the `svm_call` vmcall.
+///
+/// The `Runtime` registers itself as the `CallHandler` of each `Context` it creates.
+pub trait CallHandler {
+    /// Runs the called function within a nested `Context` (having its own `AppStorage`).
+    ///
+    /// In case the called function succeeds, its storage changes are committed
+    /// (the new `State` can be retrieved from the calling app `AppStorage`).
+    /// Otherwise, the changes are discarded.
+    fn call(&self, call: AppCall) -> CallOutcome;
+}
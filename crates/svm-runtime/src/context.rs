@@ -4,10 +4,15 @@ use std::rc::Rc;
 
 use log::debug;
 
-use wasmer::Memory;
+use wasmer::{Global, Memory, Value};
 
 use svm_storage::app::AppStorage;
-use svm_types::{gas::MaybeGas, receipt::Log};
+use svm_types::gas::{MaybeGas, OOGError};
+use svm_types::receipt::{Log, StorageChange};
+use svm_types::{AppAddr, HostCtx};
+
+use crate::gas::VMCallsPricing;
+use crate::{CallHandler, Ledger};
 
 /// `Context` is a container for the accessible data by `wasmer` instances.
 ///
@@ -59,6 +64,15 @@ pub struct ContextInner {
     /// Whether gas metering is enabled or not
     pub gas_metering: bool,
 
+    /// Gas used so far by vmcalls (relevant only when `gas_metering = true`)
+    pub gas_used: u64,
+
+    /// Whether a vmcall has been charged more gas than left
+    pub gas_exhausted: bool,
+
+    /// The vmcalls price table
+    pub pricing: VMCallsPricing,
+
     /// An accessor to the App's storage
     pub storage: AppStorage,
 
@@ -71,8 +85,32 @@ pub struct ContextInner {
     /// Instance's memory
     memory: Option<Memory>,
 
+    /// Instance's gas left `Global` (shared by the Wasm instructions and the vmcalls)
+    gas_left: Option<Global>,
+
     /// Pointer to `calldata`. Tuple stores `(offset, len)`.
     calldata: Option<(usize, usize)>,
+
+    /// The `returndata` of the last app called (using `svm_call`)
+    call_returndata: Vec<u8>,
+
+    /// The `App`s being currently executed. The last one is the App owning this `Context`
+    call_stack: Vec<AppAddr>,
+
+    /// Runs the calls made by `svm_call` (`None` when app-to-app calls aren't supported)
+    call_handler: Option<*const dyn CallHandler>,
+
+    /// The balance changes made so far by the running transaction
+    pub ledger: Ledger,
+
+    /// The amount of coins transferred to the App by the running transaction
+    value: u64,
+
+    /// The `Host Context` of the running transaction (readable using the `svm_host_get` vmcalls)
+    host_ctx: HostCtx,
+
+    /// The storage changes committed by the running transaction (`None` when they aren't collected)
+    storage_changes: Option<Vec<StorageChange>>,
 }
 
 impl ContextInner {
@@ -85,13 +123,77 @@ impl ContextInner {
             storage,
             gas_metering,
             gas_limit,
+            gas_used: 0,
+            gas_exhausted: false,
+            pricing: VMCallsPricing::default(),
             logs,
             memory: None,
+            gas_left: None,
             calldata: None,
             returndata: None,
+            call_returndata: Vec::new(),
+            call_stack: Vec::new(),
+            call_handler: None,
+            ledger: Ledger::default(),
+            value: 0,
+            host_ctx: HostCtx::new(),
+            storage_changes: None,
+        }
+    }
+
+    /// Charges `gas` units for a vmcall.
+    ///
+    /// The gas is deducted from the instance's gas left (see `set_gas_left`), so the vmcalls
+    /// and the Wasm instructions are charged against a single budget.
+    ///
+    /// Returns `OOGError` in case there isn't enough gas left. The charged amount is being
+    /// recorded even upon failure, so the exceeding can be later observed by the `Runtime`.
+    pub fn use_gas(&mut self, gas: u64) -> Result<(), OOGError> {
+        if !self.gas_metering {
+            return Ok(());
+        }
+
+        let gas_left = self.gas_left();
+
+        self.gas_used = self.gas_used.saturating_add(gas);
+
+        if gas > gas_left {
+            self.gas_exhausted = true;
+            self.write_gas_left(0);
+
+            Err(OOGError {})
+        } else {
+            self.write_gas_left(gas_left - gas);
+
+            Ok(())
         }
     }
 
+    /// Returns the gas left for running the instance (Wasm instructions and vmcalls).
+    ///
+    /// Before `set_gas_left` has been called, only the vmcalls are accounted.
+    pub fn gas_left(&self) -> u64 {
+        match self.gas_left {
+            Some(ref global) => global.get().unwrap_i64() as u64,
+            None => self.gas_limit.saturating_sub(self.gas_used),
+        }
+    }
+
+    /// Sets the instance's gas left `Global` (see `svm_compiler::gas_left_global`).
+    pub fn set_gas_left(&mut self, gas_left: Global) {
+        self.gas_left = Some(gas_left);
+    }
+
+    fn write_gas_left(&self, gas_left: u64) {
+        if let Some(ref global) = self.gas_left {
+            global.set(Value::I64(gas_left as i64)).unwrap();
+        }
+    }
+
+    pub fn set_pricing(&mut self, pricing: VMCallsPricing) {
+        self.pricing = pricing;
+    }
+
     pub fn set_calldata(&mut self, offset: usize, len: usize) {
         self.calldata = Some((offset, len));
     }
@@ -102,9 +204,8 @@ impl ContextInner {
         self.calldata.unwrap()
     }
 
+    /// Sets the `returndata` pointer (calling it multiple times - the last call wins).
     pub fn set_returndata(&mut self, offset: usize, len: usize) {
-        debug_assert!(self.returndata.is_none());
-
         self.returndata = Some((offset, len));
     }
 
@@ -118,7 +219,65 @@ impl ContextInner {
         self.memory.as_ref().unwrap()
     }
 
+    /// Sets the `CallHandler` running the calls made by `svm_call`.
+    ///
+    /// The `handler` must outlive the `Context`.
+    pub fn set_call_handler(&mut self, handler: *const dyn CallHandler) {
+        self.call_handler = Some(handler);
+    }
+
+    pub fn get_call_handler(&self) -> Option<*const dyn CallHandler> {
+        self.call_handler
+    }
+
+    pub fn set_call_stack(&mut self, call_stack: Vec<AppAddr>) {
+        self.call_stack = call_stack;
+    }
+
+    pub fn get_call_stack(&self) -> &[AppAddr] {
+        &self.call_stack
+    }
+
+    pub fn set_call_returndata(&mut self, returndata: Vec<u8>) {
+        self.call_returndata = returndata;
+    }
+
+    pub fn get_call_returndata(&self) -> &[u8] {
+        &self.call_returndata
+    }
+
+    pub fn set_value(&mut self, value: u64) {
+        self.value = value;
+    }
+
+    pub fn get_value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn set_host_ctx(&mut self, host_ctx: HostCtx) {
+        self.host_ctx = host_ctx;
+    }
+
+    pub fn get_host_ctx(&self) -> &HostCtx {
+        &self.host_ctx
+    }
+
+    /// Returns the `Address` of the App owning this `Context`
+    pub fn app_addr(&self) -> &AppAddr {
+        debug_assert!(!self.call_stack.is_empty());
+
+        self.call_stack.last().unwrap()
+    }
+
     pub fn take_logs(&mut self) -> Vec<Log> {
         std::mem::take(&mut self.logs)
     }
+
+    pub fn set_storage_changes(&mut self, changes: Vec<StorageChange>) {
+        self.storage_changes = Some(changes);
+    }
+
+    pub fn take_storage_changes(&mut self) -> Option<Vec<StorageChange>> {
+        self.storage_changes.take()
+    }
 }
@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+use std::convert::TryInto;
+use std::rc::Rc;
+
+use svm_kv::key::concat_ns_to_key;
+use svm_kv::traits::RawKV;
+use svm_types::Address;
+
+use crate::env::traits::BalanceStore;
+
+/// The namespace under which the balances are stored.
+const BALANCES_NS: &[u8] = b"balance";
+
+/// `BalanceStore` implementation backed-by a `RawKV`.
+///
+/// Each balance is stored as `balance:<account address> -> <balance (8 bytes, Big-Endian)>`.
+pub struct KVBalanceStore {
+    kv: Rc<RefCell<dyn RawKV>>,
+}
+
+impl KVBalanceStore {
+    /// Creates a new `KVBalanceStore` on top of `kv`
+    pub fn new(kv: Rc<RefCell<dyn RawKV>>) -> Self {
+        Self { kv }
+    }
+
+    fn key(addr: &Address) -> Vec<u8> {
+        concat_ns_to_key(BALANCES_NS, addr.as_slice())
+    }
+}
+
+impl BalanceStore for KVBalanceStore {
+    fn balance(&self, addr: &Address) -> u64 {
+        let key = Self::key(addr);
+
+        match self.kv.borrow().get(&key) {
+            Some(bytes) => {
+                let bytes: [u8; 8] = bytes[..].try_into().unwrap();
+
+                u64::from_be_bytes(bytes)
+            }
+            None => 0,
+        }
+    }
+
+    fn set_balances(&mut self, balances: &[(Address, u64)]) {
+        let entries: Vec<(Vec<u8>, [u8; 8])> = balances
+            .iter()
+            .map(|(addr, balance)| (Self::key(addr), balance.to_be_bytes()))
+            .collect();
+
+        let changes: Vec<(&[u8], &[u8])> = entries.iter().map(|(k, v)| (&k[..], &v[..])).collect();
+
+        self.kv.borrow_mut().set(&changes);
+    }
+}
@@ -1,9 +1,11 @@
 mod address_compute;
+mod balance_store;
 mod env;
 mod hasher;
 mod serializer_types;
 
 pub use address_compute::{DefaultAppAddressCompute, DefaultAppTemplateAddressCompute};
+pub use balance_store::KVBalanceStore;
 pub use env::{DefaultMemAppStore, DefaultMemAppTemplateStore, DefaultMemoryEnv};
 pub use hasher::DefaultTemplateHasher;
 pub use serializer_types::DefaultSerializerTypes;
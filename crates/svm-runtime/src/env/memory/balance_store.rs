@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use svm_types::Address;
+
+use crate::env::traits::BalanceStore;
+
+/// In-memory `BalanceStore` implementation.
+/// Should be used for testing purposes only.
+#[derive(Default)]
+pub struct MemBalanceStore {
+    balances: HashMap<Address, u64>,
+}
+
+impl MemBalanceStore {
+    /// Initializes a new `MemBalanceStore`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the balance of account `addr` (useful for funding accounts within tests).
+    pub fn set_balance(&mut self, addr: &Address, balance: u64) {
+        self.balances.insert(addr.clone(), balance);
+    }
+}
+
+impl BalanceStore for MemBalanceStore {
+    fn balance(&self, addr: &Address) -> u64 {
+        self.balances.get(addr).copied().unwrap_or(0)
+    }
+
+    fn set_balances(&mut self, balances: &[(Address, u64)]) {
+        for (addr, balance) in balances {
+            self.set_balance(addr, *balance);
+        }
+    }
+}
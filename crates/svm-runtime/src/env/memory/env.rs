@@ -1,4 +1,6 @@
+use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 use crate::env::default::{
     DefaultAppAddressCompute, DefaultAppTemplateAddressCompute, DefaultTemplateHasher,
@@ -6,7 +8,7 @@ use crate::env::default::{
 
 use crate::env::{
     memory::{MemAppStore, MemAppTemplateStore},
-    traits::{Env, EnvSerializerTypes, EnvTypes},
+    traits::{BalanceStore, Env, EnvSerializerTypes, EnvTypes},
 };
 
 pub struct MemoryEnvTypes<Ser>(PhantomData<Ser>);
@@ -34,6 +36,8 @@ where
     app_store: <MemoryEnvTypes<Ser> as EnvTypes>::AppStore,
 
     template_store: <MemoryEnvTypes<Ser> as EnvTypes>::TemplateStore,
+
+    balance_store: Option<Rc<RefCell<dyn BalanceStore>>>,
 }
 
 impl<Ser> MemoryEnv<Ser>
@@ -48,8 +52,16 @@ where
         Self {
             app_store,
             template_store,
+            balance_store: None,
         }
     }
+
+    /// Enables the balance ledger, backed-by `balance_store`.
+    pub fn with_balance_store(mut self, balance_store: Rc<RefCell<dyn BalanceStore>>) -> Self {
+        self.balance_store = Some(balance_store);
+
+        self
+    }
 }
 
 impl<Ser> Env for MemoryEnv<Ser>
@@ -73,6 +85,10 @@ where
     fn get_app_store_mut(&mut self) -> &mut <Self::Types as EnvTypes>::AppStore {
         &mut self.app_store
     }
+
+    fn get_balance_store(&self) -> Option<Rc<RefCell<dyn BalanceStore>>> {
+        self.balance_store.clone()
+    }
 }
 
 /// `MemoryEnv` with default serialization.
@@ -1,7 +1,9 @@
 mod app_store;
+mod balance_store;
 mod env;
 mod template_store;
 
 pub use app_store::{DefaultMemAppStore, MemAppStore};
+pub use balance_store::MemBalanceStore;
 pub use env::{DefaultMemoryEnv, MemoryEnv, MemoryEnvTypes};
 pub use template_store::{DefaultMemAppTemplateStore, MemAppTemplateStore};
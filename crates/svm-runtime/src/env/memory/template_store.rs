@@ -4,13 +4,14 @@ use svm_codec::serializers::{AppTemplateDeserializer, AppTemplateSerializer};
 use svm_types::{AppTemplate, AuthorAddr, TemplateAddr};
 
 use crate::env::default::DefaultSerializerTypes as DSer;
-use crate::env::traits::{AppTemplateStore, EnvSerializerTypes};
+use crate::env::traits::{without_code, AppTemplateStore, EnvSerializerTypes};
 use crate::env::types::AppTemplateHash;
 
 /// An in-memory implementation of `AppTemplateStore`
 pub struct MemAppTemplateStore<S, D> {
-    bytes: HashMap<AppTemplateHash, Vec<u8>>,
+    bytes: HashMap<TemplateAddr, Vec<u8>>,
     hash: HashMap<TemplateAddr, AppTemplateHash>,
+    code: HashMap<AppTemplateHash, Vec<u8>>,
     phantom: PhantomData<(S, D)>,
 }
 
@@ -25,6 +26,7 @@ where
         Self {
             bytes: HashMap::new(),
             hash: HashMap::new(),
+            code: HashMap::new(),
             phantom: PhantomData,
         }
     }
@@ -44,18 +46,26 @@ where
     ) {
         self.hash.insert(addr.clone(), hash.clone());
 
-        let bytes = S::serialize(template, author);
-        self.bytes.insert(hash.clone(), bytes);
+        self.code
+            .entry(hash.clone())
+            .or_insert_with(|| template.code.clone());
+
+        let bytes = S::serialize(&without_code(template), author);
+        self.bytes.insert(addr.clone(), bytes);
     }
 
     fn load(&self, addr: &TemplateAddr) -> Option<(AppTemplate, AuthorAddr)> {
-        let hash = self.hash.get(addr);
+        let hash = self.hash.get(addr)?;
+        let bytes = self.bytes.get(addr)?;
+
+        let (mut template, author) = D::deserialize(&bytes[..])?;
+        template.code = self.load_code(hash)?;
+
+        Some((template, author))
+    }
 
-        hash.and_then(|h| {
-            self.bytes
-                .get(&h)
-                .and_then(|bytes| D::deserialize(&bytes[..]))
-        })
+    fn load_code(&self, hash: &AppTemplateHash) -> Option<Vec<u8>> {
+        self.code.get(hash).cloned()
     }
 }
 
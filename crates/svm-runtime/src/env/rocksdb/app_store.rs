@@ -1,12 +1,17 @@
-use std::{marker::PhantomData, path::Path};
+use std::{io, marker::PhantomData, path::Path};
 
 use svm_codec::serializers::{AppDeserializer, AppSerializer};
+use svm_kv::{rocksdb::Rocksdb, traits::RawKV};
 use svm_types::{App, AppAddr, CreatorAddr};
 
-use crate::env::traits::AppStore;
+use crate::env::default::DefaultSerializerTypes as DSer;
+use crate::env::traits::{AppStore, EnvSerializerTypes};
+
+use log::info;
 
 /// `AppStore` implementation backed-by `rocksdb`
 pub struct RocksdbAppStore<S, D> {
+    db: Rocksdb,
     phantom: PhantomData<(S, D)>,
 }
 
@@ -15,14 +20,17 @@ where
     S: AppSerializer,
     D: AppDeserializer,
 {
-    /// New `RocksdbAppStore` instance
-    pub fn new<P>(_path: P) -> Self
+    /// New `RocksdbAppStore` instance at the given path
+    ///
+    /// Returns an error when the underlying `rocksdb` can't be opened.
+    pub fn new<P>(path: P) -> io::Result<Self>
     where
         P: AsRef<Path>,
     {
-        Self {
+        Ok(Self {
+            db: Rocksdb::new(path)?,
             phantom: PhantomData,
-        }
+        })
     }
 }
 
@@ -31,11 +39,31 @@ where
     S: AppSerializer,
     D: AppDeserializer,
 {
-    fn store(&mut self, _app: &App, _creator: &CreatorAddr, _addr: &AppAddr) {
-        todo!()
+    fn store(&mut self, app: &App, creator: &CreatorAddr, addr: &AppAddr) {
+        info!("Storing `App`: \n{:?}", app);
+        info!("     Account Address: {:?}", addr.inner());
+
+        let bytes = S::serialize(app, creator);
+
+        // app addr -> (app, creator)
+        let entry = (addr.inner().as_slice(), &bytes[..]);
+
+        self.db.set(&[entry]);
     }
 
-    fn load(&self, _addr: &AppAddr) -> Option<(App, CreatorAddr)> {
-        todo!()
+    fn load(&self, addr: &AppAddr) -> Option<(App, CreatorAddr)> {
+        let addr = addr.inner().as_slice();
+
+        info!("Loading `App` account {:?}", addr);
+
+        self.db
+            .get(addr)
+            .and_then(|bytes| D::deserialize(&bytes[..]))
     }
 }
+
+/// `RocksdbAppStore` with default serialization.
+pub type DefaultRocksdbAppStore = RocksdbAppStore<
+    <DSer as EnvSerializerTypes>::AppSerializer,
+    <DSer as EnvSerializerTypes>::AppDeserializer,
+>;
@@ -1,11 +1,14 @@
+use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 use crate::{
     env::default::{
-        DefaultAppAddressCompute, DefaultAppTemplateAddressCompute, DefaultTemplateHasher,
+        DefaultAppAddressCompute, DefaultAppTemplateAddressCompute, DefaultSerializerTypes,
+        DefaultTemplateHasher,
     },
     env::rocksdb::{RocksdbAppStore, RocksdbAppTemplateStore},
-    env::traits::{Env, EnvSerializerTypes, EnvTypes},
+    env::traits::{BalanceStore, Env, EnvSerializerTypes, EnvTypes},
 };
 
 pub struct RocksdbEnvTypes<Ser>(PhantomData<Ser>);
@@ -34,6 +37,8 @@ where
     app_store: <RocksdbEnvTypes<Ser> as EnvTypes>::AppStore,
 
     template_store: <RocksdbEnvTypes<Ser> as EnvTypes>::TemplateStore,
+
+    balance_store: Option<Rc<RefCell<dyn BalanceStore>>>,
 }
 
 impl<Ser> RocksdbEnv<Ser>
@@ -48,8 +53,16 @@ where
         Self {
             app_store,
             template_store,
+            balance_store: None,
         }
     }
+
+    /// Enables the balance ledger, backed-by `balance_store`.
+    pub fn with_balance_store(mut self, balance_store: Rc<RefCell<dyn BalanceStore>>) -> Self {
+        self.balance_store = Some(balance_store);
+
+        self
+    }
 }
 
 impl<Ser> Env for RocksdbEnv<Ser>
@@ -73,4 +86,11 @@ where
     fn get_app_store_mut(&mut self) -> &mut <Self::Types as EnvTypes>::AppStore {
         &mut self.app_store
     }
+
+    fn get_balance_store(&self) -> Option<Rc<RefCell<dyn BalanceStore>>> {
+        self.balance_store.clone()
+    }
 }
+
+/// `RocksdbEnv` with default serialization.
+pub type DefaultRocksdbEnv = RocksdbEnv<DefaultSerializerTypes>;
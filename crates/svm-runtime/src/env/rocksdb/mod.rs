@@ -2,6 +2,6 @@ mod app_store;
 mod env;
 mod template_store;
 
-pub use app_store::RocksdbAppStore;
-pub use env::{RocksdbEnv, RocksdbEnvTypes};
-pub use template_store::RocksdbAppTemplateStore;
+pub use app_store::{DefaultRocksdbAppStore, RocksdbAppStore};
+pub use env::{DefaultRocksdbEnv, RocksdbEnv, RocksdbEnvTypes};
+pub use template_store::{DefaultRocksdbAppTemplateStore, RocksdbAppTemplateStore};
@@ -1,9 +1,10 @@
-use std::{marker::PhantomData, path::Path};
+use std::{io, marker::PhantomData, path::Path};
 
 use svm_kv::{rocksdb::Rocksdb, traits::RawKV};
 use svm_types::{AppTemplate, AuthorAddr, TemplateAddr};
 
-use crate::env::traits::AppTemplateStore;
+use crate::env::default::DefaultSerializerTypes as DSer;
+use crate::env::traits::{without_code, AppTemplateStore, EnvSerializerTypes};
 use crate::env::types::AppTemplateHash;
 
 use svm_codec::serializers::{AppTemplateDeserializer, AppTemplateSerializer};
@@ -22,14 +23,16 @@ where
     D: AppTemplateDeserializer,
 {
     /// Creates a new template store at the given path
-    pub fn new<P>(path: P) -> Self
+    ///
+    /// Returns an error when the underlying `rocksdb` can't be opened.
+    pub fn new<P>(path: P) -> io::Result<Self>
     where
         P: AsRef<Path>,
     {
-        Self {
-            db: Rocksdb::new(path),
+        Ok(Self {
+            db: Rocksdb::new(path)?,
             phantom: PhantomData,
-        }
+        })
     }
 }
 
@@ -49,13 +52,16 @@ where
         info!("     Account Address: {:?}", addr.inner());
         info!("     Hash: {:?}", hash);
 
-        let bytes = S::serialize(template, author);
+        let bytes = S::serialize(&without_code(template), author);
+
+        // template addr -> code-hash || template (without code)
+        let mut value = hash.0.to_vec();
+        value.extend_from_slice(&bytes);
 
-        // template addr -> code-hash
-        let entry1 = (addr.inner().as_slice(), &hash.0[..]);
+        let entry1 = (addr.inner().as_slice(), &value[..]);
 
         // code-hash -> code
-        let entry2 = (&hash.0[..], &bytes[..]);
+        let entry2 = (&hash.0[..], &template.code[..]);
 
         self.db.set(&[entry1, entry2]);
     }
@@ -65,10 +71,27 @@ where
 
         info!("Loading `AppTemplate` account {:?}", addr);
 
-        self.db.get(addr).and_then(|hash| {
-            self.db
-                .get(&hash)
-                .and_then(|bytes| D::deserialize(&bytes[..]))
-        })
+        let value = self.db.get(addr)?;
+
+        if value.len() < 32 {
+            return None;
+        }
+
+        let (hash, bytes) = value.split_at(32);
+
+        let (mut template, author) = D::deserialize(bytes)?;
+        template.code = self.db.get(hash)?;
+
+        Some((template, author))
+    }
+
+    fn load_code(&self, hash: &AppTemplateHash) -> Option<Vec<u8>> {
+        self.db.get(&hash.0)
     }
 }
+
+/// `RocksdbAppTemplateStore` with default serialization.
+pub type DefaultRocksdbAppTemplateStore = RocksdbAppTemplateStore<
+    <DSer as EnvSerializerTypes>::TemplateSerializer,
+    <DSer as EnvSerializerTypes>::TemplateDeserializer,
+>;
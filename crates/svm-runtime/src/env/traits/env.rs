@@ -1,7 +1,10 @@
+use std::cell::RefCell;
 use std::io::Cursor;
+use std::rc::Rc;
 
 use crate::env::traits::{
     AppAddressCompute, AppStore, AppTemplateAddressCompute, AppTemplateHasher, AppTemplateStore,
+    BalanceStore,
 };
 use crate::env::types::AppTemplateHash;
 
@@ -64,6 +67,11 @@ pub trait Env {
     /// Borrows mutably environment's `App`(s) store
     fn get_app_store_mut(&mut self) -> &mut <Self::Types as EnvTypes>::AppStore;
 
+    /// Returns environment's balances store (`None` when the balance ledger is disabled)
+    fn get_balance_store(&self) -> Option<Rc<RefCell<dyn BalanceStore>>> {
+        None
+    }
+
     /// Computes `AppTemplate` Hash
     fn compute_template_hash(&self, template: &AppTemplate) -> AppTemplateHash {
         <Self::Types as EnvTypes>::TemplateHasher::hash(template)
@@ -84,10 +92,22 @@ pub trait Env {
     /// Parses raw a deploy-template.
     /// On success returns `AppTemplate`,
     /// On failure returns `ParseError`.
+    ///
+    /// The code of a compact deploy-template is loaded from the `AppTemplate`(s) store.
+    /// (its code hash should be computed the same as `compute_template_hash` does).
+    /// When there's no such code, returns `ParseError::CodeNotFound`.
     fn parse_deploy_template(&self, bytes: &[u8]) -> Result<AppTemplate, ParseError> {
         let mut cursor = Cursor::new(bytes);
 
-        let template = template::decode_deploy_template(&mut cursor)?;
+        let (mut template, code_hash) = template::decode_compact_deploy_template(&mut cursor)?;
+
+        if let Some(hash) = code_hash {
+            let store = self.get_template_store();
+
+            template.code = store
+                .load_code(&AppTemplateHash(hash))
+                .ok_or(ParseError::CodeNotFound)?;
+        }
 
         Ok(template)
     }
@@ -116,7 +136,8 @@ pub trait Env {
 
     /// Stores the following:
     /// * `TemplateAddress` -> `TemplateHash`
-    /// * `TemplateHash` -> `AppTemplate` data
+    /// * `TemplateAddress` -> `AppTemplate` data (excluding its code)
+    /// * `TemplateHash` -> `AppTemplate` code
     fn store_template(&mut self, template: &AppTemplate, author: &AuthorAddr) -> TemplateAddr {
         let addr = self.derive_template_address(template);
         let hash = self.compute_template_hash(template);
@@ -144,6 +165,21 @@ pub trait Env {
         }
     }
 
+    /// Associates an existing `App` with another `AppTemplate` (used when migrating an app).
+    /// Returns `false` when there's no `App` with the given address.
+    fn switch_app_template(&mut self, addr: &AppAddr, template: &TemplateAddr) -> bool {
+        if let Some((mut app, creator)) = self.load_app(addr) {
+            app.template = template.clone();
+
+            let store = self.get_app_store_mut();
+            store.store(&app, &creator, addr);
+
+            true
+        } else {
+            false
+        }
+    }
+
     /// Given an `App` address, loads the `AppTemplate` the app is associated with.
     fn load_template_by_app(
         &self,
@@ -8,4 +8,6 @@ pub use compute_address::{AppAddressCompute, AppTemplateAddressCompute};
 pub use env::{Env, EnvTypes};
 pub use hasher::AppTemplateHasher;
 pub use serializer_types::EnvSerializerTypes;
-pub use store::{AppStore, AppTemplateStore};
+pub use store::{AppStore, AppTemplateStore, BalanceStore};
+
+pub(crate) use store::without_code;
@@ -1,5 +1,5 @@
 use crate::env::types::AppTemplateHash;
-use svm_types::{App, AppAddr, AppTemplate, AuthorAddr, CreatorAddr, TemplateAddr};
+use svm_types::{Address, App, AppAddr, AppTemplate, AuthorAddr, CreatorAddr, TemplateAddr};
 
 /// A persistent store for `AppTemplate`(s).
 pub trait AppTemplateStore {
@@ -8,7 +8,7 @@ pub trait AppTemplateStore {
     /// template - Struct holding the data of the Template. (struct representing the parsed raw data).
     /// author   - The `Address` of the Template Author.
     /// addr     - The `Address` of the Template.
-    /// hash     - Template's code Hash.
+    /// hash     - Template's code Hash (the code is stored under it).
     fn store(
         &mut self,
         template: &AppTemplate,
@@ -21,6 +21,25 @@ pub trait AppTemplateStore {
     /// and deserializes it into `AppTemplate`. Returns `None` if `AppTemplatee` doesn't exist.
     #[must_use]
     fn load(&self, addr: &TemplateAddr) -> Option<(AppTemplate, AuthorAddr)>;
+
+    /// Given a code `hash`, fetches the code stored under it.
+    ///
+    /// Code is content-addressed, so templates sharing the same code store it only once.
+    #[must_use]
+    fn load_code(&self, hash: &AppTemplateHash) -> Option<Vec<u8>>;
+}
+
+/// Returns a copy of `template` without its `code`.
+///
+/// Used by the `AppTemplateStore`(s) for storing a template apart from its (content-addressed) code.
+pub(crate) fn without_code(template: &AppTemplate) -> AppTemplate {
+    AppTemplate {
+        version: template.version,
+        name: template.name.clone(),
+        code: Vec::new(),
+        data: template.data.clone(),
+        ctors: template.ctors.clone(),
+    }
 }
 
 /// A persistent store for `A}pp`(s)
@@ -33,3 +52,13 @@ pub trait AppStore {
     #[must_use]
     fn load(&self, addr: &AppAddr) -> Option<(App, CreatorAddr)>;
 }
+
+/// A persistent store for account balances.
+pub trait BalanceStore {
+    /// Returns the balance of account `addr` (`0` for an unknown account).
+    #[must_use]
+    fn balance(&self, addr: &Address) -> u64;
+
+    /// Stores a batch of balances. Each entry is a tuple denoting `(account, new balance)`.
+    fn set_balances(&mut self, balances: &[(Address, u64)]);
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+use svm_storage::app::{MigrationError, UnknownState};
+use svm_types::{AppAddr, State, TemplateAddr};
+
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum MigrateError {
+    AppNotFound(AppAddr),
+    TemplateNotFound(TemplateAddr),
+    UnknownState(State),
+    Layout(MigrationError),
+}
+
+impl From<MigrationError> for MigrateError {
+    fn from(err: MigrationError) -> Self {
+        Self::Layout(err)
+    }
+}
+
+impl From<UnknownState> for MigrateError {
+    fn from(err: UnknownState) -> Self {
+        Self::UnknownState(err.0)
+    }
+}
+
+impl fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrateError::AppNotFound(addr) => write!(f, "App `{:?}` not found", addr),
+            MigrateError::TemplateNotFound(addr) => write!(f, "Template `{:?}` not found", addr),
+            MigrateError::UnknownState(state) => write!(f, "Unknown app state `{:?}`", state),
+            MigrateError::Layout(err) => err.fmt(f),
+        }
+    }
+}
@@ -1,3 +1,7 @@
+mod migrate;
 mod validate;
+mod vmcall;
 
+pub use migrate::MigrateError;
 pub use validate::ValidateError;
+pub use vmcall::VMCallError;
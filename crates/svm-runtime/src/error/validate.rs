@@ -2,12 +2,16 @@ use std::fmt;
 
 use svm_codec::ParseError;
 use svm_gas::error::ProgramError;
+use svm_types::{AppAddr, TemplateAddr};
 
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum ValidateError {
     Parse(ParseError),
     Program(ProgramError),
+    TemplateNotFound(TemplateAddr),
+    AppNotFound(AppAddr),
+    FuncNotFound(String),
 }
 
 impl From<ParseError> for ValidateError {
@@ -27,6 +31,9 @@ impl fmt::Display for ValidateError {
         match self {
             ValidateError::Program(err) => err.fmt(f),
             ValidateError::Parse(err) => err.fmt(f),
+            ValidateError::TemplateNotFound(addr) => write!(f, "Template `{:?}` not found", addr),
+            ValidateError::AppNotFound(addr) => write!(f, "App `{:?}` not found", addr),
+            ValidateError::FuncNotFound(func) => write!(f, "Function `{}` not found", func),
         }
     }
 }
@@ -0,0 +1,78 @@
+use std::error::Error;
+use std::fmt;
+
+use svm_storage::app::VecError;
+use svm_types::gas::OOGError;
+
+/// An error raised by a vmcall. It aborts the running app (i.e it traps the instance).
+///
+/// Once the instance has been trapped, the `Runtime` turns it into a `ReceiptError`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VMCallError {
+    /// Reached Out-of-Gas
+    OOG,
+
+    /// Accessing the memory cells `[offset, offset + len)` which exceed the instance memory
+    MemoryOutOfBounds { offset: u32, len: u32 },
+
+    /// Accessing a variable which isn't part of the app layout (or isn't of the expected length)
+    InvalidVarId(u32),
+
+    /// Setting a variable to a `value` which doesn't fit into its length
+    ValueOverflow { var_id: u32, value: u64 },
+
+    /// Accessing element `index` of a vector variable which has no such element
+    IndexOutOfBounds { var_id: u32, index: u32 },
+
+    /// Rolling-back (or releasing) a storage savepoint which isn't open
+    InvalidSavepoint(u32),
+}
+
+impl From<OOGError> for VMCallError {
+    fn from(_err: OOGError) -> Self {
+        Self::OOG
+    }
+}
+
+impl From<VecError> for VMCallError {
+    fn from(err: VecError) -> Self {
+        match err {
+            VecError::InvalidVar(var_id) => Self::InvalidVarId(var_id.0),
+            VecError::IndexOutOfBounds { var_id, index } => Self::IndexOutOfBounds {
+                var_id: var_id.0,
+                index,
+            },
+            VecError::LengthOverflow { var_id, len } => Self::ValueOverflow {
+                var_id: var_id.0,
+                value: len,
+            },
+        }
+    }
+}
+
+impl fmt::Display for VMCallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VMCallError::OOG => write!(f, "Reached Out-of-Gas"),
+            VMCallError::MemoryOutOfBounds { offset, len } => write!(
+                f,
+                "Memory out-of-bounds (offset = {}, length = {})",
+                offset, len
+            ),
+            VMCallError::InvalidVarId(var_id) => write!(f, "Invalid variable id `{}`", var_id),
+            VMCallError::ValueOverflow { var_id, value } => write!(
+                f,
+                "Value `{}` overflows variable `{}` length",
+                value, var_id
+            ),
+            VMCallError::IndexOutOfBounds { var_id, index } => write!(
+                f,
+                "Index `{}` is out-of-bounds for vector variable `{}`",
+                index, var_id
+            ),
+            VMCallError::InvalidSavepoint(id) => write!(f, "Invalid savepoint id `{}`", id),
+        }
+    }
+}
+
+impl Error for VMCallError {}
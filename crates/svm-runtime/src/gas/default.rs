@@ -1,20 +1,51 @@
-use svm_types::{AppTemplate, AppTransaction, SpawnApp};
+use svm_gas::{EstimateMode, Gas};
+use svm_types::{AppTemplate, SpawnApp};
 
-use super::estimator::{DeployTemplateEst, ExecAppEst, GasEstimator, SpawnAppEst};
+use crate::error::ValidateError;
+
+use super::{GasEstimator, VMCallsPricing};
+
+/// The price of each byte of a transaction's payload.
+const PAYLOAD_BYTE_PRICE: u64 = 1000;
 
 /// Default Gas estimation. Implements the `GasEstimator` trait.
+///
+/// Installation has no price of its own (it's covered by the payload price).
+///
+/// Functions containing loops or branches are estimated with an unbounded `Gas::Range`
+/// (their running relies on the dynamic gas metering).
 pub struct DefaultGasEstimator;
 
 impl GasEstimator for DefaultGasEstimator {
-    fn est_deploy_template(_bytes: &[u8], _template: &AppTemplate) -> DeployTemplateEst {
-        todo!()
+    fn est_payload(bytes: &[u8]) -> u64 {
+        PAYLOAD_BYTE_PRICE * (bytes.len() as u64)
+    }
+
+    fn est_install_template(_template: &AppTemplate) -> u64 {
+        0
     }
 
-    fn est_spawn_app(_bytes: &[u8], _spawn: &SpawnApp) -> SpawnAppEst {
-        todo!()
+    fn est_install_app(_spawn: &SpawnApp) -> u64 {
+        0
     }
 
-    fn est_exec_app(_bytes: &[u8], _tx: &AppTransaction) -> ExecAppEst {
-        todo!()
+    fn est_func(
+        template: &AppTemplate,
+        func_name: &str,
+        pricing: &VMCallsPricing,
+    ) -> Result<Gas, ValidateError> {
+        let funcs = svm_gas::estimate_exports(&template.code, pricing, EstimateMode::Dynamic)?;
+
+        let func_gas = |name: &str| {
+            funcs
+                .get(name)
+                .copied()
+                .ok_or_else(|| ValidateError::FuncNotFound(name.to_string()))
+        };
+
+        let alloc = func_gas("svm_alloc")?;
+        let func = func_gas(func_name)?;
+
+        Ok(alloc * func)
     }
 }
@@ -1,6 +1,10 @@
 use svm_gas::Gas;
 use svm_types::{AppTemplate, AppTransaction, SpawnApp};
 
+use crate::error::ValidateError;
+
+use super::VMCallsPricing;
+
 /// Holds estimated gas for deploying a new `AppTemplate`.
 pub struct DeployTemplateEst {
     pub payload: Gas,
@@ -8,6 +12,13 @@ pub struct DeployTemplateEst {
     pub install: Gas,
 }
 
+impl DeployTemplateEst {
+    /// The total estimated gas
+    pub fn total(&self) -> Gas {
+        self.payload * self.install
+    }
+}
+
 /// Holds estimated gas for spawning an new `App`.
 pub struct SpawnAppEst {
     pub payload: Gas,
@@ -17,6 +28,13 @@ pub struct SpawnAppEst {
     pub ctor: Gas,
 }
 
+impl SpawnAppEst {
+    /// The total estimated gas
+    pub fn total(&self) -> Gas {
+        self.payload * self.install * self.ctor
+    }
+}
+
 /// Holds estimated gas for executing an `AppTransaction`.
 pub struct ExecAppEst {
     pub payload: Gas,
@@ -24,14 +42,75 @@ pub struct ExecAppEst {
     pub exec: Gas,
 }
 
+impl ExecAppEst {
+    /// The total estimated gas
+    pub fn total(&self) -> Gas {
+        self.payload * self.exec
+    }
+}
+
 /// Trait in charge on doing gas estimation.
+///
+/// The `payload` and `install` prices are the ones being charged by the `Runtime`,
+/// so they must be known-ahead precisely.
 pub trait GasEstimator {
+    /// Returns the gas required for a transaction's payload. (`bytes` is the transaction raw format).
+    fn est_payload(bytes: &[u8]) -> u64;
+
+    /// Returns the gas required for installing `template`.
+    fn est_install_template(template: &AppTemplate) -> u64;
+
+    /// Returns the gas required for installing the app of `spawn`.
+    fn est_install_app(spawn: &SpawnApp) -> u64;
+
+    /// Estimates the gas required for running function `func_name` of `template` (priced according to `pricing`).
+    /// The estimation includes the running of `svm_alloc` (used for allocating the function's `calldata`).
+    fn est_func(
+        template: &AppTemplate,
+        func_name: &str,
+        pricing: &VMCallsPricing,
+    ) -> Result<Gas, ValidateError>;
+
     /// Estimates the gas required for deploying `template`. (`bytes` is the deploy-template raw format).
-    fn est_deploy_template(bytes: &[u8], template: &AppTemplate) -> DeployTemplateEst;
+    fn est_deploy_template(bytes: &[u8], template: &AppTemplate) -> DeployTemplateEst {
+        DeployTemplateEst {
+            payload: Gas::Fixed(Self::est_payload(bytes)),
+            install: Gas::Fixed(Self::est_install_template(template)),
+        }
+    }
+
+    /// Estimates the gas required for spawning app `spawn` of `template`. (`bytes` is the spawn-app raw format).
+    fn est_spawn_app(
+        bytes: &[u8],
+        spawn: &SpawnApp,
+        template: &AppTemplate,
+        pricing: &VMCallsPricing,
+    ) -> Result<SpawnAppEst, ValidateError> {
+        let ctor = Self::est_func(template, &spawn.ctor_name, pricing)?;
+
+        let est = SpawnAppEst {
+            payload: Gas::Fixed(Self::est_payload(bytes)),
+            install: Gas::Fixed(Self::est_install_app(spawn)),
+            ctor,
+        };
+
+        Ok(est)
+    }
+
+    /// Estimates the gas required for executing transaction `tx` of an app of `template`. (`bytes` is the exec-app raw format).
+    fn est_exec_app(
+        bytes: &[u8],
+        tx: &AppTransaction,
+        template: &AppTemplate,
+        pricing: &VMCallsPricing,
+    ) -> Result<ExecAppEst, ValidateError> {
+        let exec = Self::est_func(template, &tx.func_name, pricing)?;
 
-    /// Estimates the gas required for spawning app `spawn`. (`bytes` is the spawn-app raw format).
-    fn est_spawn_app(bytes: &[u8], spawn: &SpawnApp) -> SpawnAppEst;
+        let est = ExecAppEst {
+            payload: Gas::Fixed(Self::est_payload(bytes)),
+            exec,
+        };
 
-    /// Estimates the gas required for executing transaction `tx`. (`bytes` is the exec-app raw format).
-    fn est_exec_app(bytes: &[u8], tx: &AppTransaction) -> ExecAppEst;
+        Ok(est)
+    }
 }
@@ -3,4 +3,5 @@ mod estimator;
 mod pricing;
 
 pub use default::DefaultGasEstimator;
-pub use estimator::GasEstimator;
+pub use estimator::{DeployTemplateEst, ExecAppEst, GasEstimator, SpawnAppEst};
+pub use pricing::VMCallsPricing;
@@ -1,46 +1,310 @@
-/// Updates current running `App`'s `left gas`.
+use svm_gas::traits::VMCallsGasEstimator;
+use svm_gas::{FuncIndex, Gas};
+
+/// The gas price of each vmcall.
+///
+/// The price of `svm_log`, `svm_set_returndata` and the maps and vectors vmcalls
+/// is made of a fixed part and a part which scales with the number of bytes involved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VMCallsPricing {
+    /// `svm_get32` price
+    pub get32: u64,
+
+    /// `svm_set32` price
+    pub set32: u64,
+
+    /// `svm_get64` price
+    pub get64: u64,
+
+    /// `svm_set64` price
+    pub set64: u64,
+
+    /// `svm_load160` price
+    pub load160: u64,
+
+    /// `svm_store160` price
+    pub store160: u64,
+
+    /// `svm_calldata_offset` price
+    pub calldata_offset: u64,
+
+    /// `svm_calldata_len` price
+    pub calldata_len: u64,
+
+    /// `svm_set_returndata` fixed price
+    pub set_returndata: u64,
+
+    /// `svm_set_returndata` price per byte of `returndata`
+    pub set_returndata_per_byte: u64,
+
+    /// `svm_log` fixed price
+    pub log: u64,
+
+    /// `svm_log` price per byte of the logged message
+    pub log_per_byte: u64,
+
+    /// `svm_call` fixed price (the gas used by the called app is charged on top of it)
+    pub call: u64,
+
+    /// `svm_call_returndata_len` price
+    pub call_returndata_len: u64,
+
+    /// `svm_call_returndata_copy` fixed price
+    pub call_returndata_copy: u64,
+
+    /// `svm_call_returndata_copy` price per copied byte
+    pub call_returndata_copy_per_byte: u64,
+
+    /// `svm_value` price
+    pub value: u64,
+
+    /// `svm_balance` price
+    pub balance: u64,
+
+    /// `svm_transfer` price
+    pub transfer: u64,
+
+    /// `svm_host_get_len` price
+    pub host_get_len: u64,
+
+    /// `svm_host_get` fixed price
+    pub host_get: u64,
+
+    /// `svm_host_get` price per copied byte
+    pub host_get_per_byte: u64,
+
+    /// `svm_map_get` fixed price
+    pub map_get: u64,
+
+    /// `svm_map_get` price per byte of the key and value
+    pub map_get_per_byte: u64,
+
+    /// `svm_map_set` fixed price
+    pub map_set: u64,
+
+    /// `svm_map_set` price per byte of the key and value
+    pub map_set_per_byte: u64,
+
+    /// `svm_vec_len` price
+    pub vec_len: u64,
+
+    /// `svm_vec_get` fixed price
+    pub vec_get: u64,
+
+    /// `svm_vec_get` price per byte of the value
+    pub vec_get_per_byte: u64,
+
+    /// `svm_vec_push` fixed price
+    pub vec_push: u64,
+
+    /// `svm_vec_push` price per byte of the value
+    pub vec_push_per_byte: u64,
+
+    /// `svm_savepoint` price
+    pub savepoint: u64,
+
+    /// `svm_rollback_to` price
+    pub rollback_to: u64,
+
+    /// `svm_release` price
+    pub release: u64,
+}
+
+impl Default for VMCallsPricing {
+    fn default() -> Self {
+        Self {
+            get32: 10,
+            set32: 20,
+            get64: 10,
+            set64: 20,
+            load160: 20,
+            store160: 40,
+            calldata_offset: 2,
+            calldata_len: 2,
+            set_returndata: 10,
+            set_returndata_per_byte: 1,
+            log: 20,
+            log_per_byte: 1,
+            call: 100,
+            call_returndata_len: 2,
+            call_returndata_copy: 10,
+            call_returndata_copy_per_byte: 1,
+            value: 2,
+            balance: 10,
+            transfer: 50,
+            host_get_len: 2,
+            host_get: 10,
+            host_get_per_byte: 1,
+            map_get: 20,
+            map_get_per_byte: 1,
+            map_set: 40,
+            map_set_per_byte: 2,
+            vec_len: 10,
+            vec_get: 20,
+            vec_get_per_byte: 1,
+            vec_push: 50,
+            vec_push_per_byte: 2,
+            savepoint: 10,
+            rollback_to: 30,
+            release: 10,
+        }
+    }
+}
+
+/// Estimates the vmcalls according to their price table.
+///
+/// Since the number of bytes of `svm_log`, `svm_set_returndata` (and others) isn't known-ahead,
+/// their estimation is a `Gas::Range` covering any possible length (up to `u32::MAX` bytes).
+///
+/// The gas used by an app called using `svm_call` isn't known-ahead as well,
+/// so `svm_call` is estimated as an unbounded `Gas::Range`.
+///
+/// Imported functions which aren't `SVM` vmcalls (i.e host functions) aren't being charged.
+impl VMCallsGasEstimator for VMCallsPricing {
+    fn estimate_code(&self, _func_idx: FuncIndex, module: &str, name: &str) -> Gas {
+        if module != "svm" {
+            return Gas::Fixed(0);
+        }
+
+        let per_byte = |price: u64, byte_price: u64| Gas::Range {
+            min: price,
+            max: price.saturating_add(byte_price.saturating_mul(u64::from(u32::MAX))),
+        };
+
+        match name {
+            "svm_get32" => Gas::Fixed(self.get32),
+            "svm_set32" => Gas::Fixed(self.set32),
+            "svm_get64" => Gas::Fixed(self.get64),
+            "svm_set64" => Gas::Fixed(self.set64),
+            "svm_load160" => Gas::Fixed(self.load160),
+            "svm_store160" => Gas::Fixed(self.store160),
+            "svm_calldata_offset" => Gas::Fixed(self.calldata_offset),
+            "svm_calldata_len" => Gas::Fixed(self.calldata_len),
+            "svm_set_returndata" => per_byte(self.set_returndata, self.set_returndata_per_byte),
+            "svm_log" => per_byte(self.log, self.log_per_byte),
+            "svm_call" => Gas::unbounded(self.call),
+            "svm_call_returndata_len" => Gas::Fixed(self.call_returndata_len),
+            "svm_call_returndata_copy" => per_byte(
+                self.call_returndata_copy,
+                self.call_returndata_copy_per_byte,
+            ),
+            "svm_value" => Gas::Fixed(self.value),
+            "svm_balance" => Gas::Fixed(self.balance),
+            "svm_transfer" => Gas::Fixed(self.transfer),
+            "svm_host_get_len" => Gas::Fixed(self.host_get_len),
+            "svm_host_get" => per_byte(self.host_get, self.host_get_per_byte),
+            "svm_map_get" => per_byte(self.map_get, self.map_get_per_byte),
+            "svm_map_set" => per_byte(self.map_set, self.map_set_per_byte),
+            "svm_vec_len" => Gas::Fixed(self.vec_len),
+            "svm_vec_get" => per_byte(self.vec_get, self.vec_get_per_byte),
+            "svm_vec_push" => per_byte(self.vec_push, self.vec_push_per_byte),
+            "svm_savepoint" => Gas::Fixed(self.savepoint),
+            "svm_rollback_to" => Gas::Fixed(self.rollback_to),
+            "svm_release" => Gas::Fixed(self.release),
+            _ => Gas::Fixed(0),
+        }
+    }
+}
+
+/// Charges the running `App` for the vmcall being executed.
+///
+/// The price is taken from the `VMCallsPricing` of the `Context`.
+/// In case there isn't enough gas left, the macro returns (using `?`) an `OOGError`
+/// from the calling vmcall. This aborts the running instance with an `Out-of-Gas` trap.
 #[macro_export]
 macro_rules! use_gas {
     ("get32", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed get32, $ctx)
     }};
     ("set32", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed set32, $ctx)
     }};
     ("get64", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed get64, $ctx)
     }};
     ("set64", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed set64, $ctx)
     }};
     ("load160", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed load160, $ctx)
     }};
     ("store160", $ctx:expr) => {{
-        //
-    }};
-    ("host_get64", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed store160, $ctx)
     }};
     ("calldata_offset", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed calldata_offset, $ctx)
     }};
     ("calldata_len", $ctx:expr) => {{
-        //
+        $crate::use_gas!(@fixed calldata_len, $ctx)
+    }};
+    ("set_returndata", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte set_returndata, set_returndata_per_byte, $ctx, $nbytes)
+    }};
+    ("log", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte log, log_per_byte, $ctx, $nbytes)
     }};
-    ("set_returndata", $ctx:expr) => {{
-        //
+    ("call", $ctx:expr) => {{
+        $crate::use_gas!(@fixed call, $ctx)
     }};
-    ("log", $ctx:expr) => {{
-        //
+    ("call_returndata_len", $ctx:expr) => {{
+        $crate::use_gas!(@fixed call_returndata_len, $ctx)
+    }};
+    ("call_returndata_copy", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte call_returndata_copy, call_returndata_copy_per_byte, $ctx, $nbytes)
+    }};
+    ("value", $ctx:expr) => {{
+        $crate::use_gas!(@fixed value, $ctx)
+    }};
+    ("balance", $ctx:expr) => {{
+        $crate::use_gas!(@fixed balance, $ctx)
+    }};
+    ("transfer", $ctx:expr) => {{
+        $crate::use_gas!(@fixed transfer, $ctx)
+    }};
+    ("host_get_len", $ctx:expr) => {{
+        $crate::use_gas!(@fixed host_get_len, $ctx)
+    }};
+    ("host_get", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte host_get, host_get_per_byte, $ctx, $nbytes)
+    }};
+    ("map_get", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte map_get, map_get_per_byte, $ctx, $nbytes)
+    }};
+    ("map_set", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte map_set, map_set_per_byte, $ctx, $nbytes)
+    }};
+    ("vec_len", $ctx:expr) => {{
+        $crate::use_gas!(@fixed vec_len, $ctx)
+    }};
+    ("vec_get", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte vec_get, vec_get_per_byte, $ctx, $nbytes)
+    }};
+    ("vec_push", $ctx:expr, $nbytes:expr) => {{
+        $crate::use_gas!(@per_byte vec_push, vec_push_per_byte, $ctx, $nbytes)
+    }};
+    ("savepoint", $ctx:expr) => {{
+        $crate::use_gas!(@fixed savepoint, $ctx)
+    }};
+    ("rollback_to", $ctx:expr) => {{
+        $crate::use_gas!(@fixed rollback_to, $ctx)
+    }};
+    ("release", $ctx:expr) => {{
+        $crate::use_gas!(@fixed release, $ctx)
     }};
 
-    ($ctx:expr) => {{
-        use crate::Context
+    (@fixed $price:ident, $ctx:expr) => {{
+        let mut borrow = $ctx.borrow_mut();
+        let price = borrow.pricing.$price;
 
-        if ctx.gas_metering {
-            // TODO: hardcode the `gas` pricing for each vmcall.
+        borrow.use_gas(price)?;
+    }};
+    (@per_byte $price:ident, $per_byte:ident, $ctx:expr, $nbytes:expr) => {{
+        let mut borrow = $ctx.borrow_mut();
+        let pricing = &borrow.pricing;
+        let price = pricing
+            .$price
+            .saturating_add(pricing.$per_byte.saturating_mul($nbytes as u64));
 
-        }
+        borrow.use_gas(price)?;
     }};
 }
@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_types::receipt::{BalanceChange, ReceiptError};
+use svm_types::Address;
+
+use crate::env::traits::BalanceStore;
+
+/// Tracks the balance changes made by a running transaction, on top of a `BalanceStore`.
+///
+/// The changes are kept pending until `commit` is called (i.e when the transaction succeeds).
+/// When there is no `BalanceStore` (i.e the balance ledger is disabled), each account has a zero balance.
+#[derive(Clone, Default)]
+pub struct Ledger {
+    store: Option<Rc<RefCell<dyn BalanceStore>>>,
+
+    /// The pending changes (ordered by the first time each account has been touched)
+    changes: Vec<BalanceChange>,
+}
+
+impl Ledger {
+    /// Creates a new `Ledger` having no pending changes
+    pub fn new(store: Option<Rc<RefCell<dyn BalanceStore>>>) -> Self {
+        Self {
+            store,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Returns the balance of account `addr` (including the pending changes)
+    pub fn balance(&self, addr: &Address) -> u64 {
+        match self.changes.iter().find(|change| &change.addr == addr) {
+            Some(change) => change.new_balance,
+            None => self.stored_balance(addr),
+        }
+    }
+
+    /// Transfers `amount` coins from account `src` to account `dst`.
+    ///
+    /// Returns `ReceiptError::InsufficientFunds` (leaving the balances untouched) when `src` can't afford `amount`.
+    pub fn transfer(
+        &mut self,
+        src: &Address,
+        dst: &Address,
+        amount: u64,
+    ) -> Result<(), ReceiptError> {
+        let balance = self.balance(src);
+
+        if balance < amount {
+            return Err(ReceiptError::InsufficientFunds {
+                addr: src.clone(),
+                balance,
+                amount,
+            });
+        }
+
+        if amount == 0 || src == dst {
+            return Ok(());
+        }
+
+        self.set_balance(src, balance - amount);
+
+        let dst_balance = self.balance(dst);
+        self.set_balance(dst, dst_balance.saturating_add(amount));
+
+        Ok(())
+    }
+
+    /// Returns the pending changes
+    pub fn changes(&self) -> &[BalanceChange] {
+        &self.changes
+    }
+
+    /// Overrides the pending changes (used for adopting the changes made by a successful `svm_call`)
+    pub fn set_changes(&mut self, changes: Vec<BalanceChange>) {
+        self.changes = changes;
+    }
+
+    /// Persists the pending changes into the `BalanceStore`.
+    /// Returns the changes that actually modified a balance.
+    pub fn commit(&mut self) -> Vec<BalanceChange> {
+        let changes: Vec<BalanceChange> = self
+            .changes
+            .drain(..)
+            .filter(|change| change.old_balance != change.new_balance)
+            .collect();
+
+        if let Some(store) = &self.store {
+            if !changes.is_empty() {
+                let balances: Vec<(Address, u64)> = changes
+                    .iter()
+                    .map(|change| (change.addr.clone(), change.new_balance))
+                    .collect();
+
+                store.borrow_mut().set_balances(&balances);
+            }
+        }
+
+        changes
+    }
+
+    fn stored_balance(&self, addr: &Address) -> u64 {
+        self.store
+            .as_ref()
+            .map_or(0, |store| store.borrow().balance(addr))
+    }
+
+    fn set_balance(&mut self, addr: &Address, new_balance: u64) {
+        match self.changes.iter_mut().find(|change| &change.addr == addr) {
+            Some(change) => change.new_balance = new_balance,
+            None => {
+                let old_balance = self.stored_balance(addr);
+
+                self.changes.push(BalanceChange {
+                    addr: addr.clone(),
+                    old_balance,
+                    new_balance,
+                });
+            }
+        }
+    }
+}
@@ -11,10 +11,10 @@
 
 /// Implements the most high-level API of `SVM`.
 mod runtime;
-pub use runtime::{Config, DefaultRuntime, Runtime, RuntimePtr};
+pub use runtime::{Config, DefaultRuntime, ModuleCache, Runtime, RuntimePtr};
 
 #[cfg(feature = "default-rocksdb")]
-pub use runtime::create_rocksdb_runtime;
+pub use runtime::{create_rocksdb_runtime, create_rocksdb_runtime_with_config};
 
 /// Gas estimation and metering.
 pub mod gas;
@@ -29,6 +29,14 @@ pub use import::ExternImport;
 mod context;
 pub use context::Context;
 
+/// App-to-app calls (see the `svm_call` vmcall).
+mod call;
+pub use call::{AppCall, CallHandler, CallOutcome, MAX_CALL_DEPTH};
+
+/// The native coin balances ledger.
+mod ledger;
+pub use ledger::Ledger;
+
 /// Implements common functionalities to be consnumed by tests.
 pub mod testing;
 
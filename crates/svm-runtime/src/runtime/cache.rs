@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{error, info};
+use lru::LruCache;
+
+use svm_compiler::CompilationError;
+use wasmer::{Module, Store};
+
+use crate::env::types::AppTemplateHash;
+
+/// The name of the directory (under the `kv_path`) holding the persisted compiled artifacts.
+const ARTIFACTS_DIR: &str = "artifacts";
+
+/// An LRU cache of compiled `Module`s, keyed by their `AppTemplate` hash.
+///
+/// When an `artifacts_path` is given, each compiled `Module` is also persisted (serialized) to disk.
+/// On a cache miss, a persisted artifact is preferred over recompiling the template's code.
+///
+/// The artifacts are persisted under a directory named after the `wasmer` version and
+/// the `svm_compiler::ARTIFACT_VERSION`, so artifacts emitted by another compiler are never loaded.
+/// An artifact failing to deserialize is treated as a cache miss (and overwritten once recompiled).
+pub struct ModuleCache {
+    modules: LruCache<AppTemplateHash, Module>,
+
+    artifacts_path: Option<PathBuf>,
+}
+
+impl ModuleCache {
+    /// Creates a new `ModuleCache` holding up to `capacity` compiled modules.
+    ///
+    /// When `kv_path` is given, the compiled artifacts are persisted under it.
+    pub fn new(capacity: usize, kv_path: Option<&Path>) -> Self {
+        let artifacts_path = kv_path.map(|path| {
+            let version = format!(
+                "wasmer-{}-v{}",
+                wasmer::VERSION,
+                svm_compiler::ARTIFACT_VERSION
+            );
+
+            path.join(ARTIFACTS_DIR).join(version)
+        });
+
+        Self {
+            modules: LruCache::new(capacity),
+            artifacts_path,
+        }
+    }
+
+    /// Returns the compiled `Module` of the template (having hash `hash` and wasm `code`).
+    ///
+    /// In case the `Module` isn't in the cache, it's loaded from disk (if persisted)
+    /// or compiled (and persisted if required).
+    pub fn get_or_compile(
+        &mut self,
+        store: &Store,
+        hash: &AppTemplateHash,
+        code: &[u8],
+    ) -> Result<Module, CompilationError> {
+        if let Some(module) = self.modules.get(hash) {
+            return Ok(module.clone());
+        }
+
+        let module = match self.load_artifact(store, hash) {
+            Some(module) => module,
+            None => {
+                let module = svm_compiler::compile(store, code)?;
+                self.store_artifact(hash, &module);
+
+                module
+            }
+        };
+
+        self.modules.put(hash.clone(), module.clone());
+
+        Ok(module)
+    }
+
+    /// Returns whether a compiled `Module` of template hash `hash` is in the cache.
+    pub fn contains(&self, hash: &AppTemplateHash) -> bool {
+        self.modules.contains(hash)
+    }
+
+    /// The number of cached modules.
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    fn load_artifact(&self, store: &Store, hash: &AppTemplateHash) -> Option<Module> {
+        let path = self.artifact_path(hash)?;
+
+        if !path.exists() {
+            return None;
+        }
+
+        // Safety: the artifacts directory is written only by `store_artifact`.
+        match unsafe { Module::deserialize_from_file(store, &path) } {
+            Ok(module) => {
+                info!("loaded compiled artifact {:?}", path);
+
+                Some(module)
+            }
+            Err(e) => {
+                // e.g a corrupted file, it will be overwritten once the code is recompiled
+                error!("failed loading compiled artifact {:?} ({})", path, e);
+
+                None
+            }
+        }
+    }
+
+    fn store_artifact(&self, hash: &AppTemplateHash, module: &Module) {
+        if let (Some(dir), Some(path)) = (self.artifacts_path.as_ref(), self.artifact_path(hash)) {
+            let res = fs::create_dir_all(dir)
+                .map_err(|e| e.to_string())
+                .and_then(|_| module.serialize_to_file(&path).map_err(|e| e.to_string()));
+
+            if let Err(e) = res {
+                error!("failed persisting compiled artifact {:?} ({})", path, e);
+            }
+        }
+    }
+
+    /// Returns the path of the persisted artifact of the template having hash `hash`.
+    ///
+    /// Returns `None` when the artifacts aren't persisted.
+    pub fn artifact_path(&self, hash: &AppTemplateHash) -> Option<PathBuf> {
+        self.artifacts_path.as_ref().map(|dir| {
+            let file_name = svm_common::fmt::fmt_hex(&hash.0, "");
+
+            dir.join(file_name)
+        })
+    }
+}
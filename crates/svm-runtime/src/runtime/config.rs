@@ -1,10 +1,43 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use svm_codec::version::Format;
+use svm_storage::kv::Retention;
+
+use crate::gas::VMCallsPricing;
+
+/// The default maximum number of compiled templates held in memory
+pub const DEFAULT_MODULE_CACHE_SIZE: usize = 100;
+
 /// Runtime configuration
 #[derive(Debug, Clone)]
 pub struct Config {
     /// The path for the key-value store
     pub kv_path: PathBuf,
+
+    /// The vmcalls price table
+    pub pricing: VMCallsPricing,
+
+    /// The maximum number of compiled templates held in memory
+    pub module_cache_size: usize,
+
+    /// Whether to persist the compiled templates to disk (under `kv_path`)
+    pub persist_artifacts: bool,
+
+    /// Whether to collect the storage changes made by a transaction into its receipt
+    pub collect_storage_changes: bool,
+
+    /// The maximum storage size (in bytes) an app may occupy (see `svm_storage::app::StorageUsage`).
+    /// A transaction leaving its app above that limit fails. `None` means no limit.
+    pub max_storage_size: Option<u64>,
+
+    /// The transactions versions accepted by the runtime (per raw format).
+    /// Defaults to all the versions supported by `svm-codec`.
+    pub accepted_versions: HashMap<Format, Vec<u16>>,
+
+    /// The retention policy of the apps storage key-value store states.
+    /// The store is pruned after each transaction, so only the states as of the last transactions are kept.
+    pub retention: Retention,
 }
 
 impl Config {
@@ -12,6 +45,71 @@ impl Config {
     pub fn new<P: AsRef<Path>>(kv_path: P) -> Self {
         Self {
             kv_path: kv_path.as_ref().to_path_buf(),
+            pricing: VMCallsPricing::default(),
+            module_cache_size: DEFAULT_MODULE_CACHE_SIZE,
+            persist_artifacts: false,
+            collect_storage_changes: false,
+            max_storage_size: None,
+            accepted_versions: default_accepted_versions(),
+            retention: Retention::KeepAll,
         }
     }
+
+    /// Overrides the default vmcalls price table
+    pub fn with_pricing(mut self, pricing: VMCallsPricing) -> Self {
+        self.pricing = pricing;
+
+        self
+    }
+
+    /// Overrides the default size of the compiled templates cache
+    pub fn with_module_cache_size(mut self, size: usize) -> Self {
+        self.module_cache_size = size;
+
+        self
+    }
+
+    /// Sets whether to persist the compiled templates to disk
+    pub fn with_persist_artifacts(mut self, persist: bool) -> Self {
+        self.persist_artifacts = persist;
+
+        self
+    }
+
+    /// Sets whether to collect the storage changes made by a transaction into its receipt
+    pub fn with_storage_changes(mut self, collect: bool) -> Self {
+        self.collect_storage_changes = collect;
+
+        self
+    }
+
+    /// Sets the maximum storage size (in bytes) an app may occupy
+    pub fn with_max_storage_size(mut self, size: u64) -> Self {
+        self.max_storage_size = Some(size);
+
+        self
+    }
+
+    /// Overrides the versions of raw format `format` accepted by the runtime
+    pub fn with_accepted_versions(mut self, format: Format, versions: &[u16]) -> Self {
+        self.accepted_versions.insert(format, versions.to_vec());
+
+        self
+    }
+
+    /// Overrides the default (`KeepAll`) retention policy of the apps storage key-value store states
+    pub fn with_retention(mut self, retention: Retention) -> Self {
+        self.retention = retention;
+
+        self
+    }
+}
+
+fn default_accepted_versions() -> HashMap<Format, Vec<u16>> {
+    let formats = [Format::DeployTemplate, Format::SpawnApp, Format::ExecApp];
+
+    formats
+        .iter()
+        .map(|format| (*format, format.versions().to_vec()))
+        .collect()
 }
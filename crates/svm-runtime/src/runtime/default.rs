@@ -1,5 +1,6 @@
 use core::panic;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::c_void;
 use std::fmt;
 use std::marker::PhantomData;
@@ -8,29 +9,36 @@ use std::path::Path;
 use log::{debug, error, info};
 
 use crate::env::traits::{Env, EnvTypes};
-use crate::error::ValidateError;
+use crate::error::{MigrateError, VMCallError, ValidateError};
 use crate::gas::GasEstimator;
 use crate::storage::StorageBuilderFn;
 use crate::vmcalls;
-use crate::{Config, Context, ExternImport, Runtime};
+use crate::{
+    AppCall, CallHandler, CallOutcome, Config, Context, ExternImport, Ledger, ModuleCache, Runtime,
+};
 
+use svm_codec::version::Format;
 use svm_codec::ParseError;
 use svm_ffi::svm_env_t;
-use svm_gas::Gas;
-use svm_layout::DataLayout;
-use svm_storage::app::AppStorage;
+use svm_gas::{EstimateMode, Gas};
+use svm_layout::{DataLayout, VarId};
+use svm_storage::app::{AppStorage, LayoutMigration, StorageUsage, UnknownState};
+use svm_storage::kv::Retention;
 
 use svm_types::gas::{MaybeGas, OOGError};
-use svm_types::receipt::{self, ExecReceipt, Log, ReceiptError, SpawnAppReceipt, TemplateReceipt};
+use svm_types::receipt::{
+    self, BalanceChange, ExecReceipt, Log, ReceiptError, SpawnAppReceipt, StorageChange,
+    TemplateReceipt,
+};
 
 use svm_types::{
-    AppAddr, AppTemplate, AppTransaction, AuthorAddr, CreatorAddr, SpawnApp, State, TemplateAddr,
-    Type,
+    Address, AppAddr, AppTemplate, AppTransaction, AuthorAddr, CreatorAddr, HostCtx, SpawnApp,
+    State, TemplateAddr, Type,
 };
 
 use wasmer::{
     Export, Exports, Extern, Function, ImportObject, Instance, Memory, MemoryType, Module,
-    NativeFunc, Store, Type as WasmerType, Value as WasmerValue, WasmPtr,
+    NativeFunc, RuntimeError, Store, Type as WasmerType, Value as WasmerValue, WasmPtr,
 };
 
 /// Default `Runtime` implementation based on `Wasmer`.
@@ -47,20 +55,37 @@ pub struct DefaultRuntime<ENV, GE> {
     /// builds a `AppStorage` instance.
     storage_builder: Box<StorageBuilderFn>,
 
+    /// The `Store` shared by all the compiled modules.
+    store: Store,
+
+    /// Caches the compiled modules (keyed by their template hash).
+    module_cache: RefCell<ModuleCache>,
+
+    /// The key-value store states as of the last transactions (retained according to `Config::retention`).
+    kv_history: RefCell<VecDeque<State>>,
+
     phantom: PhantomData<GE>,
 }
 
 impl<TY, ENV, GE> Runtime for DefaultRuntime<ENV, GE>
 where
     TY: EnvTypes,
-    ENV: Env<Types = TY>,
-    GE: GasEstimator,
+    ENV: Env<Types = TY> + 'static,
+    GE: GasEstimator + 'static,
 {
+    fn accepts_version(&self, format: Format, version: u16) -> bool {
+        match self.config.accepted_versions.get(&format) {
+            Some(versions) => versions.contains(&version),
+            None => false,
+        }
+    }
+
     fn validate_template(&self, bytes: &[u8]) -> Result<(), ValidateError> {
         let template = self.parse_deploy_template(bytes)?;
         let code = &template.code;
 
-        svm_gas::validate_code(code).map_err(|e| e.into())
+        // The executed code is metered dynamically, so it may contain loops and branches
+        svm_gas::validate_code_with_mode(code, EstimateMode::Dynamic).map_err(|e| e.into())
     }
 
     fn validate_app(&self, bytes: &[u8]) -> Result<(), ValidateError> {
@@ -70,8 +95,7 @@ where
     }
 
     fn validate_tx(&self, bytes: &[u8]) -> Result<AppAddr, ValidateError> {
-        self.env
-            .parse_exec_app(bytes)
+        self.parse_exec_app(bytes)
             .map(|tx| tx.app)
             .map_err(|e| e.into())
     }
@@ -79,30 +103,58 @@ where
     fn estimate_deploy_template(&self, bytes: &[u8]) -> Result<Gas, ValidateError> {
         self.validate_template(bytes)?;
 
-        todo!()
+        let template = self.parse_deploy_template(bytes)?;
+        let est = GE::est_deploy_template(bytes, &template);
+
+        Ok(est.total())
     }
 
     fn estimate_spawn_app(&self, bytes: &[u8]) -> Result<Gas, ValidateError> {
-        self.validate_app(bytes)?;
+        let spawn = self.parse_spawn_app(bytes)?;
+        let template_addr = &spawn.app.template;
+
+        let (template, _author) = self
+            .env
+            .load_template(template_addr)
+            .ok_or_else(|| ValidateError::TemplateNotFound(template_addr.clone()))?;
 
-        todo!()
+        let est = GE::est_spawn_app(bytes, &spawn, &template, &self.config.pricing)?;
+
+        Ok(est.total())
     }
 
     fn estimate_exec_app(&self, bytes: &[u8]) -> Result<Gas, ValidateError> {
-        self.validate_tx(bytes)?;
+        let tx = self.parse_exec_app(bytes)?;
+
+        let (template, _template_addr, _author, _creator) = self
+            .env
+            .load_template_by_app(&tx.app)
+            .ok_or_else(|| ValidateError::AppNotFound(tx.app.clone()))?;
 
-        todo!()
+        let est = GE::est_exec_app(bytes, &tx, &template, &self.config.pricing)?;
+
+        Ok(est.total())
     }
 
     fn deploy_template(
         &mut self,
         bytes: &[u8],
         author: &AuthorAddr,
+        _host_ctx: &HostCtx,
         gas_limit: MaybeGas,
     ) -> TemplateReceipt {
         info!("runtime `deploy_template`");
 
-        let template = self.parse_deploy_template(bytes).unwrap();
+        let template = match self.parse_deploy_template(bytes) {
+            Ok(template) => template,
+            Err(e) => {
+                return TemplateReceipt::from_err(
+                    ReceiptError::ParseFailed(e.to_string()),
+                    Vec::new(),
+                )
+            }
+        };
+
         let install_gas = self.compute_install_template_gas(bytes, &template);
 
         if gas_limit >= install_gas {
@@ -119,11 +171,21 @@ where
         &mut self,
         bytes: &[u8],
         creator: &CreatorAddr,
+        host_ctx: &HostCtx,
         gas_limit: MaybeGas,
     ) -> SpawnAppReceipt {
         info!("runtime `spawn_app`");
 
-        let spawn = self.parse_spawn_app(bytes).unwrap();
+        let spawn = match self.parse_spawn_app(bytes) {
+            Ok(spawn) => spawn,
+            Err(e) => {
+                return SpawnAppReceipt::from_err(
+                    ReceiptError::ParseFailed(e.to_string()),
+                    Vec::new(),
+                )
+            }
+        };
+
         let install_gas = self.compute_install_app_gas(bytes, &spawn);
 
         let gas_left = gas_limit - install_gas;
@@ -146,24 +208,157 @@ where
                 let addr = self.install_app(&spawn, creator);
                 let gas_used = install_gas.into();
 
-                self.call_ctor(creator, spawn, &addr, gas_used, gas_left)
+                self.call_ctor(creator, host_ctx, spawn, &addr, gas_used, gas_left)
             }
         }
     }
 
-    fn exec_app(&self, bytes: &[u8], state: &State, gas_limit: MaybeGas) -> ExecReceipt {
-        let tx = self.parse_exec_app(bytes).unwrap();
-        let gas_used = MaybeGas::with(0);
+    fn exec_app(
+        &self,
+        bytes: &[u8],
+        host_ctx: &HostCtx,
+        state: &State,
+        gas_limit: MaybeGas,
+    ) -> ExecReceipt {
+        let tx = match self.parse_exec_app(bytes) {
+            Ok(tx) => tx,
+            Err(e) => {
+                return ExecReceipt::from_err(ReceiptError::ParseFailed(e.to_string()), Vec::new())
+            }
+        };
+
+        let payload_gas = GE::est_payload(bytes);
+
+        match gas_limit - payload_gas {
+            Err(..) => ExecReceipt::new_oog(Vec::new()),
+            Ok(gas_left) => {
+                let gas_used = MaybeGas::with(payload_gas);
+                let sender = host_ctx.sender().unwrap_or_else(Address::zeros);
 
-        self.exec(&tx, state, gas_used, gas_limit, false)
+                self.exec(&tx, &sender, host_ctx, state, gas_used, gas_left, false)
+            }
+        }
+    }
+
+    fn storage_usage(&self, app_addr: &AppAddr, state: &State) -> Option<StorageUsage> {
+        let (template, _template_addr, _author, _creator) =
+            self.env.load_template_by_app(app_addr)?;
+
+        let storage = self
+            .open_app_storage(app_addr, state, &template.data)
+            .ok()?;
+
+        Some(storage.usage())
+    }
+
+    fn migrate_app(
+        &mut self,
+        app_addr: &AppAddr,
+        state: &State,
+        template_addr: &TemplateAddr,
+        mapping: &[(VarId, VarId)],
+    ) -> Result<State, MigrateError> {
+        info!("runtime `migrate_app` (app={:?})", app_addr);
+
+        let (template, _template_addr, _author, _creator) = self
+            .env
+            .load_template_by_app(app_addr)
+            .ok_or_else(|| MigrateError::AppNotFound(app_addr.clone()))?;
+
+        let (new_template, _author) = self
+            .env
+            .load_template(template_addr)
+            .ok_or_else(|| MigrateError::TemplateNotFound(template_addr.clone()))?;
+
+        let migration =
+            LayoutMigration::new(template.data.clone(), new_template.data.clone(), mapping)?;
+
+        let mut storage = self.open_app_storage(app_addr, state, &template.data)?;
+        let new_state = storage.migrate(&migration)?;
+
+        self.apply_retention(&mut storage);
+
+        // from now on the app is executed (and its storage is opened) using the new `DataLayout`
+        let switched = self.env.switch_app_template(app_addr, template_addr);
+        debug_assert!(switched);
+
+        Ok(new_state)
+    }
+}
+
+impl<TY, ENV, GE> CallHandler for DefaultRuntime<ENV, GE>
+where
+    TY: EnvTypes,
+    ENV: Env<Types = TY> + 'static,
+    GE: GasEstimator + 'static,
+{
+    fn call(&self, call: AppCall) -> CallOutcome {
+        info!("runtime `call` (app={:?})", call.app);
+
+        let tx = AppTransaction {
+            version: Format::ExecApp.latest(),
+            app: call.app,
+            value: 0,
+            func_name: call.func_name,
+            calldata: call.calldata,
+        };
+
+        let (template, template_addr, _author, _creator) = match self.load_template(&tx) {
+            Ok(loaded) => loaded,
+            Err(_) => return CallOutcome::failure(0, Vec::new()),
+        };
+
+        let store = &self.store;
+        // the called app runs against the current state (i.e including the changes committed so far)
+        let storage = (self.storage_builder)(&tx.app, &template.data, &self.config);
+        let mut ctx = self.create_context(&tx.app, storage, call.gas_limit);
+
+        {
+            let mut borrow = ctx.borrow_mut();
+
+            borrow.set_call_stack(call.call_stack);
+            borrow.set_host_ctx(call.host_ctx);
+            borrow.ledger.set_changes(call.balance_changes);
+        }
+
+        let (import_object, funcs_envs) = self.create_import_object(store, &mut ctx);
+
+        let (result, logs) = self._exec(
+            store,
+            &ctx,
+            &tx,
+            &template,
+            &template_addr,
+            &import_object,
+            call.gas_limit,
+            false,
+        );
+
+        self.funcs_envs_destroy(funcs_envs);
+
+        match result {
+            Ok((_new_state, returndata, gas_used)) => CallOutcome {
+                success: true,
+                returndata: returndata.unwrap_or_default(),
+                gas_used: gas_used.unwrap_or(0),
+                logs,
+                balance_changes: ctx.borrow().ledger.changes().to_vec(),
+            },
+            Err(e) => {
+                info!("runtime `call` has failed ({:?})", e);
+
+                // a failed call consumes all the gas it has been given
+                CallOutcome::failure(call.gas_limit.unwrap_or(0), logs)
+            }
+        }
     }
 }
 
 impl<TY, ENV, GE> DefaultRuntime<ENV, GE>
 where
     TY: EnvTypes,
-    ENV: Env<Types = TY>,
-    GE: GasEstimator,
+    ENV: Env<Types = TY> + 'static,
+    GE: GasEstimator + 'static,
 {
     /// Initializes a new `DefaultRuntime`.
     pub fn new<P: AsRef<Path>>(
@@ -173,18 +368,48 @@ where
         storage_builder: Box<StorageBuilderFn>,
     ) -> Self {
         let config = Config::new(kv_path);
+
+        Self::new_with_config(env, config, imports, storage_builder)
+    }
+
+    /// Initializes a new `DefaultRuntime` using the given `Config`.
+    pub fn new_with_config(
+        env: ENV,
+        config: Config,
+        imports: &Vec<ExternImport>,
+        storage_builder: Box<StorageBuilderFn>,
+    ) -> Self {
         let imports = imports as *const _;
 
+        let artifacts_path = if config.persist_artifacts {
+            Some(config.kv_path.as_path())
+        } else {
+            None
+        };
+        let module_cache = ModuleCache::new(config.module_cache_size, artifacts_path);
+
         Self {
             env,
             config,
             imports,
             storage_builder,
+            store: svm_compiler::new_store(),
+            module_cache: RefCell::new(module_cache),
+            kv_history: RefCell::new(VecDeque::new()),
             phantom: PhantomData::<GE>,
         }
     }
 
-    /// Initialize a new `AppStorage` and returndata it.
+    /// Returns the cache of the compiled modules.
+    /// This method is of `pub` visibility since it's helpful for tests that want to
+    /// observe which templates have been compiled.
+    pub fn module_cache(&self) -> std::cell::Ref<'_, ModuleCache> {
+        self.module_cache.borrow()
+    }
+
+    /// Initialize a new `AppStorage` (at the app `State` `state`) and returns it.
+    /// Returns `UnknownState` when `state` isn't the app's current `State` (see `AppStorage::check_state`).
+    ///
     /// This method is of `pub` visibility since it's also helpful for tests that want to
     /// observe that app storage data.
     pub fn open_app_storage(
@@ -192,13 +417,30 @@ where
         addr: &AppAddr,
         state: &State,
         layout: &DataLayout,
-    ) -> AppStorage {
-        (self.storage_builder)(addr, state, layout, &self.config)
+    ) -> Result<AppStorage, UnknownState> {
+        let storage = (self.storage_builder)(addr, layout, &self.config);
+        storage.check_state(state)?;
+
+        Ok(storage)
+    }
+
+    /// Returns the current `State` of app `app_addr` (`None` when there is no such app).
+    ///
+    /// An app called by another app (see `AppCall`) gets a new `State` without being the target of a transaction,
+    /// so its current `State` can be looked up using this method.
+    pub fn app_state(&self, app_addr: &AppAddr) -> Option<State> {
+        let (template, _template_addr, _author, _creator) =
+            self.env.load_template_by_app(app_addr)?;
+
+        let storage = (self.storage_builder)(app_addr, &template.data, &self.config);
+
+        Some(storage.head())
     }
 
     fn call_ctor(
         &mut self,
         creator: &CreatorAddr,
+        host_ctx: &HostCtx,
         spawn: SpawnApp,
         app_addr: &AppAddr,
         gas_used: MaybeGas,
@@ -206,7 +448,15 @@ where
     ) -> SpawnAppReceipt {
         let ctor = self.build_ctor_call(creator, spawn, app_addr);
 
-        let ctor_receipt = self.exec(&ctor, &State::zeros(), gas_used, gas_left, true);
+        let ctor_receipt = self.exec(
+            &ctor,
+            creator.inner(),
+            host_ctx,
+            &State::zeros(),
+            gas_used,
+            gas_left,
+            true,
+        );
 
         receipt::into_spawn_app_receipt(ctor_receipt, app_addr)
     }
@@ -234,18 +484,23 @@ where
         app_addr: &AppAddr,
     ) -> AppTransaction {
         AppTransaction {
-            version: 0,
+            version: Format::ExecApp.latest(),
             app: app_addr.clone(),
+            value: spawn.value,
             func_name: spawn.ctor_name,
             calldata: spawn.calldata,
         }
     }
 
+    /// Executes `tx` on behalf of `sender` (the transaction `value` is transferred from `sender` to the app).
+    #[allow(clippy::too_many_arguments)]
     fn exec(
         &self,
         tx: &AppTransaction,
+        sender: &Address,
+        host_ctx: &HostCtx,
         state: &State,
-        _gas_used: MaybeGas,
+        gas_used: MaybeGas,
         gas_left: MaybeGas,
         with_spawn: bool,
     ) -> ExecReceipt {
@@ -257,12 +512,33 @@ where
                 ExecReceipt::from_err(e, empty_logs)
             }
             Ok((template, template_addr, _author, _creator)) => {
-                let store = svm_compiler::new_store();
-                let mut ctx = self.create_context(&template, &tx.app, &state, gas_left);
-                let (import_object, funcs_envs) = self.create_import_object(&store, &mut ctx);
+                let store = &self.store;
+                let storage = match self.open_app_storage(&tx.app, state, &template.data) {
+                    Ok(storage) => storage,
+                    Err(UnknownState(state)) => {
+                        let err = ReceiptError::UnknownState {
+                            app_addr: tx.app.clone(),
+                            state,
+                        };
+                        return ExecReceipt::from_err(err, Vec::new());
+                    }
+                };
+
+                // the changes committed by the apps called (recursively) are discarded if the transaction fails
+                let kv_state = storage.kv_head();
+
+                let mut ctx = self.create_context(&tx.app, storage, gas_left);
+
+                ctx.borrow_mut().set_host_ctx(host_ctx.clone());
+
+                if let Err(e) = self.transfer_value(&ctx, tx, sender) {
+                    return ExecReceipt::from_err(e, Vec::new());
+                }
+
+                let (import_object, funcs_envs) = self.create_import_object(store, &mut ctx);
 
                 let (result, logs) = self._exec(
-                    &store,
+                    store,
                     &ctx,
                     &tx,
                     &template,
@@ -274,7 +550,30 @@ where
 
                 self.funcs_envs_destroy(funcs_envs);
 
-                let receipt = self.make_receipt(result, logs);
+                // adding the gas used prior to the execution (for example, the `spawn-app` installation)
+                let result = result.map(|(new_state, returndata, exec_gas)| {
+                    let gas_used = exec_gas.map(|exec_gas| exec_gas + gas_used.unwrap_or(0));
+
+                    (new_state, returndata, gas_used)
+                });
+
+                let balance_changes = match result {
+                    Ok(..) => self.commit_balances(&ctx),
+                    Err(..) => Vec::new(),
+                };
+
+                let storage_changes = match result {
+                    Ok(..) => ctx.borrow_mut().take_storage_changes(),
+                    Err(..) => None,
+                };
+
+                if result.is_err() {
+                    ctx.borrow_mut().storage.rewind_kv(&kv_state);
+                }
+
+                self.apply_retention(&mut ctx.borrow_mut().storage);
+
+                let receipt = self.make_receipt(result, logs, balance_changes, storage_changes);
 
                 info!("receipt: {:?}", receipt);
 
@@ -283,6 +582,26 @@ where
         }
     }
 
+    /// Prunes the key-value store states (via `storage`) according to `Config::retention`.
+    ///
+    /// It's called once a transaction is done, so the `State` the transaction may have been rewound to
+    /// (see `exec`) isn't pruned while in use.
+    fn apply_retention(&self, storage: &mut AppStorage) {
+        if let Retention::KeepLast(n) = self.config.retention {
+            let mut history = self.kv_history.borrow_mut();
+
+            history.push_back(storage.kv_head());
+
+            while history.len() > n {
+                history.pop_front();
+            }
+
+            let keep = history.iter().cloned().collect::<Vec<_>>();
+
+            storage.prune_kv(&keep);
+        }
+    }
+
     fn funcs_envs_destroy(&self, mut funcs_envs: Vec<*mut svm_env_t>) {
         for func_env in funcs_envs.drain(..) {
             unsafe {
@@ -308,7 +627,7 @@ where
     ) {
         let empty_logs = Vec::new();
 
-        let module = self.compile_template(store, tx, &template, &template_addr, gas_left);
+        let module = self.compile_template(store, tx, &template, &template_addr);
         if let Err(err) = module {
             return (Err(err), empty_logs);
         }
@@ -344,7 +663,11 @@ where
 
         let mut instance = instance.unwrap();
 
-        self.set_memory(ctx, &mut instance);
+        self.set_gas_left(ctx, &instance, gas_left);
+
+        if let Err(err) = self.set_memory(ctx, tx, template_addr, &mut instance) {
+            return (Err(err), empty_logs);
+        }
 
         let wasm_ptr = self.alloc_calldata(tx, template_addr, &mut instance);
         if let Err(err) = wasm_ptr {
@@ -352,10 +675,14 @@ where
         }
 
         // we assert that `svm_alloc` didn't touch the `returndata`
-        // TODO: return an error instead of `panic`
-        self.assert_no_returndata(ctx);
+        if let Err(err) = self.assert_no_returndata(ctx, tx, template_addr) {
+            return (Err(err), empty_logs);
+        }
 
-        self.set_calldata(ctx, &tx.calldata, wasm_ptr.unwrap());
+        let calldata = self.set_calldata(ctx, tx, template_addr, wasm_ptr.unwrap());
+        if let Err(err) = calldata {
+            return (Err(err), empty_logs);
+        }
 
         let func = match self.get_func(tx, template_addr, &instance) {
             Err(e) => return (Err(e), empty_logs),
@@ -365,58 +692,170 @@ where
         let func_res = func.call(&[]);
         let logs = self.take_logs(ctx);
 
-        let gas_used = self.instance_gas_used(&instance);
+        let gas_used = self.instance_gas_used(ctx, &instance, gas_left);
         if gas_used.is_err() {
             return (Err(ReceiptError::OOG), logs);
         }
 
         let result = match func_res {
-            Err(e) => Err(ReceiptError::FuncFailed {
-                app_addr: tx.app.clone(),
-                template_addr: template_addr.clone(),
-                func: tx.func_name.clone(),
-                msg: e.to_string(),
-            }),
-            Ok(returns) => {
-                let returndata = self.take_returndata(ctx, returns);
-                let new_state = self.commit_chages(ctx);
-
-                Ok((Some(new_state), Some(returndata), gas_used.unwrap()))
-            }
+            Err(e) => Err(self.func_failed_error(tx, template_addr, e)),
+            Ok(returns) => self
+                .take_returndata(ctx, tx, template_addr, returns)
+                .and_then(|returndata| {
+                    let new_state = self.commit_chages(ctx, tx, template_addr)?;
+
+                    Ok((Some(new_state), Some(returndata), gas_used.unwrap()))
+                }),
         };
 
         (result, logs)
     }
 
+    fn transfer_value(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        sender: &Address,
+    ) -> Result<(), ReceiptError> {
+        let mut borrow = ctx.borrow_mut();
+
+        borrow.set_value(tx.value);
+        borrow.ledger.transfer(sender, tx.app.inner(), tx.value)
+    }
+
     #[inline]
-    fn commit_chages(&self, ctx: &Context) -> State {
-        let storage = &mut ctx.borrow_mut().storage;
-        storage.commit()
+    fn commit_balances(&self, ctx: &Context) -> Vec<BalanceChange> {
+        ctx.borrow_mut().ledger.commit()
+    }
+
+    fn commit_chages(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+    ) -> Result<State, ReceiptError> {
+        let mut borrow = ctx.borrow_mut();
+
+        if let Some(limit) = self.config.max_storage_size {
+            let size = borrow.storage.pending_usage().bytes;
+
+            if size > limit {
+                return Err(ReceiptError::StorageLimitExceeded {
+                    app_addr: tx.app.clone(),
+                    template_addr: template_addr.clone(),
+                    size,
+                    limit,
+                });
+            }
+        }
+
+        if self.config.collect_storage_changes {
+            let changes = borrow.storage.changes();
+            borrow.set_storage_changes(changes);
+        }
+
+        Ok(borrow.storage.commit())
     }
 
     #[inline]
-    fn assert_no_returndata(&self, ctx: &Context) {
-        assert!(ctx.borrow().returndata.is_none())
+    fn assert_no_returndata(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+    ) -> Result<(), ReceiptError> {
+        if ctx.borrow().returndata.is_none() {
+            return Ok(());
+        }
+
+        Err(ReceiptError::FuncFailed {
+            app_addr: tx.app.clone(),
+            template_addr: template_addr.clone(),
+            func: "svm_alloc".to_string(),
+            msg: "`svm_alloc` isn't allowed to set the `returndata`".to_string(),
+        })
     }
 
-    fn take_returndata(&self, ctx: &Context, returns: Box<[WasmerValue]>) -> Vec<u8> {
+    fn take_returndata(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+        returns: Box<[WasmerValue]>,
+    ) -> Result<Vec<u8>, ReceiptError> {
         let data = ctx.borrow().returndata;
 
         match data {
-            Some((offset, len)) => self.read_memory(ctx, offset, len),
-            None => Vec::new(),
+            Some((offset, len)) => self.read_memory(ctx, tx, template_addr, offset, len),
+            None => Ok(Vec::new()),
         }
     }
 
-    fn read_memory(&self, ctx: &Context, offset: usize, len: usize) -> Vec<u8> {
+    fn read_memory(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, ReceiptError> {
         let borrow = ctx.borrow();
         let memory = borrow.get_memory();
 
-        // TODO: guard again out-of-bounds
         let view = memory.view::<u8>();
-        let cells = &view[offset..(offset + len)];
+        let cells = offset
+            .checked_add(len)
+            .and_then(|end| view.get(offset..end));
 
-        cells.iter().map(|c| c.get()).collect()
+        match cells {
+            Some(cells) => Ok(cells.iter().map(|c| c.get()).collect()),
+            None => Err(ReceiptError::MemoryOutOfBounds {
+                app_addr: tx.app.clone(),
+                template_addr: template_addr.clone(),
+                offset: offset as u32,
+                len: len as u32,
+            }),
+        }
+    }
+
+    /// Turns the trap of a failed function into a `ReceiptError`.
+    ///
+    /// Traps raised by a vmcall (see `VMCallError`) are mapped into their matching `ReceiptError`.
+    fn func_failed_error(
+        &self,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+        err: RuntimeError,
+    ) -> ReceiptError {
+        let app_addr = tx.app.clone();
+        let template_addr = template_addr.clone();
+
+        match err.downcast::<VMCallError>() {
+            Ok(VMCallError::OOG) => ReceiptError::OOG,
+            Ok(VMCallError::MemoryOutOfBounds { offset, len }) => ReceiptError::MemoryOutOfBounds {
+                app_addr,
+                template_addr,
+                offset,
+                len,
+            },
+            Ok(VMCallError::InvalidVarId(var_id)) => ReceiptError::InvalidVarId {
+                app_addr,
+                template_addr,
+                var_id,
+            },
+            Ok(err) => ReceiptError::FuncFailed {
+                app_addr,
+                template_addr,
+                func: tx.func_name.clone(),
+                msg: err.to_string(),
+            },
+            Err(err) => ReceiptError::FuncFailed {
+                app_addr,
+                template_addr,
+                func: tx.func_name.clone(),
+                msg: err.to_string(),
+            },
+        }
     }
 
     fn take_logs(&self, ctx: &Context) -> Vec<Log> {
@@ -427,25 +866,43 @@ where
         &self,
         result: Result<(Option<State>, Option<Vec<u8>>, MaybeGas), ReceiptError>,
         logs: Vec<Log>,
+        balance_changes: Vec<BalanceChange>,
+        storage_changes: Option<Vec<StorageChange>>,
     ) -> ExecReceipt {
         match result {
             Err(e) => ExecReceipt::from_err(e, logs),
             Ok((new_state, returndata, gas_used)) => ExecReceipt {
-                version: 0,
+                version: Format::Receipt.latest(),
                 success: true,
                 error: None,
                 returndata,
                 new_state,
                 gas_used,
                 logs,
+                balance_changes,
+                storage_changes,
+                storage_changes_truncated: false,
             },
         }
     }
 
-    fn set_memory(&self, ctx: &Context, instance: &mut Instance) {
-        // TODO: raise when no exported memory
-        let memory = instance.exports.get_memory("memory").unwrap();
+    fn set_memory(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+        instance: &mut Instance,
+    ) -> Result<(), ReceiptError> {
+        let memory = instance.exports.get_memory("memory").map_err(|_| {
+            ReceiptError::MissingMemoryExport {
+                app_addr: tx.app.clone(),
+                template_addr: template_addr.clone(),
+            }
+        })?;
+
         ctx.borrow_mut().set_memory(memory.clone());
+
+        Ok(())
     }
 
     fn alloc_calldata(
@@ -472,6 +929,10 @@ where
         let offset = alloc.call(size);
 
         if offset.is_err() {
+            if svm_compiler::gas_left(instance).is_none() {
+                return Err(ReceiptError::OOG);
+            }
+
             let err = ReceiptError::FuncFailed {
                 app_addr: tx.app.clone(),
                 template_addr: template_addr.clone(),
@@ -486,17 +947,24 @@ where
         Ok(WasmPtr::new(offset))
     }
 
-    fn set_calldata(&self, ctx: &Context, calldata: &[u8], ptr: WasmPtr<u8>) {
+    fn set_calldata(
+        &self,
+        ctx: &Context,
+        tx: &AppTransaction,
+        template_addr: &TemplateAddr,
+        ptr: WasmPtr<u8>,
+    ) -> Result<(), ReceiptError> {
+        let calldata = &tx.calldata;
+
         let (offset, len) = {
             let borrow = ctx.borrow();
             let memory = borrow.get_memory();
-            let offset = ptr.offset();
 
             // Each WASM instance memory contains at least one `WASM Page`. (A `Page` size is 64KB)
             // The `len(calldata)` will be less than the `WASM Page` size.
             //
-            // In any case, the `alloc_memory` is in charge of allocating enough memory
-            // for the program to run (so we don't need to have any bounds-checking here).
+            // In any case, the `svm_alloc` is in charge of allocating enough memory
+            // for the program to run (a misbehaving `svm_alloc` results in a `MemoryOutOfBounds` error).
 
             // TODO: add to `validate_template` checking that `calldata` doesn't exceed ???
             // (we'll need to decide on a `calldata` limit).
@@ -505,23 +973,70 @@ where
             let offset = ptr.offset() as usize;
             let len = calldata.len();
 
-            // TODO: guard again out-of-bounds
-            let view = &memory.view::<u8>()[offset..(offset + len)];
-
-            for (cell, &byte) in view.iter().zip(calldata.iter()) {
-                cell.set(byte);
+            let view = memory.view::<u8>();
+            let cells = offset
+                .checked_add(len)
+                .and_then(|end| view.get(offset..end));
+
+            match cells {
+                Some(cells) => {
+                    for (cell, &byte) in cells.iter().zip(calldata.iter()) {
+                        cell.set(byte);
+                    }
+                }
+                None => {
+                    return Err(ReceiptError::MemoryOutOfBounds {
+                        app_addr: tx.app.clone(),
+                        template_addr: template_addr.clone(),
+                        offset: offset as u32,
+                        len: len as u32,
+                    })
+                }
             }
 
             (offset, len)
         };
 
         ctx.borrow_mut().set_calldata(offset, len);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn set_gas_left(&self, ctx: &Context, instance: &Instance, gas_left: MaybeGas) {
+        // When gas metering is disabled, the instance is given unlimited gas
+        let gas_left = gas_left.unwrap_or(std::u64::MAX);
+
+        svm_compiler::set_gas_left(instance, gas_left);
+
+        // the vmcalls are charged against the instance's gas left
+        let global = svm_compiler::gas_left_global(instance);
+        ctx.borrow_mut().set_gas_left(global);
     }
 
     #[inline]
-    fn instance_gas_used(&self, _instance: &Instance) -> Result<MaybeGas, OOGError> {
-        // TODO: read `gas_used` out of `instance`
-        Ok(MaybeGas::new())
+    fn instance_gas_used(
+        &self,
+        ctx: &Context,
+        instance: &Instance,
+        gas_limit: MaybeGas,
+    ) -> Result<MaybeGas, OOGError> {
+        if gas_limit.is_none() {
+            return Ok(MaybeGas::new());
+        }
+
+        let gas_limit = gas_limit.unwrap();
+
+        // The vmcalls are charged against the instance's gas left,
+        // so it accounts for both the Wasm instructions and the vmcalls.
+        if ctx.borrow().gas_exhausted {
+            return Err(OOGError {});
+        }
+
+        match svm_compiler::gas_left(instance) {
+            Some(gas_left) if gas_left <= gas_limit => Ok(MaybeGas::with(gas_limit - gas_left)),
+            _ => Err(OOGError {}),
+        }
     }
 
     fn instantiate(
@@ -559,15 +1074,22 @@ where
 
     fn create_context(
         &self,
-        template: &AppTemplate,
         app_addr: &AppAddr,
-        state: &State,
+        storage: AppStorage,
         gas_limit: MaybeGas,
     ) -> Context {
-        let layout = &template.data;
-        let storage = self.open_app_storage(app_addr, state, layout);
+        let ctx = Context::new(gas_limit, storage);
+
+        {
+            let mut borrow = ctx.borrow_mut();
 
-        Context::new(gas_limit, storage)
+            borrow.set_pricing(self.config.pricing.clone());
+            borrow.ledger = Ledger::new(self.env.get_balance_store());
+            borrow.set_call_stack(vec![app_addr.clone()]);
+            borrow.set_call_handler(self as &dyn CallHandler);
+        }
+
+        ctx
     }
 
     fn create_import_object(
@@ -622,44 +1144,63 @@ where
         tx: &AppTransaction,
         template: &AppTemplate,
         template_addr: &TemplateAddr,
-        gas_left: MaybeGas,
     ) -> Result<Module, ReceiptError> {
         info!("runtime `compile_template` (template={:?})", template_addr);
 
-        let gas_metering = gas_left.is_some();
-        let gas_left = gas_left.unwrap_or(0);
+        let hash = self.env.compute_template_hash(template);
+        let mut cache = self.module_cache.borrow_mut();
 
-        svm_compiler::compile(store, &template.code, gas_left, gas_metering).or_else(|e| {
-            error!("module module failed (template={:?})", template_addr);
+        cache
+            .get_or_compile(store, &hash, &template.code)
+            .map_err(|e| {
+                error!("module compilation failed (template={:?})", template_addr);
 
-            Err(ReceiptError::CompilationFailed {
-                app_addr: tx.app.clone(),
-                template_addr: template_addr.clone(),
-                msg: e.to_string(),
+                ReceiptError::CompilationFailed {
+                    app_addr: tx.app.clone(),
+                    template_addr: template_addr.clone(),
+                    msg: e.to_string(),
+                }
             })
-        })
     }
 
     fn parse_deploy_template(&self, bytes: &[u8]) -> Result<AppTemplate, ParseError> {
-        self.env.parse_deploy_template(bytes)
+        let template = self.env.parse_deploy_template(bytes)?;
+
+        self.assert_accepted_version(Format::DeployTemplate, template.version)?;
+
+        Ok(template)
     }
 
     fn parse_spawn_app(&self, bytes: &[u8]) -> Result<SpawnApp, ParseError> {
-        self.env.parse_spawn_app(bytes)
+        let spawn = self.env.parse_spawn_app(bytes)?;
+
+        self.assert_accepted_version(Format::SpawnApp, spawn.version)?;
+
+        Ok(spawn)
     }
 
     fn parse_exec_app(&self, bytes: &[u8]) -> Result<AppTransaction, ParseError> {
-        self.env.parse_exec_app(bytes)
+        let tx = self.env.parse_exec_app(bytes)?;
+
+        self.assert_accepted_version(Format::ExecApp, tx.version)?;
+
+        Ok(tx)
+    }
+
+    fn assert_accepted_version(&self, format: Format, version: u16) -> Result<(), ParseError> {
+        if self.accepts_version(format, version) {
+            Ok(())
+        } else {
+            Err(ParseError::UnsupportedVersion(version))
+        }
     }
 
     /// Gas
-    fn compute_install_template_gas(&self, bytes: &[u8], _template: &AppTemplate) -> u64 {
-        // todo!()
-        1000 * (bytes.len() as u64)
+    fn compute_install_template_gas(&self, bytes: &[u8], template: &AppTemplate) -> u64 {
+        GE::est_payload(bytes) + GE::est_install_template(template)
     }
 
-    fn compute_install_app_gas(&self, bytes: &[u8], _spawn: &SpawnApp) -> u64 {
-        // todo!()
-        1000 * (bytes.len() as u64)
+    fn compute_install_app_gas(&self, bytes: &[u8], spawn: &SpawnApp) -> u64 {
+        GE::est_payload(bytes) + GE::est_install_app(spawn)
     }
 }
@@ -1,3 +1,4 @@
+mod cache;
 mod config;
 mod default;
 mod ptr;
@@ -7,9 +8,10 @@ mod runtime;
 mod rocksdb;
 
 #[cfg(feature = "default-rocksdb")]
-pub use rocksdb::create_rocksdb_runtime;
+pub use rocksdb::{create_rocksdb_runtime, create_rocksdb_runtime_with_config};
 
-pub use config::Config;
+pub use cache::ModuleCache;
+pub use config::{Config, DEFAULT_MODULE_CACHE_SIZE};
 pub use default::DefaultRuntime;
 pub use ptr::RuntimePtr;
 pub use runtime::Runtime;
@@ -1,55 +1,97 @@
-use std::{ffi::c_void, path::Path};
+use std::cell::RefCell;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
 
 use crate::env::rocksdb::{RocksdbAppStore, RocksdbAppTemplateStore, RocksdbEnv};
 use crate::env::traits::EnvSerializerTypes;
+use crate::storage::StorageBuilderFn;
 
 use svm_layout::DataLayout;
-use svm_storage::app::AppStorage;
-use svm_types::{AppAddr, State};
+use svm_storage::app::{AppKVStore, AppStorage};
+use svm_storage::kv::{RocksdbStatefulKV, StatefulKV};
+use svm_types::AppAddr;
 
 use crate::{gas::GasEstimator, runtime::DefaultRuntime, Config, ExternImport};
 
-use wasmer::Export;
-
 /// Creates a new `Runtime` backed by `rocksdb` for persistence.
+///
+/// The templates, the apps and the apps storage are persisted under separate
+/// `rocksdb` databases (`templates`, `apps` and `storage`) placed under `kv_path`.
+///
+/// Returns an error when the `kv_path` directory can't be created,
+/// or when any of the `rocksdb` databases can't be opened.
 pub fn create_rocksdb_runtime<P, S, GE>(
     kv_path: P,
     imports: *const Vec<ExternImport>,
-) -> DefaultRuntime<RocksdbEnv<S>, GE>
+) -> io::Result<DefaultRuntime<RocksdbEnv<S>, GE>>
 where
     P: AsRef<Path>,
-    S: EnvSerializerTypes,
-    GE: GasEstimator,
+    S: EnvSerializerTypes + 'static,
+    GE: GasEstimator + 'static,
 {
-    let env = app_env_build(&kv_path);
+    let config = Config::new(kv_path);
+
+    create_rocksdb_runtime_with_config(config, imports)
+}
+
+/// Creates a new `Runtime` backed by `rocksdb` (similar to `create_rocksdb_runtime`) using the given `Config`.
+///
+/// The `rocksdb` databases are placed under `config.kv_path`.
+pub fn create_rocksdb_runtime_with_config<S, GE>(
+    config: Config,
+    imports: *const Vec<ExternImport>,
+) -> io::Result<DefaultRuntime<RocksdbEnv<S>, GE>>
+where
+    S: EnvSerializerTypes + 'static,
+    GE: GasEstimator + 'static,
+{
+    let kv_path = config.kv_path.clone();
+
+    std::fs::create_dir_all(&kv_path)?;
+
+    let env = app_env_build(&kv_path)?;
     let imports = unsafe { &*imports };
 
-    DefaultRuntime::new(env, kv_path, imports, Box::new(app_storage_build))
+    let state_kv = RocksdbStatefulKV::open(kv_path.join("storage"))?;
+    let state_kv: Rc<RefCell<dyn StatefulKV>> = Rc::new(RefCell::new(state_kv));
+
+    let runtime =
+        DefaultRuntime::new_with_config(env, config, imports, app_storage_build(&state_kv));
+
+    Ok(runtime)
 }
 
-fn app_env_build<P, S>(kv_path: &P) -> RocksdbEnv<S>
+fn app_env_build<P, S>(kv_path: &P) -> io::Result<RocksdbEnv<S>>
 where
     P: AsRef<Path>,
     S: EnvSerializerTypes,
 {
+    let kv_path = kv_path.as_ref();
+
     let app_store = RocksdbAppStore::<
         <S as EnvSerializerTypes>::AppSerializer,
         <S as EnvSerializerTypes>::AppDeserializer,
-    >::new(kv_path);
+    >::new(kv_path.join("apps"))?;
 
     let template_store = RocksdbAppTemplateStore::<
         <S as EnvSerializerTypes>::TemplateSerializer,
         <S as EnvSerializerTypes>::TemplateDeserializer,
-    >::new(kv_path);
+    >::new(kv_path.join("templates"))?;
 
-    RocksdbEnv::new(app_store, template_store)
+    let env = RocksdbEnv::new(app_store, template_store);
+
+    Ok(env)
 }
 
-fn app_storage_build(
-    _addr: &AppAddr,
-    _state: &State,
-    _layout: &DataLayout,
-    _config: &Config,
-) -> AppStorage {
-    todo!()
+fn app_storage_build(state_kv: &Rc<RefCell<dyn StatefulKV>>) -> Box<StorageBuilderFn> {
+    let state_kv = Rc::clone(state_kv);
+
+    let func = move |app_addr: &AppAddr, layout: &DataLayout, _config: &Config| {
+        let app_kv = AppKVStore::new(app_addr.inner().clone(), &state_kv);
+
+        AppStorage::new(layout.clone(), app_kv)
+    };
+
+    Box::new(func)
 }
@@ -1,11 +1,19 @@
-use crate::error::ValidateError;
+use crate::error::{MigrateError, ValidateError};
 
+use svm_codec::version::Format;
 use svm_gas::Gas;
+use svm_layout::VarId;
+use svm_storage::app::StorageUsage;
 use svm_types::receipt::{ExecReceipt, SpawnAppReceipt, TemplateReceipt};
-use svm_types::{gas::MaybeGas, AppAddr, AuthorAddr, CreatorAddr, State};
+use svm_types::{gas::MaybeGas, AppAddr, AuthorAddr, CreatorAddr, HostCtx, State, TemplateAddr};
 
 /// Specifies the interface of a `SVM` Runtime.
 pub trait Runtime {
+    /// Returns whether the runtime accepts transactions of raw format `format` encoded using `version`.
+    ///
+    /// Transactions of a non-accepted version fail parsing (see `svm_codec::ParseError::UnsupportedVersion`).
+    fn accepts_version(&self, format: Format, version: u16) -> bool;
+
     /// Validates raw `deploy-template` transaction prior to executing it.
     fn validate_template(&self, bytes: &[u8]) -> Result<(), ValidateError>;
 
@@ -29,25 +37,66 @@ pub trait Runtime {
         &mut self,
         bytes: &[u8],
         author: &AuthorAddr,
+        host_ctx: &HostCtx,
         gas_limit: MaybeGas,
     ) -> TemplateReceipt;
 
     /// Spawn a new app out of an existing app-template.
+    ///
+    /// The `value` of the `spawn-app` transaction is transferred from the `creator` to the new app.
+    /// The app constructor can read the `host_ctx` fields using the `svm_host_get` vmcalls.
     fn spawn_app(
         &mut self,
         bytes: &[u8],
         creator: &CreatorAddr,
+        host_ctx: &HostCtx,
         gas_limit: MaybeGas,
     ) -> SpawnAppReceipt;
 
-    /// Executes an app-transaction. Returns `ExecReceipt`.
+    /// Executes an app-transaction sent by the `sender` of `host_ctx`. Returns `ExecReceipt`.
+    /// The app can read the `host_ctx` fields using the `svm_host_get` vmcalls.
     /// On success:
     /// * Persists changes to the app's own storage.
+    /// * Persists the balance changes (including the transaction `value` transferred from `sender` to the app).
     /// * Receipt returns the app's new storage state.
     /// * Receipt informs the amount of gas used.
+    /// * Receipt lists the balance changes.
     ///
     /// On failure:
     /// * Receipt returns the occurred error
     /// * Receipt informs the amount of gas used (transaction gas limit)
-    fn exec_app(&self, bytes: &[u8], state: &State, gas_limit: MaybeGas) -> ExecReceipt;
+    ///
+    /// The `state` must be the app's current `State`, otherwise the receipt returns `ReceiptError::UnknownState`.
+    fn exec_app(
+        &self,
+        bytes: &[u8],
+        host_ctx: &HostCtx,
+        state: &State,
+        gas_limit: MaybeGas,
+    ) -> ExecReceipt;
+
+    /// Returns the storage usage of the app `app_addr` as of the storage `state`.
+    ///
+    /// Returns `None` when there is no such app, or when `state` isn't its current `State`.
+    fn storage_usage(&self, app_addr: &AppAddr, state: &State) -> Option<StorageUsage>;
+
+    /// Migrates app `app_addr` (as of the storage `state`) to the (already deployed) template `template_addr`,
+    /// and returns the app's new storage `State`.
+    ///
+    /// The app's storage is migrated from its current template `DataLayout` to the new template's one.
+    /// The `mapping` is a list of `(old var, new var)` pairs. It's validated so that no variable loses bytes
+    /// and no map (or vector) moves to another variable (see `svm_storage::app::LayoutMigration`).
+    /// The storage is rewritten under a single commit.
+    ///
+    /// From then on the app is associated with the new template, so its transactions are executed
+    /// using the new template's code and `DataLayout`.
+    ///
+    /// The `state` must be the app's current `State` (otherwise `MigrateError::UnknownState` is returned).
+    fn migrate_app(
+        &mut self,
+        app_addr: &AppAddr,
+        state: &State,
+        template_addr: &TemplateAddr,
+        mapping: &[(VarId, VarId)],
+    ) -> Result<State, MigrateError>;
 }
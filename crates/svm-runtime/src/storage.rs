@@ -1,8 +1,11 @@
 use svm_layout::DataLayout;
 use svm_storage::app::AppStorage;
-use svm_types::{AppAddr, State};
+use svm_types::AppAddr;
 
 use crate::Config;
 
 /// `AppStorage` building function signature.
-pub type StorageBuilderFn = dyn Fn(&AppAddr, &State, &DataLayout, &Config) -> AppStorage;
+///
+/// The built `AppStorage` is at the current `State` of the underlying key-value store,
+/// checking it's at the app `State` is done by the `Runtime` (see `DefaultRuntime::open_app_storage`).
+pub type StorageBuilderFn = dyn Fn(&AppAddr, &DataLayout, &Config) -> AppStorage;
@@ -5,16 +5,20 @@ use std::path::Path;
 use std::rc::Rc;
 
 use crate::env::memory::{DefaultMemAppStore, DefaultMemAppTemplateStore, DefaultMemoryEnv};
+use crate::env::traits::BalanceStore;
 use crate::{gas::DefaultGasEstimator, storage::StorageBuilderFn};
 use crate::{Config, Context, DefaultRuntime, ExternImport};
 
 use svm_codec::api::builder::{AppTxBuilder, DeployAppTemplateBuilder, SpawnAppBuilder};
+use svm_codec::template::CodeHash;
 use svm_layout::DataLayout;
 use svm_storage::{
     app::{AppKVStore, AppStorage},
     kv::{FakeKV, StatefulKV},
 };
-use svm_types::{gas::MaybeGas, receipt::Log, Address, AppAddr, State, TemplateAddr, WasmValue};
+use svm_types::{
+    gas::MaybeGas, receipt::Log, Address, AppAddr, HostCtx, State, TemplateAddr, WasmValue,
+};
 
 use wasmer::{Export, ImportObject, Instance, Memory, MemoryType, Module, Pages, Store};
 
@@ -25,7 +29,8 @@ pub enum WasmFile<'a> {
 }
 
 impl<'a> WasmFile<'a> {
-    fn into_bytes(self) -> Vec<u8> {
+    /// Returns the binary Wasm (compiling it first when given as text)
+    pub fn into_bytes(self) -> Vec<u8> {
         match self {
             Self::Text(text) => wat::parse_str(text).unwrap(),
             Self::Binary(wasm) => wasm.to_vec(),
@@ -59,25 +64,28 @@ pub fn wasmer_memory(store: &Store) -> Memory {
 }
 
 /// Compiles a wasm program in text format (a.k.a WAST) into a `Module` (`wasmer`)
-pub fn wasmer_compile(store: &Store, wasm_file: WasmFile, gas_limit: MaybeGas) -> Module {
+pub fn wasmer_compile(store: &Store, wasm_file: WasmFile) -> Module {
     let wasm = wasm_file.into_bytes();
 
-    let gas_metering = gas_limit.is_some();
-    let gas_limit = gas_limit.unwrap_or(0);
-
-    svm_compiler::compile(store, &wasm, gas_limit, gas_metering).unwrap()
+    svm_compiler::compile(store, &wasm).unwrap()
 }
 
-/// Instantiate a `wasmer` instance
+/// Instantiate a `wasmer` instance and sets its gas left to `gas_limit`.
+///
+/// When `gas_limit` is `None` (i.e gas metering is disabled), the instance is given unlimited gas.
 pub fn wasmer_instantiate(
     store: &Store,
     import_object: &ImportObject,
     wasm_file: WasmFile,
     gas_limit: MaybeGas,
 ) -> Instance {
-    let module = wasmer_compile(store, wasm_file, gas_limit);
+    let module = wasmer_compile(store, wasm_file);
+    let instance = Instance::new(&module, import_object).unwrap();
+
+    let gas_left = gas_limit.unwrap_or(std::u64::MAX);
+    svm_compiler::set_gas_left(&instance, gas_left);
 
-    Instance::new(&module, import_object).unwrap()
+    instance
 }
 
 pub fn blank_storage(app_addr: &Address, layout: &DataLayout) -> AppStorage {
@@ -97,13 +105,45 @@ pub fn memory_state_kv_init() -> Rc<RefCell<dyn StatefulKV>> {
 pub fn create_memory_runtime(
     state_kv: &Rc<RefCell<dyn StatefulKV>>,
     imports: &Vec<ExternImport>,
+) -> DefaultRuntime<DefaultMemoryEnv, DefaultGasEstimator> {
+    let config = Config::new(Path::new("mem"));
+
+    create_memory_runtime_with_config(state_kv, imports, config)
+}
+
+/// Creates an in-memory `Runtime` (similar to `create_memory_runtime`) using the given `Config`.
+pub fn create_memory_runtime_with_config(
+    state_kv: &Rc<RefCell<dyn StatefulKV>>,
+    imports: &Vec<ExternImport>,
+    config: Config,
 ) -> DefaultRuntime<DefaultMemoryEnv, DefaultGasEstimator> {
     let storage_builder = runtime_memory_storage_builder(state_kv);
 
     let env = runtime_memory_env_builder();
-    let kv_path = Path::new("mem");
 
-    DefaultRuntime::new(env, &kv_path, imports, Box::new(storage_builder))
+    DefaultRuntime::new_with_config(env, config, imports, Box::new(storage_builder))
+}
+
+/// Creates an in-memory `Runtime` (similar to `create_memory_runtime`) having its balance ledger backed-by `balance_store`.
+pub fn create_memory_runtime_with_balances(
+    state_kv: &Rc<RefCell<dyn StatefulKV>>,
+    imports: &Vec<ExternImport>,
+    balance_store: &Rc<RefCell<dyn BalanceStore>>,
+) -> DefaultRuntime<DefaultMemoryEnv, DefaultGasEstimator> {
+    let config = Config::new(Path::new("mem"));
+    let storage_builder = runtime_memory_storage_builder(state_kv);
+
+    let env = runtime_memory_env_builder().with_balance_store(Rc::clone(balance_store));
+
+    DefaultRuntime::new_with_config(env, config, imports, Box::new(storage_builder))
+}
+
+/// Creates a `HostCtx` holding only the transaction `sender`.
+pub fn host_ctx_with_sender(sender: &Address) -> HostCtx {
+    let mut host_ctx = HostCtx::new();
+    host_ctx.set(HostCtx::SENDER, sender.as_slice().to_vec());
+
+    host_ctx
 }
 
 /// Returns a function (wrapped inside `Box`) that initializes an App's storage client.
@@ -112,14 +152,11 @@ pub fn runtime_memory_storage_builder(
 ) -> Box<StorageBuilderFn> {
     let state_kv = Rc::clone(state_kv);
 
-    let func = move |app_addr: &AppAddr, state: &State, layout: &DataLayout, _config: &Config| {
+    let func = move |app_addr: &AppAddr, layout: &DataLayout, _config: &Config| {
         let app_addr = app_addr.inner();
         let app_kv = AppKVStore::new(app_addr.clone(), &state_kv);
 
-        let mut storage = AppStorage::new(layout.clone(), app_kv);
-        storage.rewind(state);
-
-        storage
+        AppStorage::new(layout.clone(), app_kv)
     };
 
     Box::new(func)
@@ -152,6 +189,23 @@ pub fn build_template(
         .build()
 }
 
+/// Synthesizes a raw compact deploy-template transaction (its code is referenced by `code_hash`).
+pub fn build_compact_template(
+    version: u16,
+    name: &str,
+    data: DataLayout,
+    ctors: &[String],
+    code_hash: &CodeHash,
+) -> Vec<u8> {
+    DeployAppTemplateBuilder::new()
+        .with_version(version)
+        .with_name(name)
+        .with_code_hash(code_hash)
+        .with_data(&data)
+        .with_ctors(ctors)
+        .build()
+}
+
 /// Synthesizes a raw spaw-app transaction.
 pub fn build_app(
     version: u16,
@@ -0,0 +1,53 @@
+use svm_types::gas::OOGError;
+use svm_types::Address;
+
+use super::memory::read_memory;
+use crate::{use_gas, Context};
+
+/// Returns the amount of coins transferred to the running app by the executed transaction.
+pub fn value(ctx: &Context) -> Result<u64, OOGError> {
+    use_gas!("value", ctx);
+
+    let value = ctx.borrow().get_value();
+
+    Ok(value)
+}
+
+/// Returns the balance of account `Address` stored at memory offset `addr_ptr` (`Address::len()` bytes).
+///
+/// The balance takes into account the transfers made so far by the executed transaction.
+/// An out-of-bounds `addr_ptr` is treated as an unknown account (having a zero balance).
+pub fn balance(ctx: &Context, addr_ptr: u32) -> Result<u64, OOGError> {
+    use_gas!("balance", ctx);
+
+    let addr = match read_memory(ctx, addr_ptr, Address::len() as u32) {
+        Some(bytes) => Address::from(&bytes[..]),
+        None => return Ok(0),
+    };
+
+    let balance = ctx.borrow().ledger.balance(&addr);
+
+    Ok(balance)
+}
+
+/// Transfers `amount` coins from the running app to account `Address` stored at memory offset `dst_ptr`.
+///
+/// Returns whether the transfer has succeeded (`1`) or not (`0`).
+/// A transfer fails when the app balance is less than `amount` (the balances are left untouched).
+///
+/// The transfer takes effect only if the executed transaction succeeds.
+pub fn transfer(ctx: &Context, dst_ptr: u32, amount: u64) -> Result<u32, OOGError> {
+    use_gas!("transfer", ctx);
+
+    let dst = match read_memory(ctx, dst_ptr, Address::len() as u32) {
+        Some(bytes) => Address::from(&bytes[..]),
+        None => return Ok(0),
+    };
+
+    let mut borrow = ctx.borrow_mut();
+    let src = borrow.app_addr().inner().clone();
+
+    let res = borrow.ledger.transfer(&src, &dst, amount);
+
+    Ok(res.is_ok() as u32)
+}
@@ -0,0 +1,129 @@
+use std::cmp;
+
+use svm_types::gas::{MaybeGas, OOGError};
+use svm_types::{Address, AppAddr};
+
+use super::memory::{read_memory, store_memory};
+use crate::error::VMCallError;
+use crate::{use_gas, AppCall, Context, MAX_CALL_DEPTH};
+
+/// Calls function `func_name` of app `app_addr` and returns whether the call has succeeded (`1`) or not (`0`).
+///
+/// * `app_addr_ptr`  - Memory offset of the called app `Address` (`Address::len()` bytes).
+/// * `func_name_ptr` - Memory offset of the called function name (UTF-8 encoded).
+/// * `func_name_len` - The byte-length of the called function name.
+/// * `calldata_ptr`  - Memory offset of the `calldata` to be given to the called function.
+/// * `calldata_len`  - The byte-length of the `calldata`.
+/// * `gas`           - The maximum amount of gas the called function may use.
+///
+/// The called function runs within a nested `Context` (having its own `AppStorage`).
+/// Its `returndata` can be read afterwards using `svm_call_returndata_len` and `svm_call_returndata_copy`.
+///
+/// The gas used by the called function and its logs are added to the calling app.
+/// In case the call fails, the storage changes and the transfers made by the called app
+/// (and any app it called) are discarded, and the calling app continues running.
+///
+/// Calling an app already being executed (re-entrancy) or exceeding `MAX_CALL_DEPTH` fails the call.
+pub fn call(
+    ctx: &Context,
+    app_addr_ptr: u32,
+    func_name_ptr: u32,
+    func_name_len: u32,
+    calldata_ptr: u32,
+    calldata_len: u32,
+    gas: u64,
+) -> Result<u32, OOGError> {
+    use_gas!("call", ctx);
+
+    ctx.borrow_mut().set_call_returndata(Vec::new());
+
+    let call = {
+        let borrow = ctx.borrow();
+
+        let app = read_memory(ctx, app_addr_ptr, Address::len() as u32);
+        let func_name = read_memory(ctx, func_name_ptr, func_name_len)
+            .and_then(|bytes| String::from_utf8(bytes).ok());
+        let calldata = read_memory(ctx, calldata_ptr, calldata_len);
+
+        let (app, func_name, calldata) = match (app, func_name, calldata) {
+            (Some(app), Some(func_name), Some(calldata)) => {
+                let app: AppAddr = Address::from(&app[..]).into();
+
+                (app, func_name, calldata)
+            }
+            _ => return Ok(0),
+        };
+
+        let mut call_stack = borrow.get_call_stack().to_vec();
+
+        if call_stack.contains(&app) || call_stack.len() >= MAX_CALL_DEPTH {
+            return Ok(0);
+        }
+
+        call_stack.push(app.clone());
+
+        let gas_limit = if borrow.gas_metering {
+            MaybeGas::with(cmp::min(gas, borrow.gas_left()))
+        } else {
+            MaybeGas::new()
+        };
+
+        AppCall {
+            app,
+            func_name,
+            calldata,
+            gas_limit,
+            call_stack,
+            balance_changes: borrow.ledger.changes().to_vec(),
+            host_ctx: borrow.get_host_ctx().clone(),
+        }
+    };
+
+    let handler = match ctx.borrow().get_call_handler() {
+        Some(handler) => handler,
+        None => return Ok(0),
+    };
+
+    let kv_state = ctx.borrow().storage.kv_head();
+
+    // Safety: the `CallHandler` (i.e the `Runtime`) outlives the `Context`
+    let outcome = unsafe { (*handler).call(call) };
+
+    let mut borrow = ctx.borrow_mut();
+
+    if outcome.success {
+        // the called app has been given our pending balance changes, so we adopt its own
+        borrow.ledger.set_changes(outcome.balance_changes);
+    } else {
+        // discarding the changes committed by apps called (recursively) by the failed one
+        borrow.storage.rewind_kv(&kv_state);
+    }
+
+    borrow.logs.extend(outcome.logs);
+    borrow.set_call_returndata(outcome.returndata);
+    borrow.use_gas(outcome.gas_used)?;
+
+    Ok(outcome.success as u32)
+}
+
+/// Returns the byte-length of the `returndata` of the last app called (using `svm_call`).
+pub fn call_returndata_len(ctx: &Context) -> Result<u32, OOGError> {
+    use_gas!("call_returndata_len", ctx);
+
+    let len = ctx.borrow().get_call_returndata().len();
+
+    Ok(len as u32)
+}
+
+/// Copies the `returndata` of the last app called (using `svm_call`) into memory, starting at offset `offset`.
+///
+/// Raises `VMCallError::MemoryOutOfBounds` when the `returndata` doesn't fit into the memory.
+pub fn call_returndata_copy(ctx: &Context, offset: u32) -> Result<(), VMCallError> {
+    let len = ctx.borrow().get_call_returndata().len();
+
+    use_gas!("call_returndata_copy", ctx, len);
+
+    let returndata = ctx.borrow().get_call_returndata().to_vec();
+
+    store_memory(ctx, offset, &returndata)
+}
@@ -1,16 +1,18 @@
+use svm_types::gas::OOGError;
+
 use crate::use_gas;
 use crate::Context;
 
-pub fn calldata_offset(ctx: &Context) -> i32 {
+pub fn calldata_offset(ctx: &Context) -> Result<i32, OOGError> {
     use_gas!("calldata_offset", ctx);
 
-    calldata(ctx).0 as i32
+    Ok(calldata(ctx).0 as i32)
 }
 
-pub fn calldata_len(ctx: &Context) -> i32 {
+pub fn calldata_len(ctx: &Context) -> Result<i32, OOGError> {
     use_gas!("calldata_len", ctx);
 
-    calldata(ctx).1 as i32
+    Ok(calldata(ctx).1 as i32)
 }
 
 #[inline]
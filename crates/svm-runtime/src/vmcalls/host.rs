@@ -0,0 +1,41 @@
+use svm_types::gas::OOGError;
+
+use super::memory::write_memory;
+use crate::{use_gas, Context};
+
+/// Returns the byte-length of field `field` of the `HostCtx` (`0` if the field is missing).
+pub fn host_get_len(ctx: &Context, field: u32) -> Result<u32, OOGError> {
+    use_gas!("host_get_len", ctx);
+
+    let borrow = ctx.borrow();
+    let len = borrow
+        .get_host_ctx()
+        .get(field)
+        .map_or(0, |value| value.len());
+
+    Ok(len as u32)
+}
+
+/// Copies field `field` of the `HostCtx` into memory, starting at offset `offset`.
+///
+/// Returns whether the field has been copied (`1`) or not (`0`).
+/// Copying fails when the field is missing or when it doesn't fit into the memory.
+pub fn host_get(ctx: &Context, field: u32, offset: u32) -> Result<u32, OOGError> {
+    let value = ctx
+        .borrow()
+        .get_host_ctx()
+        .get(field)
+        .map(|value| value.to_vec());
+    let len = value.as_ref().map_or(0, |value| value.len());
+
+    use_gas!("host_get", ctx, len);
+
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(0),
+    };
+
+    let res = write_memory(ctx, offset, &value);
+
+    Ok(res.is_some() as u32)
+}
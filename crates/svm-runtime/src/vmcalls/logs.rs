@@ -1,22 +1,17 @@
+use crate::error::VMCallError;
 use crate::{use_gas, Context};
 
 use svm_types::receipt::Log;
 
-pub fn log(ctx: &Context, msg_ptr: u32, msg_len: u32, code: u32) {
-    use_gas!("log", ctx);
+use super::memory::load_memory;
 
-    let start = msg_ptr as usize;
-    let end = start + msg_len as usize;
+/// Appends a `Log` made of message `[msg_ptr, msg_ptr + msg_len)` and code `code`.
+///
+/// Raises `VMCallError::MemoryOutOfBounds` when the message is out of the memory bounds.
+pub fn log(ctx: &Context, msg_ptr: u32, msg_len: u32, code: u32) -> Result<(), VMCallError> {
+    use_gas!("log", ctx, msg_len);
 
-    let msg: Vec<u8> = {
-        let borrow = ctx.borrow();
-        let memory = borrow.get_memory();
-
-        memory.view()[start..end]
-            .iter()
-            .map(|cell| cell.get())
-            .collect()
-    };
+    let msg = load_memory(ctx, msg_ptr, msg_len)?;
 
     let log = Log {
         msg,
@@ -24,4 +19,6 @@ pub fn log(ctx: &Context, msg_ptr: u32, msg_len: u32, code: u32) {
     };
 
     ctx.borrow_mut().logs.push(log);
+
+    Ok(())
 }
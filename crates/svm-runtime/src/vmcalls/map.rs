@@ -0,0 +1,66 @@
+use crate::error::VMCallError;
+use crate::{use_gas, Context};
+
+use svm_layout::VarId;
+
+use super::memory::{load_memory, store_memory};
+use super::storage::var_len;
+
+/// Copies the value stored under key `[key_ptr, key_ptr + key_len)` of map variable `var_id`
+/// into memory cells `[val_ptr, val_ptr + val_len)`.
+///
+/// The value is truncated (or padded with zeros) to `val_len` bytes.
+/// Returns the byte-length of the stored value (`0` if there is no such entry).
+///
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist,
+/// and `VMCallError::MemoryOutOfBounds` when the memory cells are out-of-bounds.
+pub fn map_get(
+    ctx: &Context,
+    var_id: u32,
+    key_ptr: u32,
+    key_len: u32,
+    val_ptr: u32,
+    val_len: u32,
+) -> Result<u32, VMCallError> {
+    use_gas!("map_get", ctx, key_len as u64 + val_len as u64);
+
+    var_len(ctx, var_id, u32::MAX)?;
+
+    let key = load_memory(ctx, key_ptr, key_len)?;
+    let value = ctx.borrow().storage.map_get(VarId(var_id), &key);
+    let value = value.unwrap_or_default();
+
+    let mut buf = vec![0; val_len as usize];
+    let n = value.len().min(buf.len());
+    buf[..n].copy_from_slice(&value[..n]);
+
+    store_memory(ctx, val_ptr, &buf)?;
+
+    Ok(value.len() as u32)
+}
+
+/// Stores memory cells `[val_ptr, val_ptr + val_len)` under key `[key_ptr, key_ptr + key_len)`
+/// of map variable `var_id`.
+///
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist,
+/// and `VMCallError::MemoryOutOfBounds` when the memory cells are out-of-bounds.
+pub fn map_set(
+    ctx: &Context,
+    var_id: u32,
+    key_ptr: u32,
+    key_len: u32,
+    val_ptr: u32,
+    val_len: u32,
+) -> Result<(), VMCallError> {
+    use_gas!("map_set", ctx, key_len as u64 + val_len as u64);
+
+    var_len(ctx, var_id, u32::MAX)?;
+
+    let key = load_memory(ctx, key_ptr, key_len)?;
+    let value = load_memory(ctx, val_ptr, val_len)?;
+
+    let storage = &mut ctx.borrow_mut().storage;
+    storage.map_set(VarId(var_id), &key, value);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::error::VMCallError;
+use crate::Context;
+
+/// Reads memory cells `[offset, offset + len)`. Returns `None` when out-of-bounds.
+pub(crate) fn read_memory(ctx: &Context, offset: u32, len: u32) -> Option<Vec<u8>> {
+    let borrow = ctx.borrow();
+    let memory = borrow.get_memory();
+
+    let start = offset as usize;
+    let end = start.checked_add(len as usize)?;
+    let view = memory.view::<u8>();
+
+    view.get(start..end)
+        .map(|cells| cells.iter().map(|cell| cell.get()).collect())
+}
+
+/// Copies `bytes` into memory starting at offset `offset`. Returns `None` when out-of-bounds.
+pub(crate) fn write_memory(ctx: &Context, offset: u32, bytes: &[u8]) -> Option<()> {
+    let borrow = ctx.borrow();
+    let memory = borrow.get_memory();
+
+    let start = offset as usize;
+    let end = start.checked_add(bytes.len())?;
+    let view = memory.view::<u8>();
+    let cells = view.get(start..end)?;
+
+    for (cell, &byte) in cells.iter().zip(bytes.iter()) {
+        cell.set(byte);
+    }
+
+    Some(())
+}
+
+/// Same as `read_memory`, but raises `VMCallError::MemoryOutOfBounds` when out-of-bounds.
+pub(crate) fn load_memory(ctx: &Context, offset: u32, len: u32) -> Result<Vec<u8>, VMCallError> {
+    read_memory(ctx, offset, len).ok_or(VMCallError::MemoryOutOfBounds { offset, len })
+}
+
+/// Same as `write_memory`, but raises `VMCallError::MemoryOutOfBounds` when out-of-bounds.
+pub(crate) fn store_memory(ctx: &Context, offset: u32, bytes: &[u8]) -> Result<(), VMCallError> {
+    write_memory(ctx, offset, bytes).ok_or(VMCallError::MemoryOutOfBounds {
+        offset,
+        len: bytes.len() as u32,
+    })
+}
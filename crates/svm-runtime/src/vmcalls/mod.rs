@@ -2,15 +2,28 @@ use wasmer::{Exports, Function, ImportObject, Store};
 
 use crate::Context;
 
+mod balance;
+mod call;
 mod calldata;
+mod host;
 mod logs;
+mod map;
+mod memory;
 mod returndata;
+mod savepoint;
 mod storage;
+mod vec;
 
+pub use balance::{balance, transfer, value};
+pub use call::{call, call_returndata_copy, call_returndata_len};
 pub use calldata::{calldata_len, calldata_offset};
+pub use host::{host_get, host_get_len};
 pub use logs::log;
+pub use map::{map_get, map_set};
 pub use returndata::set_returndata;
+pub use savepoint::{release, rollback_to, savepoint};
 pub use storage::{get32, get64, load160, set32, set64, store160};
+pub use vec::{vec_get, vec_len, vec_push};
 
 macro_rules! func {
     ($store:ident, $ctx:ident, $f:expr) => {{
@@ -35,5 +48,33 @@ pub fn wasmer_register(store: &Store, ctx: &Context, ns: &mut Exports) {
     ns.insert("svm_load160", func!(store, ctx, load160));
     ns.insert("svm_store160", func!(store, ctx, store160));
 
+    ns.insert("svm_map_get", func!(store, ctx, map_get));
+    ns.insert("svm_map_set", func!(store, ctx, map_set));
+
+    ns.insert("svm_vec_len", func!(store, ctx, vec_len));
+    ns.insert("svm_vec_get", func!(store, ctx, vec_get));
+    ns.insert("svm_vec_push", func!(store, ctx, vec_push));
+
+    ns.insert("svm_savepoint", func!(store, ctx, savepoint));
+    ns.insert("svm_rollback_to", func!(store, ctx, rollback_to));
+    ns.insert("svm_release", func!(store, ctx, release));
+
     ns.insert("svm_log", func!(store, ctx, log));
+
+    ns.insert("svm_call", func!(store, ctx, call));
+    ns.insert(
+        "svm_call_returndata_len",
+        func!(store, ctx, call_returndata_len),
+    );
+    ns.insert(
+        "svm_call_returndata_copy",
+        func!(store, ctx, call_returndata_copy),
+    );
+
+    ns.insert("svm_value", func!(store, ctx, value));
+    ns.insert("svm_balance", func!(store, ctx, balance));
+    ns.insert("svm_transfer", func!(store, ctx, transfer));
+
+    ns.insert("svm_host_get_len", func!(store, ctx, host_get_len));
+    ns.insert("svm_host_get", func!(store, ctx, host_get));
 }
@@ -1,9 +1,13 @@
+use svm_types::gas::OOGError;
+
 use crate::use_gas;
 use crate::Context;
 
-pub fn set_returndata(ctx: &Context, offset: u32, length: u32) {
-    use_gas!("set_returndata", ctx);
+pub fn set_returndata(ctx: &Context, offset: u32, length: u32) -> Result<(), OOGError> {
+    use_gas!("set_returndata", ctx, length);
 
     ctx.borrow_mut()
-        .set_returndata(offset as usize, length as usize)
+        .set_returndata(offset as usize, length as usize);
+
+    Ok(())
 }
@@ -0,0 +1,44 @@
+use crate::error::VMCallError;
+use crate::{use_gas, Context};
+
+/// Creates a new (nested) savepoint of the app's storage and returns its id.
+///
+/// The storage changes made after the savepoint can be reverted using `rollback_to`.
+pub fn savepoint(ctx: &Context) -> Result<u32, VMCallError> {
+    use_gas!("savepoint", ctx);
+
+    let storage = &mut ctx.borrow_mut().storage;
+
+    Ok(storage.savepoint())
+}
+
+/// Reverts the storage changes made since savepoint `id` was created
+/// (and releases it alongside the savepoints nested in it).
+///
+/// Raises `VMCallError::InvalidSavepoint` when there is no open savepoint `id`.
+pub fn rollback_to(ctx: &Context, id: u32) -> Result<(), VMCallError> {
+    use_gas!("rollback_to", ctx);
+
+    let storage = &mut ctx.borrow_mut().storage;
+
+    if storage.rollback_to(id) {
+        Ok(())
+    } else {
+        Err(VMCallError::InvalidSavepoint(id))
+    }
+}
+
+/// Releases savepoint `id` (and the savepoints nested in it) keeping the storage changes made since its creation.
+///
+/// Raises `VMCallError::InvalidSavepoint` when there is no open savepoint `id`.
+pub fn release(ctx: &Context, id: u32) -> Result<(), VMCallError> {
+    use_gas!("release", ctx);
+
+    let storage = &mut ctx.borrow_mut().storage;
+
+    if storage.release(id) {
+        Ok(())
+    } else {
+        Err(VMCallError::InvalidSavepoint(id))
+    }
+}
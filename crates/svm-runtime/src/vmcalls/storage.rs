@@ -1,153 +1,128 @@
+use crate::error::VMCallError;
 use crate::{use_gas, Context};
 
 use byteorder::{ByteOrder, LittleEndian};
 
 use svm_layout::VarId;
 
-macro_rules! store_n_impl {
-    ($nbytes:expr, $ctx:ident, $mem_ptr:expr, $var_id:expr) => {{
-        use svm_layout::VarId;
+use super::memory::{load_memory, store_memory};
 
-        let bytes: Vec<u8> = {
-            let borrow = $ctx.borrow();
-            let memory = borrow.get_memory();
-            let start = $mem_ptr as usize;
-            let end = start + $nbytes;
-            let view = &memory.view::<u8>()[start..end];
-
-            view.iter().map(|cell| cell.get()).collect()
-        };
-        assert_eq!(bytes.len(), $nbytes);
-
-        let storage = &mut $ctx.borrow_mut().storage;
-        storage.write_var(VarId($var_id), bytes);
-    }};
-}
-
-macro_rules! load_n_impl {
-    ($nbytes:expr, $ctx:ident, $var_id:expr, $mem_ptr:expr) => {{
-        use svm_layout::VarId;
-
-        let storage = &$ctx.borrow().storage;
-
-        let bytes = storage.read_var(VarId($var_id));
-        let nbytes = bytes.len();
-        assert_eq!(nbytes, $nbytes);
-
-        let borrow = $ctx.borrow();
-        let memory = borrow.get_memory();
-        let start = $mem_ptr as usize;
-        let end = start + $nbytes;
-        let view = &memory.view::<u8>()[start..end];
+/// Returns the length of variable `var_id`.
+///
+/// Raises `VMCallError::InvalidVarId` when there is no such variable or when its length
+/// isn't within `[1, max_len]`.
+pub(crate) fn var_len(ctx: &Context, var_id: u32, max_len: u32) -> Result<u32, VMCallError> {
+    let storage = &ctx.borrow().storage;
 
-        for (cell, &byte) in view.iter().zip(bytes.iter()) {
-            cell.set(byte);
-        }
-    }};
+    match storage.try_var_layout(VarId(var_id)) {
+        Some((_off, len)) if len > 0 && len <= max_len => Ok(len),
+        _ => Err(VMCallError::InvalidVarId(var_id)),
+    }
 }
 
 /// Stores memory cells `[mem_ptr, mem_ptr + 1, ..., mem_ptr + 19]` into variable `var_id`.
 ///
-/// # Panics
-///
-/// Panics if variable `var_id`'s length isn't 20 bytes.
-pub fn store160(ctx: &Context, mem_ptr: u32, var_id: u32) {
+/// Raises `VMCallError::InvalidVarId` if variable `var_id`'s length isn't 20 bytes,
+/// and `VMCallError::MemoryOutOfBounds` when the memory cells are out-of-bounds.
+pub fn store160(ctx: &Context, mem_ptr: u32, var_id: u32) -> Result<(), VMCallError> {
     use_gas!("store160", ctx);
 
-    store_n_impl!(20, ctx, mem_ptr, var_id);
+    if var_len(ctx, var_id, 20)? != 20 {
+        return Err(VMCallError::InvalidVarId(var_id));
+    }
+
+    let bytes = load_memory(ctx, mem_ptr, 20)?;
+
+    let storage = &mut ctx.borrow_mut().storage;
+    storage.write_var(VarId(var_id), bytes);
+
+    Ok(())
 }
 
 /// Loads variable `var_id` data into memory cells `[mem_ptr, mem_ptr + 1, ..., mem_ptr + 19]`
 ///
-/// Returns the variable's length.
-///
-/// # Panics
-///
-/// Panics if variable `var_id`'s length isn't 20 bytes.
-pub fn load160(ctx: &Context, var_id: u32, mem_ptr: u32) {
+/// Raises `VMCallError::InvalidVarId` if variable `var_id`'s length isn't 20 bytes,
+/// and `VMCallError::MemoryOutOfBounds` when the memory cells are out-of-bounds.
+pub fn load160(ctx: &Context, var_id: u32, mem_ptr: u32) -> Result<(), VMCallError> {
     use_gas!("load160", ctx);
 
-    load_n_impl!(20, ctx, var_id, mem_ptr);
+    if var_len(ctx, var_id, 20)? != 20 {
+        return Err(VMCallError::InvalidVarId(var_id));
+    }
+
+    let bytes = ctx.borrow().storage.read_var(VarId(var_id));
+
+    store_memory(ctx, mem_ptr, &bytes)
 }
 
 /// Returns the data stored by variable `var_id` as 32-bit integer.
 ///
-/// # Panics
-///
-/// Panics when variable `var_id` doesn't exist or when it consumes more than 32-bit.
-pub fn get32(ctx: &Context, var_id: u32) -> u32 {
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 32-bit.
+pub fn get32(ctx: &Context, var_id: u32) -> Result<u32, VMCallError> {
     use_gas!("get32", ctx);
 
-    let storage = &ctx.borrow().storage;
+    let nbytes = var_len(ctx, var_id, 4)? as usize;
 
+    let storage = &ctx.borrow().storage;
     let bytes = storage.read_var(VarId(var_id));
-    let nbytes = bytes.len();
-
-    assert!(nbytes <= 4);
 
     let num = LittleEndian::read_uint(&bytes, nbytes);
 
-    debug_assert!(num <= std::u32::MAX as u64);
+    debug_assert!(num <= u32::MAX as u64);
 
-    num as u32
+    Ok(num as u32)
 }
 
 /// Sets the data of variable `var_id` to Little-Endian representation of `value`.
 ///
-/// # Panics
-///
-/// Panics when variable `var_id` doesn't exist or when it consumes more than 32-bit,
-/// or when it has not enough bytes to hold `value`.
-pub fn set32(ctx: &Context, var_id: u32, value: u32) {
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 32-bit,
+/// and `VMCallError::ValueOverflow` when it has not enough bytes to hold `value`.
+pub fn set32(ctx: &Context, var_id: u32, value: u32) -> Result<(), VMCallError> {
     use_gas!("set32", ctx);
 
-    let storage = &mut ctx.borrow_mut().storage;
-
-    let (_off, nbytes) = storage.var_layout(VarId(var_id));
+    let nbytes = var_len(ctx, var_id, 4)?;
 
-    assert!(nbytes <= 4);
-
-    let mut buf = vec![0; nbytes as usize];
-    LittleEndian::write_uint(&mut buf, value as u64, nbytes as usize);
-
-    storage.write_var(VarId(var_id), buf);
+    set_uint(ctx, var_id, value as u64, nbytes)
 }
 
 /// Returns the data stored by variable `var_id` as 64-bit integer.
 ///
-/// # Panics
-///
-/// Panics when variable `var_id` doesn't exist or when it consumes more than 64-bit.
-pub fn get64(ctx: &Context, var_id: u32) -> u64 {
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 64-bit.
+pub fn get64(ctx: &Context, var_id: u32) -> Result<u64, VMCallError> {
     use_gas!("get64", ctx);
 
-    let storage = &ctx.borrow().storage;
+    let nbytes = var_len(ctx, var_id, 8)? as usize;
 
+    let storage = &ctx.borrow().storage;
     let bytes = storage.read_var(VarId(var_id));
-    let nbytes = bytes.len();
-
-    assert!(nbytes <= 8);
 
-    LittleEndian::read_uint(&bytes, nbytes)
+    Ok(LittleEndian::read_uint(&bytes, nbytes))
 }
 
 /// Sets the data of variable `var_id` to Little-Endian representation of `value`.
 ///
-/// # Panics
-///
-/// Panics when variable `var_id` consumes more than 64-bit,
-/// or when it has not enough bytes to hold `value`.
-pub fn set64(ctx: &Context, var_id: u32, value: u64) {
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 64-bit,
+/// and `VMCallError::ValueOverflow` when it has not enough bytes to hold `value`.
+pub fn set64(ctx: &Context, var_id: u32, value: u64) -> Result<(), VMCallError> {
     use_gas!("set64", ctx);
 
-    let storage = &mut ctx.borrow_mut().storage;
+    let nbytes = var_len(ctx, var_id, 8)?;
 
-    let (_off, nbytes) = storage.var_layout(VarId(var_id));
+    set_uint(ctx, var_id, value, nbytes)
+}
+
+fn set_uint(ctx: &Context, var_id: u32, value: u64, nbytes: u32) -> Result<(), VMCallError> {
+    let nbits = 8 * nbytes;
 
-    assert!(nbytes <= 8);
+    if nbits < 64 && value >> nbits != 0 {
+        return Err(VMCallError::ValueOverflow { var_id, value });
+    }
 
     let mut buf = vec![0; nbytes as usize];
     LittleEndian::write_uint(&mut buf, value, nbytes as usize);
 
+    let storage = &mut ctx.borrow_mut().storage;
     storage.write_var(VarId(var_id), buf);
+
+    Ok(())
 }
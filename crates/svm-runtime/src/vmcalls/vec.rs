@@ -0,0 +1,68 @@
+use crate::error::VMCallError;
+use crate::{use_gas, Context};
+
+use svm_layout::VarId;
+
+use super::memory::{load_memory, store_memory};
+
+/// Returns the number of elements of vector variable `var_id`.
+///
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 32-bit.
+pub fn vec_len(ctx: &Context, var_id: u32) -> Result<u32, VMCallError> {
+    use_gas!("vec_len", ctx);
+
+    let storage = &ctx.borrow().storage;
+    let len = storage.vec_len(VarId(var_id))?;
+
+    Ok(len)
+}
+
+/// Copies element `index` of vector variable `var_id` into memory cells `[val_ptr, val_ptr + val_len)`.
+///
+/// The element is truncated (or padded with zeros) to `val_len` bytes.
+/// Returns the byte-length of the stored element.
+///
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 32-bit,
+/// `VMCallError::IndexOutOfBounds` when the vector has no element `index`
+/// and `VMCallError::MemoryOutOfBounds` when the memory cells are out-of-bounds.
+pub fn vec_get(
+    ctx: &Context,
+    var_id: u32,
+    index: u32,
+    val_ptr: u32,
+    val_len: u32,
+) -> Result<u32, VMCallError> {
+    use_gas!("vec_get", ctx, val_len);
+
+    let value = ctx.borrow().storage.vec_get(VarId(var_id), index)?;
+
+    let mut buf = vec![0; val_len as usize];
+    let n = value.len().min(buf.len());
+    buf[..n].copy_from_slice(&value[..n]);
+
+    store_memory(ctx, val_ptr, &buf)?;
+
+    Ok(value.len() as u32)
+}
+
+/// Appends memory cells `[val_ptr, val_ptr + val_len)` to vector variable `var_id`.
+/// Returns the index of the new element.
+///
+/// Raises `VMCallError::InvalidVarId` when variable `var_id` doesn't exist or when it consumes more than 32-bit,
+/// `VMCallError::ValueOverflow` when the variable has not enough bytes to hold the new vector length
+/// and `VMCallError::MemoryOutOfBounds` when the memory cells are out-of-bounds.
+pub fn vec_push(
+    ctx: &Context,
+    var_id: u32,
+    val_ptr: u32,
+    val_len: u32,
+) -> Result<u32, VMCallError> {
+    use_gas!("vec_push", ctx, val_len);
+
+    let value = load_memory(ctx, val_ptr, val_len)?;
+
+    let storage = &mut ctx.borrow_mut().storage;
+    let index = storage.vec_push(VarId(var_id), value)?;
+
+    Ok(index)
+}
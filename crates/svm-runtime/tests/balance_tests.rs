@@ -0,0 +1,362 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_codec::api::builder::{AppTxBuilder, SpawnAppBuilder};
+use svm_kv::memory::MemRawKV;
+use svm_layout::DataLayout;
+use svm_runtime::env::default::KVBalanceStore;
+use svm_runtime::env::memory::{DefaultMemoryEnv, MemBalanceStore};
+use svm_runtime::env::traits::BalanceStore;
+use svm_runtime::gas::DefaultGasEstimator;
+use svm_runtime::{testing, DefaultRuntime, Runtime};
+
+use svm_types::receipt::{BalanceChange, ReceiptError};
+use svm_types::{gas::MaybeGas, Address, AppAddr, HostCtx, State, TemplateAddr};
+
+type MemoryRuntime = DefaultRuntime<DefaultMemoryEnv, DefaultGasEstimator>;
+
+fn balances_runtime(balances: &[(Address, u64)]) -> (MemoryRuntime, Rc<RefCell<MemBalanceStore>>) {
+    let mut store = MemBalanceStore::new();
+
+    for (addr, balance) in balances {
+        store.set_balance(addr, *balance);
+    }
+
+    let store = Rc::new(RefCell::new(store));
+    let balance_store: Rc<RefCell<dyn BalanceStore>> = store.clone();
+
+    let state_kv = testing::memory_state_kv_init();
+    let imports = Box::leak(Box::new(Vec::new()));
+
+    let runtime = testing::create_memory_runtime_with_balances(&state_kv, imports, &balance_store);
+
+    (runtime, store)
+}
+
+fn deploy_template(runtime: &mut MemoryRuntime) -> TemplateAddr {
+    let author = Address::of("author").into();
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(
+        0,
+        "My Template",
+        layout,
+        &ctors,
+        include_str!("wasm/runtime_balance.wast").into(),
+    );
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    receipt.addr.unwrap()
+}
+
+fn spawn_app(runtime: &mut MemoryRuntime, value: u64) -> (AppAddr, State) {
+    let template_addr = deploy_template(runtime);
+    let creator = Address::of("creator").into();
+
+    let bytes = SpawnAppBuilder::new()
+        .with_version(1)
+        .with_template(&template_addr)
+        .with_name("My App")
+        .with_value(value)
+        .with_ctor("ctor")
+        .with_calldata(&Vec::new())
+        .build();
+
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    (
+        receipt.get_app_addr().clone(),
+        receipt.get_init_state().clone(),
+    )
+}
+
+fn build_app_tx(app_addr: &AppAddr, func: &str, value: u64, calldata: &Vec<u8>) -> Vec<u8> {
+    AppTxBuilder::new()
+        .with_version(1)
+        .with_app(app_addr)
+        .with_value(value)
+        .with_func(func)
+        .with_calldata(calldata)
+        .build()
+}
+
+#[test]
+fn kv_balance_store_set_and_get() {
+    let kv = Rc::new(RefCell::new(MemRawKV::new()));
+    let mut store = KVBalanceStore::new(kv);
+
+    let addr1 = Address::of("addr1");
+    let addr2 = Address::of("addr2");
+
+    assert_eq!(store.balance(&addr1), 0);
+
+    store.set_balances(&[(addr1.clone(), 10), (addr2.clone(), 20)]);
+    assert_eq!(store.balance(&addr1), 10);
+    assert_eq!(store.balance(&addr2), 20);
+
+    store.set_balances(&[(addr1.clone(), 5)]);
+    assert_eq!(store.balance(&addr1), 5);
+    assert_eq!(store.balance(&addr2), 20);
+}
+
+#[test]
+fn default_runtime_spawn_app_transfers_value() {
+    let creator = Address::of("creator");
+    let (mut runtime, store) = balances_runtime(&[(creator.clone(), 100)]);
+
+    let template_addr = deploy_template(&mut runtime);
+
+    let bytes = SpawnAppBuilder::new()
+        .with_version(1)
+        .with_template(&template_addr)
+        .with_name("My App")
+        .with_value(40)
+        .with_ctor("ctor")
+        .with_calldata(&Vec::new())
+        .build();
+
+    let receipt = runtime.spawn_app(
+        &bytes,
+        &creator.clone().into(),
+        &HostCtx::new(),
+        MaybeGas::new(),
+    );
+    assert!(receipt.success);
+
+    let app_addr = receipt.get_app_addr().inner().clone();
+
+    assert_eq!(
+        receipt.get_balance_changes(),
+        &[
+            BalanceChange {
+                addr: creator.clone(),
+                old_balance: 100,
+                new_balance: 60,
+            },
+            BalanceChange {
+                addr: app_addr.clone(),
+                old_balance: 0,
+                new_balance: 40,
+            }
+        ]
+    );
+
+    assert_eq!(store.borrow().balance(&creator), 60);
+    assert_eq!(store.borrow().balance(&app_addr), 40);
+}
+
+#[test]
+fn default_runtime_spawn_app_insufficient_funds() {
+    let creator = Address::of("creator");
+    let (mut runtime, store) = balances_runtime(&[(creator.clone(), 10)]);
+
+    let template_addr = deploy_template(&mut runtime);
+
+    let bytes = SpawnAppBuilder::new()
+        .with_version(1)
+        .with_template(&template_addr)
+        .with_name("My App")
+        .with_value(40)
+        .with_ctor("ctor")
+        .with_calldata(&Vec::new())
+        .build();
+
+    let receipt = runtime.spawn_app(
+        &bytes,
+        &creator.clone().into(),
+        &HostCtx::new(),
+        MaybeGas::new(),
+    );
+    assert!(!receipt.success);
+
+    assert_eq!(
+        receipt.error.unwrap(),
+        ReceiptError::InsufficientFunds {
+            addr: creator.clone(),
+            balance: 10,
+            amount: 40,
+        }
+    );
+
+    assert_eq!(store.borrow().balance(&creator), 10);
+}
+
+#[test]
+fn default_runtime_exec_app_transfers_value() {
+    let sender = Address::of("sender");
+    let (mut runtime, store) = balances_runtime(&[(sender.clone(), 100)]);
+    let (app_addr, state) = spawn_app(&mut runtime, 0);
+
+    let bytes = build_app_tx(&app_addr, "get_value", 25, &Vec::new());
+    let receipt = runtime.exec_app(
+        &bytes,
+        &testing::host_ctx_with_sender(&sender),
+        &state,
+        MaybeGas::new(),
+    );
+    assert!(receipt.success);
+
+    // the app observes the transaction `value`
+    let returndata = receipt.returndata.as_ref().unwrap();
+    assert_eq!(returndata, &25u64.to_le_bytes());
+
+    assert_eq!(
+        receipt.get_balance_changes(),
+        &[
+            BalanceChange {
+                addr: sender.clone(),
+                old_balance: 100,
+                new_balance: 75,
+            },
+            BalanceChange {
+                addr: app_addr.inner().clone(),
+                old_balance: 0,
+                new_balance: 25,
+            }
+        ]
+    );
+
+    assert_eq!(store.borrow().balance(&sender), 75);
+    assert_eq!(store.borrow().balance(app_addr.inner()), 25);
+}
+
+#[test]
+fn default_runtime_exec_app_insufficient_funds() {
+    let sender = Address::of("sender");
+    let (mut runtime, store) = balances_runtime(&[(sender.clone(), 10)]);
+    let (app_addr, state) = spawn_app(&mut runtime, 0);
+
+    let bytes = build_app_tx(&app_addr, "get_value", 25, &Vec::new());
+    let receipt = runtime.exec_app(
+        &bytes,
+        &testing::host_ctx_with_sender(&sender),
+        &state,
+        MaybeGas::new(),
+    );
+    assert!(!receipt.success);
+
+    assert_eq!(
+        receipt.error.unwrap(),
+        ReceiptError::InsufficientFunds {
+            addr: sender.clone(),
+            balance: 10,
+            amount: 25,
+        }
+    );
+
+    assert!(receipt.balance_changes.is_empty());
+    assert_eq!(store.borrow().balance(&sender), 10);
+}
+
+#[test]
+fn default_runtime_exec_app_balance_and_transfer() {
+    let creator = Address::of("creator");
+    let sender = Address::of("sender");
+    let dst = Address::of("dst");
+
+    let (mut runtime, store) = balances_runtime(&[(creator, 100), (dst.clone(), 5)]);
+    let (app_addr, state) = spawn_app(&mut runtime, 100);
+
+    let calldata = dst.as_slice().to_vec();
+
+    let bytes = build_app_tx(&app_addr, "get_balance", 0, &calldata);
+    let receipt = runtime.exec_app(
+        &bytes,
+        &testing::host_ctx_with_sender(&sender),
+        &state,
+        MaybeGas::new(),
+    );
+    assert!(receipt.success);
+
+    let returndata = receipt.returndata.as_ref().unwrap();
+    assert_eq!(returndata, &5u64.to_le_bytes());
+    assert!(receipt.balance_changes.is_empty());
+
+    let bytes = build_app_tx(&app_addr, "transfer", 0, &calldata);
+    let receipt = runtime.exec_app(
+        &bytes,
+        &testing::host_ctx_with_sender(&sender),
+        &state,
+        MaybeGas::new(),
+    );
+    assert!(receipt.success);
+
+    let returndata = receipt.returndata.as_ref().unwrap();
+    assert_eq!(returndata, &1u32.to_le_bytes());
+
+    assert_eq!(
+        receipt.get_balance_changes(),
+        &[
+            BalanceChange {
+                addr: app_addr.inner().clone(),
+                old_balance: 100,
+                new_balance: 70,
+            },
+            BalanceChange {
+                addr: dst.clone(),
+                old_balance: 5,
+                new_balance: 35,
+            }
+        ]
+    );
+
+    assert_eq!(store.borrow().balance(app_addr.inner()), 70);
+    assert_eq!(store.borrow().balance(&dst), 35);
+}
+
+#[test]
+fn default_runtime_exec_app_transfer_insufficient_funds() {
+    let sender = Address::of("sender");
+    let dst = Address::of("dst");
+
+    let (mut runtime, store) = balances_runtime(&[]);
+    let (app_addr, state) = spawn_app(&mut runtime, 0);
+
+    let calldata = dst.as_slice().to_vec();
+    let bytes = build_app_tx(&app_addr, "transfer", 0, &calldata);
+    let receipt = runtime.exec_app(
+        &bytes,
+        &testing::host_ctx_with_sender(&sender),
+        &state,
+        MaybeGas::new(),
+    );
+
+    // the transfer fails, but the app continues running
+    assert!(receipt.success);
+
+    let returndata = receipt.returndata.as_ref().unwrap();
+    assert_eq!(returndata, &0u32.to_le_bytes());
+
+    assert!(receipt.balance_changes.is_empty());
+    assert_eq!(store.borrow().balance(&dst), 0);
+}
+
+#[test]
+fn default_runtime_exec_app_failure_discards_transfers() {
+    let creator = Address::of("creator");
+    let sender = Address::of("sender");
+    let dst = Address::of("dst");
+
+    let (mut runtime, store) = balances_runtime(&[(creator, 100), (sender.clone(), 100)]);
+    let (app_addr, state) = spawn_app(&mut runtime, 100);
+
+    let calldata = dst.as_slice().to_vec();
+    let bytes = build_app_tx(&app_addr, "transfer_and_fail", 10, &calldata);
+    let receipt = runtime.exec_app(
+        &bytes,
+        &testing::host_ctx_with_sender(&sender),
+        &state,
+        MaybeGas::new(),
+    );
+    assert!(!receipt.success);
+
+    assert!(receipt.balance_changes.is_empty());
+
+    assert_eq!(store.borrow().balance(&sender), 100);
+    assert_eq!(store.borrow().balance(app_addr.inner()), 100);
+    assert_eq!(store.borrow().balance(&dst), 0);
+}
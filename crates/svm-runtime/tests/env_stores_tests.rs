@@ -0,0 +1,302 @@
+use svm_layout::DataLayout;
+use svm_runtime::env::traits::{AppStore, AppTemplateStore};
+use svm_runtime::env::types::AppTemplateHash;
+use svm_types::{Address, App, AppTemplate, AuthorAddr, CreatorAddr, TemplateAddr};
+
+// The same test-suite runs against each `Env` stores implementation,
+// in order to make sure they all behave identically.
+macro_rules! env_stores_tests {
+    ($env:ident, $app_store:expr, $template_store:expr) => {
+        mod $env {
+            #[test]
+            fn template_store_store_and_load() {
+                let mut store = $template_store("store_and_load");
+
+                super::template_store_store_and_load(&mut store);
+            }
+
+            #[test]
+            fn template_store_shared_code_hash() {
+                let mut store = $template_store("shared_code_hash");
+
+                super::template_store_shared_code_hash(&mut store);
+            }
+
+            #[test]
+            fn template_store_load_unknown() {
+                let store = $template_store("load_unknown");
+
+                super::template_store_load_unknown(&store);
+            }
+
+            #[test]
+            fn app_store_store_and_load() {
+                let mut store = $app_store("store_and_load");
+
+                super::app_store_store_and_load(&mut store);
+            }
+
+            #[test]
+            fn app_store_load_unknown() {
+                let store = $app_store("load_unknown");
+
+                super::app_store_load_unknown(&store);
+            }
+        }
+    };
+}
+
+env_stores_tests!(
+    memory,
+    |_name| svm_runtime::env::memory::DefaultMemAppStore::new(),
+    |_name| svm_runtime::env::memory::DefaultMemAppTemplateStore::new()
+);
+
+#[cfg(feature = "default-rocksdb")]
+env_stores_tests!(
+    rocksdb,
+    |name| {
+        svm_runtime::env::rocksdb::DefaultRocksdbAppStore::new(rocksdb_path("apps", name)).unwrap()
+    },
+    |name| {
+        svm_runtime::env::rocksdb::DefaultRocksdbAppTemplateStore::new(rocksdb_path(
+            "templates",
+            name,
+        ))
+        .unwrap()
+    }
+);
+
+#[cfg(feature = "default-rocksdb")]
+#[test]
+fn rocksdb_stores_persist_across_restarts() {
+    use svm_runtime::env::rocksdb::{DefaultRocksdbAppStore, DefaultRocksdbAppTemplateStore};
+
+    let apps_path = rocksdb_path("apps", "restarts");
+    let templates_path = rocksdb_path("templates", "restarts");
+
+    let (template, author) = (template("My Template"), author());
+    let (app, creator) = (app("My App"), creator());
+
+    let template_addr = TemplateAddr::new(Address::of("template"));
+    let app_addr = Address::of("my-app").into();
+    let hash = AppTemplateHash([0xAB; 32]);
+
+    {
+        let mut templates = DefaultRocksdbAppTemplateStore::new(&templates_path).unwrap();
+        templates.store(&template, &author, &template_addr, &hash);
+
+        let mut apps = DefaultRocksdbAppStore::new(&apps_path).unwrap();
+        apps.store(&app, &creator, &app_addr);
+    }
+
+    let templates = DefaultRocksdbAppTemplateStore::new(&templates_path).unwrap();
+    let apps = DefaultRocksdbAppStore::new(&apps_path).unwrap();
+
+    assert_eq!(templates.load(&template_addr), Some((template, author)));
+    assert_eq!(apps.load(&app_addr), Some((app, creator)));
+}
+
+#[cfg(feature = "default-rocksdb")]
+#[test]
+fn rocksdb_runtime_fails_when_kv_path_cant_be_created() {
+    use svm_runtime::env::default::DefaultSerializerTypes;
+    use svm_runtime::gas::DefaultGasEstimator;
+    use svm_runtime::ExternImport;
+
+    // a file is placed where the `kv_path` parent directory should be
+    let file = rocksdb_path("runtime", "not-a-dir");
+    std::fs::write(&file, b"").unwrap();
+
+    let imports: Vec<ExternImport> = Vec::new();
+
+    let runtime = svm_runtime::create_rocksdb_runtime::<
+        _,
+        DefaultSerializerTypes,
+        DefaultGasEstimator,
+    >(file.join("kv"), &imports);
+
+    assert!(runtime.is_err());
+}
+
+#[cfg(feature = "default-rocksdb")]
+#[test]
+fn rocksdb_runtime_fails_when_kv_path_is_in_use() {
+    use svm_runtime::env::default::DefaultSerializerTypes;
+    use svm_runtime::gas::DefaultGasEstimator;
+    use svm_runtime::ExternImport;
+
+    let kv_path = rocksdb_path("runtime", "in-use");
+    let imports: Vec<ExternImport> = Vec::new();
+
+    let create = || {
+        svm_runtime::create_rocksdb_runtime::<_, DefaultSerializerTypes, DefaultGasEstimator>(
+            &kv_path, &imports,
+        )
+    };
+
+    // the `rocksdb` databases are locked by the first runtime
+    let runtime = create().unwrap();
+    assert!(create().is_err());
+
+    drop(runtime);
+    assert!(create().is_ok());
+}
+
+#[cfg(feature = "default-rocksdb")]
+#[test]
+fn rocksdb_runtime_with_retention_persists_across_restarts() {
+    use svm_layout::VarId;
+    use svm_runtime::env::default::DefaultSerializerTypes;
+    use svm_runtime::gas::DefaultGasEstimator;
+    use svm_runtime::{testing, Config, ExternImport, Runtime};
+    use svm_storage::kv::Retention;
+    use svm_types::{gas::MaybeGas, HostCtx};
+
+    let kv_path = rocksdb_path("runtime", "retention");
+    let _ = std::fs::remove_dir_all(&kv_path);
+
+    let imports: Vec<ExternImport> = Vec::new();
+
+    let create = || {
+        let config = Config::new(&kv_path).with_retention(Retention::KeepLast(2));
+
+        svm_runtime::create_rocksdb_runtime_with_config::<DefaultSerializerTypes, DefaultGasEstimator>(
+            config, &imports,
+        )
+        .unwrap()
+    };
+
+    let mut runtime = create();
+
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+
+    let bytes = testing::build_template(0, "My Template", layout.clone(), &ctors, wasm.into());
+    let receipt = runtime.deploy_template(&bytes, &author(), &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    let template_addr = receipt.addr.unwrap();
+
+    let bytes = testing::build_app(0, &template_addr, "My App", "ctor", &vec![]);
+    let receipt = runtime.spawn_app(&bytes, &creator(), &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    let app_addr = receipt.get_app_addr().clone();
+    let mut state = receipt.get_init_state().clone();
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    for _ in 0..5 {
+        let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+        assert!(receipt.success);
+
+        state = receipt.get_new_state().clone();
+    }
+
+    drop(runtime);
+
+    // the pruned states aren't required for executing the app after a restart
+    let runtime = create();
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(receipt.success);
+
+    let storage = runtime
+        .open_app_storage(&app_addr, receipt.get_new_state(), &layout)
+        .unwrap();
+    assert_eq!(storage.read_var(VarId(0)), 6u64.to_le_bytes().to_vec());
+}
+
+#[cfg(feature = "default-rocksdb")]
+fn rocksdb_path(store: &str, name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir()
+        .join("svm-env-stores-tests")
+        .join(store);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir.join(name)
+}
+
+fn template_store_store_and_load(store: &mut impl AppTemplateStore) {
+    let template = template("My Template");
+    let author = author();
+    let addr = TemplateAddr::new(Address::of("template"));
+    let hash = AppTemplateHash([0x10; 32]);
+
+    store.store(&template, &author, &addr, &hash);
+
+    assert_eq!(store.load(&addr), Some((template, author)));
+}
+
+fn template_store_shared_code_hash(store: &mut impl AppTemplateStore) {
+    let hash = AppTemplateHash([0x20; 32]);
+    let author = author();
+
+    let addr1 = TemplateAddr::new(Address::of("template #1"));
+    let addr2 = TemplateAddr::new(Address::of("template #2"));
+
+    store.store(&template("Template #1"), &author, &addr1, &hash);
+    store.store(&template("Template #2"), &author, &addr2, &hash);
+
+    // templates sharing the same code hash share their (content-addressed) code
+    // while each keeps its own data
+    assert_eq!(
+        store.load(&addr1),
+        Some((template("Template #1"), author.clone()))
+    );
+    assert_eq!(store.load(&addr2), Some((template("Template #2"), author)));
+
+    assert_eq!(store.load_code(&hash), Some(template("").code));
+}
+
+fn template_store_load_unknown(store: &impl AppTemplateStore) {
+    let addr = TemplateAddr::new(Address::of("unknown-template"));
+    let hash = AppTemplateHash([0x30; 32]);
+
+    assert!(store.load(&addr).is_none());
+    assert!(store.load_code(&hash).is_none());
+}
+
+fn app_store_store_and_load(store: &mut impl AppStore) {
+    let app = app("My App");
+    let creator = creator();
+    let addr = Address::of("my-app").into();
+
+    store.store(&app, &creator, &addr);
+
+    assert_eq!(store.load(&addr), Some((app, creator)));
+}
+
+fn app_store_load_unknown(store: &impl AppStore) {
+    let addr = Address::of("unknown-app").into();
+
+    assert!(store.load(&addr).is_none());
+}
+
+fn template(name: &str) -> AppTemplate {
+    AppTemplate {
+        version: 0,
+        name: name.to_string(),
+        code: vec![0x0C, 0x00, 0x0D, 0x0E],
+        data: DataLayout::from(vec![4, 8].as_slice()),
+        ctors: vec!["init".to_string()],
+    }
+}
+
+fn app(name: &str) -> App {
+    App {
+        name: name.to_string(),
+        template: TemplateAddr::new(Address::of("template")),
+    }
+}
+
+fn author() -> AuthorAddr {
+    Address::of("author").into()
+}
+
+fn creator() -> CreatorAddr {
+    Address::of("creator").into()
+}
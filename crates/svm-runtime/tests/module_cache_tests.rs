@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+
+use svm_layout::DataLayout;
+use svm_runtime::env::types::AppTemplateHash;
+use svm_runtime::{testing, Config, ModuleCache, Runtime};
+use svm_types::{gas::MaybeGas, Address, AppAddr, HostCtx, State};
+
+use wasmer::{imports, Instance, Module, NativeFunc};
+
+fn module_wasm(value: i32) -> Vec<u8> {
+    let wat = format!(
+        r#"
+        (module
+          (func (export "get") (result i32)
+            i32.const {}))"#,
+        value
+    );
+
+    wat::parse_str(wat).unwrap()
+}
+
+fn call_get(module: &Module) -> i32 {
+    let instance = Instance::new(module, &imports! {}).unwrap();
+    svm_compiler::set_gas_left(&instance, 1000);
+
+    let func: NativeFunc<(), i32> = instance.exports.get_native_function("get").unwrap();
+
+    func.call().unwrap()
+}
+
+fn artifacts_kv_path(test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("svm-module-cache-{}", test));
+    let _ = std::fs::remove_dir_all(&path);
+
+    path
+}
+
+#[test]
+fn module_cache_get_or_compile_caches_module() {
+    let store = svm_compiler::new_store();
+    let mut cache = ModuleCache::new(10, None);
+
+    let hash = AppTemplateHash([1; 32]);
+    let wasm = module_wasm(10);
+
+    assert!(cache.is_empty());
+
+    let module = cache.get_or_compile(&store, &hash, &wasm).unwrap();
+    assert_eq!(call_get(&module), 10);
+    assert!(cache.contains(&hash));
+
+    // a cache hit doesn't look at the code
+    let module = cache.get_or_compile(&store, &hash, &[]).unwrap();
+    assert_eq!(call_get(&module), 10);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn module_cache_evicts_least_recently_used() {
+    let store = svm_compiler::new_store();
+    let mut cache = ModuleCache::new(2, None);
+
+    let hash1 = AppTemplateHash([1; 32]);
+    let hash2 = AppTemplateHash([2; 32]);
+    let hash3 = AppTemplateHash([3; 32]);
+
+    cache
+        .get_or_compile(&store, &hash1, &module_wasm(1))
+        .unwrap();
+    cache
+        .get_or_compile(&store, &hash2, &module_wasm(2))
+        .unwrap();
+
+    // touching `hash1` makes `hash2` the least recently used
+    cache.get_or_compile(&store, &hash1, &[]).unwrap();
+    cache
+        .get_or_compile(&store, &hash3, &module_wasm(3))
+        .unwrap();
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&hash1));
+    assert!(!cache.contains(&hash2));
+    assert!(cache.contains(&hash3));
+}
+
+#[test]
+fn module_cache_loads_persisted_artifacts() {
+    let kv_path = artifacts_kv_path("persist");
+    let store = svm_compiler::new_store();
+
+    let hash = AppTemplateHash([1; 32]);
+
+    let mut cache = ModuleCache::new(10, Some(&kv_path));
+    cache
+        .get_or_compile(&store, &hash, &module_wasm(7))
+        .unwrap();
+
+    let artifact = cache.artifact_path(&hash).unwrap();
+    assert!(artifact.starts_with(kv_path.join("artifacts")));
+    assert!(artifact.exists());
+
+    // a fresh cache (i.e after a restart) loads the artifact instead of compiling
+    let mut cache = ModuleCache::new(10, Some(&kv_path));
+    let module = cache.get_or_compile(&store, &hash, &[]).unwrap();
+    assert_eq!(call_get(&module), 7);
+
+    let _ = std::fs::remove_dir_all(&kv_path);
+}
+
+#[test]
+fn module_cache_ignores_artifacts_of_other_versions() {
+    let kv_path = artifacts_kv_path("versions");
+    let store = svm_compiler::new_store();
+
+    let hash = AppTemplateHash([3; 32]);
+
+    // an artifact persisted without the compiler version (i.e by an older release)
+    let legacy = kv_path
+        .join("artifacts")
+        .join(svm_common::fmt::fmt_hex(&hash.0, ""));
+    std::fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+
+    let module = svm_compiler::compile(&store, &module_wasm(7)).unwrap();
+    module.serialize_to_file(&legacy).unwrap();
+
+    let mut cache = ModuleCache::new(10, Some(&kv_path));
+    let module = cache
+        .get_or_compile(&store, &hash, &module_wasm(8))
+        .unwrap();
+    assert_eq!(call_get(&module), 8);
+
+    let _ = std::fs::remove_dir_all(&kv_path);
+}
+
+#[test]
+fn module_cache_recompiles_corrupted_artifacts() {
+    let kv_path = artifacts_kv_path("corrupted");
+    let store = svm_compiler::new_store();
+
+    let hash = AppTemplateHash([2; 32]);
+
+    let mut cache = ModuleCache::new(10, Some(&kv_path));
+    cache
+        .get_or_compile(&store, &hash, &module_wasm(7))
+        .unwrap();
+
+    let artifact = cache.artifact_path(&hash).unwrap();
+    std::fs::write(&artifact, b"corrupted").unwrap();
+
+    // the corrupted artifact is treated as a cache miss
+    let mut cache = ModuleCache::new(10, Some(&kv_path));
+    let module = cache
+        .get_or_compile(&store, &hash, &module_wasm(8))
+        .unwrap();
+    assert_eq!(call_get(&module), 8);
+
+    // and it's been overwritten by the recompiled one
+    let mut cache = ModuleCache::new(10, Some(&kv_path));
+    let module = cache.get_or_compile(&store, &hash, &[]).unwrap();
+    assert_eq!(call_get(&module), 8);
+
+    let _ = std::fs::remove_dir_all(&kv_path);
+}
+
+#[test]
+fn default_runtime_templates_with_same_code_share_module() {
+    let state_kv = testing::memory_state_kv_init();
+    let imports = Box::leak(Box::new(Vec::new()));
+    let config = Config::new("mem").with_module_cache_size(10);
+
+    let mut runtime = testing::create_memory_runtime_with_config(&state_kv, imports, config);
+
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let maybe_gas = MaybeGas::new();
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
+
+    let mut app: Option<(AppAddr, State)> = None;
+
+    for name in &["Template #1", "Template #2"] {
+        let bytes = testing::build_template(
+            version,
+            name,
+            layout.clone(),
+            &ctors,
+            include_str!("wasm/runtime_exec_app.wast").into(),
+        );
+
+        let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
+        assert!(receipt.success);
+
+        let template_addr = receipt.addr.unwrap();
+        let calldata = vec![];
+
+        // the templates have the same address (it's derived from the code), hence the same app
+        let (app_addr, state) = app.take().unwrap_or_else(|| {
+            let bytes = testing::build_app(version, &template_addr, "My App", "ctor", &calldata);
+            let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+            assert!(receipt.success);
+
+            (
+                receipt.get_app_addr().clone(),
+                receipt.get_init_state().clone(),
+            )
+        });
+
+        let bytes = testing::build_app_tx(version, &app_addr, "add", &calldata);
+        let receipt = runtime.exec_app(&bytes, &host_ctx, &state, maybe_gas);
+        assert!(receipt.success);
+
+        app = Some((app_addr, receipt.get_new_state().clone()));
+    }
+
+    assert_eq!(runtime.module_cache().len(), 1);
+}
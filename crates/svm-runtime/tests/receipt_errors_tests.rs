@@ -0,0 +1,193 @@
+use svm_layout::DataLayout;
+use svm_runtime::env::memory::DefaultMemoryEnv;
+use svm_runtime::gas::DefaultGasEstimator;
+use svm_runtime::testing::{self, WasmFile};
+use svm_runtime::{DefaultRuntime, Runtime};
+
+use svm_types::receipt::ReceiptError;
+use svm_types::{gas::MaybeGas, Address, AppAddr, HostCtx, State, TemplateAddr};
+
+type MemoryRuntime = DefaultRuntime<DefaultMemoryEnv, DefaultGasEstimator>;
+
+fn memory_runtime() -> MemoryRuntime {
+    let state_kv = testing::memory_state_kv_init();
+    let imports = Box::leak(Box::new(Vec::new()));
+
+    testing::create_memory_runtime(&state_kv, imports)
+}
+
+fn deploy_template(runtime: &mut MemoryRuntime, wasm: WasmFile) -> TemplateAddr {
+    let author = Address::of("author").into();
+    let layout: DataLayout = vec![2].into();
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(0, "My Template", layout, &ctors, wasm);
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    receipt.addr.unwrap()
+}
+
+fn spawn_app(runtime: &mut MemoryRuntime) -> (TemplateAddr, AppAddr, State) {
+    let wasm = include_str!("wasm/runtime_errors.wast").into();
+    let template_addr = deploy_template(runtime, wasm);
+
+    let creator = Address::of("creator").into();
+    let bytes = testing::build_app(0, &template_addr, "My App", "ctor", &Vec::new());
+
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    (
+        template_addr,
+        receipt.get_app_addr().clone(),
+        receipt.get_init_state().clone(),
+    )
+}
+
+fn exec_app(
+    runtime: &MemoryRuntime,
+    app_addr: &AppAddr,
+    state: &State,
+    func: &str,
+) -> ReceiptError {
+    let bytes = testing::build_app_tx(0, app_addr, func, &Vec::new());
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, state, MaybeGas::new());
+    assert!(!receipt.success);
+
+    receipt.error.unwrap()
+}
+
+#[test]
+fn default_runtime_deploy_template_parse_failed() {
+    let mut runtime = memory_runtime();
+    let author = Address::of("author").into();
+    let bytes = vec![0xFF, 0xFF];
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), MaybeGas::new());
+
+    assert!(!receipt.success);
+    assert!(matches!(receipt.error, Some(ReceiptError::ParseFailed(..))));
+}
+
+#[test]
+fn default_runtime_spawn_app_parse_failed() {
+    let mut runtime = memory_runtime();
+    let creator = Address::of("creator").into();
+    let bytes = vec![0xFF];
+
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), MaybeGas::new());
+
+    assert!(!receipt.success);
+    assert!(matches!(receipt.error, Some(ReceiptError::ParseFailed(..))));
+}
+
+#[test]
+fn default_runtime_exec_app_parse_failed() {
+    let runtime = memory_runtime();
+    let host_ctx = HostCtx::new();
+    let bytes = vec![0x00, 0x00, 0x10];
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &State::zeros(), MaybeGas::new());
+
+    assert!(!receipt.success);
+    assert!(matches!(receipt.error, Some(ReceiptError::ParseFailed(..))));
+}
+
+#[test]
+fn default_runtime_spawn_app_missing_memory_export() {
+    let mut runtime = memory_runtime();
+
+    let wasm = r#"
+        (module
+          (func (export "svm_alloc") (param i32) (result i32)
+            i32.const 0)
+
+          (func (export "ctor")
+            nop))"#
+        .into();
+
+    let template_addr = deploy_template(&mut runtime, wasm);
+
+    let creator = Address::of("creator").into();
+    let bytes = testing::build_app(0, &template_addr, "My App", "ctor", &Vec::new());
+
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), MaybeGas::new());
+    assert!(!receipt.success);
+
+    let app_addr = receipt.app_addr.clone().unwrap();
+
+    assert_eq!(
+        receipt.error.unwrap(),
+        ReceiptError::MissingMemoryExport {
+            app_addr,
+            template_addr,
+        }
+    );
+}
+
+#[test]
+fn default_runtime_exec_app_invalid_var_id() {
+    let mut runtime = memory_runtime();
+    let (template_addr, app_addr, state) = spawn_app(&mut runtime);
+
+    let err = exec_app(&runtime, &app_addr, &state, "invalid_var");
+
+    assert_eq!(
+        err,
+        ReceiptError::InvalidVarId {
+            app_addr,
+            template_addr,
+            var_id: 5,
+        }
+    );
+}
+
+#[test]
+fn default_runtime_exec_app_value_overflow() {
+    let mut runtime = memory_runtime();
+    let (_template_addr, app_addr, state) = spawn_app(&mut runtime);
+
+    let err = exec_app(&runtime, &app_addr, &state, "value_overflow");
+
+    assert!(matches!(err, ReceiptError::FuncFailed { .. }));
+}
+
+#[test]
+fn default_runtime_exec_app_returndata_out_of_bounds() {
+    let mut runtime = memory_runtime();
+    let (template_addr, app_addr, state) = spawn_app(&mut runtime);
+
+    let err = exec_app(&runtime, &app_addr, &state, "returndata_out_of_bounds");
+
+    assert_eq!(
+        err,
+        ReceiptError::MemoryOutOfBounds {
+            app_addr,
+            template_addr,
+            offset: 65530,
+            len: 10,
+        }
+    );
+}
+
+#[test]
+fn default_runtime_exec_app_log_out_of_bounds() {
+    let mut runtime = memory_runtime();
+    let (template_addr, app_addr, state) = spawn_app(&mut runtime);
+
+    let err = exec_app(&runtime, &app_addr, &state, "log_out_of_bounds");
+
+    assert_eq!(
+        err,
+        ReceiptError::MemoryOutOfBounds {
+            app_addr,
+            template_addr,
+            offset: 65530,
+            len: 10,
+        }
+    );
+}
@@ -3,14 +3,28 @@ use svm_sdk as sdk;
 use svm_sdk::traits::Encoder;
 use svm_sdk::CallData;
 
+use svm_codec::version::Format;
 use svm_codec::{Field, ParseError};
+use svm_common::{DefaultKeyHasher, KeyHasher};
 
-use svm_gas::error::ProgramError;
+use svm_gas::{error::ProgramError, Gas};
 use svm_layout::{DataLayout, VarId};
-use svm_runtime::{error::ValidateError, testing, Runtime};
+use svm_runtime::env::memory::DefaultMemoryEnv;
+use svm_runtime::error::{MigrateError, ValidateError};
+use svm_runtime::gas::DefaultGasEstimator;
+use svm_runtime::{testing, Config, DefaultRuntime, Runtime};
 
-use svm_types::receipt::{ExecReceipt, Log, ReceiptError, SpawnAppReceipt, TemplateReceipt};
-use svm_types::{gas::MaybeGas, Address};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use svm_storage::app::{MigrationError, StorageUsage};
+use svm_storage::kv::{FakeKV, Retention, StatefulKV};
+use svm_types::receipt::{
+    ExecReceipt, Log, ReceiptError, SpawnAppReceipt, StorageChange, TemplateReceipt,
+};
+use svm_types::{gas::MaybeGas, Address, AppAddr, HostCtx, State, TemplateAddr};
+
+type MemoryRuntime = DefaultRuntime<DefaultMemoryEnv, DefaultGasEstimator>;
 
 macro_rules! default_runtime {
     () => {{
@@ -77,7 +91,8 @@ fn default_runtime_validate_app_invalid_raw_format() {
 fn default_runtime_validate_tx_invalid_raw_format() {
     let runtime = default_runtime!();
 
-    let bytes = vec![0xFF, 0xFF];
+    // version `0` followed by a truncated app address
+    let bytes = vec![0x00, 0x00, 0xFF];
 
     let parse_err = ParseError::NotEnoughBytes(Field::AppAddr);
     let expected = Err(ValidateError::Parse(parse_err));
@@ -86,6 +101,46 @@ fn default_runtime_validate_tx_invalid_raw_format() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn default_runtime_validate_tx_unsupported_version() {
+    let runtime = default_runtime!();
+
+    let app_addr = Address::of("my-app").into();
+    let bytes = testing::build_app_tx(0xFF, &app_addr, "do_something", &vec![]);
+
+    let parse_err = ParseError::UnsupportedVersion(0xFF);
+    let expected = Err(ValidateError::Parse(parse_err));
+
+    let actual = runtime.validate_tx(&bytes);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn default_runtime_exec_app_not_accepted_version() {
+    let state_kv = testing::memory_state_kv_init();
+    let imports = Box::leak(Box::new(Vec::new()));
+    let config = Config::new("mem").with_accepted_versions(Format::ExecApp, &[]);
+
+    let mut runtime = testing::create_memory_runtime_with_config(&state_kv, imports, config);
+
+    assert!(runtime.accepts_version(Format::SpawnApp, 0));
+    assert!(!runtime.accepts_version(Format::ExecApp, 0));
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(!receipt.success);
+
+    assert_eq!(
+        receipt.get_error(),
+        &ReceiptError::ParseFailed("Unsupported version `0`".to_string())
+    );
+}
+
 #[test]
 fn default_runtime_deploy_template_reaches_oog() {
     let mut runtime = default_runtime!();
@@ -104,7 +159,7 @@ fn default_runtime_deploy_template_reaches_oog() {
     );
 
     let expected = TemplateReceipt::new_oog();
-    let actual = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let actual = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert_eq!(expected, actual);
 }
 
@@ -125,11 +180,70 @@ fn default_runtime_deploy_template_has_enough_gas() {
         include_str!("wasm/runtime_app_ctor.wast").into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, gas_limit);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), gas_limit);
     assert!(receipt.success);
     assert!(receipt.gas_used.is_some());
 }
 
+#[test]
+fn default_runtime_deploy_compact_template() {
+    let mut runtime = default_runtime!();
+
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let maybe_gas = MaybeGas::new();
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
+    let wasm = include_str!("wasm/runtime_call_callee.wast");
+
+    let bytes = testing::build_template(0, "Full", layout.clone(), &ctors, wasm.into());
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    let full_addr = receipt.addr.unwrap();
+
+    // deploying the same code again, referencing it by its hash
+    let code = testing::WasmFile::from(wasm).into_bytes();
+    let code_hash = DefaultKeyHasher::hash(&code);
+
+    let bytes = testing::build_compact_template(1, "Compact", layout, &ctors, &code_hash);
+    assert!(runtime.validate_template(&bytes).is_ok());
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    // the template address is derived from the (resolved) code
+    let compact_addr = receipt.addr.unwrap();
+    assert_eq!(full_addr, compact_addr);
+
+    let bytes = testing::build_app(0, &compact_addr, "My App", "ctor", &vec![]);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+}
+
+#[test]
+fn default_runtime_deploy_compact_template_code_not_found() {
+    let mut runtime = default_runtime!();
+
+    let author = Address::of("author").into();
+    let ctors = vec!["ctor".to_string()];
+    let code_hash = [0xFF; 32];
+
+    let bytes =
+        testing::build_compact_template(1, "Compact", DataLayout::empty(), &ctors, &code_hash);
+
+    let expected = Err(ValidateError::Parse(ParseError::CodeNotFound));
+    assert_eq!(runtime.validate_template(&bytes), expected);
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), MaybeGas::new());
+    assert!(!receipt.success);
+
+    assert_eq!(
+        receipt.error.unwrap(),
+        ReceiptError::ParseFailed(ParseError::CodeNotFound.to_string())
+    );
+}
+
 #[test]
 fn default_runtime_spawn_app_with_non_ctor_fails() {
     let mut runtime = default_runtime!();
@@ -149,7 +263,7 @@ fn default_runtime_spawn_app_with_non_ctor_fails() {
         include_str!("wasm/runtime_app_ctor.wast").into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let template_addr = receipt.addr.unwrap();
@@ -162,13 +276,54 @@ fn default_runtime_spawn_app_with_non_ctor_fails() {
     let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
     let maybe_gas = MaybeGas::new();
 
-    let receipt = runtime.spawn_app(&bytes, &creator, maybe_gas);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
     assert!(matches!(
         receipt.error.unwrap(),
         ReceiptError::FuncNotAllowed { .. }
     ));
 }
 
+#[test]
+fn default_runtime_spawn_app_with_unsupported_opcode_fails() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let maybe_gas = MaybeGas::new();
+    let ctors = vec!["ctor".to_string()];
+
+    // invalid wasm (has floats)
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        DataLayout::empty(),
+        &ctors,
+        include_str!("wasm/wasm_with_floats.wast").into(),
+    );
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    let template_addr = receipt.addr.unwrap();
+
+    // 2) spawn app (compilation should fail)
+    let name = "My App";
+    let ctor = "ctor";
+    let calldata = vec![];
+
+    let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+
+    match receipt.error.unwrap() {
+        ReceiptError::CompilationFailed { msg, .. } => {
+            assert_eq!(msg, "function #0 contains an unsupported opcode `F32Const`")
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn default_runtime_spawn_app_with_ctor_reaches_oog() {
     let mut runtime = default_runtime!();
@@ -188,7 +343,7 @@ fn default_runtime_spawn_app_with_ctor_reaches_oog() {
         include_str!("wasm/runtime_app_ctor.wast").into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let template_addr = receipt.addr.unwrap();
@@ -207,12 +362,11 @@ fn default_runtime_spawn_app_with_ctor_reaches_oog() {
     };
 
     let expected = SpawnAppReceipt::new_oog(vec![log]);
-    let actual = runtime.spawn_app(&bytes, &creator, maybe_gas);
+    let actual = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
     assert_eq!(expected, actual);
 }
 
 #[test]
-#[ignore = "temporarily skipping this test until wasmer cranelift will support middlewares"]
 fn default_runtime_spawn_app_with_ctor_with_enough_gas() {
     let mut runtime = default_runtime!();
 
@@ -234,7 +388,7 @@ fn default_runtime_spawn_app_with_ctor_with_enough_gas() {
         include_str!("wasm/runtime_app_ctor.wast").into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
     assert!(receipt.gas_used.is_some());
 
@@ -247,13 +401,13 @@ fn default_runtime_spawn_app_with_ctor_with_enough_gas() {
     let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
     let gas_limit = MaybeGas::with(1_000_000);
 
-    let receipt = runtime.spawn_app(&bytes, &creator, gas_limit);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), gas_limit);
     assert!(receipt.success);
     assert!(receipt.gas_used.is_some());
 
     let addr = receipt.get_app_addr();
     let state = receipt.get_init_state();
-    let storage = runtime.open_app_storage(&addr, &state, &layout);
+    let storage = runtime.open_app_storage(&addr, &state, &layout).unwrap();
 
     let var = storage.read_var(VarId(0));
     assert_eq!(var, 10_20_30_40_50_60_70_80u64.to_le_bytes());
@@ -278,7 +432,7 @@ fn default_runtime_exec_app_with_ctor_fails() {
         (&include_bytes!("wasm/runtime_calldata.wasm")[..]).into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let template_addr = receipt.addr.unwrap();
@@ -288,8 +442,9 @@ fn default_runtime_exec_app_with_ctor_fails() {
     let ctor = "initialize";
     let calldata = vec![];
     let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
     let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
-    let receipt = runtime.spawn_app(&bytes, &creator, maybe_gas);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let app_addr = receipt.get_app_addr();
@@ -298,7 +453,7 @@ fn default_runtime_exec_app_with_ctor_fails() {
     // 3) execute a transaction
     let calldata = Vec::new();
     let bytes = testing::build_app_tx(version, &app_addr, ctor, &calldata);
-    let receipt = runtime.exec_app(&bytes, &init_state, maybe_gas);
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, maybe_gas);
 
     assert!(matches!(
         receipt.error.unwrap(),
@@ -307,7 +462,6 @@ fn default_runtime_exec_app_with_ctor_fails() {
 }
 
 #[test]
-#[ignore = "temporarily skipping this test until wasmer cranelift will support middlewares"]
 fn default_runtime_exec_app_reaches_oog() {
     let mut runtime = default_runtime!();
 
@@ -315,9 +469,10 @@ fn default_runtime_exec_app_reaches_oog() {
     let version = 0;
     let author = Address::of("author").into();
     let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
     let maybe_gas = MaybeGas::new();
-    let layout: DataLayout = vec![4].into();
-    let ctors = vec!["ctors".to_string()];
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
 
     let bytes = testing::build_template(
         version,
@@ -327,7 +482,7 @@ fn default_runtime_exec_app_reaches_oog() {
         include_str!("wasm/runtime_exec_app.wast").into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let template_addr = receipt.addr.unwrap();
@@ -338,7 +493,7 @@ fn default_runtime_exec_app_reaches_oog() {
     let calldata = vec![];
 
     let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
-    let receipt = runtime.spawn_app(&bytes, &creator, maybe_gas);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
 
     let app_addr = receipt.get_app_addr();
     let init_state = receipt.get_init_state();
@@ -351,73 +506,934 @@ fn default_runtime_exec_app_reaches_oog() {
     let logs = Vec::new();
 
     let expected = ExecReceipt::new_oog(logs);
-    let actual = runtime.exec_app(&bytes, &init_state, maybe_gas);
+    let actual = runtime.exec_app(&bytes, &host_ctx, &init_state, maybe_gas);
 
     assert_eq!(expected, actual)
 }
 
 #[test]
-fn default_runtime_calldata_returndata() {
+fn default_runtime_exec_app_with_enough_gas() {
     let mut runtime = default_runtime!();
 
     // 1) deploying the template
     let version = 0;
     let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
     let maybe_gas = MaybeGas::new();
-    let layout: DataLayout = vec![20].into();
-    let ctors = vec!["initialize".to_string()];
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
 
     let bytes = testing::build_template(
         version,
         "My Template",
         layout.clone(),
         &ctors,
-        (&include_bytes!("wasm/runtime_calldata.wasm")[..]).into(),
+        include_str!("wasm/runtime_exec_app.wast").into(),
     );
 
-    let receipt = runtime.deploy_template(&bytes, &author, maybe_gas);
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let template_addr = receipt.addr.unwrap();
 
     // 2) spawn app
     let name = "My App";
-    let ctor = "initialize";
+    let ctor = "ctor";
     let calldata = vec![];
-    let creator = Address::of("creator").into();
+
     let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
-    let receipt = runtime.spawn_app(&bytes, &creator, maybe_gas);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+
+    let app_addr = receipt.get_app_addr();
+    let init_state = receipt.get_init_state();
+
+    // 3) executing an app-transaction
+    let func = "add";
+    let calldata = vec![];
+    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+    let gas_limit = MaybeGas::with(1_000_000);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, gas_limit);
+    assert!(receipt.success);
+
+    // the payload costs 1000 gas units per byte.
+    // `svm_alloc` costs 1 gas unit and `add` costs 4 gas units,
+    // the `svm_get64` and `svm_set64` vmcalls cost 10 and 20 gas units
+    let payload_gas = 1000 * bytes.len() as u64;
+    assert_eq!(
+        receipt.gas_used,
+        MaybeGas::with(payload_gas + 1 + 4 + 10 + 20)
+    );
+
+    let state = receipt.get_new_state();
+    let storage = runtime
+        .open_app_storage(&app_addr, &state, &layout)
+        .unwrap();
+
+    let var = storage.read_var(VarId(0));
+    assert_eq!(var, 1u64.to_le_bytes());
+}
+
+#[test]
+fn default_runtime_exec_app_reads_host_ctx() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let sender = Address::of("sender");
+    let maybe_gas = MaybeGas::new();
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        DataLayout::empty(),
+        &ctors,
+        include_str!("wasm/runtime_host_ctx.wast").into(),
+    );
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    let template_addr = receipt.addr.unwrap();
+
+    // 2) spawn app
+    let bytes = testing::build_app(version, &template_addr, "My App", "ctor", &vec![]);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
     let app_addr = receipt.get_app_addr();
     let init_state = receipt.get_init_state();
 
-    // 3) execute a transaction
-    let func = "store_addr";
-    let msg: sdk::Address = [0x10; 20].into();
+    // 3) executing an app-transaction reading the `sender` out of the `HostCtx`
+    let bytes = testing::build_app_tx(version, &app_addr, "get_sender", &vec![]);
+    let host_ctx = testing::host_ctx_with_sender(&sender);
 
-    let mut calldata = Vec::new();
-    msg.encode(&mut calldata);
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, maybe_gas);
+    assert!(receipt.success);
 
-    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+    let returndata = receipt.returndata.unwrap();
+    assert_eq!(returndata, sender.as_slice());
+}
+
+#[test]
+fn default_runtime_exec_app_vmcalls_reach_oog() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let maybe_gas = MaybeGas::new();
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
 
-    let receipt = runtime.exec_app(&bytes, &init_state, maybe_gas);
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        layout,
+        &ctors,
+        include_str!("wasm/runtime_exec_app.wast").into(),
+    );
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
     assert!(receipt.success);
 
-    let state = receipt.get_new_state();
+    let template_addr = receipt.addr.unwrap();
 
-    // 4) execute a transaction with `returndata`
-    let func = "return_addr";
-    let calldata = Vec::new();
+    // 2) spawn app
+    let name = "My App";
+    let ctor = "ctor";
+    let calldata = vec![];
+
+    let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+
+    let app_addr = receipt.get_app_addr();
+    let init_state = receipt.get_init_state();
 
+    // 3) executing an app-transaction
+    // (there is enough gas for the payload and the wasm instructions but not for the `svm_set64` vmcall)
+    let func = "add";
+    let calldata = vec![];
     let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+    let payload_gas = 1000 * bytes.len() as u64;
+    let maybe_gas = MaybeGas::with(payload_gas + 20);
+    let logs = Vec::new();
 
-    let receipt = runtime.exec_app(&bytes, &state, maybe_gas);
-    assert!(receipt.success);
+    let expected = ExecReceipt::new_oog(logs);
+    let actual = runtime.exec_app(&bytes, &host_ctx, &init_state, maybe_gas);
 
-    let bytes = receipt.returndata.unwrap();
-    let mut calldata = CallData::new(&bytes);
+    assert_eq!(expected, actual)
+}
 
-    let addr: sdk::Address = calldata.next_1();
-    assert_eq!(addr.as_slice(), &[0x10; 20]);
+#[test]
+fn default_runtime_estimate_matches_gas_used() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let gas_limit = MaybeGas::with(10_000_000);
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        layout,
+        &ctors,
+        include_str!("wasm/runtime_exec_app.wast").into(),
+    );
+
+    let estimate = runtime.estimate_deploy_template(&bytes).unwrap();
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), gas_limit);
+    assert_eq!(Gas::Fixed(receipt.gas_used.unwrap()), estimate);
+
+    let template_addr = receipt.addr.unwrap();
+
+    // 2) spawn app
+    let name = "My App";
+    let ctor = "ctor";
+    let calldata = vec![];
+
+    let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
+
+    let estimate = runtime.estimate_spawn_app(&bytes).unwrap();
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), gas_limit);
+    assert_eq!(Gas::Fixed(receipt.gas_used.unwrap()), estimate);
+
+    let app_addr = receipt.get_app_addr();
+    let init_state = receipt.get_init_state();
+
+    // 3) executing an app-transaction
+    let func = "add";
+    let calldata = vec![];
+    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+
+    let estimate = runtime.estimate_exec_app(&bytes).unwrap();
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, gas_limit);
+    assert_eq!(Gas::Fixed(receipt.gas_used.unwrap()), estimate);
+}
+
+#[test]
+fn default_runtime_estimate_exec_app_with_branches() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let gas_limit = MaybeGas::with(10_000_000);
+    let layout: DataLayout = vec![8].into();
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        layout,
+        &ctors,
+        include_str!("wasm/runtime_estimate_branches.wast").into(),
+    );
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), gas_limit);
+    let template_addr = receipt.addr.unwrap();
+
+    // 2) spawn app
+    let name = "My App";
+    let ctor = "ctor";
+    let calldata = vec![];
+
+    let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), gas_limit);
+
+    let app_addr = receipt.get_app_addr();
+    let init_state = receipt.get_init_state();
+
+    // 3) estimating an app-transaction (its cost depends on the branch taken)
+    let func = "run";
+    let calldata = vec![];
+    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+    let payload_gas = 1000 * bytes.len() as u64;
+
+    // `svm_alloc` costs 1 gas unit.
+    // `run` costs 2 gas units for `svm_calldata_len` + 1 gas unit for `i32.eqz`.
+    // The `then` branch costs 2 gas units for its instructions + 20 gas units for `svm_set64`.
+    let min = payload_gas + 1 + 2 + 1;
+    let max = min + 2 + 20;
+
+    let estimate = runtime.estimate_exec_app(&bytes).unwrap();
+    assert_eq!(estimate, Gas::Range { min, max });
+
+    // the `calldata` is empty, so the `then` branch is taken
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, gas_limit);
+    assert_eq!(receipt.gas_used, MaybeGas::with(max));
+}
+
+#[test]
+fn default_runtime_estimate_exec_app_with_loop() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template (having a loop)
+    let version = 0;
+    let author = Address::of("author").into();
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let gas_limit = MaybeGas::with(10_000_000);
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        DataLayout::empty(),
+        &ctors,
+        include_str!("wasm/runtime_loop.wast").into(),
+    );
+
+    assert!(runtime.validate_template(&bytes).is_ok());
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), gas_limit);
+    let template_addr = receipt.addr.unwrap();
+
+    // 2) spawn app
+    let name = "My App";
+    let ctor = "ctor";
+    let calldata = vec![];
+
+    let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), gas_limit);
+
+    let app_addr = receipt.get_app_addr();
+    let init_state = receipt.get_init_state();
+
+    // 3) estimating an app-transaction (its cost depends on the number of iterations)
+    let func = "run";
+    let calldata = vec![];
+    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+    let payload_gas = 1000 * bytes.len() as u64;
+
+    // `svm_alloc` costs 1 gas unit.
+    // `run` costs 2 gas units before the loop and 5 gas units for each iteration.
+    let estimate = runtime.estimate_exec_app(&bytes).unwrap();
+    assert_eq!(estimate, Gas::unbounded(payload_gas + 1 + 2 + 5));
+
+    // the execution is metered dynamically
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, gas_limit);
+    assert_eq!(
+        receipt.gas_used,
+        MaybeGas::with(payload_gas + 1 + 2 + 5 * 10)
+    );
+}
+
+#[test]
+fn default_runtime_estimate_exec_app_app_not_found() {
+    let runtime = default_runtime!();
+
+    let version = 0;
+    let app_addr = Address::of("my-app").into();
+    let calldata = vec![];
+    let bytes = testing::build_app_tx(version, &app_addr, "run", &calldata);
+
+    let expected = Err(ValidateError::AppNotFound(app_addr));
+    let actual = runtime.estimate_exec_app(&bytes);
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn default_runtime_calldata_returndata() {
+    let mut runtime = default_runtime!();
+
+    // 1) deploying the template
+    let version = 0;
+    let author = Address::of("author").into();
+    let maybe_gas = MaybeGas::new();
+    let layout: DataLayout = vec![20].into();
+    let ctors = vec!["initialize".to_string()];
+
+    let bytes = testing::build_template(
+        version,
+        "My Template",
+        layout.clone(),
+        &ctors,
+        (&include_bytes!("wasm/runtime_calldata.wasm")[..]).into(),
+    );
+
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    let template_addr = receipt.addr.unwrap();
+
+    // 2) spawn app
+    let name = "My App";
+    let ctor = "initialize";
+    let calldata = vec![];
+    let creator = Address::of("creator").into();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app(version, &template_addr, name, ctor, &calldata);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    let app_addr = receipt.get_app_addr();
+    let init_state = receipt.get_init_state();
+
+    // 3) execute a transaction
+    let func = "store_addr";
+    let msg: sdk::Address = [0x10; 20].into();
+
+    let mut calldata = Vec::new();
+    msg.encode(&mut calldata);
+
+    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, maybe_gas);
+    assert!(receipt.success);
+
+    let state = receipt.get_new_state();
+
+    // 4) execute a transaction with `returndata`
+    let func = "return_addr";
+    let calldata = Vec::new();
+
+    let bytes = testing::build_app_tx(version, &app_addr, func, &calldata);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, maybe_gas);
+    assert!(receipt.success);
+
+    let bytes = receipt.returndata.unwrap();
+    let mut calldata = CallData::new(&bytes);
+
+    let addr: sdk::Address = calldata.next_1();
+    assert_eq!(addr.as_slice(), &[0x10; 20]);
+}
+
+#[test]
+fn default_runtime_exec_app_call_succeeds() {
+    let mut runtime = default_runtime!();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let (callee, _) = deploy_and_spawn(
+        &mut runtime,
+        "Callee",
+        include_str!("wasm/runtime_call_callee.wast"),
+    );
+    let (caller, state) = deploy_and_spawn(
+        &mut runtime,
+        "Caller",
+        include_str!("wasm/runtime_call_caller.wast"),
+    );
+
+    let calldata = encode_addr(callee.inner());
+    let bytes = testing::build_app_tx(0, &caller, "call_inc", &calldata);
+    let gas_limit = MaybeGas::with(1_000_000);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, gas_limit);
+    assert!(receipt.success);
+
+    // the callee `returndata` is the callee's incremented counter
+    let returndata = receipt.returndata.as_ref().unwrap();
+    assert_eq!(returndata, &1u64.to_le_bytes());
+
+    // the callee logs are propagated to the caller
+    assert_eq!(
+        receipt.logs,
+        vec![Log {
+            msg: b"inc".to_vec(),
+            code: 1
+        }]
+    );
+
+    // the callee gas is charged from the caller
+    let payload_gas = 1000 * bytes.len() as u64;
+    let callee_vmcalls_gas = 10 + 20 + 10 + (20 + 3) + (10 + 8);
+    assert!(receipt.gas_used.unwrap() > payload_gas + 100 + callee_vmcalls_gas);
+
+    let state = receipt.get_new_state();
+    assert_eq!(read_var64(&runtime, &caller, state), 1);
+
+    // the callee has got a new `State` as well
+    let callee_state = runtime.app_state(&callee).unwrap();
+    assert_eq!(read_var64(&runtime, &callee, &callee_state), 1);
+
+    // calling again (against the new `State`)
+    let receipt = runtime.exec_app(&bytes, &host_ctx, state, gas_limit);
+    assert!(receipt.success);
+
+    let returndata = receipt.returndata.as_ref().unwrap();
+    assert_eq!(returndata, &2u64.to_le_bytes());
+
+    let callee_state = runtime.app_state(&callee).unwrap();
+    assert_eq!(read_var64(&runtime, &callee, &callee_state), 2);
+}
+
+#[test]
+fn default_runtime_exec_app_call_fails_and_caller_continues() {
+    let mut runtime = default_runtime!();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let (callee, _) = deploy_and_spawn(
+        &mut runtime,
+        "Callee",
+        include_str!("wasm/runtime_call_callee.wast"),
+    );
+    let (caller, state) = deploy_and_spawn(
+        &mut runtime,
+        "Caller",
+        include_str!("wasm/runtime_call_caller.wast"),
+    );
+
+    let calldata = encode_addr(callee.inner());
+    let bytes = testing::build_app_tx(0, &caller, "call_fail", &calldata);
+    let gas_limit = MaybeGas::with(10_000_000);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, gas_limit);
+    assert!(receipt.success);
+    assert_eq!(receipt.returndata, Some(Vec::new()));
+
+    // the failed call consumes all the gas given to it
+    assert!(receipt.gas_used.unwrap() > 1_000_000);
+
+    // the callee storage changes have been discarded
+    let state = receipt.get_new_state();
+    assert_eq!(read_var64(&runtime, &caller, state), 0);
+
+    let callee_state = runtime.app_state(&callee).unwrap();
+    assert_eq!(read_var64(&runtime, &callee, &callee_state), 0);
+}
+
+#[test]
+fn default_runtime_exec_app_caller_fails_after_call() {
+    let mut runtime = default_runtime!();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let (callee, _) = deploy_and_spawn(
+        &mut runtime,
+        "Callee",
+        include_str!("wasm/runtime_call_callee.wast"),
+    );
+    let (caller, state) = deploy_and_spawn(
+        &mut runtime,
+        "Caller",
+        include_str!("wasm/runtime_call_caller.wast"),
+    );
+
+    let callee_state = runtime.app_state(&callee).unwrap();
+
+    let calldata = encode_addr(callee.inner());
+    let bytes = testing::build_app_tx(0, &caller, "call_inc_and_fail", &calldata);
+    let gas_limit = MaybeGas::with(10_000_000);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, gas_limit);
+    assert!(!receipt.success);
+
+    // the changes committed by the (successful) callee have been discarded alongside the caller's
+    assert_eq!(runtime.app_state(&callee).unwrap(), callee_state);
+    assert_eq!(read_var64(&runtime, &callee, &callee_state), 0);
+
+    assert_eq!(runtime.app_state(&caller).unwrap(), state);
+    assert_eq!(read_var64(&runtime, &caller, &state), 0);
+}
+
+#[test]
+fn default_runtime_retention_keep_last() {
+    let fake_kv = Rc::new(RefCell::new(FakeKV::new()));
+    let state_kv: Rc<RefCell<dyn StatefulKV>> = fake_kv.clone();
+    let imports = Box::leak(Box::new(Vec::new()));
+    let config = Config::new("mem").with_retention(Retention::KeepLast(2));
+
+    let mut runtime = testing::create_memory_runtime_with_config(&state_kv, imports, config);
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, mut state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    for _ in 0..10 {
+        let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+        assert!(receipt.success);
+
+        state = receipt.get_new_state().clone();
+
+        // only the states as of the last two transactions are kept
+        assert!(fake_kv.borrow().flushed_count() <= 2);
+    }
+
+    assert_eq!(read_var64(&runtime, &app_addr, &state), 10);
+}
+
+#[test]
+fn default_runtime_retention_keeps_the_rewound_state() {
+    let fake_kv = Rc::new(RefCell::new(FakeKV::new()));
+    let state_kv: Rc<RefCell<dyn StatefulKV>> = fake_kv.clone();
+    let imports = Box::leak(Box::new(Vec::new()));
+    let config = Config::new("mem").with_retention(Retention::KeepLast(1));
+
+    let mut runtime = testing::create_memory_runtime_with_config(&state_kv, imports, config);
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let (callee, _) = deploy_and_spawn(
+        &mut runtime,
+        "Callee",
+        include_str!("wasm/runtime_call_callee.wast"),
+    );
+    let (caller, state) = deploy_and_spawn(
+        &mut runtime,
+        "Caller",
+        include_str!("wasm/runtime_call_caller.wast"),
+    );
+
+    let callee_state = runtime.app_state(&callee).unwrap();
+
+    let calldata = encode_addr(callee.inner());
+    let gas_limit = MaybeGas::with(10_000_000);
+
+    // the callee commits (and flushes) its changes before the caller fails,
+    // so the transaction is rewound to the (retained) state it has started with
+    let bytes = testing::build_app_tx(0, &caller, "call_inc_and_fail", &calldata);
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, gas_limit);
+    assert!(!receipt.success);
+
+    assert_eq!(runtime.app_state(&callee).unwrap(), callee_state);
+    assert_eq!(read_var64(&runtime, &callee, &callee_state), 0);
+    assert_eq!(fake_kv.borrow().flushed_count(), 1);
+
+    let bytes = testing::build_app_tx(0, &callee, "inc", &vec![]);
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &callee_state, gas_limit);
+    assert!(receipt.success);
+
+    assert_eq!(read_var64(&runtime, &callee, receipt.get_new_state()), 1);
+    assert_eq!(fake_kv.borrow().flushed_count(), 1);
+}
+
+#[test]
+fn default_runtime_exec_app_call_reentrancy_fails() {
+    let mut runtime = default_runtime!();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let (caller, state) = deploy_and_spawn(
+        &mut runtime,
+        "Caller",
+        include_str!("wasm/runtime_call_caller.wast"),
+    );
+
+    // the caller calls itself
+    let calldata = encode_addr(caller.inner());
+    let bytes = testing::build_app_tx(0, &caller, "call_inc", &calldata);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(receipt.success);
+
+    let state = receipt.get_new_state();
+    assert_eq!(read_var64(&runtime, &caller, state), 0);
+}
+
+#[test]
+fn default_runtime_exec_app_call_app_not_found() {
+    let mut runtime = default_runtime!();
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+
+    let (caller, state) = deploy_and_spawn(
+        &mut runtime,
+        "Caller",
+        include_str!("wasm/runtime_call_caller.wast"),
+    );
+
+    let calldata = encode_addr(&Address::of("no-such-app"));
+    let bytes = testing::build_app_tx(0, &caller, "call_inc", &calldata);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(receipt.success);
+
+    let state = receipt.get_new_state();
+    assert_eq!(read_var64(&runtime, &caller, state), 0);
+}
+
+#[test]
+fn default_runtime_exec_app_storage_changes_not_collected_by_default() {
+    let mut runtime = default_runtime!();
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(receipt.success);
+    assert!(receipt.get_storage_changes().is_none());
+}
+
+#[test]
+fn default_runtime_exec_app_collects_storage_changes() {
+    let state_kv = testing::memory_state_kv_init();
+    let imports = Box::leak(Box::new(Vec::new()));
+    let config = Config::new("mem").with_storage_changes(true);
+
+    let mut runtime = testing::create_memory_runtime_with_config(&state_kv, imports, config);
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(receipt.success);
+
+    assert_eq!(
+        receipt.get_storage_changes().unwrap(),
+        &[StorageChange {
+            var_id: 0,
+            old_value: 0u64.to_le_bytes().to_vec(),
+            new_value: 1u64.to_le_bytes().to_vec(),
+        }]
+    );
+
+    // a failing transaction has no storage changes
+    let bytes = testing::build_app_tx(0, &app_addr, "no-such-func", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(!receipt.success);
+    assert!(receipt.get_storage_changes().is_none());
+}
+
+#[test]
+fn default_runtime_storage_usage() {
+    let mut runtime = default_runtime!();
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, init_state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let usage = runtime.storage_usage(&app_addr, &init_state).unwrap();
+    assert_eq!(usage, StorageUsage::default());
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, MaybeGas::new());
+    assert!(receipt.success);
+
+    // var #0 (8 bytes) lives in page #0
+    let state = receipt.get_new_state();
+    let usage = runtime.storage_usage(&app_addr, state).unwrap();
+
+    assert_eq!(
+        usage,
+        StorageUsage {
+            pages: 1,
+            entries: 0,
+            bytes: 32
+        }
+    );
+
+    let unknown_app = Address::of("unknown-app").into();
+    assert!(runtime.storage_usage(&unknown_app, state).is_none());
+}
+
+#[test]
+fn default_runtime_exec_app_unknown_state() {
+    let mut runtime = default_runtime!();
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, init_state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, MaybeGas::new());
+    assert!(receipt.success);
+
+    let state = receipt.get_new_state().clone();
+
+    // the app is no longer at its `init_state`
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, MaybeGas::new());
+    assert!(!receipt.success);
+    assert_eq!(
+        receipt.get_error(),
+        &ReceiptError::UnknownState {
+            app_addr: app_addr.clone(),
+            state: init_state.clone(),
+        }
+    );
+
+    assert!(runtime.storage_usage(&app_addr, &init_state).is_none());
+
+    let wasm = include_str!("wasm/runtime_migrated_app.wast");
+    let template_addr = deploy_template(&mut runtime, "Migrated", vec![4, 8].into(), wasm);
+    let mapping = [(VarId(0), VarId(1))];
+
+    let err = runtime.migrate_app(&app_addr, &init_state, &template_addr, &mapping);
+    assert_eq!(err, Err(MigrateError::UnknownState(init_state)));
+
+    // the app storage is left untouched
+    assert_eq!(read_var64(&runtime, &app_addr, &state), 1);
+}
+
+#[test]
+fn default_runtime_exec_app_storage_limit_exceeded() {
+    let state_kv = testing::memory_state_kv_init();
+    let imports = Box::leak(Box::new(Vec::new()));
+    let config = Config::new("mem").with_max_storage_size(16);
+
+    let mut runtime = testing::create_memory_runtime_with_config(&state_kv, imports, config);
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &state, MaybeGas::new());
+    assert!(!receipt.success);
+
+    // var #0 (8 bytes) would make page #0 non-empty (32 bytes)
+    match receipt.get_error() {
+        ReceiptError::StorageLimitExceeded {
+            app_addr: addr,
+            size: 32,
+            limit: 16,
+            ..
+        } => assert_eq!(addr, &app_addr),
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    // the app storage is left untouched
+    assert_eq!(read_var64(&runtime, &app_addr, &state), 0);
+}
+
+#[test]
+fn default_runtime_migrate_app() {
+    let mut runtime = default_runtime!();
+
+    let wasm = include_str!("wasm/runtime_exec_app.wast");
+    let (app_addr, init_state) = deploy_and_spawn(&mut runtime, "My App", wasm);
+
+    let host_ctx = testing::host_ctx_with_sender(&Address::of("sender"));
+    let bytes = testing::build_app_tx(0, &app_addr, "add", &vec![]);
+
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &init_state, MaybeGas::new());
+    let state = receipt.get_new_state().clone();
+
+    // var #0 would lose bytes
+    // (template addresses are derived from the code, so a different module is deployed)
+    let wasm = include_str!("wasm/runtime_app_ctor.wast");
+    let layout: DataLayout = vec![4].into();
+    let template_addr = deploy_template(&mut runtime, "Truncated", layout, wasm);
+
+    let err = runtime.migrate_app(&app_addr, &state, &template_addr, &[(VarId(0), VarId(0))]);
+
+    assert_eq!(
+        err,
+        Err(MigrateError::Layout(MigrationError::VarTruncated {
+            old_var: VarId(0),
+            new_var: VarId(0),
+            old_len: 8,
+            new_len: 4,
+        }))
+    );
+
+    let unknown_template = Address::of("unknown-template").into();
+    let err = runtime.migrate_app(&app_addr, &state, &unknown_template, &[]);
+
+    assert_eq!(
+        err,
+        Err(MigrateError::TemplateNotFound(unknown_template.clone()))
+    );
+
+    let unknown_app = Address::of("unknown-app").into();
+    let err = runtime.migrate_app(&unknown_app, &state, &template_addr, &[]);
+
+    assert_eq!(err, Err(MigrateError::AppNotFound(unknown_app)));
+
+    // the template's var #0 (8 bytes) moves to var #1
+    let wasm = include_str!("wasm/runtime_migrated_app.wast");
+    let new_layout: DataLayout = vec![4, 8].into();
+    let template_addr = deploy_template(&mut runtime, "Migrated", new_layout.clone(), wasm);
+    let mapping = [(VarId(0), VarId(1))];
+
+    let new_state = runtime
+        .migrate_app(&app_addr, &state, &template_addr, &mapping)
+        .unwrap();
+
+    let storage = runtime
+        .open_app_storage(&app_addr, &new_state, &new_layout)
+        .unwrap();
+
+    assert_eq!(storage.read_var(VarId(0)), vec![0; 4]);
+    assert_eq!(storage.read_var(VarId(1)), 1u64.to_le_bytes().to_vec());
+
+    // executing the migrated app runs the new template (incrementing var #1)
+    let receipt = runtime.exec_app(&bytes, &host_ctx, &new_state, MaybeGas::new());
+    assert!(receipt.success);
+
+    let storage = runtime
+        .open_app_storage(&app_addr, receipt.get_new_state(), &new_layout)
+        .unwrap();
+
+    assert_eq!(storage.read_var(VarId(0)), vec![0; 4]);
+    assert_eq!(storage.read_var(VarId(1)), 2u64.to_le_bytes().to_vec());
+}
+
+fn deploy_template(
+    runtime: &mut MemoryRuntime,
+    name: &str,
+    layout: DataLayout,
+    wasm: &str,
+) -> TemplateAddr {
+    let version = 0;
+    let author = Address::of("author").into();
+    let ctors = vec!["ctor".to_string()];
+
+    let bytes = testing::build_template(version, name, layout, &ctors, wasm.into());
+    let receipt = runtime.deploy_template(&bytes, &author, &HostCtx::new(), MaybeGas::new());
+    assert!(receipt.success);
+
+    receipt.addr.unwrap()
+}
+
+fn deploy_and_spawn(runtime: &mut MemoryRuntime, name: &str, wasm: &str) -> (AppAddr, State) {
+    let version = 0;
+    let creator = Address::of("creator").into();
+    let maybe_gas = MaybeGas::new();
+    let layout: DataLayout = vec![8].into();
+
+    let template_addr = deploy_template(runtime, name, layout, wasm);
+    let calldata = vec![];
+
+    let bytes = testing::build_app(version, &template_addr, name, "ctor", &calldata);
+    let receipt = runtime.spawn_app(&bytes, &creator, &HostCtx::new(), maybe_gas);
+    assert!(receipt.success);
+
+    (
+        receipt.get_app_addr().clone(),
+        receipt.get_init_state().clone(),
+    )
+}
+
+fn encode_addr(addr: &Address) -> Vec<u8> {
+    let addr: sdk::Address = addr.as_slice().to_vec().into();
+
+    let mut calldata = Vec::new();
+    addr.encode(&mut calldata);
+
+    calldata
+}
+
+fn read_var64(runtime: &MemoryRuntime, app_addr: &AppAddr, state: &State) -> u64 {
+    let layout: DataLayout = vec![8].into();
+    let storage = runtime.open_app_storage(app_addr, state, &layout).unwrap();
+
+    let bytes = storage.read_var(VarId(0));
+
+    u64::from_le_bytes([
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+    ])
 }
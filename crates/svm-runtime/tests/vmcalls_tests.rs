@@ -6,8 +6,8 @@ use std::ffi::c_void;
 use wasmer::{imports, Function, NativeFunc};
 
 use svm_layout::{DataLayout, VarId};
-use svm_runtime::{testing, vmcalls, Context};
-use svm_types::{gas::MaybeGas, receipt::Log, Address};
+use svm_runtime::{gas::VMCallsPricing, testing, vmcalls, Context};
+use svm_types::{gas::MaybeGas, receipt::Log, Address, HostCtx};
 
 macro_rules! assert_vars32 {
     ($instance:expr, $( $var_id:expr => $expected:expr), *) => {{
@@ -126,6 +126,60 @@ fn vmcalls_get32_set32() {
     assert_storage!(ctx, 0 => [5, 0, 0, 0], 1 => [10, 0]);
 }
 
+#[test]
+fn vmcalls_savepoint_rollback_to_release() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::new();
+    let layout: DataLayout = vec![4, 2].into();
+
+    let store = testing::wasmer_store();
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new(gas_limit, storage);
+
+    let import_object = imports! {
+        "svm" => {
+            "svm_get32" => func!(store, ctx, vmcalls::get32),
+            "svm_set32" => func!(store, ctx, vmcalls::set32),
+            "svm_savepoint" => func!(store, ctx, vmcalls::savepoint),
+            "svm_rollback_to" => func!(store, ctx, vmcalls::rollback_to),
+            "svm_release" => func!(store, ctx, vmcalls::release),
+        }
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/savepoint.wast").into(),
+        gas_limit,
+    );
+
+    let set: NativeFunc<(u32, u32)> = instance.exports.get_native_function("set").unwrap();
+    let savepoint: NativeFunc<(), u32> = instance.exports.get_native_function("savepoint").unwrap();
+    let rollback_to: NativeFunc<u32> = instance.exports.get_native_function("rollback_to").unwrap();
+    let release: NativeFunc<u32> = instance.exports.get_native_function("release").unwrap();
+
+    set.call(0, 5).unwrap();
+
+    let outer = savepoint.call().unwrap();
+    set.call(0, 6).unwrap();
+
+    let inner = savepoint.call().unwrap();
+    set.call(1, 10).unwrap();
+
+    assert_vars32!(instance, 0 => 6, 1 => 10);
+
+    rollback_to.call(inner).unwrap();
+    assert_vars32!(instance, 0 => 6, 1 => 0);
+
+    // savepoint `inner` isn't open anymore
+    assert!(rollback_to.call(inner).is_err());
+
+    release.call(outer).unwrap();
+    assert_vars32!(instance, 0 => 6, 1 => 0);
+
+    assert_storage!(ctx, 0 => [6, 0, 0, 0], 1 => [0, 0]);
+}
+
 #[test]
 fn vmcalls_get64_set64() {
     let app_addr = Address::of("my-app");
@@ -289,3 +343,342 @@ fn vmcalls_log() {
         }]
     );
 }
+
+#[test]
+fn vmcalls_get64_set64_use_gas() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::with(1000);
+    let layout: DataLayout = vec![4, 2].into();
+
+    let store = testing::wasmer_store();
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new(gas_limit, storage);
+
+    let import_object = imports! {
+        "svm" => {
+            "svm_get64" => func!(store, ctx, vmcalls::get64),
+            "svm_set64" => func!(store, ctx, vmcalls::set64),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/get64_set64.wast").into(),
+        gas_limit,
+    );
+
+    var_add64!(instance, 0, 5);
+
+    let pricing = VMCallsPricing::default();
+    assert_eq!(ctx.borrow().gas_used, pricing.get64 + pricing.set64);
+}
+
+#[test]
+fn vmcalls_log_price_scales_with_msg_len() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::with(1000);
+    let layout = DataLayout::empty();
+
+    let store = testing::wasmer_store();
+    let memory = testing::wasmer_memory(&store);
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new_with_memory(memory.clone(), gas_limit, storage);
+
+    let pricing = VMCallsPricing {
+        log: 100,
+        log_per_byte: 10,
+        ..VMCallsPricing::default()
+    };
+    ctx.borrow_mut().set_pricing(pricing);
+
+    let import_object = imports! {
+        "svm" => {
+            "memory" => memory.clone(),
+            "svm_log" => func!(store, ctx, vmcalls::log),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/log.wast").into(),
+        gas_limit,
+    );
+
+    let func = instance.exports.get_function("sayHello").unwrap();
+    let _ = func.call(&[]).unwrap();
+
+    // `msg_len = len('Hello World') = 11`
+    assert_eq!(ctx.borrow().gas_used, 100 + 11 * 10);
+}
+
+#[test]
+fn vmcalls_reaches_oog() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::with(25);
+    let layout: DataLayout = vec![4, 2].into();
+
+    let store = testing::wasmer_store();
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new(gas_limit, storage);
+
+    let import_object = imports! {
+        "svm" => {
+            "svm_get64" => func!(store, ctx, vmcalls::get64),
+            "svm_set64" => func!(store, ctx, vmcalls::set64),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/get64_set64.wast").into(),
+        gas_limit,
+    );
+
+    // `get64` + `set64` cost 30 gas units by default
+    let func: NativeFunc<(u32, u64), ()> = instance.exports.get_native_function("add").unwrap();
+    let res = func.call(0, 5);
+
+    assert!(res.is_err());
+    assert!(ctx.borrow().gas_used > 25);
+    assert_storage!(ctx, 0 => [0, 0, 0, 0]);
+}
+
+fn get64_set64_instance(gas_limit: u64, shared_budget: bool) -> (Context, wasmer::Instance) {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::with(gas_limit);
+    let layout: DataLayout = vec![4, 2].into();
+
+    let store = testing::wasmer_store();
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new(gas_limit, storage);
+
+    let import_object = imports! {
+        "svm" => {
+            "svm_get64" => func!(store, ctx, vmcalls::get64),
+            "svm_set64" => func!(store, ctx, vmcalls::set64),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/get64_set64.wast").into(),
+        gas_limit,
+    );
+
+    if shared_budget {
+        let global = svm_compiler::gas_left_global(&instance);
+        ctx.borrow_mut().set_gas_left(global);
+    }
+
+    (ctx, instance)
+}
+
+#[test]
+fn vmcalls_share_gas_left_with_wasm() {
+    let pricing = VMCallsPricing::default();
+    let vmcalls_gas = pricing.get64 + pricing.set64;
+
+    // measuring the gas used by the Wasm instructions alone
+    let (_ctx, instance) = get64_set64_instance(1000, false);
+    var_add64!(instance, 0, 5);
+    let wasm_gas = 1000 - svm_compiler::gas_left(&instance).unwrap();
+
+    let (ctx, instance) = get64_set64_instance(1000, true);
+    var_add64!(instance, 0, 5);
+
+    let gas_left = svm_compiler::gas_left(&instance).unwrap();
+    assert_eq!(gas_left, 1000 - wasm_gas - vmcalls_gas);
+    assert_eq!(ctx.borrow().gas_left(), gas_left);
+    assert_eq!(ctx.borrow().gas_used, vmcalls_gas);
+}
+
+#[test]
+fn vmcalls_and_wasm_exceeding_gas_limit_together_reaches_oog() {
+    let pricing = VMCallsPricing::default();
+    let vmcalls_gas = pricing.get64 + pricing.set64;
+
+    let (_ctx, instance) = get64_set64_instance(1000, false);
+    var_add64!(instance, 0, 5);
+    let wasm_gas = 1000 - svm_compiler::gas_left(&instance).unwrap();
+
+    // each of the Wasm instructions and the vmcalls fits the limit alone, but not both
+    let gas_limit = wasm_gas + vmcalls_gas - 1;
+    assert!(wasm_gas <= gas_limit && vmcalls_gas <= gas_limit);
+
+    let (ctx, instance) = get64_set64_instance(gas_limit, true);
+
+    let func: NativeFunc<(u32, u64), ()> = instance.exports.get_native_function("add").unwrap();
+    let res = func.call(0, 5);
+
+    assert!(res.is_err());
+    assert_storage!(ctx, 0 => [0, 0, 0, 0]);
+}
+
+#[test]
+fn vmcalls_host_get() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::new();
+    let layout = DataLayout::empty();
+
+    let store = testing::wasmer_store();
+    let memory = testing::wasmer_memory(&store);
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new_with_memory(memory.clone(), gas_limit, storage);
+
+    let mut host_ctx = HostCtx::new();
+    host_ctx.set(3, 10u64.to_le_bytes().to_vec());
+    host_ctx.set(5, 20u64.to_le_bytes().to_vec());
+    ctx.borrow_mut().set_host_ctx(host_ctx);
+
+    let import_object = imports! {
+        "svm" => {
+            "memory" => memory.clone(),
+            "svm_host_get_len" => func!(store, ctx, vmcalls::host_get_len),
+            "svm_host_get" => func!(store, ctx, vmcalls::host_get),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/host_get.wast").into(),
+        gas_limit,
+    );
+
+    let get_len: NativeFunc<u32, u32> = instance
+        .exports
+        .get_native_function("get_host_ctx_len")
+        .unwrap();
+
+    assert_eq!(get_len.call(3).unwrap(), 8);
+    assert_eq!(get_len.call(4).unwrap(), 0);
+
+    assert_host_ctx!(instance, 3 => 10, 4 => 0, 5 => 20);
+}
+
+#[test]
+fn vmcalls_map_get_map_set() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::new();
+
+    // `var #0` is a map
+    let layout: DataLayout = vec![4].into();
+
+    let store = testing::wasmer_store();
+    let memory = testing::wasmer_memory(&store);
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new_with_memory(memory.clone(), gas_limit, storage);
+
+    let import_object = imports! {
+        "svm" => {
+            "memory" => memory.clone(),
+            "svm_map_get" => func!(store, ctx, vmcalls::map_get),
+            "svm_map_set" => func!(store, ctx, vmcalls::map_set),
+            "svm_vec_len" => func!(store, ctx, vmcalls::vec_len),
+            "svm_vec_get" => func!(store, ctx, vmcalls::vec_get),
+            "svm_vec_push" => func!(store, ctx, vmcalls::vec_push),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/map_vec.wast").into(),
+        gas_limit,
+    );
+
+    // key: memory cells `[0, 5)`, value: memory cells `[10, 13)`
+    let view = memory.view::<u8>();
+    for (cell, byte) in view[0..5].iter().zip(b"alice".iter()) {
+        cell.set(*byte);
+    }
+    for (cell, byte) in view[10..13].iter().zip([10, 20, 30].iter()) {
+        cell.set(*byte);
+    }
+
+    let map_get: NativeFunc<(u32, u32, u32, u32, u32), u32> =
+        instance.exports.get_native_function("map_get").unwrap();
+    let map_set: NativeFunc<(u32, u32, u32, u32, u32), ()> =
+        instance.exports.get_native_function("map_set").unwrap();
+
+    // there is no entry for `alice` yet
+    assert_eq!(map_get.call(0, 0, 5, 20, 4).unwrap(), 0);
+
+    map_set.call(0, 0, 5, 10, 3).unwrap();
+
+    // the value is padded with zeros
+    assert_eq!(map_get.call(0, 0, 5, 20, 4).unwrap(), 3);
+
+    let value: Vec<u8> = view[20..24].iter().map(|cell| cell.get()).collect();
+    assert_eq!(value, vec![10, 20, 30, 0]);
+
+    let entry = ctx.borrow().storage.map_get(VarId(0), b"alice");
+    assert_eq!(entry, Some(vec![10, 20, 30]));
+
+    // accessing a map variable which isn't part of the layout
+    assert!(map_set.call(1, 0, 5, 10, 3).is_err());
+}
+
+#[test]
+fn vmcalls_vec_push_vec_get() {
+    let app_addr = Address::of("my-app");
+    let gas_limit = MaybeGas::new();
+
+    // `var #0` is a vector (holding its length)
+    let layout: DataLayout = vec![4].into();
+
+    let store = testing::wasmer_store();
+    let memory = testing::wasmer_memory(&store);
+    let storage = testing::blank_storage(&app_addr, &layout);
+    let ctx = Context::new_with_memory(memory.clone(), gas_limit, storage);
+
+    let import_object = imports! {
+        "svm" => {
+            "memory" => memory.clone(),
+            "svm_map_get" => func!(store, ctx, vmcalls::map_get),
+            "svm_map_set" => func!(store, ctx, vmcalls::map_set),
+            "svm_vec_len" => func!(store, ctx, vmcalls::vec_len),
+            "svm_vec_get" => func!(store, ctx, vmcalls::vec_get),
+            "svm_vec_push" => func!(store, ctx, vmcalls::vec_push),
+        },
+    };
+
+    let instance = testing::wasmer_instantiate(
+        &store,
+        &import_object,
+        include_str!("wasm/map_vec.wast").into(),
+        gas_limit,
+    );
+
+    let view = memory.view::<u8>();
+    for (cell, byte) in view[0..4].iter().zip([10, 20, 30, 40].iter()) {
+        cell.set(*byte);
+    }
+
+    let vec_len: NativeFunc<u32, u32> = instance.exports.get_native_function("vec_len").unwrap();
+    let vec_get: NativeFunc<(u32, u32, u32, u32), u32> =
+        instance.exports.get_native_function("vec_get").unwrap();
+    let vec_push: NativeFunc<(u32, u32, u32), u32> =
+        instance.exports.get_native_function("vec_push").unwrap();
+
+    assert_eq!(vec_len.call(0).unwrap(), 0);
+
+    assert_eq!(vec_push.call(0, 0, 2).unwrap(), 0);
+    assert_eq!(vec_push.call(0, 2, 2).unwrap(), 1);
+
+    assert_eq!(vec_len.call(0).unwrap(), 2);
+
+    assert_eq!(vec_get.call(0, 1, 10, 2).unwrap(), 2);
+
+    let value: Vec<u8> = view[10..12].iter().map(|cell| cell.get()).collect();
+    assert_eq!(value, vec![30, 40]);
+
+    // the vector has no element `#2`
+    assert!(vec_get.call(0, 2, 10, 2).is_err());
+
+    assert_storage!(ctx, 0 => [2, 0, 0, 0]);
+}
@@ -0,0 +1,22 @@
+(module
+  (func $host_get_len (import "svm" "svm_host_get_len") (param $field i32) (result i32))
+  (func $host_get (import "svm" "svm_host_get") (param $field i32) (param $offset i32) (result i32))
+
+  (import "svm" "memory" (memory $mem 1))
+
+  (func (export "get_host_ctx_len") (param $field i32) (result i32)
+	local.get $field
+	call $host_get_len)
+
+  ;; copies field `field` into memory offset `0` and loads it (as a Little-Endian `i64`)
+  ;; returns `0` if the field is missing
+  (func (export "get_host_ctx") (param $field i32) (result i64)
+	i64.const 0
+	local.get $field
+	i32.const 0 ;; offset
+	call $host_get
+	i32.eqz
+	br_if 0
+	drop
+	i32.const 0
+	i64.load))
@@ -0,0 +1,41 @@
+(module
+  (func $map_get (import "svm" "svm_map_get") (param i32 i32 i32 i32 i32) (result i32))
+  (func $map_set (import "svm" "svm_map_set") (param i32 i32 i32 i32 i32))
+  (func $vec_len (import "svm" "svm_vec_len") (param i32) (result i32))
+  (func $vec_get (import "svm" "svm_vec_get") (param i32 i32 i32 i32) (result i32))
+  (func $vec_push (import "svm" "svm_vec_push") (param i32 i32 i32) (result i32))
+
+  (import "svm" "memory" (memory 1))
+
+  (func (export "map_get") (param $var_id i32) (param $key_ptr i32) (param $key_len i32) (param $val_ptr i32) (param $val_len i32) (result i32)
+	get_local $var_id
+	get_local $key_ptr
+	get_local $key_len
+	get_local $val_ptr
+	get_local $val_len
+	call $map_get)
+
+  (func (export "map_set") (param $var_id i32) (param $key_ptr i32) (param $key_len i32) (param $val_ptr i32) (param $val_len i32)
+	get_local $var_id
+	get_local $key_ptr
+	get_local $key_len
+	get_local $val_ptr
+	get_local $val_len
+	call $map_set)
+
+  (func (export "vec_len") (param $var_id i32) (result i32)
+	get_local $var_id
+	call $vec_len)
+
+  (func (export "vec_get") (param $var_id i32) (param $index i32) (param $val_ptr i32) (param $val_len i32) (result i32)
+	get_local $var_id
+	get_local $index
+	get_local $val_ptr
+	get_local $val_len
+	call $vec_get)
+
+  (func (export "vec_push") (param $var_id i32) (param $val_ptr i32) (param $val_len i32) (result i32)
+	get_local $var_id
+	get_local $val_ptr
+	get_local $val_len
+	call $vec_push))
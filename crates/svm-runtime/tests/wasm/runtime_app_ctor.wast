@@ -1,9 +1,12 @@
 (module
   (func $set64 (import "svm" "svm_set64") (param i32 i64))
 
-  (memory 1)  ;; memory `0` (default) is initialized with one page
+  (memory (export "memory") 1)  ;; memory `0` (default) is initialized with one page
 
-  (func (export "ctor") (param i64)
-    i32.const 0  ;; var_id = 0
-    get_local 0  ;; var's value 
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 0)
+
+  (func (export "ctor")
+    i32.const 0                  ;; var_id = 0
+    i64.const 1020304050607080   ;; var's value
     call $set64))
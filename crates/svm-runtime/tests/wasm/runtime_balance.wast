@@ -0,0 +1,34 @@
+(module
+  (func $calldata_offset (import "svm" "svm_calldata_offset") (result i32))
+  (func $set_returndata (import "svm" "svm_set_returndata") (param i32 i32))
+  (func $value (import "svm" "svm_value") (result i64))
+  (func $balance (import "svm" "svm_balance") (param i32) (result i64))
+  (func $transfer (import "svm" "svm_transfer") (param i32 i64) (result i32))
+
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 1024)
+
+  (func (export "ctor")
+    nop)
+
+  ;; returns the transaction `value`
+  (func (export "get_value")
+    (i64.store (i32.const 0) (call $value))
+    (call $set_returndata (i32.const 0) (i32.const 8)))
+
+  ;; returns the balance of the account given as `calldata`
+  (func (export "get_balance")
+    (i64.store (i32.const 0) (call $balance (call $calldata_offset)))
+    (call $set_returndata (i32.const 0) (i32.const 8)))
+
+  ;; transfers 30 coins to the account given as `calldata` and returns whether it has succeeded
+  (func (export "transfer")
+    (i32.store (i32.const 0) (call $transfer (call $calldata_offset) (i64.const 30)))
+    (call $set_returndata (i32.const 0) (i32.const 4)))
+
+  ;; transfers 30 coins to the account given as `calldata` and then traps
+  (func (export "transfer_and_fail")
+    (drop (call $transfer (call $calldata_offset) (i64.const 30)))
+    unreachable))
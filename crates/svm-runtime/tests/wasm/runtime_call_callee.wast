@@ -0,0 +1,33 @@
+(module
+  (func $get64 (import "svm" "svm_get64") (param i32) (result i64))
+  (func $set64 (import "svm" "svm_set64") (param i32 i64))
+  (func $set_returndata (import "svm" "svm_set_returndata") (param i32 i32))
+  (func $log (import "svm" "svm_log") (param i32 i32 i32))
+
+  (memory (export "memory") 1)
+
+  (data (i32.const 0) "inc")
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 1024)
+
+  (func (export "ctor")
+    nop)
+
+  (func $inc
+    i32.const 0
+    (i64.add (call $get64 (i32.const 0)) (i64.const 1))
+    call $set64)
+
+  ;; increments var #0 and returns its new value
+  (func (export "inc")
+    call $inc
+
+    (i64.store (i32.const 16) (call $get64 (i32.const 0)))
+    (call $log (i32.const 0) (i32.const 3) (i32.const 1))
+    (call $set_returndata (i32.const 16) (i32.const 8)))
+
+  ;; increments var #0 and then traps
+  (func (export "fail")
+    call $inc
+    unreachable))
@@ -0,0 +1,49 @@
+(module
+  (func $calldata_offset (import "svm" "svm_calldata_offset") (result i32))
+  (func $call (import "svm" "svm_call") (param i32 i32 i32 i32 i32 i64) (result i32))
+  (func $call_returndata_len (import "svm" "svm_call_returndata_len") (result i32))
+  (func $call_returndata_copy (import "svm" "svm_call_returndata_copy") (param i32))
+  (func $set_returndata (import "svm" "svm_set_returndata") (param i32 i32))
+  (func $set64 (import "svm" "svm_set64") (param i32 i64))
+
+  (memory (export "memory") 1)
+
+  (data (i32.const 0) "inc")
+  (data (i32.const 16) "fail")
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 1024)
+
+  (func (export "ctor")
+    nop)
+
+  ;; calls function `inc` of the app whose (ABI-encoded) address is given as the `calldata`
+  (func (export "call_inc")
+    (call $do_call (i32.const 0) (i32.const 3)))
+
+  ;; calls function `fail` of the app whose (ABI-encoded) address is given as the `calldata`
+  (func (export "call_fail")
+    (call $do_call (i32.const 16) (i32.const 4)))
+
+  ;; calls function `inc` of the app whose (ABI-encoded) address is given as the `calldata` and then traps
+  (func (export "call_inc_and_fail")
+    (call $do_call (i32.const 0) (i32.const 3))
+    unreachable)
+
+  (func $do_call (param $func_ptr i32) (param $func_len i32)
+    ;; storing the call status under var #0
+    i32.const 0
+    ;; skipping the ABI `Address` marker byte
+    (call $call
+      (i32.add (call $calldata_offset) (i32.const 1))
+      (local.get $func_ptr)
+      (local.get $func_len)
+      (i32.const 0)
+      (i32.const 0)
+      (i64.const 1000000))
+    i64.extend_i32_u
+    call $set64
+
+    ;; returning the called app `returndata`
+    (call $call_returndata_copy (i32.const 2048))
+    (call $set_returndata (i32.const 2048) (call $call_returndata_len))))
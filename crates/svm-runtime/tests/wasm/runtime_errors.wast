@@ -0,0 +1,29 @@
+(module
+  (func $get64 (import "svm" "svm_get64") (param i32) (result i64))
+  (func $set64 (import "svm" "svm_set64") (param i32 i64))
+  (func $set_returndata (import "svm" "svm_set_returndata") (param i32 i32))
+  (func $log (import "svm" "svm_log") (param i32 i32 i32))
+
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 1024)
+
+  (func (export "ctor")
+    nop)
+
+  ;; reads variable #5 (the layout has a single variable)
+  (func (export "invalid_var")
+    (drop (call $get64 (i32.const 5))))
+
+  ;; sets variable #0 (of 2 bytes) to a value requiring 3 bytes
+  (func (export "value_overflow")
+    (call $set64 (i32.const 0) (i64.const 0x10000)))
+
+  ;; sets a `returndata` exceeding the memory (of a single page)
+  (func (export "returndata_out_of_bounds")
+    (call $set_returndata (i32.const 65530) (i32.const 10)))
+
+  ;; logs a message exceeding the memory (of a single page)
+  (func (export "log_out_of_bounds")
+    (call $log (i32.const 65530) (i32.const 10) (i32.const 0))))
@@ -0,0 +1,21 @@
+(module
+  (func $calldata_len (import "svm" "svm_calldata_len") (result i32))
+  (func $set64 (import "svm" "svm_set64") (param i32 i64))
+
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 0)
+
+  (func (export "ctor")
+  	nop)
+
+  (func (export "run")
+    ;; when the `calldata` is empty, var #0 is set to `1`
+    call $calldata_len
+    i32.eqz
+    if
+      i32.const 0  ;; var_id = 0
+      i64.const 1
+      call $set64
+    end))
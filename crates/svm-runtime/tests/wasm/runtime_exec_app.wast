@@ -2,22 +2,25 @@
   (func $get64 (import "svm" "svm_get64") (param i32) (result i64))
   (func $set64 (import "svm" "svm_set64") (param i32 i64))
 
-  (import "svm" "memory" (memory 1))
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 0)
 
   (func (export "ctor")
   	nop)
 
-  (func (export "add") (param i64)
+  (func (export "add")
     ;; push var_id = 0 for later `$set64` usage
-    i32.const 0  
+    i32.const 0
 
     ;; read var #0
     i32.const 0  ;; var_id = 0
     call $get64
 
     ;; calculate var #0 new value
-    get_local 0
+    i64.const 1
     i64.add
 
     ;; store var #0 new value
-    call $set64))
\ No newline at end of file
+    call $set64))
@@ -0,0 +1,17 @@
+(module
+  (func $host_get_len (import "svm" "svm_host_get_len") (param i32) (result i32))
+  (func $host_get (import "svm" "svm_host_get") (param i32 i32) (result i32))
+  (func $set_returndata (import "svm" "svm_set_returndata") (param i32 i32))
+
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 1024)
+
+  (func (export "ctor")
+    nop)
+
+  ;; returns the `sender` field (#0) of the `HostCtx`
+  (func (export "get_sender")
+    (drop (call $host_get (i32.const 0) (i32.const 0)))
+    (call $set_returndata (i32.const 0) (call $host_get_len (i32.const 0)))))
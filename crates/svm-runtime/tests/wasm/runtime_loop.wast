@@ -0,0 +1,22 @@
+(module
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 0)
+
+  (func (export "ctor")
+  	nop)
+
+  (func (export "run")
+    (local $i i32)
+
+    ;; iterating 10 times
+    i32.const 10
+    local.set $i
+
+    (loop $loop
+      local.get $i
+      i32.const 1
+      i32.sub
+      local.tee $i
+      br_if $loop)))
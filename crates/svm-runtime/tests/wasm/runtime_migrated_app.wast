@@ -0,0 +1,26 @@
+(module
+  (func $get64 (import "svm" "svm_get64") (param i32) (result i64))
+  (func $set64 (import "svm" "svm_set64") (param i32 i64))
+
+  (memory (export "memory") 1)
+
+  (func (export "svm_alloc") (param i32) (result i32)
+    i32.const 0)
+
+  (func (export "ctor")
+  	nop)
+
+  (func (export "add")
+    ;; push var_id = 1 for later `$set64` usage
+    i32.const 1
+
+    ;; read var #1
+    i32.const 1  ;; var_id = 1
+    call $get64
+
+    ;; calculate var #1 new value
+    i64.const 1
+    i64.add
+
+    ;; store var #1 new value
+    call $set64))
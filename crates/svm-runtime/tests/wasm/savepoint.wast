@@ -0,0 +1,27 @@
+(module
+  ;; import `SVM` vmcalls
+  (func $get32 (import "svm" "svm_get32") (param i32) (result i32))
+  (func $set32 (import "svm" "svm_set32") (param i32 i32))
+  (func $savepoint (import "svm" "svm_savepoint") (result i32))
+  (func $rollback_to (import "svm" "svm_rollback_to") (param i32))
+  (func $release (import "svm" "svm_release") (param i32))
+
+  (func (export "get") (param $var_id i32) (result i32)
+	get_local $var_id
+	call $get32)
+
+  (func (export "set") (param $var_id i32) (param $value i32)
+	get_local $var_id
+	get_local $value
+	call $set32)
+
+  (func (export "savepoint") (result i32)
+	call $savepoint)
+
+  (func (export "rollback_to") (param $id i32)
+	get_local $id
+	call $rollback_to)
+
+  (func (export "release") (param $id i32)
+	get_local $id
+	call $release))
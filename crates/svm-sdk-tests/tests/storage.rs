@@ -37,4 +37,5 @@ fn storage_tests() {
     pass(&t, "tests/storage/i32_field.rs");
     pass(&t, "tests/storage/u64_field.rs");
     pass(&t, "tests/storage/i64_field.rs");
+    pass(&t, "tests/storage/map_field.rs");
 }
@@ -0,0 +1,45 @@
+use svm_sdk::{app, Address, Amount, Map};
+
+#[app]
+mod App {
+    #[storage]
+    struct Storage {
+        balances: Map<Address, Amount>,
+
+        flags: svm_sdk::Map<u32, bool>,
+
+        owners: Map<u64, Address>,
+    }
+}
+
+fn main() {
+    let zero_addr = Address::from([0; Address::len()]);
+    let ones_addr = Address::from([0xFF; Address::len()]);
+    let twos_addr = Address::from([0x22; Address::len()]);
+
+    // `balances`
+    let balance = Storage::get_balances(&ones_addr);
+    assert_eq!(balance, Amount(0));
+
+    Storage::set_balances(&ones_addr, Amount(10));
+    Storage::set_balances(&twos_addr, Amount(20));
+
+    assert_eq!(Storage::get_balances(&ones_addr), Amount(10));
+    assert_eq!(Storage::get_balances(&twos_addr), Amount(20));
+    assert_eq!(Storage::get_balances(&zero_addr), Amount(0));
+
+    // `flags`
+    assert_eq!(Storage::get_flags(1), false);
+
+    Storage::set_flags(1, true);
+
+    assert_eq!(Storage::get_flags(1), true);
+    assert_eq!(Storage::get_flags(2), false);
+
+    // `owners`
+    assert_eq!(Storage::get_owners(7), zero_addr);
+
+    Storage::set_owners(7, &ones_addr);
+
+    assert_eq!(Storage::get_owners(7), ones_addr);
+}
@@ -43,8 +43,37 @@ extern "C" {
     /// at memory offset `offset` (of byte-length `length`)
     /// and it's associated message code (for signaling errors severity such as `trace/info/error` etc.)
     fn svm_log(offset: u32, length: u32, code: u32);
+
+    /// Returns the `value` field of the current executed transaction.
+    fn svm_value() -> u64;
+
+    /// Receives an account address.
+    /// (The `Address::len()` bytes starting at memory offset `offset`)
+    ///
+    /// Returns the account balance.
+    fn svm_balance(offset: u32) -> u64;
+
+    /// Transfers `amount` coins from the current running `app` ("the source")
+    /// to the account ("the destination") which is address is starts offset `dst_offset` (`Address::len()` of bytes).
+    ///
+    /// Returns `1` on success and `0` when the `app` balance is less than `amount`.
+    fn svm_transfer(dst_offset: u32, amount: u64) -> u32;
+
+    /// Returns the byte-length of field `field` of the `Host Context` (`0` when missing).
+    fn svm_host_get_len(field: u32) -> u32;
+
+    /// Copies field `field` of the `Host Context` starting at memory offset `offset`.
+    ///
+    /// Returns `1` on success and `0` when the field is missing.
+    fn svm_host_get(field: u32, offset: u32) -> u32;
 }
 
+/// `Host Context` field holding the current executed transaction `sender` (`Address::len()` of bytes).
+const HOST_CTX_SENDER: u32 = 0;
+
+/// `Host Context` field holding the Spacemesh layer the current executed transaction is running at (Big-Endian `u64`).
+const HOST_CTX_LAYER_ID: u32 = 1;
+
 /// ## Spacemesh Imports
 ///
 /// WASM imports under namespace `sm` for SVM programs targeting Spacemesh Full-Node (i.e `go-spacemesh`).
@@ -54,31 +83,10 @@ extern "C" {
 /// should bring their own imports.
 #[link(wasm_import_module = "sm")]
 extern "C" {
-    /// Returns the `value` field of the current executed transaction.
-    fn sm_value() -> u64;
-
-    /// Receives an account address.
-    /// (The `Address::len()` bytes starting at memory offset `offset`)
-    ///
-    /// Returns the account balance.
-    fn sm_balance(offset: u32) -> u64;
-
-    /// Receives an offset to allocated `Address` (`Address::len()` of bytes).
-    /// The node will copy the address of the current executed transaction `sender`
-    /// starting at offset `offset`.
-    fn sm_sender(offset: u32);
-
     /// Receives an offset to allocated `Address` (`Address::len()` of bytes).
     /// The node will copy the address of the current executed transaction `app`
     /// starting at offset `offset`.
     fn sm_app(offset: u32);
-
-    /// Returns the Spacemesh layer the current executed transaction is running at.
-    fn sm_layer() -> u64;
-
-    /// Transfers `amount` coins from the current running `app` ("the source")
-    /// to the account ("the destination") which is address is starts offset `dst_offset` (`Address::len()` of bytes).
-    fn sm_transfer(dst_offset: u32, amount: u64);
 }
 
 /// Regarding why we don't use any concurrency primitives for initializing `HOST`
@@ -206,7 +214,7 @@ impl Host for InnerHost {
     #[inline]
     fn value(&self) -> Amount {
         unsafe {
-            let value = sm_value();
+            let value = svm_value();
 
             Amount(value)
         }
@@ -217,7 +225,8 @@ impl Host for InnerHost {
         unsafe {
             let offset = self.alloc_addr();
 
-            sm_sender(offset);
+            let ok = svm_host_get(HOST_CTX_SENDER, offset);
+            assert!(ok != 0);
 
             offset.into()
         }
@@ -237,9 +246,18 @@ impl Host for InnerHost {
     #[inline]
     fn layer_id(&self) -> LayerId {
         unsafe {
-            let id = sm_layer();
+            let ptr = svm_sdk_alloc::alloc(8);
+            let offset = ptr.offset();
+
+            let ok = svm_host_get(HOST_CTX_LAYER_ID, offset as u32);
+            assert!(ok != 0);
+
+            let bytes = core::slice::from_raw_parts(offset as *const u8, 8);
+
+            let mut id = [0; 8];
+            id.copy_from_slice(bytes);
 
-            LayerId(id)
+            LayerId(u64::from_be_bytes(id))
         }
     }
 
@@ -248,7 +266,7 @@ impl Host for InnerHost {
         unsafe {
             let offset = addr.offset() as u32;
 
-            let amount = sm_balance(offset);
+            let amount = svm_balance(offset);
 
             Amount(amount)
         }
@@ -259,7 +277,9 @@ impl Host for InnerHost {
         unsafe {
             let dst = dst.offset() as u32;
 
-            sm_transfer(dst, amount.0);
+            // a failed transfer (insufficient funds) aborts the running transaction
+            let ok = svm_transfer(dst, amount.0);
+            assert!(ok != 0);
         }
     }
 
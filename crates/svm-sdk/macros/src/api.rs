@@ -28,6 +28,10 @@ pub fn json_data_layout(schema: &Schema) -> Value {
                 acc.extend(vec![*byte_count; *length as usize]);
                 acc
             }
+            Var::Map { byte_count, .. } => {
+                acc.push(*byte_count);
+                acc
+            }
         });
 
     json!({ "data": data })
@@ -125,6 +129,7 @@ fn storage_api(schema: &Schema) -> Value {
         .map(|v| match v {
             Var::Primitive { .. } => emit_primitive_var(v),
             Var::Array { .. } => emit_array_var(v),
+            Var::Map { .. } => emit_map_var(v),
         })
         .collect();
 
@@ -177,6 +182,29 @@ fn emit_array_var(var: &Var) -> Value {
     }
 }
 
+fn emit_map_var(var: &Var) -> Value {
+    if let Var::Map {
+        id,
+        offset,
+        name,
+        byte_count,
+        key_ty,
+        value_ty,
+        ..
+    } = var
+    {
+        json!({
+            "id": id.0,
+            "offset": offset,
+            "name": name.to_string(),
+            "type": format!("Map<{}, {}>", typify(key_ty), typify(value_ty)),
+            "byte_count": byte_count
+        })
+    } else {
+        unreachable!()
+    }
+}
+
 fn typify(ty: &PrimType) -> String {
     match ty.as_str() {
         "svm_sdk :: Amount" => "Amount".to_string(),
@@ -1,7 +1,7 @@
 use proc_macro2::{Ident, Span, TokenStream};
 
 use quote::{quote, ToTokens};
-use syn::{Error, Field, Fields, ItemStruct, Path, PathArguments, Result};
+use syn::{Error, Field, Fields, GenericArgument, ItemStruct, Path, PathArguments, Result};
 
 use super::{attr, Var, VarId};
 use attr::{has_storage_attr, StructAttr};
@@ -52,6 +52,10 @@ pub fn storage_vars(strukt: &Struct) -> Result<Vec<Var>> {
                 offset += var.byte_count() * (length as usize);
                 id = next_var(id, length);
             }
+            Var::Map { .. } => {
+                offset += var.byte_count();
+                id = next_var(id, 1);
+            }
         }
 
         vars.push(var);
@@ -70,6 +74,21 @@ fn field_var(field: &Field, id: VarId, offset: usize) -> Result<Var> {
     }
 
     let name = field_ident(field);
+
+    if let Some((key_ty, value_ty)) = parse_map_type(&field.ty)? {
+        // A `Map` variable reserves a 4-byte variable, its entries are stored under hashed sub-keys.
+        let var = Var::Map {
+            id,
+            name,
+            key_ty,
+            value_ty,
+            offset,
+            byte_count: 4,
+        };
+
+        return Ok(var);
+    }
+
     let ty = Type::new(&field.ty)?;
 
     let var = match ty {
@@ -101,7 +120,7 @@ fn field_var(field: &Field, id: VarId, offset: usize) -> Result<Var> {
         _ => {
             return Err(Error::new(
                 span,
-                "`#[storage]` supports only Primitive (for example: `svm_sdk::Amount`) and Array types.",
+                "`#[storage]` supports only Primitive (for example: `svm_sdk::Amount`), Array and `Map` types.",
             ));
         }
     };
@@ -109,6 +128,44 @@ fn field_var(field: &Field, id: VarId, offset: usize) -> Result<Var> {
     Ok(var)
 }
 
+fn parse_map_type(ty: &syn::Type) -> Result<Option<(PrimType, PrimType)>> {
+    let path = match ty {
+        syn::Type::Path(path) => path,
+        _ => return Ok(None),
+    };
+
+    let segment = path.path.segments.last().unwrap();
+
+    if segment.ident != "Map" {
+        return Ok(None);
+    }
+
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        let tys: Vec<_> = args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(syn::Type::Path(path)) => Some(path),
+                _ => None,
+            })
+            .collect();
+
+        if args.args.len() == 2 && tys.len() == 2 {
+            let key_ty = PrimType::new(tys[0])?;
+            let value_ty = PrimType::new(tys[1])?;
+
+            return Ok(Some((key_ty, value_ty)));
+        }
+    }
+
+    let span = Span::call_site();
+
+    Err(Error::new(
+        span,
+        "`Map` fields should be declared as `Map<K, V>` (`K` and `V` are Primitive types).",
+    ))
+}
+
 fn ensure_named_fields(fields: &Fields) -> Result<()> {
     if let Fields::Named(..) = fields {
         Ok(())
@@ -262,6 +319,26 @@ fn getter_ast(var: &Var) -> TokenStream {
                 ty => unreachable!(format!("Type `{}` is not supported", ty)),
             }
         }
+        Var::Map {
+            id,
+            name,
+            key_ty,
+            value_ty,
+            ..
+        } => {
+            let getter_name = getter_ident(name);
+            let (key_param, key_arg) = param_ast(key_ty, "key");
+            let key_ty = sdk_type_ast(key_ty);
+            let value_ty = sdk_type_ast(value_ty);
+
+            quote! {
+                fn #getter_name (key: #key_param) -> #value_ty {
+                    #includes
+
+                    svm_sdk::storage::ops::map_get::<StorageImpl, #key_ty, #value_ty>(#id, #key_arg)
+                }
+            }
+        }
     }
 }
 
@@ -373,6 +450,48 @@ fn setter_ast(var: &Var) -> TokenStream {
                 ty => unreachable!(format!("Type `{}` is not supported", ty)),
             }
         }
+        Var::Map {
+            id,
+            name,
+            key_ty,
+            value_ty,
+            ..
+        } => {
+            let setter_name = setter_ident(name);
+            let (key_param, key_arg) = param_ast(key_ty, "key");
+            let (value_param, value_arg) = param_ast(value_ty, "value");
+            let key_ty = sdk_type_ast(key_ty);
+            let value_ty = sdk_type_ast(value_ty);
+
+            quote! {
+                fn #setter_name (key: #key_param, value: #value_param) {
+                    #includes
+
+                    svm_sdk::storage::ops::map_set::<StorageImpl, #key_ty, #value_ty>(#id, #key_arg, #value_arg);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the type of a `Map` getter/setter parameter and the expression passing it by reference.
+///
+/// (`Address` parameters are taken by reference, like the other setters do).
+fn param_ast(ty: &PrimType, param: &str) -> (TokenStream, TokenStream) {
+    let ident = Ident::new(param, Span::call_site());
+    let sdk_ty = sdk_type_ast(ty);
+
+    match ty.as_str() {
+        "svm_sdk :: Address" | "Address" => (quote! { &#sdk_ty }, quote! { #ident }),
+        _ => (quote! { #sdk_ty }, quote! { &#ident }),
+    }
+}
+
+fn sdk_type_ast(ty: &PrimType) -> TokenStream {
+    match ty.as_str() {
+        "svm_sdk :: Amount" | "Amount" => quote! { svm_sdk::Amount },
+        "svm_sdk :: Address" | "Address" => quote! { svm_sdk::Address },
+        _ => quote! { #ty },
     }
 }
 
@@ -21,6 +21,14 @@ pub enum Var {
         length: u32,
         byte_count: usize,
     },
+    Map {
+        id: VarId,
+        offset: usize,
+        name: Ident,
+        key_ty: PrimType,
+        value_ty: PrimType,
+        byte_count: usize,
+    },
 }
 
 impl Var {
@@ -28,6 +36,7 @@ impl Var {
         match *self {
             Var::Primitive { byte_count, .. } => byte_count,
             Var::Array { byte_count, .. } => byte_count,
+            Var::Map { byte_count, .. } => byte_count,
         }
     }
 }
@@ -52,6 +61,20 @@ impl fmt::Debug for Var {
                 elem_ty.as_str(),
                 length
             ),
+            Var::Map {
+                id,
+                name,
+                key_ty,
+                value_ty,
+                ..
+            } => writeln!(
+                f,
+                "Var #{} - {}: Map<{}, {}>",
+                id.0,
+                name,
+                key_ty.as_str(),
+                value_ty.as_str()
+            ),
         }
     }
 }
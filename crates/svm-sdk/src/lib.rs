@@ -35,6 +35,9 @@
 /// In case the storage field type isn't supported, a compile-time error will be raised.
 /// For each field a corresponding getter and setter methods will be generated.
 ///
+/// A field can also be a `Map<K, V>` (where `K` and `V` are primitives, for example `Map<Address, Amount>`).
+/// Its generated getter and setter take the entry's key (i.e `get_balances(&addr)` and `set_balances(&addr, value)`).
+///
 /// Here is a simple example of declaring a storage:
 ///
 /// ```rust
@@ -364,7 +367,7 @@ pub mod host {
 pub mod traits {
     pub use svm_abi_encoder::Encoder;
     pub use svm_sdk_host::traits::Host;
-    pub use svm_sdk_storage::Storage;
+    pub use svm_sdk_storage::{MapItem, Storage};
 }
 
 pub mod storage {
@@ -408,9 +411,13 @@ pub mod storage {
             array_set_amount,
 
             array_get_addr,
-            array_set_addr
+            array_set_addr,
+
+            map_get,
+            map_set
         };
     }
 }
 
+pub use svm_sdk_storage::Map;
 pub use svm_sdk_types::*;
@@ -13,6 +13,10 @@ extern "C" {
     fn svm_store160(offset: u32, var_id: u32);
 
     fn svm_load160(var_id: u32, offset: u32);
+
+    fn svm_map_get(var_id: u32, key_ptr: u32, key_len: u32, val_ptr: u32, val_len: u32) -> u32;
+
+    fn svm_map_set(var_id: u32, key_ptr: u32, key_len: u32, val_ptr: u32, val_len: u32);
 }
 
 pub struct ExtStorage;
@@ -41,4 +45,34 @@ impl Storage for ExtStorage {
     fn load160(var_id: u32, offset: usize) {
         unsafe { svm_load160(var_id, offset as u32) }
     }
+
+    fn map_get(
+        var_id: u32,
+        key_offset: usize,
+        key_len: usize,
+        val_offset: usize,
+        val_len: usize,
+    ) -> u32 {
+        unsafe {
+            svm_map_get(
+                var_id,
+                key_offset as u32,
+                key_len as u32,
+                val_offset as u32,
+                val_len as u32,
+            )
+        }
+    }
+
+    fn map_set(var_id: u32, key_offset: usize, key_len: usize, val_offset: usize, val_len: usize) {
+        unsafe {
+            svm_map_set(
+                var_id,
+                key_offset as u32,
+                key_len as u32,
+                val_offset as u32,
+                val_len as u32,
+            )
+        }
+    }
 }
@@ -12,6 +12,7 @@
 #![allow(unreachable_code)]
 
 mod ext;
+mod map;
 mod mock;
 mod traits;
 
@@ -24,8 +25,11 @@ pub use ext::ExtStorage;
 #[cfg(feature = "mock")]
 pub use mock::MockStorage;
 
+pub use map::{Map, MapItem};
 pub use traits::Storage;
 
+use map::MAX_ITEM_SIZE;
+
 use svm_sdk_types::{Address, Amount};
 
 pub fn get32<S: Storage>(var_id: u32) -> u32 {
@@ -169,6 +173,46 @@ pub fn array_set_addr<S: Storage>(var_id: u32, index: usize, length: u32, value:
     store160::<S>(var_id, slice)
 }
 
+// Map
+
+pub fn map_get<S: Storage, K: MapItem, V: MapItem>(var_id: u32, key: &K) -> V {
+    let key = item_bytes(key);
+    let mut value = [0; MAX_ITEM_SIZE];
+
+    S::map_get(
+        var_id,
+        key.as_ptr() as usize,
+        K::SIZE,
+        value.as_mut_ptr() as usize,
+        V::SIZE,
+    );
+
+    V::read_bytes(&value[..V::SIZE])
+}
+
+pub fn map_set<S: Storage, K: MapItem, V: MapItem>(var_id: u32, key: &K, value: &V) {
+    let key = item_bytes(key);
+    let value = item_bytes(value);
+
+    S::map_set(
+        var_id,
+        key.as_ptr() as usize,
+        K::SIZE,
+        value.as_ptr() as usize,
+        V::SIZE,
+    );
+}
+
+#[inline]
+fn item_bytes<T: MapItem>(item: &T) -> [u8; MAX_ITEM_SIZE] {
+    assert!(T::SIZE <= MAX_ITEM_SIZE);
+
+    let mut buf = [0; MAX_ITEM_SIZE];
+    item.write_bytes(&mut buf[..T::SIZE]);
+
+    buf
+}
+
 #[inline]
 fn cell_offset(var_id: u32, index: usize, length: u32) -> u32 {
     let index = index as u32;
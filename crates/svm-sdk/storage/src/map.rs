@@ -0,0 +1,95 @@
+use svm_sdk_types::{Address, Amount};
+
+/// The maximum byte-length of a `Map` key or value.
+pub const MAX_ITEM_SIZE: usize = 20;
+
+/// A type which can serve as a `Map` key or value.
+///
+/// Each such type is stored as a fixed-size blob of `SIZE` bytes.
+pub trait MapItem: Sized {
+    const SIZE: usize;
+
+    fn write_bytes(&self, buf: &mut [u8]);
+
+    fn read_bytes(buf: &[u8]) -> Self;
+}
+
+macro_rules! impl_int_item {
+    ($($ty:ty),*) => {
+        $(
+            impl MapItem for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn write_bytes(&self, buf: &mut [u8]) {
+                    buf.copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_bytes(buf: &[u8]) -> Self {
+                    let mut bytes = [0; core::mem::size_of::<$ty>()];
+                    bytes.copy_from_slice(buf);
+
+                    <$ty>::from_le_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_int_item!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+impl MapItem for bool {
+    const SIZE: usize = 1;
+
+    fn write_bytes(&self, buf: &mut [u8]) {
+        buf[0] = *self as u8;
+    }
+
+    fn read_bytes(buf: &[u8]) -> Self {
+        match buf[0] {
+            0 => false,
+            1 => true,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl MapItem for Amount {
+    const SIZE: usize = 8;
+
+    fn write_bytes(&self, buf: &mut [u8]) {
+        self.0.write_bytes(buf)
+    }
+
+    fn read_bytes(buf: &[u8]) -> Self {
+        Amount(u64::read_bytes(buf))
+    }
+}
+
+impl MapItem for Address {
+    const SIZE: usize = 20;
+
+    fn write_bytes(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self.as_slice())
+    }
+
+    fn read_bytes(buf: &[u8]) -> Self {
+        use svm_sdk_alloc::alloc;
+
+        let ptr = alloc(Self::SIZE);
+
+        let slice = unsafe {
+            let dst = ptr.as_ptr() as *mut u8;
+
+            core::ptr::copy_nonoverlapping(buf.as_ptr(), dst, Self::SIZE);
+
+            core::slice::from_raw_parts(ptr.as_ptr(), Self::SIZE)
+        };
+
+        slice.into()
+    }
+}
+
+/// A `#[storage]` field type for declaring a map variable (from `K` keys to `V` values).
+///
+/// The `#[storage]` macro consumes the field and generates a getter and a setter taking a key.
+pub struct Map<K: MapItem, V: MapItem>(core::marker::PhantomData<(K, V)>);
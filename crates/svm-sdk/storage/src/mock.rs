@@ -26,12 +26,15 @@ enum Var {
 
 pub struct InnerStorage {
     vars: HashMap<u32, Var>,
+
+    maps: HashMap<(u32, Vec<u8>), Vec<u8>>,
 }
 
 impl InnerStorage {
     pub fn new() -> Self {
         Self {
             vars: HashMap::new(),
+            maps: HashMap::new(),
         }
     }
 
@@ -69,6 +72,44 @@ impl InnerStorage {
         self.load_vec(var_id, offset, 20)
     }
 
+    pub fn map_get(
+        &self,
+        var_id: u32,
+        key_offset: usize,
+        key_len: usize,
+        val_offset: usize,
+        val_len: usize,
+    ) -> u32 {
+        let key = self.from_raw_parts(key_offset, key_len).to_vec();
+        let value = self.maps.get(&(var_id, key)).cloned().unwrap_or_default();
+
+        let mut buf = vec![0; val_len];
+        let n = core::cmp::min(value.len(), val_len);
+        buf[..n].copy_from_slice(&value[..n]);
+
+        unsafe {
+            let dst = val_offset as *mut u8;
+
+            core::ptr::copy_nonoverlapping(buf.as_ptr(), dst, val_len)
+        }
+
+        value.len() as u32
+    }
+
+    pub fn map_set(
+        &mut self,
+        var_id: u32,
+        key_offset: usize,
+        key_len: usize,
+        val_offset: usize,
+        val_len: usize,
+    ) {
+        let key = self.from_raw_parts(key_offset, key_len).to_vec();
+        let value = self.from_raw_parts(val_offset, val_len).to_vec();
+
+        self.maps.insert((var_id, key), value);
+    }
+
     fn get_var<F>(&self, var_id: u32, default: F) -> Var
     where
         F: Fn() -> Var,
@@ -108,6 +149,7 @@ impl InnerStorage {
 
     fn clear(&mut self) {
         self.vars.clear();
+        self.maps.clear();
     }
 }
 
@@ -171,6 +213,24 @@ impl Storage for MockStorage {
 
         storage.load160(var_id, offset)
     }
+
+    fn map_get(
+        var_id: u32,
+        key_offset: usize,
+        key_len: usize,
+        val_offset: usize,
+        val_len: usize,
+    ) -> u32 {
+        let mut storage = Self::instance();
+
+        storage.map_get(var_id, key_offset, key_len, val_offset, val_len)
+    }
+
+    fn map_set(var_id: u32, key_offset: usize, key_len: usize, val_offset: usize, val_len: usize) {
+        let mut storage = Self::instance();
+
+        storage.map_set(var_id, key_offset, key_len, val_offset, val_len)
+    }
 }
 
 #[cfg(test)]
@@ -267,4 +327,31 @@ mod tests {
     fn storage_mock_load160_store160() {
         check_load_store!(20, load160, store160);
     }
+
+    #[test]
+    fn storage_mock_map_get_map_set() {
+        test(|| {
+            let var1 = 1;
+            let var2 = 2;
+
+            let key = vec![0x10u8; 4];
+            let value = vec![0x20u8; 8];
+
+            let off = alloc(8).offset();
+
+            let n = MockStorage::map_get(var1, key.as_ptr() as usize, 4, off, 8);
+            assert_eq!(n, 0);
+            assert_eq!(MockStorage::from_raw_parts(off, 8), vec![0; 8]);
+
+            MockStorage::map_set(var1, key.as_ptr() as usize, 4, value.as_ptr() as usize, 8);
+
+            let n = MockStorage::map_get(var1, key.as_ptr() as usize, 4, off, 8);
+            assert_eq!(n, 8);
+            assert_eq!(MockStorage::from_raw_parts(off, 8), vec![0x20; 8]);
+
+            // the same key under another variable is a different entry
+            let n = MockStorage::map_get(var2, key.as_ptr() as usize, 4, off, 8);
+            assert_eq!(n, 0);
+        });
+    }
 }
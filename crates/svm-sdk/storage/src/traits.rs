@@ -10,4 +10,14 @@ pub trait Storage {
     fn store160(var_id: u32, offset: usize);
 
     fn load160(var_id: u32, offset: usize);
+
+    fn map_get(
+        var_id: u32,
+        key_offset: usize,
+        key_len: usize,
+        val_offset: usize,
+        val_len: usize,
+    ) -> u32;
+
+    fn map_set(var_id: u32, key_offset: usize, key_len: usize, val_offset: usize, val_len: usize);
 }
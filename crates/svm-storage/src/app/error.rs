@@ -0,0 +1,57 @@
+use std::fmt;
+
+use svm_layout::VarId;
+use svm_types::State;
+
+/// The reasons a vector operation (see `AppStorage::vec_push`) fails.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VecError {
+    /// Variable `var_id` isn't part of the layout, or it can't hold a vector length (i.e it isn't 1 to 4 bytes long)
+    InvalidVar(VarId),
+
+    /// Element `index` is out-of-bounds for vector variable `var_id`
+    IndexOutOfBounds {
+        /// The vector variable
+        var_id: VarId,
+
+        /// The accessed index
+        index: u32,
+    },
+
+    /// Vector variable `var_id` has not enough bytes to hold its new length `len`
+    LengthOverflow {
+        /// The vector variable
+        var_id: VarId,
+
+        /// The overflowing length
+        len: u64,
+    },
+}
+
+impl fmt::Display for VecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VecError::InvalidVar(var_id) => write!(f, "Invalid vector variable `{}`", var_id.0),
+            VecError::IndexOutOfBounds { var_id, index } => write!(
+                f,
+                "Index `{}` is out-of-bounds for vector variable `{}`",
+                index, var_id.0
+            ),
+            VecError::LengthOverflow { var_id, len } => write!(
+                f,
+                "Length `{}` overflows vector variable `{}`",
+                len, var_id.0
+            ),
+        }
+    }
+}
+
+/// The app has no such `State` (i.e it isn't the app's current `State`, see `AppStorage::check_state`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownState(pub State);
+
+impl fmt::Display for UnknownState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unknown app state `{:?}`", self.0)
+    }
+}
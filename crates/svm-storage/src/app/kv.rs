@@ -7,7 +7,10 @@ use svm_common::{DefaultKeyHasher, KeyHasher};
 use svm_types::{Address, State};
 
 /// An application-aware (and `State`-aware) key-value store interface responsible of
-/// mapping `u32` input keys (given as a 4 byte-length slice) to global keys under a raw key-value store.
+/// mapping input keys to global keys under a raw key-value store.
+///
+/// Fixed-size variables pages are addressed by `u32` keys (given as a 4 byte-length slice),
+/// while dynamic-size entries (maps and vectors) are addressed by their 32 byte-length hashed sub-keys.
 ///
 /// The mapping is dependant on the contextual app's `Address` (see the `new` method).
 pub struct AppKVStore {
@@ -54,6 +57,11 @@ impl StatefulKV for AppKVStore {
         self.kv.borrow_mut().rewind(state);
     }
 
+    #[inline]
+    fn prune(&mut self, keep: &[State]) {
+        self.kv.borrow_mut().prune(keep);
+    }
+
     #[inline]
     #[must_use]
     fn head(&self) -> State {
@@ -73,8 +81,6 @@ impl AppKVStore {
 
     #[inline]
     fn build_key(&self, key: &[u8]) -> Vec<u8> {
-        debug_assert_eq!(key.len(), 4);
-
         let mut buf = Vec::with_capacity(Address::len() + key.len());
 
         buf.extend_from_slice(self.app_addr.as_slice());
@@ -0,0 +1,315 @@
+use svm_common::{DefaultKeyHasher, KeyHasher};
+use svm_layout::{DataLayout, VarId};
+use svm_types::State;
+
+use super::KV_VALUE_SIZE;
+
+/// The depth of an app's pages Merkle tree.
+///
+/// The tree is a sparse binary Merkle tree whose leaves are indexed by the storage page keys (`u32`).
+pub const TREE_DEPTH: u32 = 32;
+
+/// The depth of an app's entries Merkle tree.
+///
+/// The tree is a sparse binary Merkle tree whose leaves are indexed by the sub-keys (32 bytes)
+/// of the maps and vectors entries.
+pub const ENTRIES_TREE_DEPTH: u32 = 256;
+
+/// The hash of an empty subtree.
+///
+/// A leaf of an all-zeros page is considered empty, and so is a node having two empty children.
+/// Thus, the root of an app having no storage (or only zeros) is `State::zeros()`.
+pub(crate) const EMPTY_HASH: [u8; 32] = [0; 32];
+
+const LEAF_TAG: u8 = 0;
+
+const NODE_TAG: u8 = 1;
+
+const ENTRY_LEAF_TAG: u8 = 2;
+
+const STATE_TAG: u8 = 3;
+
+/// A proof for a single storage page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageProof {
+    /// The page key
+    pub page: u32,
+
+    /// The page raw data (`KV_VALUE_SIZE` bytes)
+    pub value: Vec<u8>,
+
+    /// The siblings along the path from the page leaf up to the root (the leaf sibling comes first)
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// A proof for a variable's value against an app state root.
+///
+/// A variable may span multiple pages, so the proof consists of a `PageProof` per page (ordered by page key).
+///
+/// The app state root commits to both the pages tree and the entries tree (see `state_root`),
+/// so the proof carries the entries tree root as well.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateProof {
+    /// The proofs of the pages holding the variable
+    pub pages: Vec<PageProof>,
+
+    /// The root of the maps and vectors entries tree
+    pub entries_root: [u8; 32],
+}
+
+/// Verifies that variable `var_id` (laid out according to `layout`) holds `value`
+/// under the app state root `root`.
+///
+/// Doesn't require any access to the app's storage, so it can be used by light clients.
+pub fn verify(
+    layout: &DataLayout,
+    root: &State,
+    var_id: VarId,
+    value: &[u8],
+    proof: &StateProof,
+) -> bool {
+    let (offset, length) = match layout.try_get_var(var_id) {
+        Some(var) => var,
+        None => return false,
+    };
+
+    if value.len() != length as usize {
+        return false;
+    }
+
+    let slices = page_slices(offset, length, KV_VALUE_SIZE);
+
+    if slices.len() != proof.pages.len() {
+        return false;
+    }
+
+    let mut start = 0;
+
+    for ((page, page_off, len), page_proof) in slices.iter().zip(proof.pages.iter()) {
+        if page_proof.page != *page || page_proof.value.len() != KV_VALUE_SIZE as usize {
+            return false;
+        }
+
+        let end = start + *len as usize;
+
+        let page_off = *page_off as usize;
+        let page_slice = &page_proof.value[page_off..page_off + *len as usize];
+
+        if page_slice != &value[start..end] {
+            return false;
+        }
+
+        let leaf = leaf_hash(*page, &page_proof.value);
+
+        let pages_root = match compute_root(*page, leaf, &page_proof.siblings) {
+            Some(pages_root) => pages_root,
+            None => return false,
+        };
+
+        if state_root(&pages_root, &proof.entries_root)[..] != root.as_slice()[..] {
+            return false;
+        }
+
+        start = end;
+    }
+
+    true
+}
+
+/// Computes the hash of the leaf of page `page` holding `value`.
+pub(crate) fn leaf_hash(page: u32, value: &[u8]) -> [u8; 32] {
+    if value.iter().all(|&b| b == 0) {
+        return EMPTY_HASH;
+    }
+
+    let mut buf = Vec::with_capacity(1 + 4 + value.len());
+
+    buf.push(LEAF_TAG);
+    buf.extend_from_slice(&page.to_be_bytes());
+    buf.extend_from_slice(value);
+
+    DefaultKeyHasher::hash(&buf)
+}
+
+/// Computes the hash of a node having children `left` and `right`.
+pub(crate) fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if left == &EMPTY_HASH && right == &EMPTY_HASH {
+        return EMPTY_HASH;
+    }
+
+    let mut buf = Vec::with_capacity(1 + 32 + 32);
+
+    buf.push(NODE_TAG);
+    buf.extend_from_slice(left);
+    buf.extend_from_slice(right);
+
+    DefaultKeyHasher::hash(&buf)
+}
+
+/// Computes the hash of the leaf of the entry stored under sub-key `key` holding `value`.
+///
+/// Unlike pages, an entry always has a non-empty leaf (even when its `value` is empty),
+/// since an existing entry is distinguishable from a missing one.
+pub(crate) fn entry_leaf_hash(key: &[u8], value: &[u8]) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(1 + 4 + key.len() + value.len());
+
+    buf.push(ENTRY_LEAF_TAG);
+    buf.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    buf.extend_from_slice(key);
+    buf.extend_from_slice(value);
+
+    DefaultKeyHasher::hash(&buf)
+}
+
+/// Computes the app state root out of the pages tree root and the entries tree root.
+pub(crate) fn state_root(pages_root: &[u8; 32], entries_root: &[u8; 32]) -> [u8; 32] {
+    if pages_root == &EMPTY_HASH && entries_root == &EMPTY_HASH {
+        return EMPTY_HASH;
+    }
+
+    let mut buf = Vec::with_capacity(1 + 32 + 32);
+
+    buf.push(STATE_TAG);
+    buf.extend_from_slice(pages_root);
+    buf.extend_from_slice(entries_root);
+
+    DefaultKeyHasher::hash(&buf)
+}
+
+/// Returns the index of the node at tree level `level` on the path of page `page`.
+///
+/// (Level `0` holds the leaves and level `TREE_DEPTH` holds the root).
+#[inline]
+pub(crate) fn node_index(page: u32, level: u32) -> u32 {
+    ((page as u64) >> level) as u32
+}
+
+/// Returns the index of the node at entries tree level `level` on the path of sub-key `key`.
+///
+/// The index is `key` (a Big-Endian 256-bit integer) shifted right by `level` bits.
+pub(crate) fn entry_node_index(key: &[u8; 32], level: u32) -> [u8; 32] {
+    let bytes = (level / 8) as usize;
+    let bits = level % 8;
+
+    let mut index = [0; 32];
+
+    for i in bytes..32 {
+        let low = key[i - bytes] as u16;
+        let high = if i > bytes {
+            key[i - bytes - 1] as u16
+        } else {
+            0
+        };
+
+        index[i] = (((high << 8 | low) >> bits) & 0xFF) as u8;
+    }
+
+    index
+}
+
+/// Computes the root from the leaf hash of page `page` and its `siblings`.
+///
+/// Returns `None` when there isn't a sibling per tree level.
+fn compute_root(page: u32, leaf: [u8; 32], siblings: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if siblings.len() != TREE_DEPTH as usize {
+        return None;
+    }
+
+    let mut hash = leaf;
+
+    for (level, sibling) in siblings.iter().enumerate() {
+        let is_right = node_index(page, level as u32) & 1 == 1;
+
+        hash = if is_right {
+            node_hash(sibling, &hash)
+        } else {
+            node_hash(&hash, sibling)
+        };
+    }
+
+    Some(hash)
+}
+
+/// Splits the range `[offset, offset + length)` into per-page slices (each page has `page_size` bytes).
+///
+/// Each slice is a tuple of `(page, offset within the page, length)`.
+pub(crate) fn page_slices(offset: u32, length: u32, page_size: u32) -> Vec<(u32, u32, u32)> {
+    let mut slices = Vec::new();
+
+    let mut offset = offset;
+    let mut remaining = length;
+
+    while remaining > 0 {
+        let page = offset / page_size;
+        let page_off = offset % page_size;
+        let len = remaining.min(page_size - page_off);
+
+        slices.push((page, page_off, len));
+
+        offset += len;
+        remaining -= len;
+    }
+
+    slices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merkle_empty_tree_root_is_zeros() {
+        let siblings = vec![EMPTY_HASH; TREE_DEPTH as usize];
+        let leaf = leaf_hash(7, &[0; KV_VALUE_SIZE as usize]);
+
+        let root = compute_root(7, leaf, &siblings).unwrap();
+
+        assert_eq!(&root[..], State::zeros().as_slice());
+    }
+
+    #[test]
+    fn merkle_leaf_hash_depends_on_page() {
+        let value = vec![0x10; KV_VALUE_SIZE as usize];
+
+        assert_ne!(leaf_hash(0, &value), leaf_hash(1, &value));
+        assert_ne!(leaf_hash(0, &value), EMPTY_HASH);
+    }
+
+    #[test]
+    fn merkle_state_root_of_empty_trees_is_zeros() {
+        assert_eq!(state_root(&EMPTY_HASH, &EMPTY_HASH), EMPTY_HASH);
+
+        let leaf = entry_leaf_hash(&[0x10; 32], &[]);
+        assert_ne!(leaf, EMPTY_HASH);
+        assert_ne!(state_root(&EMPTY_HASH, &leaf), EMPTY_HASH);
+    }
+
+    #[test]
+    fn merkle_entry_node_index() {
+        let mut key = [0; 32];
+        key[30] = 0b1010_0101;
+        key[31] = 0b1100_0011;
+
+        assert_eq!(entry_node_index(&key, 0), key);
+
+        let index = entry_node_index(&key, 4);
+        assert_eq!(index[30], 0b0000_1010);
+        assert_eq!(index[31], 0b0101_1100);
+
+        let index = entry_node_index(&key, 8);
+        assert_eq!(index[30], 0);
+        assert_eq!(index[31], 0b1010_0101);
+
+        assert_eq!(entry_node_index(&key, ENTRIES_TREE_DEPTH), [0; 32]);
+    }
+
+    #[test]
+    fn merkle_page_slices() {
+        assert_eq!(page_slices(0, 0, 32), vec![]);
+        assert_eq!(page_slices(3, 4, 32), vec![(0, 3, 4)]);
+        assert_eq!(
+            page_slices(30, 40, 32),
+            vec![(0, 30, 2), (1, 0, 32), (2, 0, 6)]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use svm_layout::{DataLayout, VarId};
+
+/// A validated migration of an app's storage from an old `DataLayout` to a new one.
+///
+/// The migration maps each variable of the old layout to a variable of the new layout.
+/// Upon migrating (see `AppStorage::migrate`) each new variable is initialized with the value of the old variable
+/// mapped to it. A new variable being longer than its old variable is padded with trailing zeros
+/// (so Little-Endian integers keep their values). New variables having no old variable are initialized with zeros.
+///
+/// In order to make sure no variable loses bytes silently, a migration is valid only when:
+/// * Each old variable is mapped to exactly one new variable.
+/// * Each new variable has at most one old variable mapped to it.
+/// * A new variable isn't shorter than its old variable.
+///
+/// Note: maps and vectors entries are stored under sub-keys derived from their `VarId`,
+/// so a dynamic-size variable must keep its `VarId` (i.e be mapped to itself).
+/// Since the layout doesn't tell which variables are dynamic-size, it's checked upon migrating
+/// (see `MigrationError::DynamicVarMoved`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutMigration {
+    old_layout: DataLayout,
+
+    new_layout: DataLayout,
+
+    /// Maps a new variable to its old variable
+    sources: HashMap<VarId, VarId>,
+
+    /// Maps an old variable to its new variable
+    targets: HashMap<VarId, VarId>,
+}
+
+/// The reasons a `LayoutMigration` is invalid.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    UnknownOldVar(VarId),
+    UnknownNewVar(VarId),
+    UnmappedOldVar(VarId),
+    OldVarMappedTwice(VarId),
+    NewVarMappedTwice(VarId),
+    VarTruncated {
+        old_var: VarId,
+        new_var: VarId,
+        old_len: u32,
+        new_len: u32,
+    },
+    DynamicVarMoved {
+        old_var: VarId,
+        new_var: VarId,
+    },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrationError::UnknownOldVar(var_id) => {
+                write!(f, "Unknown old variable `{}`", var_id.0)
+            }
+            MigrationError::UnknownNewVar(var_id) => {
+                write!(f, "Unknown new variable `{}`", var_id.0)
+            }
+            MigrationError::UnmappedOldVar(var_id) => {
+                write!(f, "Old variable `{}` isn't mapped", var_id.0)
+            }
+            MigrationError::OldVarMappedTwice(var_id) => {
+                write!(f, "Old variable `{}` is mapped more than once", var_id.0)
+            }
+            MigrationError::NewVarMappedTwice(var_id) => {
+                write!(f, "New variable `{}` is mapped more than once", var_id.0)
+            }
+            MigrationError::VarTruncated {
+                old_var,
+                new_var,
+                old_len,
+                new_len,
+            } => write!(
+                f,
+                "Old variable `{}` ({} bytes) doesn't fit new variable `{}` ({} bytes)",
+                old_var.0, old_len, new_var.0, new_len
+            ),
+            MigrationError::DynamicVarMoved { old_var, new_var } => write!(
+                f,
+                "Old variable `{}` has entries (a map or a vector) so it can't move to new variable `{}`",
+                old_var.0, new_var.0
+            ),
+        }
+    }
+}
+
+impl LayoutMigration {
+    /// Validates a migration from `old_layout` to `new_layout`.
+    ///
+    /// The `mapping` is a list of `(old var, new var)` pairs.
+    pub fn new(
+        old_layout: DataLayout,
+        new_layout: DataLayout,
+        mapping: &[(VarId, VarId)],
+    ) -> Result<Self, MigrationError> {
+        let mut sources = HashMap::new();
+        let mut targets = HashMap::new();
+
+        for (old_var, new_var) in mapping.iter() {
+            let (_off, old_len) = old_layout
+                .try_get_var(*old_var)
+                .ok_or(MigrationError::UnknownOldVar(*old_var))?;
+
+            let (_off, new_len) = new_layout
+                .try_get_var(*new_var)
+                .ok_or(MigrationError::UnknownNewVar(*new_var))?;
+
+            if new_len < old_len {
+                return Err(MigrationError::VarTruncated {
+                    old_var: *old_var,
+                    new_var: *new_var,
+                    old_len,
+                    new_len,
+                });
+            }
+
+            if targets.insert(*old_var, *new_var).is_some() {
+                return Err(MigrationError::OldVarMappedTwice(*old_var));
+            }
+
+            if sources.insert(*new_var, *old_var).is_some() {
+                return Err(MigrationError::NewVarMappedTwice(*new_var));
+            }
+        }
+
+        for (old_var, _off, _len) in old_layout.iter() {
+            if !targets.contains_key(&old_var) {
+                return Err(MigrationError::UnmappedOldVar(old_var));
+            }
+        }
+
+        Ok(Self {
+            old_layout,
+            new_layout,
+            sources,
+            targets,
+        })
+    }
+
+    /// The layout being migrated from
+    #[inline]
+    pub fn old_layout(&self) -> &DataLayout {
+        &self.old_layout
+    }
+
+    /// The layout being migrated to
+    #[inline]
+    pub fn new_layout(&self) -> &DataLayout {
+        &self.new_layout
+    }
+
+    /// Returns the old variable mapped to the new variable `new_var` (`None` when there is no such variable).
+    #[inline]
+    pub fn source_of(&self, new_var: VarId) -> Option<VarId> {
+        self.sources.get(&new_var).copied()
+    }
+
+    /// Returns the new variable the old variable `old_var` is mapped to.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `old_var` isn't a variable of the old layout.
+    #[inline]
+    pub fn target_of(&self, old_var: VarId) -> VarId {
+        self.targets[&old_var]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layouts() -> (DataLayout, DataLayout) {
+        let old = DataLayout::from(vec![4, 2].as_slice());
+        let new = DataLayout::from(vec![8, 4, 2].as_slice());
+
+        (old, new)
+    }
+
+    #[test]
+    fn layout_migration_valid() {
+        let (old, new) = layouts();
+
+        let migration =
+            LayoutMigration::new(old, new, &[(VarId(0), VarId(1)), (VarId(1), VarId(2))]).unwrap();
+
+        assert_eq!(migration.source_of(VarId(0)), None);
+        assert_eq!(migration.source_of(VarId(1)), Some(VarId(0)));
+        assert_eq!(migration.source_of(VarId(2)), Some(VarId(1)));
+
+        assert_eq!(migration.target_of(VarId(0)), VarId(1));
+        assert_eq!(migration.target_of(VarId(1)), VarId(2));
+    }
+
+    #[test]
+    fn layout_migration_invalid() {
+        let (old, new) = layouts();
+
+        let err = |mapping: &[(VarId, VarId)]| {
+            LayoutMigration::new(old.clone(), new.clone(), mapping).unwrap_err()
+        };
+
+        assert_eq!(
+            err(&[(VarId(0), VarId(0)), (VarId(2), VarId(1))]),
+            MigrationError::UnknownOldVar(VarId(2))
+        );
+
+        assert_eq!(
+            err(&[(VarId(0), VarId(0)), (VarId(1), VarId(3))]),
+            MigrationError::UnknownNewVar(VarId(3))
+        );
+
+        assert_eq!(
+            err(&[(VarId(0), VarId(0))]),
+            MigrationError::UnmappedOldVar(VarId(1))
+        );
+
+        assert_eq!(
+            err(&[(VarId(0), VarId(0)), (VarId(0), VarId(1))]),
+            MigrationError::OldVarMappedTwice(VarId(0))
+        );
+
+        assert_eq!(
+            err(&[(VarId(0), VarId(0)), (VarId(1), VarId(0))]),
+            MigrationError::NewVarMappedTwice(VarId(0))
+        );
+
+        assert_eq!(
+            err(&[(VarId(0), VarId(2)), (VarId(1), VarId(1))]),
+            MigrationError::VarTruncated {
+                old_var: VarId(0),
+                new_var: VarId(2),
+                old_len: 4,
+                new_len: 2,
+            }
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 mod raw;
 use raw::{RawChange, RawStorage};
@@ -6,7 +6,25 @@ use raw::{RawChange, RawStorage};
 mod kv;
 pub use kv::AppKVStore;
 
+mod merkle;
+pub use merkle::{verify, PageProof, StateProof, ENTRIES_TREE_DEPTH, TREE_DEPTH};
+
+mod savepoint;
+use savepoint::Savepoint;
+pub use savepoint::SavepointId;
+
+mod usage;
+pub use usage::StorageUsage;
+
+mod migration;
+pub use migration::{LayoutMigration, MigrationError};
+
+mod error;
+pub use error::{UnknownState, VecError};
+
+use svm_common::{DefaultKeyHasher, KeyHasher};
 use svm_layout::{DataLayout, VarId};
+use svm_types::receipt::StorageChange;
 use svm_types::State;
 
 ///
@@ -19,8 +37,19 @@ use svm_types::State;
 ///
 /// Reading a variable will return the most updated value of the variable.
 ///
+/// Besides fixed-size variables, an app may have dynamic-size variables (maps and vectors).
+/// Each such variable reserves a variable under the app's layout (a vector stores its length there),
+/// while its entries are stored under hashed sub-keys derived from the variable's `VarId`.
+///
 /// If app's execution fails - no changes will be persisted (`commit` won't be called).
 ///
+/// Uncommitted changes can be partially reverted using nested savepoints (see `savepoint`).
+///
+/// The app's `State` is its state root (see `root`).
+/// Since all the apps share the same underlying key-value store, an app can't be rewound to one of its previous `State`(s)
+/// on its own (that would discard the changes committed by the other apps since). So only the app's current `State`
+/// is accepted (see `check_state`), and rewinding is done to the whole key-value store (see `rewind_kv`).
+///
 pub struct AppStorage {
     /// Interface to the raw storage (key-value wrapper)
     raw_storage: RawStorage,
@@ -30,12 +59,30 @@ pub struct AppStorage {
 
     /// Uncommited changes
     uncommitted: HashMap<VarId, Vec<u8>>,
+
+    /// Uncommited maps and vectors entries (keyed by their sub-keys)
+    uncommitted_entries: HashMap<Vec<u8>, Vec<u8>>,
+
+    /// The variables having entries written since the last commit (see `has_entries`)
+    uncommitted_dynamic_vars: HashSet<VarId>,
+
+    /// The open savepoints (the innermost is the last)
+    savepoints: Vec<Savepoint>,
+
+    /// The id of the next savepoint to be created
+    next_savepoint_id: SavepointId,
 }
 
-// TODO:
-// we need to decide whether `kv_value_size` should be
-// part of transaction (next to the `DataLayout`) or a constant value.
-const KV_VALUE_SIZE: u32 = 32;
+/// The byte-length of each page of an app's fixed-size variables storage.
+///
+/// Variables are laid out consecutively (as dictated by the app's `DataLayout`),
+/// and each page of `KV_VALUE_SIZE` bytes is stored under its own key (the page index).
+/// A variable may span multiple pages. Changing this value changes the storage layout of all apps.
+pub const KV_VALUE_SIZE: u32 = 32;
+
+const MAP_ENTRY_TAG: u8 = 0;
+
+const VEC_ENTRY_TAG: u8 = 1;
 
 impl AppStorage {
     /// New instance for managing app's variabled specified by `layout`.
@@ -45,21 +92,56 @@ impl AppStorage {
             layout,
             raw_storage: RawStorage::new(app_kv, KV_VALUE_SIZE),
             uncommitted: HashMap::new(),
+            uncommitted_entries: HashMap::new(),
+            uncommitted_dynamic_vars: HashSet::new(),
+            savepoints: Vec::new(),
+            next_savepoint_id: 0,
         }
     }
 
-    /// Rewinds the current application `State` to point to `state`.
-    #[inline]
-    pub fn rewind(&mut self, state: &State) {
-        self.raw_storage.rewind(state);
+    /// Checks that `state` is the current `State` of the application (see `head`).
+    ///
+    /// A previous `State` of the application isn't accepted, since the application can't be rewound
+    /// on its own (see `rewind_kv`).
+    pub fn check_state(&self, state: &State) -> Result<(), UnknownState> {
+        if self.head() == *state {
+            Ok(())
+        } else {
+            Err(UnknownState(state.clone()))
+        }
     }
 
-    /// Returns the current `State` of the application.
+    /// Returns the current `State` of the application (i.e its state root, see `root`).
     #[inline]
     pub fn head(&self) -> State {
+        self.root()
+    }
+
+    /// Returns the current `State` of the underlying key-value store.
+    ///
+    /// Unlike `head`, it reflects the changes committed by all the apps sharing the key-value store.
+    #[inline]
+    pub fn kv_head(&self) -> State {
         self.raw_storage.head()
     }
 
+    /// Rewinds the underlying key-value store to `kv_state` (see `kv_head`).
+    ///
+    /// It discards the changes committed (by any app) since `kv_state`,
+    /// so each app is back at the `State` it had as of `kv_state`.
+    #[inline]
+    pub fn rewind_kv(&mut self, kv_state: &State) {
+        self.raw_storage.rewind(kv_state);
+    }
+
+    /// Prunes the underlying key-value store, keeping only the `keep` states (see `kv_head`) and its `head`.
+    ///
+    /// Rewinding the key-value store to a pruned `State` isn't possible afterwards.
+    #[inline]
+    pub fn prune_kv(&mut self, keep: &[State]) {
+        self.raw_storage.prune(keep);
+    }
+
     /// Reads variable `var_id`.
     pub fn read_var(&self, var_id: VarId) -> Vec<u8> {
         let var = self.uncommitted.get(&var_id).cloned();
@@ -81,7 +163,11 @@ impl AppStorage {
 
         assert_eq!(value.len(), len as usize);
 
-        self.uncommitted.insert(var_id, value);
+        let prev = self.uncommitted.insert(var_id, value);
+
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            savepoint.record_var(var_id, prev);
+        }
     }
 
     /// Returns the layout of variable `var_id`.
@@ -91,33 +177,363 @@ impl AppStorage {
         self.layout.get_var(var_id)
     }
 
-    /// Commits modified variables into the raw storage.
-    #[must_use]
-    pub fn commit(&mut self) -> State {
-        let var_offset: HashMap<VarId, u32> = self
+    /// Returns the layout of variable `var_id` (`None` when there is no such variable).
+    /// The layout is a tuple of `(offset, length)`.
+    #[inline]
+    pub fn try_var_layout(&self, var_id: VarId) -> Option<(u32, u32)> {
+        self.layout.try_get_var(var_id)
+    }
+
+    /// Reads the value stored under `key` of map variable `var_id` (`None` when there is no such entry).
+    pub fn map_get(&self, var_id: VarId, key: &[u8]) -> Option<Vec<u8>> {
+        let key = entry_key(MAP_ENTRY_TAG, var_id, key);
+
+        self.read_entry(&key)
+    }
+
+    /// Marks entry `key` of map variable `var_id` as `dirty`. Upon `commit` will persist the entry.
+    pub fn map_set(&mut self, var_id: VarId, key: &[u8], value: Vec<u8>) {
+        let key = entry_key(MAP_ENTRY_TAG, var_id, key);
+
+        self.write_entry(var_id, key, value);
+    }
+
+    /// Returns the number of elements of vector variable `var_id`.
+    ///
+    /// The length is stored as a Little-Endian integer under the variable itself,
+    /// so a vector variable must be 1 to 4 bytes long (see `VecError::InvalidVar`).
+    pub fn vec_len(&self, var_id: VarId) -> Result<u32, VecError> {
+        self.vec_var_len(var_id)?;
+
+        Ok(self.read_vec_len(var_id))
+    }
+
+    /// Reads element `index` of vector variable `var_id`.
+    pub fn vec_get(&self, var_id: VarId, index: u32) -> Result<Vec<u8>, VecError> {
+        if index >= self.vec_len(var_id)? {
+            return Err(VecError::IndexOutOfBounds { var_id, index });
+        }
+
+        let key = entry_key(VEC_ENTRY_TAG, var_id, &index.to_be_bytes());
+        let value = self.read_entry(&key);
+
+        Ok(value.unwrap_or_default())
+    }
+
+    /// Appends `value` to vector variable `var_id` and returns the index of the new element.
+    ///
+    /// Fails with `VecError::LengthOverflow` when the vector variable has not enough bytes to hold its new length.
+    pub fn vec_push(&mut self, var_id: VarId, value: Vec<u8>) -> Result<u32, VecError> {
+        let len = self.vec_var_len(var_id)?;
+        let index = self.read_vec_len(var_id);
+        let new_len = index as u64 + 1;
+
+        if new_len >> (8 * len) != 0 {
+            return Err(VecError::LengthOverflow {
+                var_id,
+                len: new_len,
+            });
+        }
+
+        let key = entry_key(VEC_ENTRY_TAG, var_id, &index.to_be_bytes());
+        self.write_entry(var_id, key, value);
+
+        let new_len = new_len.to_le_bytes()[..len as usize].to_vec();
+        self.write_var(var_id, new_len);
+
+        Ok(index)
+    }
+
+    /// Returns the byte-length of vector variable `var_id`.
+    ///
+    /// Fails with `VecError::InvalidVar` when there is no such variable, or when it isn't 1 to 4 bytes long.
+    fn vec_var_len(&self, var_id: VarId) -> Result<u32, VecError> {
+        match self.try_var_layout(var_id) {
+            Some((_off, len)) if len > 0 && len <= 4 => Ok(len),
+            _ => Err(VecError::InvalidVar(var_id)),
+        }
+    }
+
+    /// Reads the length of vector variable `var_id` (validated by `vec_var_len`).
+    fn read_vec_len(&self, var_id: VarId) -> u32 {
+        let bytes = self.read_var(var_id);
+
+        let mut buf = [0; 4];
+        buf[..bytes.len()].copy_from_slice(&bytes);
+
+        u32::from_le_bytes(buf)
+    }
+
+    /// Creates a new (nested) savepoint and returns its id.
+    ///
+    /// The uncommitted changes made after the savepoint can be reverted using `rollback_to`.
+    /// Reads always see the latest uncommitted values (i.e of the innermost savepoint).
+    pub fn savepoint(&mut self) -> SavepointId {
+        let id = self.next_savepoint_id;
+        self.next_savepoint_id += 1;
+
+        self.savepoints.push(Savepoint::new(id));
+
+        id
+    }
+
+    /// Reverts the uncommitted changes made since savepoint `id` was created.
+    ///
+    /// Savepoint `id` and the savepoints nested in it are released.
+    /// Returns `false` (and does nothing) when there is no open savepoint `id`.
+    pub fn rollback_to(&mut self, id: SavepointId) -> bool {
+        if !self.has_savepoint(id) {
+            return false;
+        }
+
+        while let Some(savepoint) = self.savepoints.pop() {
+            for (var_id, prev) in savepoint.vars {
+                match prev {
+                    Some(value) => self.uncommitted.insert(var_id, value),
+                    None => self.uncommitted.remove(&var_id),
+                };
+            }
+
+            for (key, prev) in savepoint.entries {
+                match prev {
+                    Some(value) => self.uncommitted_entries.insert(key, value),
+                    None => self.uncommitted_entries.remove(&key),
+                };
+            }
+
+            if savepoint.id == id {
+                break;
+            }
+        }
+
+        true
+    }
+
+    /// Releases savepoint `id` (and the savepoints nested in it) keeping the changes made since its creation.
+    ///
+    /// The changes can still be reverted by rolling back to an enclosing savepoint.
+    /// Returns `false` (and does nothing) when there is no open savepoint `id`.
+    pub fn release(&mut self, id: SavepointId) -> bool {
+        if !self.has_savepoint(id) {
+            return false;
+        }
+
+        while let Some(savepoint) = self.savepoints.pop() {
+            let released = savepoint.id == id;
+
+            if let Some(outer) = self.savepoints.last_mut() {
+                outer.merge(savepoint);
+            }
+
+            if released {
+                break;
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether there is an open savepoint `id`.
+    pub fn has_savepoint(&self, id: SavepointId) -> bool {
+        self.savepoints.iter().any(|savepoint| savepoint.id == id)
+    }
+
+    /// Returns the app's state root.
+    ///
+    /// The root commits to a sparse Merkle tree over the app's storage pages (see `KV_VALUE_SIZE`)
+    /// and to a sparse Merkle tree over the app's maps and vectors entries.
+    /// So it's determined solely by the app's variables values and entries (any node can recompute it).
+    /// It reflects only the committed data.
+    #[inline]
+    pub fn root(&self) -> State {
+        self.raw_storage.root()
+    }
+
+    /// Returns a proof for the committed value of variable `var_id` against the current `root`.
+    ///
+    /// The proof can be checked using `verify` (without access to the app's storage).
+    pub fn prove(&self, var_id: VarId) -> StateProof {
+        let (off, len) = self.var_layout(var_id);
+
+        self.raw_storage.prove(off, len)
+    }
+
+    /// Returns whether entries have ever been written under variable `var_id` (i.e it's a map or a vector).
+    ///
+    /// Entries written and then rolled back (see `rollback_to`) still count.
+    pub fn has_entries(&self, var_id: VarId) -> bool {
+        self.uncommitted_dynamic_vars.contains(&var_id)
+            || self
+                .raw_storage
+                .get_meta(&dynamic_var_key(var_id))
+                .is_some()
+    }
+
+    /// Returns the changes of the modified (not yet committed) variables, ordered by `var_id`.
+    ///
+    /// Each change holds the variable's committed (old) value alongside its modified (new) value,
+    /// so it should be called before `commit`.
+    pub fn changes(&self) -> Vec<StorageChange> {
+        let mut changes = self
             .uncommitted
-            .keys()
-            .map(|var_id| {
-                let (off, _len) = self.var_layout(*var_id);
+            .iter()
+            .map(|(var_id, new_value)| {
+                let (off, len) = self.var_layout(*var_id);
+                let old_value = self.raw_storage.read(off, len);
 
-                (*var_id, off)
+                StorageChange {
+                    var_id: var_id.0,
+                    old_value,
+                    new_value: new_value.clone(),
+                }
             })
-            .collect();
+            .collect::<Vec<_>>();
 
-        let changes = self
-            .uncommitted
-            .drain()
+        changes.sort_by_key(|change| change.var_id);
+
+        changes
+    }
+
+    /// Returns the storage occupied by the app (reflects only the committed data).
+    #[inline]
+    pub fn usage(&self) -> StorageUsage {
+        self.raw_storage.usage()
+    }
+
+    /// Returns the storage the app would occupy after committing the modified variables (and entries).
+    pub fn pending_usage(&self) -> StorageUsage {
+        let changes = self.raw_changes();
+        let entries = self
+            .uncommitted_entries
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>();
+
+        self.raw_storage.usage_after_write(&changes, &entries)
+    }
+
+    /// Commits modified variables (and maps and vectors entries) into the raw storage.
+    ///
+    /// The app's state root (see `root`) and storage usage (see `usage`) are updated alongside,
+    /// and any open savepoints are released.
+    ///
+    /// Returns the new `State` of the application (i.e its new state root).
+    #[must_use]
+    pub fn commit(&mut self) -> State {
+        let changes = self.raw_changes();
+        let entries = self.uncommitted_entries.drain().collect::<Vec<_>>();
+
+        for var_id in self.uncommitted_dynamic_vars.drain() {
+            self.raw_storage.set_meta(&dynamic_var_key(var_id), &[1]);
+        }
+
+        self.uncommitted.clear();
+        self.savepoints.clear();
+
+        self.raw_storage.write(&changes, &entries);
+
+        self.root()
+    }
+
+    /// Migrates the app's storage from its current layout to a new one (see `LayoutMigration`).
+    ///
+    /// All the variables of the new layout are rewritten (alongside any uncommitted changes)
+    /// under a single commit, and from then on the storage is managed by the new layout.
+    ///
+    /// Returns the new `State` of the application (see `commit`).
+    ///
+    /// The entries of a map (or a vector) are stored under sub-keys derived from its `VarId`,
+    /// so a migration moving a variable having entries (see `has_entries`) to another `VarId` is rejected
+    /// (and the storage is left untouched).
+    ///
+    /// # Panics
+    ///
+    /// Panics when the current layout isn't the migration old layout.
+    pub fn migrate(&mut self, migration: &LayoutMigration) -> Result<State, MigrationError> {
+        assert_eq!(&self.layout, migration.old_layout());
+
+        for (old_var, _off, _len) in self.layout.iter() {
+            let new_var = migration.target_of(old_var);
+
+            if new_var != old_var && self.has_entries(old_var) {
+                return Err(MigrationError::DynamicVarMoved { old_var, new_var });
+            }
+        }
+
+        let old_vars = self
+            .layout
+            .iter()
+            .map(|(var_id, _off, _len)| (var_id, self.read_var(var_id)))
+            .collect::<HashMap<_, _>>();
+
+        self.layout = migration.new_layout().clone();
+        self.uncommitted.clear();
+
+        for (new_var, _off, len) in migration.new_layout().iter() {
+            let mut value = vec![0; len as usize];
+
+            if let Some(old_var) = migration.source_of(new_var) {
+                let old_value = &old_vars[&old_var];
+
+                value[..old_value.len()].copy_from_slice(old_value);
+            }
+
+            self.uncommitted.insert(new_var, value);
+        }
+
+        Ok(self.commit())
+    }
+
+    fn raw_changes(&self) -> Vec<RawChange> {
+        self.uncommitted
+            .iter()
             .map(|(var_id, data)| {
-                let offset = *var_offset.get(&var_id).unwrap();
+                let (offset, _len) = self.var_layout(*var_id);
 
-                RawChange { offset, data }
+                RawChange {
+                    offset,
+                    data: data.clone(),
+                }
             })
-            .collect::<Vec<_>>();
+            .collect()
+    }
 
-        self.raw_storage.write(&changes);
+    fn write_entry(&mut self, var_id: VarId, key: Vec<u8>, value: Vec<u8>) {
+        self.uncommitted_dynamic_vars.insert(var_id);
 
-        debug_assert!(self.uncommitted.is_empty());
+        let prev = self.uncommitted_entries.insert(key.clone(), value);
 
-        self.raw_storage.head()
+        if let Some(savepoint) = self.savepoints.last_mut() {
+            savepoint.record_entry(&key, prev);
+        }
     }
+
+    fn read_entry(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.uncommitted_entries.get(key).cloned();
+
+        value.or_else(|| self.raw_storage.read_entry(key))
+    }
+}
+
+/// The bookkeeping key marking `var_id` as a variable having entries (see `AppStorage::has_entries`).
+fn dynamic_var_key(var_id: VarId) -> [u8; 8] {
+    let mut key = [0; 8];
+
+    key[..4].copy_from_slice(b"dyn!");
+    key[4..].copy_from_slice(&var_id.0.to_be_bytes());
+
+    key
+}
+
+/// Derives the sub-key of entry `key` of the dynamic-size variable `var_id`.
+///
+/// The `tag` separates the entries of maps from the entries of vectors.
+fn entry_key(tag: u8, var_id: VarId, key: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(1 + 4 + key.len());
+
+    buf.push(tag);
+    buf.extend_from_slice(&var_id.0.to_be_bytes());
+    buf.extend_from_slice(key);
+
+    DefaultKeyHasher::hash(&buf).to_vec()
 }
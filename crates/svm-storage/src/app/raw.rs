@@ -1,13 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use svm_types::State;
 
-use super::AppKVStore;
+use super::merkle::{self, PageProof, StateProof, EMPTY_HASH, ENTRIES_TREE_DEPTH, TREE_DEPTH};
+use super::{AppKVStore, StorageUsage};
 
 use crate::kv::StatefulKV;
 
 /// Interface against the key-value store.
 /// Data is manipulated using `offset` and `length`.
+///
+/// The data is laid out as consecutive pages of `kv_value_size` bytes each.
+/// Page `i` holds the offsets `[i * kv_value_size, (i + 1) * kv_value_size)`
+/// and it's stored under key `i` (a Big-Endian `u32`).
+///
+/// Next to the pages, the nodes of a sparse Merkle tree over the pages are stored as well
+/// (see the `merkle` module), and so are the nodes of a sparse Merkle tree over the entries.
+/// Both trees are updated on each `write`, and their roots make up the app's state root (see `root`).
+///
+/// Each `write` creates a single key-value store checkpoint. The roots aren't indexed by their checkpoints,
+/// so the storage is rewound using the key-value store `State`(s) (see `rewind`).
+///
+/// The app's `StorageUsage` is stored as well (under its own key), and it's updated on each `write`.
 pub struct RawStorage {
     app_kv: AppKVStore,
 
@@ -25,7 +39,6 @@ pub struct RawChange {
 
 impl RawChange {
     /// The length of change's `data`
-    #[allow(unused)]
     pub fn len(&self) -> u32 {
         self.data.len() as u32
     }
@@ -40,11 +53,19 @@ impl RawStorage {
         }
     }
 
+    /// Rewinds the underlying key-value store to `state` (see `head`).
     #[inline]
     pub fn rewind(&mut self, state: &State) {
         self.app_kv.rewind(state)
     }
 
+    /// Prunes the underlying key-value store, keeping only the `keep` states (and its `head`).
+    #[inline]
+    pub fn prune(&mut self, keep: &[State]) {
+        self.app_kv.prune(keep)
+    }
+
+    /// Returns the current `State` of the underlying key-value store.
     #[inline]
     pub fn head(&self) -> State {
         self.app_kv.head()
@@ -52,116 +73,400 @@ impl RawStorage {
 
     /// Reads the raw data under `offset, offset + 1, ..., offset + length - 1`
     /// In case there is no stored blob, returns a zeros vector of length `length`.
+    ///
+    /// The data may span multiple pages (each page is stored under its own key).
     pub fn read(&self, offset: u32, length: u32) -> Vec<u8> {
-        assert!(length <= self.kv_value_size);
+        let mut data = Vec::with_capacity(length as usize);
 
-        let key = self.offset_length_key(offset, length);
-        let value = self.do_read_key(key);
+        for (page, page_off, len) in self.page_slices(offset, length) {
+            let value = self.read_page(page);
 
-        let slice = self.value_slice(&value[..], offset, length);
-        slice.to_vec()
+            let start = page_off as usize;
+            let end = start + len as usize;
+
+            data.extend_from_slice(&value[start..end]);
+        }
+
+        debug_assert_eq!(data.len(), length as usize);
+
+        data
     }
 
-    /// Write a batch of changes into underlying key-value store.
-    pub fn write(&mut self, changes: &[RawChange]) {
-        let changes = self.group_changes_by_key(changes);
+    /// Sets the bookkeeping `value` under `key` (it's persisted by the next `write`).
+    ///
+    /// Unlike entries, bookkeeping values are part of neither the Merkle trees nor the `StorageUsage`.
+    /// Their keys should be 8 bytes long, so they can't collide with any of the other keys.
+    #[inline]
+    pub fn set_meta(&mut self, key: &[u8; 8], value: &[u8]) {
+        self.app_kv.set(key, value);
+    }
 
-        let mut raw_changes = Vec::with_capacity(changes.len());
+    /// Reads the bookkeeping value stored under `key` (see `set_meta`).
+    #[inline]
+    pub fn get_meta(&self, key: &[u8; 8]) -> Option<Vec<u8>> {
+        self.app_kv.get(key)
+    }
+
+    /// Reads the raw entry stored under `key` (`None` when there is no such entry).
+    ///
+    /// Entries hold the data of dynamic-size variables (see `write`).
+    #[inline]
+    pub fn read_entry(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.app_kv.get(key)
+    }
+
+    /// Write a batch of changes along with a batch of raw `(key, value)` entries
+    /// into underlying key-value store.
+    ///
+    /// Both are persisted under the same checkpoint.
+    pub fn write(&mut self, changes: &[RawChange], entries: &[(Vec<u8>, Vec<u8>)]) {
+        let pages = self.patch_pages(changes);
+        let usage = self.usage_after(&pages, entries);
 
-        for (key, value_changes) in changes.iter() {
-            let raw_key = key.to_be_bytes();
+        let mut nodes = HashMap::new();
 
-            let mut raw_value = self.do_read_key(*key);
-            debug_assert_eq!(raw_value.len(), self.kv_value_size as usize);
+        for (page, _old, new) in pages.iter() {
+            self.update_path(&mut nodes, *page, new);
+        }
 
-            self.patch_value(&mut raw_value, &value_changes[..]);
+        let mut entry_nodes = HashMap::new();
 
-            raw_changes.push((raw_key, raw_value));
+        for (k, v) in entries.iter() {
+            self.update_entry_path(&mut entry_nodes, k, v);
         }
 
-        for (k, v) in raw_changes.iter() {
+        for (page, _old, new) in pages.iter() {
+            self.app_kv.set(&page.to_be_bytes(), new);
+        }
+
+        for ((level, index), hash) in nodes.iter() {
+            let key = node_key(*level, *index);
+
+            self.app_kv.set(&key, hash);
+        }
+
+        for ((level, index), hash) in entry_nodes.iter() {
+            let key = entry_node_key(*level, index);
+
+            self.app_kv.set(&key, hash);
+        }
+
+        for (k, v) in entries.iter() {
             self.app_kv.set(k, v);
         }
 
+        self.app_kv.set(USAGE_KEY, &usage.encode());
+
         let _state = self.app_kv.checkpoint();
 
         self.app_kv.flush();
     }
 
-    #[inline]
-    fn do_read_key(&self, key: u32) -> Vec<u8> {
-        let key = key.to_be_bytes();
+    /// Returns the current storage usage (reflects only the written data).
+    pub fn usage(&self) -> StorageUsage {
+        match self.app_kv.get(USAGE_KEY) {
+            Some(bytes) => StorageUsage::decode(&bytes),
+            None => StorageUsage::default(),
+        }
+    }
 
-        self.app_kv
-            .get(&key[..])
-            .unwrap_or(vec![0; self.kv_value_size as usize])
+    /// Returns the storage usage as it would be after calling `write` with the given `changes` and `entries`.
+    pub fn usage_after_write(
+        &self,
+        changes: &[RawChange],
+        entries: &[(Vec<u8>, Vec<u8>)],
+    ) -> StorageUsage {
+        let pages = self.patch_pages(changes);
+
+        self.usage_after(&pages, entries)
+    }
+
+    /// Returns the app's state root, derived from the roots of the pages tree and the entries tree
+    /// (reflects only the written data).
+    pub fn root(&self) -> State {
+        let pages_root = self.read_node(TREE_DEPTH, 0);
+        let entries_root = self.entries_root();
+
+        let root = merkle::state_root(&pages_root, &entries_root);
+
+        State::from(&root[..])
+    }
+
+    /// Returns a proof for the data under `offset, offset + 1, ..., offset + length - 1`
+    /// against the current `root`.
+    pub fn prove(&self, offset: u32, length: u32) -> StateProof {
+        let pages = self
+            .page_slices(offset, length)
+            .into_iter()
+            .map(|(page, _page_off, _len)| {
+                let siblings = (0..TREE_DEPTH)
+                    .map(|level| {
+                        let index = merkle::node_index(page, level);
+
+                        self.read_node(level, index ^ 1)
+                    })
+                    .collect();
+
+                PageProof {
+                    page,
+                    value: self.read_page(page),
+                    siblings,
+                }
+            })
+            .collect();
+
+        StateProof {
+            pages,
+            entries_root: self.entries_root(),
+        }
     }
 
     #[inline]
-    fn value_slice<'k>(&self, value: &'k [u8], offset: u32, length: u32) -> &'k [u8] {
-        let offset = offset as usize;
-        let length = length as usize;
+    fn read_page(&self, page: u32) -> Vec<u8> {
+        let key = page.to_be_bytes();
 
-        let value = &value[offset..offset + length];
-        debug_assert_eq!(value.len(), length);
+        let value = self
+            .app_kv
+            .get(&key[..])
+            .unwrap_or_else(|| vec![0; self.kv_value_size as usize]);
+
+        debug_assert_eq!(value.len(), self.kv_value_size as usize);
 
         value
     }
 
+    /// Recomputes the Merkle tree nodes along the path of page `page` (now holding `value`).
+    ///
+    /// The updated nodes are accumulated into `nodes`, so that subsequent pages
+    /// of the same batch see them.
+    fn update_path(&self, nodes: &mut HashMap<(u32, u32), [u8; 32]>, page: u32, value: &[u8]) {
+        let mut hash = merkle::leaf_hash(page, value);
+
+        nodes.insert((0, page), hash);
+
+        for level in 0..TREE_DEPTH {
+            let index = merkle::node_index(page, level);
+
+            let sibling = match nodes.get(&(level, index ^ 1)) {
+                Some(sibling) => *sibling,
+                None => self.read_node(level, index ^ 1),
+            };
+
+            hash = if index & 1 == 1 {
+                merkle::node_hash(&sibling, &hash)
+            } else {
+                merkle::node_hash(&hash, &sibling)
+            };
+
+            nodes.insert((level + 1, merkle::node_index(page, level + 1)), hash);
+        }
+    }
+
+    /// Recomputes the entries tree nodes along the path of the entry under sub-key `key` (now holding `value`).
+    ///
+    /// Similar to `update_path`, the updated nodes are accumulated into `nodes`.
+    fn update_entry_path(
+        &self,
+        nodes: &mut HashMap<(u32, [u8; 32]), [u8; 32]>,
+        key: &[u8],
+        value: &[u8],
+    ) {
+        let mut path = [0; 32];
+        path.copy_from_slice(key);
+
+        let mut hash = merkle::entry_leaf_hash(key, value);
+
+        nodes.insert((0, path), hash);
+
+        for level in 0..ENTRIES_TREE_DEPTH {
+            let index = merkle::entry_node_index(&path, level);
+
+            let mut sibling_index = index;
+            sibling_index[31] ^= 1;
+
+            let sibling = match nodes.get(&(level, sibling_index)) {
+                Some(sibling) => *sibling,
+                None => self.read_entry_node(level, &sibling_index),
+            };
+
+            hash = if index[31] & 1 == 1 {
+                merkle::node_hash(&sibling, &hash)
+            } else {
+                merkle::node_hash(&hash, &sibling)
+            };
+
+            let parent = merkle::entry_node_index(&path, level + 1);
+
+            nodes.insert((level + 1, parent), hash);
+        }
+    }
+
     #[inline]
-    fn patch_value(&self, value: &mut [u8], changes: &[&RawChange]) {
-        debug_assert_eq!(value.len(), self.kv_value_size as usize);
+    fn entries_root(&self) -> [u8; 32] {
+        self.read_entry_node(ENTRIES_TREE_DEPTH, &[0; 32])
+    }
 
-        for change in changes.iter() {
-            unsafe {
-                let src = change.data.as_ptr();
-                let dst = value.as_mut_ptr().offset(change.offset as isize);
-                let count = change.data.len() as usize;
+    fn read_entry_node(&self, level: u32, index: &[u8; 32]) -> [u8; 32] {
+        let key = entry_node_key(level, index);
+
+        self.read_hash(&key)
+    }
 
-                std::ptr::copy(src, dst, count)
+    fn read_node(&self, level: u32, index: u32) -> [u8; 32] {
+        let key = node_key(level, index);
+
+        self.read_hash(&key)
+    }
+
+    fn read_hash(&self, key: &[u8]) -> [u8; 32] {
+        match self.app_kv.get(key) {
+            Some(bytes) => {
+                let mut hash = EMPTY_HASH;
+                hash.copy_from_slice(&bytes);
+
+                hash
             }
+            None => EMPTY_HASH,
         }
     }
 
     #[inline]
-    pub fn offset_length_key(&self, offset: u32, length: u32) -> u32 {
-        let end_off = offset + length - 1;
+    fn page_slices(&self, offset: u32, length: u32) -> Vec<(u32, u32, u32)> {
+        merkle::page_slices(offset, length, self.kv_value_size)
+    }
 
-        end_off % self.kv_value_size
+    /// Applies the `changes` to the pages they touch.
+    ///
+    /// Returns for each touched page a tuple of `(page, old value, new value)` (ordered by page).
+    fn patch_pages(&self, changes: &[RawChange]) -> Vec<(u32, Vec<u8>, Vec<u8>)> {
+        self.group_changes_by_page(changes)
+            .into_iter()
+            .map(|(page, patches)| {
+                let old = self.read_page(page);
+                let mut new = old.clone();
+
+                for (page_off, data) in patches.iter() {
+                    let start = *page_off as usize;
+                    let end = start + data.len();
+
+                    new[start..end].copy_from_slice(data);
+                }
+
+                (page, old, new)
+            })
+            .collect()
     }
 
-    #[inline]
-    fn change_key(&self, change: &RawChange) -> u32 {
-        let length = change.data.len() as u32;
-        debug_assert!(length <= self.kv_value_size);
+    fn usage_after(
+        &self,
+        pages: &[(u32, Vec<u8>, Vec<u8>)],
+        entries: &[(Vec<u8>, Vec<u8>)],
+    ) -> StorageUsage {
+        let mut usage = self.usage();
+
+        let page_size = self.kv_value_size as u64;
+
+        for (_page, old, new) in pages.iter() {
+            match (is_empty(old), is_empty(new)) {
+                (true, false) => {
+                    usage.pages += 1;
+                    usage.bytes += page_size;
+                }
+                (false, true) => {
+                    usage.pages -= 1;
+                    usage.bytes -= page_size;
+                }
+                _ => (),
+            }
+        }
 
-        self.offset_length_key(change.offset, length)
+        for (k, v) in entries.iter() {
+            match self.read_entry(k) {
+                None => {
+                    usage.entries += 1;
+                    usage.bytes += (k.len() + v.len()) as u64;
+                }
+                Some(old) => {
+                    usage.bytes -= old.len() as u64;
+                    usage.bytes += v.len() as u64;
+                }
+            }
+        }
+
+        usage
     }
 
-    #[inline]
-    fn group_changes_by_key<'a>(
+    /// Groups the `changes` by the pages they touch.
+    ///
+    /// A change spanning multiple pages is split into multiple page patches.
+    /// The patches of each page preserve the order of the `changes`.
+    fn group_changes_by_page<'a>(
         &self,
         changes: &'a [RawChange],
-    ) -> HashMap<u32, Vec<&'a RawChange>> {
-        let tuples: Vec<_> = changes.iter().map(|c| (self.change_key(c), c)).collect();
+    ) -> BTreeMap<u32, Vec<(u32, &'a [u8])>> {
+        let mut pages: BTreeMap<u32, Vec<(u32, &'a [u8])>> = BTreeMap::new();
 
-        let mut key_changes = HashMap::new();
+        for change in changes.iter() {
+            let mut start = 0;
+
+            for (page, page_off, len) in self.page_slices(change.offset, change.len()) {
+                let end = start + len as usize;
+                let data = &change.data[start..end];
 
-        for &(key, change) in tuples.iter() {
-            let entry = key_changes.entry(key).or_insert(Vec::new());
+                pages.entry(page).or_default().push((page_off, data));
 
-            entry.push(change);
+                start = end;
+            }
         }
 
-        key_changes
+        pages
     }
 }
 
+/// The key of the `StorageUsage`.
+///
+/// The key is 6 bytes long, so it can't collide with the pages keys (4 bytes),
+/// the Merkle tree nodes keys (5 bytes) nor with the dynamic-size variables entries keys (32 bytes).
+const USAGE_KEY: &[u8] = b"usage!";
+
+#[inline]
+fn is_empty(page: &[u8]) -> bool {
+    page.iter().all(|&b| b == 0)
+}
+
+/// The key of the Merkle tree node `index` at level `level`.
+///
+/// The key is 5 bytes long, so it can't collide with the pages keys (4 bytes)
+/// nor with the dynamic-size variables entries keys (32 bytes).
+fn node_key(level: u32, index: u32) -> [u8; 5] {
+    let mut key = [0; 5];
+
+    key[0] = level as u8;
+    key[1..].copy_from_slice(&index.to_be_bytes());
+
+    key
+}
+
+/// The key of the entries Merkle tree node `index` at level `level`.
+///
+/// The key is 34 bytes long, so it can't collide with any of the other keys.
+fn entry_node_key(level: u32, index: &[u8; 32]) -> [u8; 34] {
+    let mut key = [0; 34];
+
+    key[..2].copy_from_slice(&(level as u16).to_be_bytes());
+    key[2..].copy_from_slice(index);
+
+    key
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::testing;
 
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
     use svm_types::Address;
 
     const KV_VALUE_SIZE: u32 = 32;
@@ -198,7 +503,7 @@ mod tests {
         let changes = vec![var1.clone(), var2.clone()];
 
         let mut storage = RawStorage::new(kv, KV_VALUE_SIZE);
-        storage.write(&changes);
+        storage.write(&changes, &[]);
 
         let data1 = storage.read(var1.offset, var1.len());
         assert_eq!(data1, vec![0x10, 0x20, 0x30]);
@@ -206,4 +511,92 @@ mod tests {
         let data2 = storage.read(var2.offset, var2.len());
         assert_eq!(data2, vec![0x40, 0x50]);
     }
+
+    #[test]
+    fn raw_storage_vars_spanning_pages() {
+        let addr = Address::of("my-app");
+        let kv = testing::create_app_kv(addr);
+
+        // `var1` resides in page `#0`, `var2` spans pages `#0` and `#1`,
+        // and `var3` resides in page `#2`
+        let var1 = RawChange {
+            offset: 0,
+            data: vec![0x10; 4],
+        };
+
+        let var2 = RawChange {
+            offset: 30,
+            data: vec![0x20; 8],
+        };
+
+        let var3 = RawChange {
+            offset: 67,
+            data: vec![0x30],
+        };
+
+        let changes = vec![var1.clone(), var2.clone(), var3.clone()];
+
+        let mut storage = RawStorage::new(kv, KV_VALUE_SIZE);
+        storage.write(&changes, &[]);
+
+        assert_eq!(storage.read(var1.offset, var1.len()), var1.data);
+        assert_eq!(storage.read(var2.offset, var2.len()), var2.data);
+        assert_eq!(storage.read(var3.offset, var3.len()), var3.data);
+
+        // untouched offsets remain zeros
+        assert_eq!(storage.read(4, 26), vec![0; 26]);
+        assert_eq!(storage.read(38, 29), vec![0; 29]);
+    }
+
+    const MAX_OFFSET: u32 = 4 * KV_VALUE_SIZE;
+
+    fn change_strategy() -> impl Strategy<Value = RawChange> {
+        (0..MAX_OFFSET)
+            .prop_flat_map(|offset| {
+                let max_len = (MAX_OFFSET - offset) as usize;
+
+                (Just(offset), vec(any::<u8>(), 1..=max_len))
+            })
+            .prop_map(|(offset, data)| RawChange { offset, data })
+    }
+
+    fn batches_strategy() -> impl Strategy<Value = Vec<Vec<RawChange>>> {
+        vec(vec(change_strategy(), 0..8), 1..4)
+    }
+
+    proptest! {
+        #[test]
+        fn raw_storage_matches_flat_bytes_model(
+            batches in batches_strategy(),
+            reads in vec((0..MAX_OFFSET, 0..MAX_OFFSET), 0..16)
+        ) {
+            let addr = Address::of("my-app");
+            let kv = testing::create_app_kv(addr);
+
+            let mut storage = RawStorage::new(kv, KV_VALUE_SIZE);
+            let mut model = vec![0u8; MAX_OFFSET as usize];
+
+            for changes in batches.iter() {
+                storage.write(changes, &[]);
+
+                for change in changes.iter() {
+                    let start = change.offset as usize;
+                    let end = start + change.data.len();
+
+                    model[start..end].copy_from_slice(&change.data);
+                }
+            }
+
+            prop_assert_eq!(storage.read(0, MAX_OFFSET), model.clone());
+
+            for (offset, length) in reads {
+                let length = length.min(MAX_OFFSET - offset);
+
+                let start = offset as usize;
+                let end = start + length as usize;
+
+                prop_assert_eq!(storage.read(offset, length), model[start..end].to_vec());
+            }
+        }
+    }
 }
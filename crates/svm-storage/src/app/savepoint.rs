@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use svm_layout::VarId;
+
+/// The identifier of an `AppStorage` savepoint.
+pub type SavepointId = u32;
+
+/// An undo-log of the uncommitted changes made since a savepoint was created.
+///
+/// For each variable (and map or vector entry) modified since the savepoint, the log holds its uncommitted
+/// value at the time of the savepoint (`None` when it had no uncommitted value then).
+#[derive(Debug)]
+pub(crate) struct Savepoint {
+    pub(crate) id: SavepointId,
+
+    pub(crate) vars: HashMap<VarId, Option<Vec<u8>>>,
+
+    pub(crate) entries: HashMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl Savepoint {
+    pub(crate) fn new(id: SavepointId) -> Self {
+        Self {
+            id,
+            vars: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Records the uncommitted value of `var_id` (only the first recording since the savepoint is kept).
+    pub(crate) fn record_var(&mut self, var_id: VarId, prev: Option<Vec<u8>>) {
+        self.vars.entry(var_id).or_insert(prev);
+    }
+
+    /// Records the uncommitted value of entry `key` (only the first recording since the savepoint is kept).
+    pub(crate) fn record_entry(&mut self, key: &[u8], prev: Option<Vec<u8>>) {
+        if !self.entries.contains_key(key) {
+            self.entries.insert(key.to_vec(), prev);
+        }
+    }
+
+    /// Merges the undo-log of `inner` (a savepoint created after `self`) into `self`.
+    ///
+    /// The values recorded by `self` take precedence since they are older.
+    pub(crate) fn merge(&mut self, inner: Savepoint) {
+        for (var_id, prev) in inner.vars {
+            self.record_var(var_id, prev);
+        }
+
+        for (key, prev) in inner.entries {
+            self.entries.entry(key).or_insert(prev);
+        }
+    }
+}
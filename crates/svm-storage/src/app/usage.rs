@@ -0,0 +1,47 @@
+/// The storage occupied by an app.
+///
+/// Maintained by the app's `RawStorage` on each commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StorageUsage {
+    /// The number of non-empty pages (pages holding a non-zero byte) of the fixed-size variables
+    pub pages: u32,
+
+    /// The number of maps and vectors entries
+    pub entries: u32,
+
+    /// The total byte-size: the non-empty pages bytes plus the entries keys and values bytes
+    pub bytes: u64,
+}
+
+impl StorageUsage {
+    /// The byte-length of an encoded `StorageUsage`
+    pub(crate) const ENCODED_LEN: usize = 4 + 4 + 8;
+
+    pub(crate) fn encode(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut buf = [0; Self::ENCODED_LEN];
+
+        buf[0..4].copy_from_slice(&self.pages.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.entries.to_be_bytes());
+        buf[8..16].copy_from_slice(&self.bytes.to_be_bytes());
+
+        buf
+    }
+
+    pub(crate) fn decode(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), Self::ENCODED_LEN);
+
+        let mut pages = [0; 4];
+        let mut entries = [0; 4];
+        let mut nbytes = [0; 8];
+
+        pages.copy_from_slice(&bytes[0..4]);
+        entries.copy_from_slice(&bytes[4..8]);
+        nbytes.copy_from_slice(&bytes[8..16]);
+
+        Self {
+            pages: u32::from_be_bytes(pages),
+            entries: u32::from_be_bytes(entries),
+            bytes: u64::from_be_bytes(nbytes),
+        }
+    }
+}
@@ -126,6 +126,13 @@ impl StatefulKV for ExternKV {
         // since it's the role of the `Host` to manage to do the rewind.
     }
 
+    fn prune(&mut self, _keep: &[State]) {
+        // Do nothing.
+        //
+        // Similar to `flush` and `rewind`, it's the role of the `Host`
+        // to decide on which data to retain.
+    }
+
     #[must_use]
     fn head(&self) -> State {
         unsafe {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use super::super::StatefulKV;
@@ -52,6 +52,13 @@ use svm_types::State;
 /// If we find a matching value we halt and return the found value.
 /// If we've reached the end of the journal then we move to the `unflushed` (see detailed explanation above).
 ///
+/// ## Pruning
+///
+/// Calling `prune` drops the `flushed` nodes of the non-kept states. The data of a dropped node is merged into
+/// its nearest kept descendant (unless overridden there), so the kept states are left readable.
+///
+/// Every flushed `State` is retained until `prune` is called (see `Retention`).
+///
 pub struct FakeKV {
     head: State,
 
@@ -146,6 +153,30 @@ impl StatefulKV for FakeKV {
         self.flushed_head = self.head();
     }
 
+    fn prune(&mut self, keep: &[State]) {
+        self.assert_journal_empty();
+
+        let mut kept: HashSet<State> = keep
+            .iter()
+            .filter(|state| self.flushed.contains_key(state))
+            .cloned()
+            .collect();
+
+        if !self.head.is_zeros() {
+            kept.insert(self.head.clone());
+        }
+
+        let mut flushed = HashMap::with_capacity(kept.len());
+
+        for state in kept.iter() {
+            let node = self.compact_node(state, &kept);
+
+            flushed.insert(state.clone(), node);
+        }
+
+        self.flushed = flushed;
+    }
+
     #[must_use]
     fn head(&self) -> State {
         self.head.clone()
@@ -163,6 +194,38 @@ impl FakeKV {
         }
     }
 
+    /// Returns the number of persisted states.
+    pub fn flushed_count(&self) -> usize {
+        self.flushed.len()
+    }
+
+    /// Merges the data of node `state` with the data of its non-kept ancestors.
+    ///
+    /// The merged node parent is the nearest kept ancestor (or the zero `State` when there is none).
+    fn compact_node(&self, state: &State, kept: &HashSet<State>) -> Node {
+        let mut data = HashMap::new();
+        let mut current = state;
+
+        loop {
+            let node = self.flushed.get(current).unwrap();
+
+            for (k, v) in node.data.iter() {
+                data.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+
+            let parent = &node.parent;
+
+            if parent.is_zeros() || kept.contains(parent) {
+                return Node {
+                    parent: parent.clone(),
+                    data,
+                };
+            }
+
+            current = parent;
+        }
+    }
+
     fn get_journal(&self, key: &[u8]) -> Option<Vec<u8>> {
         for (_state, changes) in self.journal.iter().rev() {
             for change in changes.iter().rev() {
@@ -525,4 +588,52 @@ mod tests {
           (k3 => v3),
         );
     }
+
+    #[test]
+    fn fake_kv_prune() {
+        let mut kv = FakeKV::new();
+
+        let (k1, v1) = (b"aaa", vec![0x10, 0x20]);
+        let (k2, v2) = (b"bbb", vec![0x30, 0x40, 0x50]);
+        let (k3, v3) = (b"ccc", vec![0x60, 0x70]);
+        let (k4, v4) = (b"aaa", vec![0x60, 0x70]);
+
+        let s1 = apply_changes!(kv,
+          (k1 => v1),
+        );
+
+        let _s2 = apply_changes!(kv,
+          (k2 => v2),
+        );
+
+        let s3 = apply_changes!(kv,
+          (k3 => v3),
+          (k1 => v4),
+        );
+
+        assert_eq!(kv.flushed_count(), 3);
+
+        // `s3` is the `head`, so it's kept implicitly
+        kv.prune(&[s1.clone()]);
+        assert_eq!(kv.flushed_count(), 2);
+
+        // `s2` data has been merged into `s3`
+        assert_transition!(kv, s1 => s3);
+        assert_keys!(kv,
+          (k1 => v4),
+          (k2 => v2),
+          (k3 => v3),
+        );
+
+        kv.rewind(&s1);
+        assert_keys!(kv, (k1 => v1),);
+        assert_no_keys!(kv, k2, k3);
+
+        // now, `s3` isn't kept anymore
+        kv.prune(&[]);
+        assert_eq!(kv.flushed_count(), 1);
+
+        assert_keys!(kv, (k1 => v1),);
+        assert_no_keys!(kv, k2, k3);
+    }
 }
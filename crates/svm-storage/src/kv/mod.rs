@@ -1,7 +1,14 @@
 mod ffi;
 mod mock;
+mod persistent;
+mod retention;
 mod traits;
 
 pub use ffi::ExternKV;
 pub use mock::FakeKV;
+pub use persistent::PersistentKV;
+pub use retention::Retention;
 pub use traits::StatefulKV;
+
+#[cfg(feature = "default-rocksdb")]
+pub use persistent::RocksdbStatefulKV;
@@ -0,0 +1,993 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::StatefulKV;
+
+use svm_common::{fmt::fmt_hex, DefaultKeyHasher, KeyHasher};
+use svm_kv::traits::RawKV;
+use svm_types::State;
+
+#[cfg(feature = "default-rocksdb")]
+use std::{io, path::Path};
+
+#[cfg(feature = "default-rocksdb")]
+use svm_kv::rocksdb::Rocksdb;
+
+/// A `PersistentKV` backed by `rocksdb` (see `svm_kv::rocksdb::Rocksdb`).
+#[cfg(feature = "default-rocksdb")]
+pub type RocksdbStatefulKV = PersistentKV<Rocksdb>;
+
+type Change = (Vec<u8>, Vec<u8>);
+
+const HEAD_KEY: &[u8] = b"head";
+
+const STATES_KEY: &[u8] = b"states";
+
+const NODE_PREFIX: u8 = b'n';
+
+const DATA_PREFIX: u8 = b'd';
+
+const UNDO_PREFIX: u8 = b'u';
+
+const INDEX_PREFIX: u8 = b'i';
+
+const SEQ_PREFIX: u8 = b's';
+
+/// `PersistentKV` is a stateful key-value store persisting its data into a raw key-value store (see `RawKV`).
+///
+/// The persisted data-structure is similar to the one used by `FakeKV` (which keeps it in-memory):
+/// each flushed `State` has a node pointing to its parent `State` and holding the changes of its checkpoint.
+/// Besides, an index holds the value of each key as of the `head`, so lookups don't walk the states history.
+///
+/// The raw key-value store holds the following entries:
+///
+/// * `head`                 -> the last flushed `State` (32 bytes)
+/// * `n` | `State`          -> the parent `State` (32 bytes) followed by the keys set by the checkpoint of `State`
+/// * `d` | `State` | `key`  -> the `value` of `key` as set by the checkpoint of `State`
+/// * `u` | `State` | `key`  -> the `value` of `key` as of the parent `State` (used for rewinding `State`)
+/// * `i` | `key`            -> the `value` of `key` as of `head`
+/// * `states`               -> the range (`first..next`) of the flushed states sequence numbers
+/// * `s` | `seq`            -> the flushed `State` having sequence number `seq` (used by `prune`)
+///
+/// The `u` and `i` entries may denote a missing `value` (see `encode_value`).
+///
+/// Pending changes are kept in a journal (in-memory) until `flush` is called.
+/// A `flush` (and a `rewind`) writes all its entries alongside the new `head` using a single `RawKV::set` batch,
+/// so when the raw key-value store applies a batch atomically (as `rocksdb` does),
+/// a crash can't leave a partially flushed checkpoint behind.
+///
+/// On initialization the `head` is restored, so the data persists across restarts.
+///
+/// ## Costs
+///
+/// * Looking up a flushed key costs a single raw read (of the index).
+/// * Rewinding walks the ancestors of the target `State` (and of the `head`, up to their common ancestor)
+///   to update the index, so it costs `O(history)` raw reads (the history length is bounded by pruning).
+/// * Pruning rewrites the kept states and deletes the others, so it costs `O(flushed states)` raw reads.
+pub struct PersistentKV<KV: RawKV> {
+    raw_kv: KV,
+
+    head: State,
+
+    flushed_head: State,
+
+    journal: Vec<(Option<State>, Vec<Change>)>,
+}
+
+/// A flushed `State` node (as persisted under its `n` | `State` entry).
+struct Node {
+    parent: State,
+
+    keys: Vec<Vec<u8>>,
+}
+
+impl<KV: RawKV> StatefulKV for PersistentKV<KV> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.get_journal(key).or_else(|| self.get_flushed(key))
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        let (_, changes) = self.journal.last_mut().unwrap();
+
+        changes.push((key.to_vec(), value.to_vec()));
+    }
+
+    fn discard(&mut self) {
+        let (_, changes) = self.journal.last_mut().unwrap();
+
+        changes.clear();
+    }
+
+    fn flush(&mut self) {
+        let (_, changes) = self.journal.last().unwrap();
+        assert_eq!(changes.len(), 0);
+
+        let n = self.journal.len();
+
+        if n == 1 {
+            return;
+        }
+
+        let mut entries = Vec::new();
+        let mut index = HashMap::new();
+        let mut parent = self.flushed_head.clone();
+        let (first, mut next) = self.seq_range();
+
+        for (state, changes) in &self.journal[0..n - 1] {
+            let state = state.as_ref().unwrap();
+
+            // the last `value` set for each `key` (in the order of the keys first `set`)
+            let mut keys: Vec<Vec<u8>> = Vec::new();
+            let mut data = HashMap::new();
+
+            for (k, v) in changes.iter() {
+                if data.insert(k.clone(), v.clone()).is_none() {
+                    keys.push(k.clone());
+                }
+            }
+
+            // a `State` flushed again (i.e re-applying its changes over its parent) is persisted once
+            let known = self.has_state(state);
+
+            for k in keys.iter() {
+                let value = data.remove(k).unwrap();
+
+                if !known {
+                    let prev = index.get(k).cloned().or_else(|| self.get_flushed(k));
+
+                    entries.push((undo_key(state, k), encode_value(prev.as_deref())));
+                    entries.push((data_key(state, k), value.clone()));
+                }
+
+                index.insert(k.clone(), value);
+            }
+
+            if !known {
+                let node = Node { parent, keys };
+
+                entries.push((node_key(state), node.encode()));
+                entries.push((seq_key(next), state.as_slice().to_vec()));
+
+                next += 1;
+            }
+
+            parent = state.clone();
+        }
+
+        for (k, v) in index {
+            entries.push((index_key(&k), encode_value(Some(&v))));
+        }
+
+        entries.push((STATES_KEY.to_vec(), encode_seq_range(first, next)));
+        entries.push((HEAD_KEY.to_vec(), parent.as_slice().to_vec()));
+
+        self.write(&entries);
+
+        self.flushed_head = parent;
+        self.journal = vec![(None, Vec::new())];
+
+        self.assert_journal_empty();
+    }
+
+    fn checkpoint(&mut self) -> State {
+        let (_, changes) = self.journal.last().unwrap();
+        let new_state = self.compute_state(changes);
+
+        let (maybe_state, _) = self.journal.last_mut().unwrap();
+        maybe_state.replace(new_state.clone());
+
+        self.head = new_state.clone();
+        self.journal.push((None, Vec::new()));
+
+        new_state
+    }
+
+    /// Rewinds to `state` and persists it as the new `head`.
+    ///
+    /// # Panics
+    ///
+    /// Panics (before persisting anything) when `state` has never been flushed (or has been pruned).
+    fn rewind(&mut self, state: &State) {
+        self.assert_journal_empty();
+
+        assert!(
+            self.has_state(state),
+            "Unknown (or not flushed) state: `{:?}`",
+            state
+        );
+
+        // the path from `state` up to the zero `State`
+        let mut path = Vec::new();
+        let mut current = state.clone();
+
+        while !current.is_zeros() {
+            let node = self.load_node(&current);
+
+            path.push(current);
+            current = node.parent;
+        }
+
+        let on_path = path.iter().collect::<HashSet<_>>();
+
+        // reverting the states between `head` and the common ancestor (with `state`) backwards,
+        // so each `key` gets its `value` as of the common ancestor
+        let mut index = HashMap::new();
+        let mut current = self.flushed_head.clone();
+
+        while !current.is_zeros() && !on_path.contains(&current) {
+            let node = self.load_node(&current);
+
+            for k in node.keys.iter() {
+                let prev = self.raw_kv.get(&undo_key(&current, k)).unwrap();
+
+                index.insert(k.clone(), prev);
+            }
+
+            current = node.parent;
+        }
+
+        // re-applying the states between the common ancestor and `state`
+        let ancestor = current;
+        let depth = path
+            .iter()
+            .position(|s| *s == ancestor)
+            .unwrap_or(path.len());
+
+        for s in path[0..depth].iter().rev() {
+            let node = self.load_node(s);
+
+            for k in node.keys.iter() {
+                let value = self.raw_kv.get(&data_key(s, k)).unwrap();
+
+                index.insert(k.clone(), encode_value(Some(&value)));
+            }
+        }
+
+        let mut entries = index
+            .into_iter()
+            .map(|(k, v)| (index_key(&k), v))
+            .collect::<Vec<_>>();
+
+        entries.push((HEAD_KEY.to_vec(), state.as_slice().to_vec()));
+
+        self.write(&entries);
+
+        self.head = state.clone();
+        self.flushed_head = state.clone();
+    }
+
+    /// Drops the nodes of the non-kept states.
+    ///
+    /// The changes of a dropped node are merged into its nearest kept descendant (unless overridden there),
+    /// so rewinding to a kept `State` is left possible.
+    ///
+    /// The kept states are rewritten (using a single `RawKV::set` batch) before the dropped ones are deleted,
+    /// so a crash in between leaves (unreachable) garbage behind, but no missing data.
+    fn prune(&mut self, keep: &[State]) {
+        self.assert_journal_empty();
+
+        let mut kept: HashSet<State> = keep
+            .iter()
+            .filter(|state| !state.is_zeros() && self.has_state(state))
+            .cloned()
+            .collect();
+
+        if !self.head.is_zeros() {
+            kept.insert(self.head.clone());
+        }
+
+        let (first, next) = self.seq_range();
+
+        let flushed = (first..next)
+            .map(|seq| {
+                let bytes = self.raw_kv.get(&seq_key(seq)).unwrap();
+
+                State::from(&bytes[..])
+            })
+            .collect::<Vec<_>>();
+
+        let mut entries = Vec::new();
+        let mut seq = next;
+
+        for state in flushed.iter().filter(|state| kept.contains(*state)) {
+            entries.extend(self.compact_node(state, &kept));
+            entries.push((seq_key(seq), state.as_slice().to_vec()));
+
+            seq += 1;
+        }
+
+        entries.push((STATES_KEY.to_vec(), encode_seq_range(next, seq)));
+
+        self.write(&entries);
+
+        let mut dropped = (first..next).map(seq_key).collect::<Vec<_>>();
+
+        for state in flushed.iter().filter(|state| !kept.contains(*state)) {
+            let node = self.load_node(state);
+
+            for k in node.keys.iter() {
+                dropped.push(data_key(state, k));
+                dropped.push(undo_key(state, k));
+            }
+
+            dropped.push(node_key(state));
+        }
+
+        let keys = dropped.iter().map(|k| &k[..]).collect::<Vec<_>>();
+
+        self.raw_kv.delete(&keys);
+    }
+
+    fn head(&self) -> State {
+        self.head.clone()
+    }
+}
+
+impl<KV: RawKV> PersistentKV<KV> {
+    /// New `PersistentKV` on top of `raw_kv`.
+    ///
+    /// The `head` is restored from `raw_kv` (or set to the zero `State` when `raw_kv` has no data).
+    pub fn new(raw_kv: KV) -> Self {
+        let head = match raw_kv.get(HEAD_KEY) {
+            Some(bytes) => State::from(&bytes[..]),
+            None => State::zeros(),
+        };
+
+        Self {
+            raw_kv,
+            head: head.clone(),
+            flushed_head: head,
+            journal: vec![(None, Vec::new())],
+        }
+    }
+
+    /// Consumes the `PersistentKV` and returns the underlying raw key-value store.
+    pub fn into_raw(self) -> KV {
+        self.raw_kv
+    }
+
+    /// Returns the number of persisted states.
+    pub fn flushed_count(&self) -> usize {
+        let (first, next) = self.seq_range();
+
+        (next - first) as usize
+    }
+
+    fn get_journal(&self, key: &[u8]) -> Option<Vec<u8>> {
+        for (_state, changes) in self.journal.iter().rev() {
+            for (k, v) in changes.iter().rev() {
+                if k == key {
+                    return Some(v.clone());
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns whether `state` has been flushed (the zero `State` always exists).
+    fn has_state(&self, state: &State) -> bool {
+        state.is_zeros() || self.raw_kv.get(&node_key(state)).is_some()
+    }
+
+    /// Looks up `key` as of `flushed_head` (using the index).
+    fn get_flushed(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.raw_kv
+            .get(&index_key(key))
+            .and_then(|bytes| decode_value(&bytes))
+    }
+
+    fn load_node(&self, state: &State) -> Node {
+        let bytes = self.raw_kv.get(&node_key(state)).unwrap_or_else(|| {
+            panic!("Missing persisted node for state: `{:?}`", state);
+        });
+
+        Node::decode(&bytes)
+    }
+
+    /// Merges node `state` with its non-kept ancestors, and returns the entries persisting the merged node.
+    ///
+    /// The merged node parent is the nearest kept ancestor (or the zero `State` when there is none).
+    /// Each merged `key` has the `value` set by the nearest (merged) node,
+    /// and the previous `value` of the farthest one (i.e its `value` as of the merged node parent).
+    fn compact_node(&self, state: &State, kept: &HashSet<State>) -> Vec<Change> {
+        let mut keys: Vec<Vec<u8>> = Vec::new();
+        let mut data = HashMap::new();
+        let mut undo = HashMap::new();
+        let mut current = state.clone();
+
+        let parent = loop {
+            let node = self.load_node(&current);
+
+            for k in node.keys.iter() {
+                if !data.contains_key(k) {
+                    let value = self.raw_kv.get(&data_key(&current, k)).unwrap();
+
+                    data.insert(k.clone(), value);
+                    keys.push(k.clone());
+                }
+
+                let prev = self.raw_kv.get(&undo_key(&current, k)).unwrap();
+                undo.insert(k.clone(), prev);
+            }
+
+            if node.parent.is_zeros() || kept.contains(&node.parent) {
+                break node.parent;
+            }
+
+            current = node.parent;
+        };
+
+        let mut entries = Vec::with_capacity(keys.len() * 2 + 1);
+
+        for k in keys.iter() {
+            entries.push((data_key(state, k), data.remove(k).unwrap()));
+            entries.push((undo_key(state, k), undo.remove(k).unwrap()));
+        }
+
+        let node = Node { parent, keys };
+        entries.push((node_key(state), node.encode()));
+
+        entries
+    }
+
+    /// Returns the range `first..next` of the flushed states sequence numbers.
+    fn seq_range(&self) -> (u64, u64) {
+        match self.raw_kv.get(STATES_KEY) {
+            Some(bytes) => {
+                let mut first = [0; 8];
+                let mut next = [0; 8];
+
+                first.copy_from_slice(&bytes[0..8]);
+                next.copy_from_slice(&bytes[8..16]);
+
+                (u64::from_be_bytes(first), u64::from_be_bytes(next))
+            }
+            None => (0, 0),
+        }
+    }
+
+    fn compute_state(&self, changes: &[Change]) -> State {
+        let capacity = changes
+            .iter()
+            .fold(State::len(), |acc, (k, v)| acc + k.len() + v.len());
+
+        let mut buf = Vec::with_capacity(capacity);
+
+        buf.extend_from_slice(self.head.as_slice());
+
+        for (k, v) in changes.iter() {
+            buf.extend_from_slice(k);
+            buf.extend_from_slice(v);
+        }
+
+        let bytes = DefaultKeyHasher::hash(&buf);
+        assert_eq!(bytes.len(), State::len());
+
+        State::from(&bytes[..])
+    }
+
+    fn write(&mut self, entries: &[Change]) {
+        let changes = entries
+            .iter()
+            .map(|(k, v)| (&k[..], &v[..]))
+            .collect::<Vec<_>>();
+
+        self.raw_kv.set(&changes);
+    }
+
+    fn assert_journal_empty(&self) {
+        assert_eq!(self.journal.len(), 1);
+
+        let (_, changes) = self.journal.last().unwrap();
+
+        assert_eq!(changes.len(), 0);
+    }
+}
+
+impl Node {
+    /// Encodes the node as its parent `State` followed by its keys (each prefixed by its 4 bytes length).
+    fn encode(&self) -> Vec<u8> {
+        let capacity = self
+            .keys
+            .iter()
+            .fold(State::len(), |acc, k| acc + 4 + k.len());
+
+        let mut buf = Vec::with_capacity(capacity);
+
+        buf.extend_from_slice(self.parent.as_slice());
+
+        for k in self.keys.iter() {
+            buf.extend_from_slice(&(k.len() as u32).to_be_bytes());
+            buf.extend_from_slice(k);
+        }
+
+        buf
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        let parent = State::from(&bytes[0..State::len()]);
+
+        let mut keys = Vec::new();
+        let mut rest = &bytes[State::len()..];
+
+        while !rest.is_empty() {
+            let mut len = [0; 4];
+            len.copy_from_slice(&rest[0..4]);
+
+            let len = u32::from_be_bytes(len) as usize;
+
+            keys.push(rest[4..4 + len].to_vec());
+            rest = &rest[4 + len..];
+        }
+
+        Self { parent, keys }
+    }
+}
+
+#[cfg(feature = "default-rocksdb")]
+impl PersistentKV<Rocksdb> {
+    /// Opens (or creates) a `RocksdbStatefulKV` under the given `path`
+    ///
+    /// Returns an error when the underlying `rocksdb` can't be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let db = Rocksdb::new(path)?;
+
+        Ok(Self::new(db))
+    }
+}
+
+impl<KV: RawKV> fmt::Debug for PersistentKV<KV> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PersistentKV")
+            .field("HEAD", &fmt_hex(&self.head.as_slice()[0..6], ""))
+            .field("journal", &self.journal.len())
+            .finish()
+    }
+}
+
+fn node_key(state: &State) -> Vec<u8> {
+    state_key(NODE_PREFIX, state, &[])
+}
+
+fn data_key(state: &State, key: &[u8]) -> Vec<u8> {
+    state_key(DATA_PREFIX, state, key)
+}
+
+fn undo_key(state: &State, key: &[u8]) -> Vec<u8> {
+    state_key(UNDO_PREFIX, state, key)
+}
+
+fn state_key(prefix: u8, state: &State, key: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(1 + State::len() + key.len());
+
+    buf.push(prefix);
+    buf.extend_from_slice(state.as_slice());
+    buf.extend_from_slice(key);
+
+    buf
+}
+
+fn index_key(key: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(1 + key.len());
+
+    buf.push(INDEX_PREFIX);
+    buf.extend_from_slice(key);
+
+    buf
+}
+
+fn seq_key(seq: u64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(9);
+
+    buf.push(SEQ_PREFIX);
+    buf.extend_from_slice(&seq.to_be_bytes());
+
+    buf
+}
+
+fn encode_seq_range(first: u64, next: u64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(16);
+
+    buf.extend_from_slice(&first.to_be_bytes());
+    buf.extend_from_slice(&next.to_be_bytes());
+
+    buf
+}
+
+/// Encodes an optional `value` (a missing `value` is encoded as `[0]`, an existing one is prefixed by `1`).
+fn encode_value(value: Option<&[u8]>) -> Vec<u8> {
+    match value {
+        None => vec![0],
+        Some(value) => {
+            let mut buf = Vec::with_capacity(1 + value.len());
+
+            buf.push(1);
+            buf.extend_from_slice(value);
+
+            buf
+        }
+    }
+}
+
+fn decode_value(bytes: &[u8]) -> Option<Vec<u8>> {
+    match bytes[0] {
+        0 => None,
+        _ => Some(bytes[1..].to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::kv::FakeKV;
+
+    use std::cell::Cell;
+    use std::collections::BTreeMap;
+
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    use svm_kv::memory::MemRawKV;
+
+    /// A `MemRawKV` counting its reads.
+    struct CountingKV {
+        kv: MemRawKV,
+
+        reads: Cell<usize>,
+    }
+
+    impl RawKV for CountingKV {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.reads.set(self.reads.get() + 1);
+
+            self.kv.get(key)
+        }
+
+        fn set(&mut self, changes: &[(&[u8], &[u8])]) {
+            self.kv.set(changes)
+        }
+
+        fn delete(&mut self, keys: &[&[u8]]) {
+            self.kv.delete(keys)
+        }
+    }
+
+    macro_rules! apply_changes {
+        ($kv:ident, $( ($k:expr => $v:expr), )* ) => {{
+            $(
+                $kv.set(&$k[..], &$v[..]);
+             )*
+
+            let state = $kv.checkpoint();
+
+            $kv.flush();
+
+            state
+        }};
+    }
+
+    #[test]
+    fn persistent_kv_empty() {
+        let kv = PersistentKV::new(MemRawKV::new());
+
+        assert_eq!(kv.head(), State::zeros());
+        assert!(kv.get(b"aaa").is_none());
+    }
+
+    #[test]
+    fn persistent_kv_states_match_fake_kv() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+        let mut fake = FakeKV::new();
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10, 0x20]), (b"bbb" => [0x30]),);
+        let f1 = apply_changes!(fake, (b"aaa" => [0x10, 0x20]), (b"bbb" => [0x30]),);
+
+        let s2 = apply_changes!(kv, (b"aaa" => [0x40]),);
+        let f2 = apply_changes!(fake, (b"aaa" => [0x40]),);
+
+        assert_eq!(s1, f1);
+        assert_eq!(s2, f2);
+    }
+
+    #[test]
+    fn persistent_kv_pending_changes_and_discard() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        kv.set(b"aaa", &[0x10]);
+        let s1 = kv.checkpoint();
+
+        kv.set(b"bbb", &[0x20]);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+        assert_eq!(kv.get(b"bbb").unwrap(), vec![0x20]);
+
+        kv.discard();
+        assert!(kv.get(b"bbb").is_none());
+
+        kv.flush();
+        assert_eq!(kv.head(), s1);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+    }
+
+    #[test]
+    fn persistent_kv_rewind() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10]),);
+        let s2 = apply_changes!(kv, (b"bbb" => [0x20]), (b"aaa" => [0x30]),);
+
+        kv.rewind(&s1);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+        assert!(kv.get(b"bbb").is_none());
+
+        kv.rewind(&s2);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x30]);
+        assert_eq!(kv.get(b"bbb").unwrap(), vec![0x20]);
+
+        kv.rewind(&State::zeros());
+        assert!(kv.get(b"aaa").is_none());
+    }
+
+    #[test]
+    fn persistent_kv_rewind_to_unknown_state_keeps_head() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10]),);
+
+        let unknown = State::from(&[0xFF; 32][..]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| kv.rewind(&unknown)));
+        assert!(result.is_err());
+
+        // the persisted `head` is left untouched
+        let kv = PersistentKV::new(kv.into_raw());
+
+        assert_eq!(kv.head(), s1);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+    }
+
+    #[test]
+    fn persistent_kv_restores_head_on_restart() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10]),);
+
+        // a checkpoint which has never been flushed
+        kv.set(b"bbb", &[0x20]);
+        let _s2 = kv.checkpoint();
+
+        let raw_kv = kv.into_raw();
+        let kv = PersistentKV::new(raw_kv);
+
+        assert_eq!(kv.head(), s1);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+        assert!(kv.get(b"bbb").is_none());
+    }
+
+    #[test]
+    fn persistent_kv_lookup_reads_the_index_once() {
+        let raw_kv = CountingKV {
+            kv: MemRawKV::new(),
+            reads: Cell::new(0),
+        };
+
+        let mut kv = PersistentKV::new(raw_kv);
+
+        let _s1 = apply_changes!(kv, (b"aaa" => [0x10]),);
+
+        for i in 0..100u32 {
+            let _state = apply_changes!(kv, (i.to_be_bytes() => [0x20]),);
+        }
+
+        let raw_kv = kv.into_raw();
+        raw_kv.reads.set(0);
+
+        let kv = PersistentKV::new(raw_kv);
+        let reads = kv.raw_kv.reads.get();
+
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+        assert!(kv.get(b"bbb").is_none());
+
+        assert_eq!(kv.raw_kv.reads.get() - reads, 2);
+    }
+
+    #[test]
+    fn persistent_kv_rewind_to_another_branch() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10]), (b"bbb" => [0x20]),);
+        let s2 = apply_changes!(kv, (b"aaa" => [0x30]), (b"ccc" => [0x40]),);
+
+        kv.rewind(&s1);
+        let s3 = apply_changes!(kv, (b"bbb" => [0x50]), (b"ddd" => [0x60]),);
+
+        // moving from branch `s3` to branch `s2` (their common ancestor is `s1`)
+        kv.rewind(&s2);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x30]);
+        assert_eq!(kv.get(b"bbb").unwrap(), vec![0x20]);
+        assert_eq!(kv.get(b"ccc").unwrap(), vec![0x40]);
+        assert!(kv.get(b"ddd").is_none());
+
+        kv.rewind(&s3);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+        assert_eq!(kv.get(b"bbb").unwrap(), vec![0x50]);
+        assert!(kv.get(b"ccc").is_none());
+        assert_eq!(kv.get(b"ddd").unwrap(), vec![0x60]);
+    }
+
+    #[test]
+    fn persistent_kv_prune() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10]), (b"bbb" => [0x20]),);
+        let s2 = apply_changes!(kv, (b"aaa" => [0x30]),);
+        let s3 = apply_changes!(kv, (b"ccc" => [0x40]),);
+        let s4 = apply_changes!(kv, (b"aaa" => [0x50]),);
+
+        assert_eq!(kv.flushed_count(), 4);
+
+        let entries = kv.raw_kv.keys().count();
+
+        // `s4` (the `head`) is kept implicitly
+        kv.prune(std::slice::from_ref(&s2));
+
+        assert_eq!(kv.flushed_count(), 2);
+        assert!(kv.raw_kv.keys().count() < entries);
+
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x50]);
+        assert_eq!(kv.get(b"ccc").unwrap(), vec![0x40]);
+
+        // `s2` has been merged with `s1`, and `s4` with `s3`
+        kv.rewind(&s2);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x30]);
+        assert_eq!(kv.get(b"bbb").unwrap(), vec![0x20]);
+        assert!(kv.get(b"ccc").is_none());
+
+        kv.rewind(&s4);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x50]);
+        assert_eq!(kv.get(b"bbb").unwrap(), vec![0x20]);
+        assert_eq!(kv.get(b"ccc").unwrap(), vec![0x40]);
+
+        for pruned in [s1, s3].iter() {
+            let result =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| kv.rewind(pruned)));
+            assert!(result.is_err());
+        }
+
+        // the pruned data is deleted on restart too
+        let kv = PersistentKV::new(kv.into_raw());
+
+        assert_eq!(kv.head(), s4);
+        assert_eq!(kv.flushed_count(), 2);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x50]);
+    }
+
+    #[test]
+    fn persistent_kv_prune_drops_other_branches() {
+        let mut kv = PersistentKV::new(MemRawKV::new());
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10]),);
+        let _s2 = apply_changes!(kv, (b"bbb" => [0x20]),);
+
+        kv.rewind(&s1);
+        let s3 = apply_changes!(kv, (b"ccc" => [0x30]),);
+
+        kv.prune(&[]);
+
+        assert_eq!(kv.flushed_count(), 1);
+
+        // only the `head` is left (merged with `s1`)
+        let entries = kv.raw_kv.keys().count();
+        let mut kv = PersistentKV::new(kv.into_raw());
+
+        assert_eq!(kv.head(), s3);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10]);
+        assert!(kv.get(b"bbb").is_none());
+        assert_eq!(kv.get(b"ccc").unwrap(), vec![0x30]);
+
+        kv.rewind(&State::zeros());
+        assert!(kv.get(b"aaa").is_none());
+        assert!(kv.get(b"ccc").is_none());
+        assert_eq!(kv.raw_kv.keys().count(), entries);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Commit(Vec<(u8, u8)>),
+        Rewind(usize),
+        Prune(Vec<usize>),
+    }
+
+    fn ops_strategy() -> impl Strategy<Value = Vec<Op>> {
+        let op = prop_oneof![
+            3 => vec((0..8u8, any::<u8>()), 0..4).prop_map(Op::Commit),
+            1 => any::<usize>().prop_map(Op::Rewind),
+            1 => vec(any::<usize>(), 0..3).prop_map(Op::Prune),
+        ];
+
+        vec(op, 1..32)
+    }
+
+    proptest! {
+        #[test]
+        fn persistent_kv_matches_states_model(ops in ops_strategy()) {
+            let mut kv = PersistentKV::new(MemRawKV::new());
+
+            // the data of each (non-pruned) flushed `State`
+            let mut model: Vec<(State, BTreeMap<u8, u8>)> = vec![(State::zeros(), BTreeMap::new())];
+
+            let data_of = |model: &[(State, BTreeMap<u8, u8>)], state: &State| {
+                model.iter().find(|(s, _)| s == state).unwrap().1.clone()
+            };
+
+            for op in ops {
+                match op {
+                    Op::Commit(changes) => {
+                        let mut data = data_of(&model, &kv.head());
+
+                        for (k, v) in changes {
+                            kv.set(&[k], &[v]);
+                            data.insert(k, v);
+                        }
+
+                        let state = kv.checkpoint();
+                        kv.flush();
+
+                        if model.iter().all(|(s, _)| *s != state) {
+                            model.push((state, data));
+                        }
+                    }
+                    Op::Rewind(i) => {
+                        let state = model[i % model.len()].0.clone();
+
+                        kv.rewind(&state);
+                    }
+                    Op::Prune(keep) => {
+                        let keep = keep
+                            .iter()
+                            .map(|i| model[i % model.len()].0.clone())
+                            .collect::<Vec<_>>();
+
+                        kv.prune(&keep);
+
+                        let head = kv.head();
+                        model.retain(|(s, _)| s.is_zeros() || *s == head || keep.contains(s));
+                    }
+                }
+
+                let data = data_of(&model, &kv.head());
+
+                for k in 0..8u8 {
+                    prop_assert_eq!(kv.get(&[k]), data.get(&k).map(|v| vec![*v]));
+                }
+            }
+
+            // each kept `State` can be rewound to
+            for (state, data) in model.iter() {
+                kv.rewind(state);
+
+                for k in 0..8u8 {
+                    prop_assert_eq!(kv.get(&[k]), data.get(&k).map(|v| vec![*v]));
+                }
+            }
+
+            prop_assert_eq!(kv.flushed_count(), model.len() - 1);
+        }
+    }
+
+    #[cfg(feature = "default-rocksdb")]
+    #[test]
+    fn rocksdb_stateful_kv_persists_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut kv = RocksdbStatefulKV::open(dir.path()).unwrap();
+
+        let s1 = apply_changes!(kv, (b"aaa" => [0x10, 0x20]),);
+        drop(kv);
+
+        let kv = RocksdbStatefulKV::open(dir.path()).unwrap();
+
+        assert_eq!(kv.head(), s1);
+        assert_eq!(kv.get(b"aaa").unwrap(), vec![0x10, 0x20]);
+    }
+}
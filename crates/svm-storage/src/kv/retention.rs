@@ -0,0 +1,12 @@
+/// The states retention policy of a `StatefulKV`.
+///
+/// The policy is applied by the `StatefulKV` owner, which knows which states are still in use
+/// (for example, the runtime prunes the key-value store after each transaction), using `StatefulKV::prune`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retention {
+    /// Every flushed `State` is kept (unless `prune` is called explicitly)
+    KeepAll,
+
+    /// Only the states as of the last `n` pruning points (for example, the last `n` transactions) are kept
+    KeepLast(usize),
+}
@@ -66,6 +66,19 @@ pub trait StatefulKV {
     ///
     fn rewind(&mut self, state: &State);
 
+    /// Drops the persisted data which isn't required anymore for reading any of the `keep` states.
+    ///
+    /// The current `head` is always kept (even when not in `keep`).
+    /// Rewinding to a pruned `State` isn't possible afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Traits implementations are expected to panic in cases there are pending changes.
+    ///
+    /// See also: `rewind`.
+    ///
+    fn prune(&mut self, keep: &[State]);
+
     /// Returns the current `State`.
     ///
     /// Its value should be the last created checkpoint `State`.
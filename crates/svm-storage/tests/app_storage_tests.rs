@@ -1,6 +1,10 @@
 use svm_layout::{DataLayout, VarId};
-use svm_storage::{app::AppStorage, testing};
-use svm_types::Address;
+use svm_storage::{
+    app::{self, AppKVStore, AppStorage, UnknownState, VecError},
+    testing,
+};
+use svm_types::receipt::StorageChange;
+use svm_types::{Address, State};
 
 macro_rules! assert_vars {
         ($app:expr, $($var_id:expr => $expected:expr), *) => {{
@@ -53,6 +57,27 @@ fn app_storage_vars_are_persisted_only_on_commit() {
     assert_vars!(app3, 0 => [10, 20, 30, 40], 1 => [50, 60]);
 }
 
+#[test]
+fn app_storage_vars_spanning_multiple_pages() {
+    // `var #0` consumes 30 bytes (offsets: `[0, 30)`)
+    // `var #1` consumes 8 bytes (offsets: `[30, 38)`) - spans pages `#0` and `#1`
+    // `var #2` consumes 40 bytes (offsets: `[38, 78)`) - spans pages `#1` and `#2`
+    let layout = DataLayout::from(vec![30, 8, 40].as_slice());
+
+    let addr = Address::of("my-app");
+    let kv = testing::create_app_kv(addr);
+
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    write_vars!(app, 0 => [0x10; 30], 1 => [0x20; 8], 2 => [0x30; 40]);
+
+    let _state = app.commit();
+
+    let app2 = AppStorage::new(layout.clone(), kv.clone());
+
+    assert_vars!(app2, 0 => vec![0x10; 30], 1 => vec![0x20; 8], 2 => vec![0x30; 40]);
+}
+
 #[test]
 #[cfg(debug_assertions)]
 #[should_panic]
@@ -67,3 +92,666 @@ fn app_storage_write_var_value_should_match_layout_length() {
     // calling `write_var` with 2-byte value (expected variable's to value to be 4 bytes)
     app.write_var(VarId(0), vec![0, 0]);
 }
+
+#[test]
+fn app_storage_map_entries_are_persisted_only_on_commit() {
+    // `var #0` is a map (reserves 4 bytes)
+    // `var #1` consumes 2 bytes
+    let layout = DataLayout::from(vec![4, 2].as_slice());
+
+    let addr = Address::of("my-app");
+    let kv = testing::create_app_kv(addr);
+
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    // missing entries have no value
+    assert_eq!(app.map_get(VarId(0), b"alice"), None);
+
+    app.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+    app.map_set(VarId(0), b"bob", vec![40]);
+    write_vars!(app, 1 => [50, 60]);
+
+    assert_eq!(app.map_get(VarId(0), b"alice"), Some(vec![10, 20, 30]));
+    assert_eq!(app.map_get(VarId(0), b"bob"), Some(vec![40]));
+
+    // the same key under another variable is a different entry
+    assert_eq!(app.map_get(VarId(1), b"alice"), None);
+
+    // `app`'s' uncomitted changes are not reflected yet
+    let app2 = AppStorage::new(layout.clone(), kv.clone());
+    assert_eq!(app2.map_get(VarId(0), b"alice"), None);
+
+    let _state = app.commit();
+
+    // map entries are committed alongside the fixed-size variables
+    let app3 = AppStorage::new(layout.clone(), kv.clone());
+
+    assert_eq!(app3.map_get(VarId(0), b"alice"), Some(vec![10, 20, 30]));
+    assert_eq!(app3.map_get(VarId(0), b"bob"), Some(vec![40]));
+    assert_vars!(app3, 1 => [50, 60]);
+}
+
+#[test]
+fn app_storage_vec_push_and_get() {
+    // `var #0` is a vector (holds its length)
+    let layout = DataLayout::from(vec![4].as_slice());
+
+    let addr = Address::of("my-app");
+    let kv = testing::create_app_kv(addr);
+
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    let out_of_bounds = |index| {
+        Err(VecError::IndexOutOfBounds {
+            var_id: VarId(0),
+            index,
+        })
+    };
+
+    assert_eq!(app.vec_len(VarId(0)), Ok(0));
+    assert_eq!(app.vec_get(VarId(0), 0), out_of_bounds(0));
+
+    assert_eq!(app.vec_push(VarId(0), vec![10, 20]), Ok(0));
+    assert_eq!(app.vec_push(VarId(0), vec![30]), Ok(1));
+
+    assert_eq!(app.vec_len(VarId(0)), Ok(2));
+    assert_eq!(app.vec_get(VarId(0), 0), Ok(vec![10, 20]));
+    assert_eq!(app.vec_get(VarId(0), 1), Ok(vec![30]));
+    assert_eq!(app.vec_get(VarId(0), 2), out_of_bounds(2));
+
+    let _state = app.commit();
+
+    let app2 = AppStorage::new(layout.clone(), kv.clone());
+
+    assert_eq!(app2.vec_len(VarId(0)), Ok(2));
+    assert_vars!(app2, 0 => [2, 0, 0, 0]);
+    assert_eq!(app2.vec_get(VarId(0), 1), Ok(vec![30]));
+}
+
+#[test]
+fn app_storage_vec_push_length_overflow() {
+    // `var #0` is a vector having a 1-byte length
+    let layout = DataLayout::from(vec![1].as_slice());
+
+    let addr = Address::of("my-app");
+    let kv = testing::create_app_kv(addr);
+
+    let mut app = AppStorage::new(layout, kv);
+
+    for i in 0..255 {
+        assert_eq!(app.vec_push(VarId(0), vec![i as u8]), Ok(i));
+    }
+
+    assert_eq!(
+        app.vec_push(VarId(0), vec![0]),
+        Err(VecError::LengthOverflow {
+            var_id: VarId(0),
+            len: 256
+        })
+    );
+    assert_eq!(app.vec_len(VarId(0)), Ok(255));
+}
+
+#[test]
+fn app_storage_vec_invalid_var() {
+    // `var #0` is too long for holding a vector length, and there is no `var #1`
+    let layout = DataLayout::from(vec![8].as_slice());
+
+    let addr = Address::of("my-app");
+    let kv = testing::create_app_kv(addr);
+
+    let mut app = AppStorage::new(layout, kv);
+
+    for &var_id in [VarId(0), VarId(1)].iter() {
+        let err = VecError::InvalidVar(var_id);
+
+        assert_eq!(app.vec_len(var_id), Err(err));
+        assert_eq!(app.vec_get(var_id, 0), Err(err));
+        assert_eq!(app.vec_push(var_id, vec![10]), Err(err));
+    }
+
+    assert_vars!(app, 0 => [0, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn app_storage_root_is_determined_by_vars_values() {
+    let layout = DataLayout::from(vec![4, 30, 8].as_slice());
+
+    // an app with no storage has a zeros root
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv);
+    assert_eq!(app.root(), State::zeros());
+
+    // the values are committed in a single commit
+    write_vars!(app, 0 => [10, 20, 30, 40], 2 => [0xFF; 8]);
+    let _state = app.commit();
+
+    let root = app.root();
+    assert_ne!(root, State::zeros());
+
+    // the same values are committed (by another node) in multiple commits,
+    // having a different history and being a different app
+    let kv = testing::create_app_kv(Address::of("another-app"));
+    let mut app2 = AppStorage::new(layout.clone(), kv);
+
+    write_vars!(app2, 1 => [0xAA; 30], 2 => [0xFF; 8]);
+    let _state = app2.commit();
+
+    write_vars!(app2, 0 => [10, 20, 30, 40], 1 => [0; 30]);
+    let _state = app2.commit();
+
+    assert_eq!(app2.root(), root);
+
+    // resetting all the variables back to zeros
+    write_vars!(app2, 0 => [0; 4], 2 => [0; 8]);
+    let _state = app2.commit();
+
+    assert_eq!(app2.root(), State::zeros());
+}
+
+#[test]
+fn app_storage_prove_and_verify() {
+    // `var #1` spans pages `#0` and `#1`
+    let layout = DataLayout::from(vec![4, 30, 8].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv);
+
+    write_vars!(app, 0 => [10, 20, 30, 40], 1 => [0xAA; 30], 2 => [0xFF; 8]);
+    let _state = app.commit();
+
+    let root = app.root();
+
+    for (var_id, value) in [
+        (0, vec![10, 20, 30, 40]),
+        (1, vec![0xAA; 30]),
+        (2, vec![0xFF; 8]),
+    ]
+    .iter()
+    {
+        let var_id = *var_id;
+        let proof = app.prove(VarId(var_id));
+
+        assert!(app::verify(&layout, &root, VarId(var_id), value, &proof));
+    }
+
+    let proof = app.prove(VarId(0));
+
+    // a tampered value
+    assert!(!app::verify(
+        &layout,
+        &root,
+        VarId(0),
+        &[10, 20, 30, 41],
+        &proof
+    ));
+
+    // a proof of another variable
+    assert!(!app::verify(
+        &layout,
+        &root,
+        VarId(2),
+        &[10, 20, 30, 40],
+        &proof
+    ));
+
+    // an unknown root
+    assert!(!app::verify(
+        &layout,
+        &State::zeros(),
+        VarId(0),
+        &[10, 20, 30, 40],
+        &proof
+    ));
+
+    // a proof with a tampered page
+    let mut tampered = proof.clone();
+    tampered.pages[0].value[31] = 0x01;
+
+    assert!(!app::verify(
+        &layout,
+        &root,
+        VarId(0),
+        &[10, 20, 30, 40],
+        &tampered
+    ));
+}
+
+#[test]
+fn app_storage_commit_returns_root_verifiable_by_proofs() {
+    // `var #0` is a map (reserves 4 bytes)
+    let layout = DataLayout::from(vec![4, 30, 8].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv);
+
+    write_vars!(app, 1 => [0xAA; 30], 2 => [0xFF; 8]);
+    let state = app.commit();
+
+    assert_eq!(state, app.root());
+    assert_eq!(state, app.head());
+
+    let proof = app.prove(VarId(1));
+    assert!(app::verify(&layout, &state, VarId(1), &[0xAA; 30], &proof));
+
+    // committing map entries only yields a new `State`
+    app.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+    let new_state = app.commit();
+
+    assert_ne!(new_state, state);
+    assert_eq!(new_state, app.root());
+
+    // a proof against the new `State` commits to the entries as well
+    let new_proof = app.prove(VarId(1));
+    assert_ne!(new_proof, proof);
+
+    assert!(app::verify(
+        &layout,
+        &new_state,
+        VarId(1),
+        &[0xAA; 30],
+        &new_proof
+    ));
+
+    assert!(!app::verify(
+        &layout,
+        &new_state,
+        VarId(1),
+        &[0xAA; 30],
+        &proof
+    ));
+}
+
+#[test]
+fn app_storage_root_is_determined_by_entries() {
+    // `var #0` is a map (reserves 4 bytes) and `var #1` is a vector (its length consumes 2 bytes)
+    let layout = DataLayout::from(vec![4, 2].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv);
+
+    app.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+    app.map_set(VarId(0), b"bob", vec![]);
+    app.vec_push(VarId(1), vec![40, 50]).unwrap();
+    let state = app.commit();
+
+    // the same entries are committed (by another node) in multiple commits and another order
+    let kv = testing::create_app_kv(Address::of("another-app"));
+    let mut app2 = AppStorage::new(layout.clone(), kv);
+
+    app2.vec_push(VarId(1), vec![40, 50]).unwrap();
+    app2.map_set(VarId(0), b"alice", vec![0xFF]);
+    let _state = app2.commit();
+
+    app2.map_set(VarId(0), b"bob", vec![]);
+    app2.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+    let state2 = app2.commit();
+
+    assert_eq!(state2, state);
+
+    // an entry holding an empty value differs from a missing entry
+    let kv = testing::create_app_kv(Address::of("yet-another-app"));
+    let mut app3 = AppStorage::new(layout, kv);
+
+    app3.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+    app3.vec_push(VarId(1), vec![40, 50]).unwrap();
+    let state3 = app3.commit();
+
+    assert_ne!(state3, state);
+}
+
+#[test]
+fn app_storage_rewind_kv_to_previous_state() {
+    let layout = DataLayout::from(vec![4, 2].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    write_vars!(app, 0 => [10, 20, 30, 40]);
+    app.map_set(VarId(1), b"alice", vec![1]);
+    let state1 = app.commit();
+    let kv_state1 = app.kv_head();
+
+    write_vars!(app, 0 => [50, 60, 70, 80]);
+    app.map_set(VarId(1), b"alice", vec![2]);
+    let state2 = app.commit();
+
+    // only the current `State` is accepted
+    assert_eq!(app.check_state(&state2), Ok(()));
+    assert_eq!(app.check_state(&state1), Err(UnknownState(state1.clone())));
+
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+    app.rewind_kv(&kv_state1);
+
+    assert_eq!(app.head(), state1);
+    assert_eq!(app.check_state(&state1), Ok(()));
+    assert_vars!(app, 0 => [10, 20, 30, 40]);
+    assert_eq!(app.map_get(VarId(1), b"alice"), Some(vec![1]));
+
+    // committing again the data of `state2` (having a different history) reaches `state2`
+    write_vars!(app, 0 => [50, 60, 70, 80]);
+    app.map_set(VarId(1), b"alice", vec![2]);
+
+    assert_eq!(app.commit(), state2);
+}
+
+#[test]
+fn app_storage_prune_kv_keeps_rewinding_to_kept_states() {
+    let layout = DataLayout::from(vec![4].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    let mut kv_states = Vec::new();
+
+    for i in 1..=4u8 {
+        write_vars!(app, 0 => [i, i, i, i]);
+        let _state = app.commit();
+
+        kv_states.push(app.kv_head());
+    }
+
+    app.prune_kv(&kv_states[1..2]);
+
+    let mut app = AppStorage::new(layout, kv);
+    assert_vars!(app, 0 => [4, 4, 4, 4]);
+
+    app.rewind_kv(&kv_states[1]);
+    assert_vars!(app, 0 => [2, 2, 2, 2]);
+
+    app.rewind_kv(&kv_states[3]);
+    assert_vars!(app, 0 => [4, 4, 4, 4]);
+}
+
+#[test]
+fn app_storage_check_unknown_state() {
+    let layout = DataLayout::from(vec![4, 2].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout, kv);
+
+    write_vars!(app, 0 => [10, 20, 30, 40]);
+    let _state = app.commit();
+
+    let unknown = State::from(&[0xFF; 32][..]);
+
+    assert_eq!(app.check_state(&unknown), Err(UnknownState(unknown)));
+}
+
+#[test]
+fn app_storage_check_state_keeps_other_apps_changes() {
+    let layout = DataLayout::from(vec![4].as_slice());
+
+    let kv = testing::create_kv();
+    let app_kv = |name| AppKVStore::new(Address::of(name), &kv);
+
+    let mut app1 = AppStorage::new(layout.clone(), app_kv("app-1"));
+    write_vars!(app1, 0 => [10, 20, 30, 40]);
+    let old_state = app1.commit();
+
+    let mut app2 = AppStorage::new(layout.clone(), app_kv("app-2"));
+    write_vars!(app2, 0 => [50, 60, 70, 80]);
+    let _state = app2.commit();
+
+    write_vars!(app1, 0 => [90, 0, 0, 0]);
+    let _state = app1.commit();
+
+    // `app #1` previous `State` isn't accepted (and the key-value store is left untouched)
+    let app1 = AppStorage::new(layout.clone(), app_kv("app-1"));
+    assert_eq!(app1.check_state(&old_state), Err(UnknownState(old_state)));
+
+    let app2 = AppStorage::new(layout, app_kv("app-2"));
+    assert_vars!(app2, 0 => [50, 60, 70, 80]);
+}
+
+#[test]
+fn app_storage_changes_of_uncommitted_vars() {
+    let layout = DataLayout::from(vec![4, 2, 3].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout, kv);
+
+    assert!(app.changes().is_empty());
+
+    write_vars!(app, 0 => [10, 20, 30, 40]);
+    let _state = app.commit();
+
+    assert!(app.changes().is_empty());
+
+    write_vars!(app, 2 => [1, 2, 3], 0 => [50, 60, 70, 80]);
+
+    // changes are ordered by `var_id`
+    assert_eq!(
+        app.changes(),
+        vec![
+            StorageChange {
+                var_id: 0,
+                old_value: vec![10, 20, 30, 40],
+                new_value: vec![50, 60, 70, 80],
+            },
+            StorageChange {
+                var_id: 2,
+                old_value: vec![0, 0, 0],
+                new_value: vec![1, 2, 3],
+            },
+        ]
+    );
+
+    let _state = app.commit();
+
+    assert!(app.changes().is_empty());
+}
+
+#[test]
+fn app_storage_nested_savepoints() {
+    let layout = DataLayout::from(vec![4, 2].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    write_vars!(app, 0 => [10, 20, 30, 40]);
+
+    let outer = app.savepoint();
+    write_vars!(app, 0 => [11, 21, 31, 41], 1 => [50, 60]);
+    app.map_set(VarId(1), b"key", vec![0xAA]);
+
+    let inner = app.savepoint();
+    write_vars!(app, 1 => [51, 61]);
+    app.map_set(VarId(1), b"key", vec![0xBB]);
+
+    // reads see the innermost values
+    assert_vars!(app, 0 => [11, 21, 31, 41], 1 => [51, 61]);
+    assert_eq!(app.map_get(VarId(1), b"key"), Some(vec![0xBB]));
+
+    assert!(app.rollback_to(inner));
+    assert!(!app.has_savepoint(inner));
+
+    assert_vars!(app, 0 => [11, 21, 31, 41], 1 => [50, 60]);
+    assert_eq!(app.map_get(VarId(1), b"key"), Some(vec![0xAA]));
+
+    // a rolled-back savepoint can't be rolled-back (or released) again
+    assert!(!app.rollback_to(inner));
+    assert!(!app.release(inner));
+
+    assert!(app.rollback_to(outer));
+
+    assert_vars!(app, 0 => [10, 20, 30, 40], 1 => [0, 0]);
+    assert_eq!(app.map_get(VarId(1), b"key"), None);
+
+    let _state = app.commit();
+
+    let app2 = AppStorage::new(layout, kv);
+    assert_vars!(app2, 0 => [10, 20, 30, 40], 1 => [0, 0]);
+}
+
+#[test]
+fn app_storage_release_savepoint_keeps_changes() {
+    let layout = DataLayout::from(vec![4, 2].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout, kv);
+
+    let outer = app.savepoint();
+    write_vars!(app, 0 => [10, 20, 30, 40]);
+
+    let inner = app.savepoint();
+    write_vars!(app, 0 => [11, 21, 31, 41], 1 => [50, 60]);
+
+    assert!(app.release(inner));
+    assert!(app.has_savepoint(outer));
+    assert_vars!(app, 0 => [11, 21, 31, 41], 1 => [50, 60]);
+
+    // the released changes are reverted by rolling back the enclosing savepoint
+    assert!(app.rollback_to(outer));
+    assert_vars!(app, 0 => [0, 0, 0, 0], 1 => [0, 0]);
+}
+
+#[test]
+fn app_storage_usage_is_updated_on_commit() {
+    // `var #0` consumes 30 bytes (offsets: `[0, 30)`)
+    // `var #1` consumes 8 bytes (offsets: `[30, 38)`) - spans pages `#0` and `#1`
+    // `var #2` is a map (reserves 4 bytes)
+    let layout = DataLayout::from(vec![30, 8, 4].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(layout.clone(), kv.clone());
+
+    assert_eq!(app.usage(), app::StorageUsage::default());
+
+    write_vars!(app, 0 => [0x10; 30], 1 => [0x20; 8]);
+    app.map_set(VarId(2), b"alice", vec![10, 20, 30]);
+
+    // an entry key is 32 bytes long
+    let expected = app::StorageUsage {
+        pages: 2,
+        entries: 1,
+        bytes: 2 * 32 + (32 + 3),
+    };
+
+    // the usage reflects only the committed data
+    assert_eq!(app.usage(), app::StorageUsage::default());
+    assert_eq!(app.pending_usage(), expected);
+
+    let _state = app.commit();
+    assert_eq!(app.usage(), expected);
+
+    // zeroing `var #1` empties page `#1` (page `#0` still holds `var #0`)
+    write_vars!(app, 1 => [0; 8]);
+    app.map_set(VarId(2), b"alice", vec![40]);
+
+    let _state = app.commit();
+
+    let app2 = AppStorage::new(layout, kv);
+
+    assert_eq!(
+        app2.usage(),
+        app::StorageUsage {
+            pages: 1,
+            entries: 1,
+            bytes: 32 + (32 + 1),
+        }
+    );
+}
+
+#[test]
+fn app_storage_migrate_layout() {
+    // `var #0` consumes 4 bytes, `var #1` consumes 2 bytes
+    let old_layout = DataLayout::from(vec![4, 2].as_slice());
+
+    // `var #0` consumes 2 bytes, `var #1` consumes 8 bytes, `var #2` consumes 4 bytes
+    let new_layout = DataLayout::from(vec![2, 8, 4].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(old_layout.clone(), kv.clone());
+
+    write_vars!(app, 0 => [10, 20, 30, 40], 1 => [50, 60]);
+    let old_state = app.commit();
+
+    // old `var #0` moves to new `var #1` (and gets widened), old `var #1` moves to new `var #0`
+    let mapping = [(VarId(0), VarId(1)), (VarId(1), VarId(0))];
+    let migration = app::LayoutMigration::new(old_layout.clone(), new_layout.clone(), &mapping);
+
+    let kv_state = app.kv_head();
+
+    let new_state = app.migrate(&migration.unwrap()).unwrap();
+    assert_ne!(new_state, old_state);
+
+    let app2 = AppStorage::new(new_layout, kv.clone());
+    app2.check_state(&new_state).unwrap();
+
+    assert_vars!(app2, 0 => [50, 60], 1 => [10, 20, 30, 40, 0, 0, 0, 0], 2 => [0, 0, 0, 0]);
+
+    // the old state is left untouched
+    let mut app3 = AppStorage::new(old_layout, kv);
+    app3.rewind_kv(&kv_state);
+    app3.check_state(&old_state).unwrap();
+
+    assert_vars!(app3, 0 => [10, 20, 30, 40], 1 => [50, 60]);
+}
+
+#[test]
+fn app_storage_migrate_layout_keeps_entries_of_unmoved_vars() {
+    // `var #0` is a map (reserves 4 bytes), `var #1` is a vector (its length consumes 2 bytes)
+    let old_layout = DataLayout::from(vec![4, 2].as_slice());
+
+    // `var #2` is new
+    let new_layout = DataLayout::from(vec![4, 4, 8].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(old_layout.clone(), kv);
+
+    app.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+    app.vec_push(VarId(1), vec![40, 50]).unwrap();
+    let _state = app.commit();
+
+    assert!(app.has_entries(VarId(0)));
+    assert!(app.has_entries(VarId(1)));
+
+    let mapping = [(VarId(0), VarId(0)), (VarId(1), VarId(1))];
+    let migration = app::LayoutMigration::new(old_layout, new_layout, &mapping).unwrap();
+
+    let _state = app.migrate(&migration).unwrap();
+
+    assert_eq!(app.map_get(VarId(0), b"alice"), Some(vec![10, 20, 30]));
+    assert_eq!(app.vec_len(VarId(1)), Ok(1));
+    assert_eq!(app.vec_get(VarId(1), 0), Ok(vec![40, 50]));
+}
+
+#[test]
+fn app_storage_migrate_layout_rejects_moving_vars_having_entries() {
+    // `var #0` is a map (reserves 4 bytes), `var #1` consumes 4 bytes
+    let old_layout = DataLayout::from(vec![4, 4].as_slice());
+    let new_layout = DataLayout::from(vec![4, 4].as_slice());
+
+    let kv = testing::create_app_kv(Address::of("my-app"));
+    let mut app = AppStorage::new(old_layout.clone(), kv);
+
+    write_vars!(app, 1 => [10, 20, 30, 40]);
+    let state = app.commit();
+
+    // the map entries are uncommitted yet
+    app.map_set(VarId(0), b"alice", vec![10, 20, 30]);
+
+    // swapping the variables would orphan the map entries
+    let mapping = [(VarId(0), VarId(1)), (VarId(1), VarId(0))];
+    let migration = app::LayoutMigration::new(old_layout, new_layout, &mapping).unwrap();
+
+    assert_eq!(
+        app.migrate(&migration),
+        Err(app::MigrationError::DynamicVarMoved {
+            old_var: VarId(0),
+            new_var: VarId(1),
+        })
+    );
+
+    // the storage is left untouched
+    assert_eq!(app.head(), state);
+    assert_vars!(app, 1 => [10, 20, 30, 40]);
+
+    // a committed map is detected as well
+    let _state = app.commit();
+    assert!(app.has_entries(VarId(0)));
+    assert!(!app.has_entries(VarId(1)));
+
+    assert!(app.migrate(&migration).is_err());
+}
@@ -14,6 +14,7 @@ impl_addr_type!(Author);
 impl_addr_type!(Creator);
 impl_addr_type!(Template);
 impl_addr_type!(App);
+impl_addr_type!(Sender);
 
 /// Address of a Template.
 pub type TemplateAddr = AddressOf<Template>;
@@ -24,6 +25,9 @@ pub type AuthorAddr = AddressOf<Author>;
 /// Address of an App Creator.
 pub type CreatorAddr = AddressOf<Creator>;
 
+/// Address of a transaction Sender.
+pub type SenderAddr = AddressOf<Sender>;
+
 /// Address of an App.
 pub type AppAddr = AddressOf<Author>;
 
@@ -11,6 +11,9 @@ pub struct AppTransaction {
     /// The `App` account address
     pub app: AppAddr,
 
+    /// The amount of coins transferred from the transaction sender to the `App`
+    pub value: u64,
+
     /// Function's name to execute
     pub func_name: String,
 
@@ -25,6 +28,7 @@ impl fmt::Debug for AppTransaction {
         f.debug_struct("AppTransaction")
             .field("version", &self.version)
             .field("app", self.app.inner())
+            .field("value", &self.value)
             .field("calldata", &calldata)
             .field("function", &self.func_name)
             .finish()
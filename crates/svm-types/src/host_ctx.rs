@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use crate::Address;
+
+/// The `Host Context` of an executed transaction.
+///
+/// A map between an `u32` field index to a raw-byte array, filled-in by the `Host` (i.e the node).
+/// It holds the `Transaction Envelope` fields (such as `sender` and `nonce`) and additional
+/// data (such as `layer_id`). Running apps can read it using the `svm_host_get` vmcalls.
+///
+/// See `Host Context` under `docs/go-spacemesh-integration.md`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HostCtx {
+    fields: BTreeMap<u32, Vec<u8>>,
+}
+
+impl HostCtx {
+    /// Field index of the transaction `sender` (`Address::len()` bytes).
+    pub const SENDER: u32 = 0;
+
+    /// Field index of the `layer_id` the transaction is executed at (`u64` Big-Endian).
+    pub const LAYER_ID: u32 = 1;
+
+    /// Field index of the transaction `nonce` (`u64` Big-Endian).
+    pub const NONCE: u32 = 2;
+
+    /// Creates an empty `HostCtx`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of field `field` (overriding any previous value)
+    pub fn set(&mut self, field: u32, value: Vec<u8>) {
+        self.fields.insert(field, value);
+    }
+
+    /// Returns the value of field `field` (`None` if missing)
+    pub fn get(&self, field: u32) -> Option<&[u8]> {
+        self.fields.get(&field).map(|value| &value[..])
+    }
+
+    /// Returns the transaction `sender` (`None` if missing or malformed)
+    pub fn sender(&self) -> Option<Address> {
+        self.get(Self::SENDER)
+            .filter(|bytes| bytes.len() == Address::len())
+            .map(Address::from)
+    }
+
+    /// Returns the number of fields
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns whether there are no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Iterates over the fields (ordered by their index)
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        self.fields
+            .iter()
+            .map(|(field, value)| (*field, &value[..]))
+    }
+}
+
+impl From<BTreeMap<u32, Vec<u8>>> for HostCtx {
+    fn from(fields: BTreeMap<u32, Vec<u8>>) -> Self {
+        Self { fields }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_ctx_get_and_set() {
+        let mut host_ctx = HostCtx::new();
+        assert!(host_ctx.is_empty());
+
+        host_ctx.set(HostCtx::NONCE, vec![0x10, 0x20]);
+        host_ctx.set(HostCtx::LAYER_ID, vec![0x1A, 0x5C, 0x2D]);
+
+        assert_eq!(host_ctx.len(), 2);
+        assert_eq!(host_ctx.get(HostCtx::NONCE), Some(&[0x10, 0x20][..]));
+        assert_eq!(
+            host_ctx.get(HostCtx::LAYER_ID),
+            Some(&[0x1A, 0x5C, 0x2D][..])
+        );
+        assert_eq!(host_ctx.get(100), None);
+
+        let fields: Vec<u32> = host_ctx.iter().map(|(field, _)| field).collect();
+        assert_eq!(fields, vec![HostCtx::LAYER_ID, HostCtx::NONCE]);
+    }
+
+    #[test]
+    fn host_ctx_sender() {
+        let mut host_ctx = HostCtx::new();
+        assert_eq!(host_ctx.sender(), None);
+
+        host_ctx.set(HostCtx::SENDER, vec![0x10, 0x20]);
+        assert_eq!(host_ctx.sender(), None);
+
+        let sender = Address::of("sender");
+        host_ctx.set(HostCtx::SENDER, sender.as_slice().to_vec());
+        assert_eq!(host_ctx.sender(), Some(sender));
+    }
+}
@@ -13,6 +13,7 @@ mod address;
 mod address_of;
 mod app;
 mod app_tx;
+mod host_ctx;
 mod spawn_app;
 mod state;
 mod template;
@@ -21,10 +22,11 @@ mod wasm_value;
 
 pub mod gas;
 pub mod receipt;
-pub use address::{Address, AppAddr, AuthorAddr, CreatorAddr, TemplateAddr};
+pub use address::{Address, AppAddr, AuthorAddr, CreatorAddr, SenderAddr, TemplateAddr};
 pub use address_of::AddressOf;
 pub use app::App;
 pub use app_tx::AppTransaction;
+pub use host_ctx::HostCtx;
 pub use spawn_app::SpawnApp;
 pub use state::State;
 pub use template::AppTemplate;
@@ -0,0 +1,14 @@
+use crate::Address;
+
+/// A change of an account balance made by a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BalanceChange {
+    /// The account `Address`
+    pub addr: Address,
+
+    /// The account balance prior to the transaction
+    pub old_balance: u64,
+
+    /// The account balance after the transaction
+    pub new_balance: u64,
+}
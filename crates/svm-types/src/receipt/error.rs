@@ -1,4 +1,4 @@
-use crate::{AppAddr, TemplateAddr};
+use crate::{Address, AppAddr, State, TemplateAddr};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ReceiptError {
@@ -32,4 +32,35 @@ pub enum ReceiptError {
         func: String,
         msg: String,
     },
+    InsufficientFunds {
+        addr: Address,
+        balance: u64,
+        amount: u64,
+    },
+    ParseFailed(String),
+    MissingMemoryExport {
+        app_addr: AppAddr,
+        template_addr: TemplateAddr,
+    },
+    MemoryOutOfBounds {
+        app_addr: AppAddr,
+        template_addr: TemplateAddr,
+        offset: u32,
+        len: u32,
+    },
+    InvalidVarId {
+        app_addr: AppAddr,
+        template_addr: TemplateAddr,
+        var_id: u32,
+    },
+    StorageLimitExceeded {
+        app_addr: AppAddr,
+        template_addr: TemplateAddr,
+        size: u64,
+        limit: u64,
+    },
+    UnknownState {
+        app_addr: AppAddr,
+        state: State,
+    },
 }
@@ -1,4 +1,4 @@
-use crate::receipt::{Log, ReceiptError};
+use crate::receipt::{BalanceChange, Log, ReceiptError, StorageChange};
 use crate::{gas::MaybeGas, State, WasmValue};
 
 use super::Receipt;
@@ -25,6 +25,17 @@ pub struct ExecReceipt {
 
     /// logged entries during execution of app's transaction
     pub logs: Vec<Log>,
+
+    /// The balances changed by the transaction (empty if execution failed)
+    pub balance_changes: Vec<BalanceChange>,
+
+    /// The storage variables written by the transaction
+    /// (`None` when the storage changes collection is turned off or when execution failed)
+    pub storage_changes: Option<Vec<StorageChange>>,
+
+    /// Whether only part of the storage changes are available
+    /// (changes which didn't fit the receipt encoding have been left out)
+    pub storage_changes_truncated: bool,
 }
 
 impl ExecReceipt {
@@ -42,6 +53,9 @@ impl ExecReceipt {
             returndata: None,
             gas_used: MaybeGas::new(),
             logs,
+            balance_changes: Vec::new(),
+            storage_changes: None,
+            storage_changes_truncated: false,
         }
     }
 
@@ -63,6 +77,14 @@ impl ExecReceipt {
         &self.logs
     }
 
+    pub fn get_balance_changes(&self) -> &[BalanceChange] {
+        &self.balance_changes
+    }
+
+    pub fn get_storage_changes(&self) -> Option<&[StorageChange]> {
+        self.storage_changes.as_deref()
+    }
+
     /// Take the Receipt's logged entries out
     pub fn take_logs(&mut self) -> Vec<Log> {
         std::mem::take(&mut self.logs)
@@ -1,15 +1,19 @@
+mod balance;
 mod deploy_template;
 mod exec_app;
 mod log;
 mod spawn_app;
+mod storage;
 
 mod error;
 pub use error::ReceiptError;
 
+pub use balance::BalanceChange;
 pub use deploy_template::TemplateReceipt;
 pub use exec_app::ExecReceipt;
 pub use log::Log;
 pub use spawn_app::{into_spawn_app_receipt, SpawnAppReceipt};
+pub use storage::StorageChange;
 
 use crate::gas::MaybeGas;
 
@@ -103,6 +107,22 @@ impl ReceiptOwned {
         }
     }
 
+    pub fn version(&self) -> u16 {
+        match self {
+            ReceiptOwned::DeployTemplate(receipt) => receipt.version,
+            ReceiptOwned::SpawnApp(receipt) => receipt.version,
+            ReceiptOwned::ExecApp(receipt) => receipt.version,
+        }
+    }
+
+    pub fn get_gas_used(&self) -> MaybeGas {
+        match self {
+            ReceiptOwned::DeployTemplate(receipt) => receipt.gas_used,
+            ReceiptOwned::SpawnApp(receipt) => receipt.gas_used,
+            ReceiptOwned::ExecApp(receipt) => receipt.gas_used,
+        }
+    }
+
     pub fn get_logs(&self) -> &[Log] {
         match self {
             ReceiptOwned::DeployTemplate(receipt) => receipt.get_logs(),
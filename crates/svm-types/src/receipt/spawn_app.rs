@@ -1,6 +1,6 @@
 use std::io::SeekFrom;
 
-use crate::receipt::{ExecReceipt, Log, ReceiptError};
+use crate::receipt::{BalanceChange, ExecReceipt, Log, ReceiptError};
 use crate::{gas::MaybeGas, AppAddr, State};
 
 /// Returned Receipt after spawning an App.
@@ -28,6 +28,9 @@ pub struct SpawnAppReceipt {
 
     /// logged entries during spawn-app's ctor running
     pub logs: Vec<Log>,
+
+    /// The balances changed by the spawn (empty if spawning failed)
+    pub balance_changes: Vec<BalanceChange>,
 }
 
 impl SpawnAppReceipt {
@@ -46,6 +49,7 @@ impl SpawnAppReceipt {
             returndata: None,
             gas_used: MaybeGas::new(),
             logs,
+            balance_changes: Vec::new(),
         }
     }
 
@@ -81,6 +85,10 @@ impl SpawnAppReceipt {
     pub fn take_logs(&mut self) -> Vec<Log> {
         std::mem::take(&mut self.logs)
     }
+
+    pub fn get_balance_changes(&self) -> &[BalanceChange] {
+        &self.balance_changes
+    }
 }
 
 #[allow(missing_docs)]
@@ -90,10 +98,11 @@ pub fn into_spawn_app_receipt(
 ) -> SpawnAppReceipt {
     let app_addr = Some(app_addr.clone());
     let logs = ctor_receipt.take_logs();
+    let version = ctor_receipt.version;
 
     if ctor_receipt.success {
         SpawnAppReceipt {
-            version: 0,
+            version,
             success: true,
             error: None,
             app_addr,
@@ -101,12 +110,13 @@ pub fn into_spawn_app_receipt(
             returndata: ctor_receipt.returndata,
             gas_used: ctor_receipt.gas_used,
             logs,
+            balance_changes: ctor_receipt.balance_changes,
         }
     } else {
         let error = ctor_receipt.error.unwrap();
 
         SpawnAppReceipt {
-            version: 0,
+            version,
             success: false,
             error: Some(error),
             app_addr,
@@ -114,6 +124,7 @@ pub fn into_spawn_app_receipt(
             returndata: None,
             gas_used: MaybeGas::new(),
             logs,
+            balance_changes: Vec::new(),
         }
     }
 }
@@ -0,0 +1,12 @@
+/// A change of an app's storage variable made by a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StorageChange {
+    /// The variable id
+    pub var_id: u32,
+
+    /// The variable value prior to the transaction
+    pub old_value: Vec<u8>,
+
+    /// The variable value after the transaction
+    pub new_value: Vec<u8>,
+}
@@ -10,6 +10,9 @@ pub struct SpawnApp {
     /// Holds all `SpawnApp` non-ctor_name related data.
     pub app: App,
 
+    /// The amount of coins transferred from the app creator to the spawned `App`
+    pub value: u64,
+
     /// ctor function name
     pub ctor_name: String,
 
@@ -21,6 +24,7 @@ impl fmt::Debug for SpawnApp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.app.fmt(f)?;
 
+        writeln!(f, "value: {}", self.value)?;
         writeln!(f, "ctor_name: {}", self.ctor_name)?;
         writeln!(
             f,